@@ -1,24 +1,62 @@
-use std::sync::Arc;
+use std::{
+    borrow::Cow,
+    rc::Rc,
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
 
 use agent_client_protocol::{ContentBlock, ContentChunk, SessionId};
 use gpui::{
-    App, AppContext, Context, ElementId, Entity, IntoElement, ParentElement, Render, RenderOnce,
-    SharedString, Styled, Window, div, prelude::FluentBuilder as _, px,
+    Animation, AnimationExt as _, AnyElement, App, AppContext, ClipboardItem, Context, ElementId,
+    Entity, Hsla, InteractiveElement as _, IntoElement, ParentElement, Pixels, Render, RenderOnce,
+    SharedString, StatefulInteractiveElement as _, Styled, Window, div,
+    prelude::FluentBuilder as _, px,
+};
+use gpui_component::{
+    ActiveTheme, Icon, IconName, Sizable,
+    button::{Button, ButtonVariants},
+    h_flex,
+    text::TextView,
+    v_flex,
 };
-use gpui_component::{ActiveTheme, Icon, IconName, h_flex, text::TextView, v_flex};
 use serde::{Deserialize, Serialize};
 
-pub type AgentIconProvider = Arc<dyn Fn(&str) -> Icon + Send + Sync>;
+use crate::avatar::Avatar;
+use crate::code_wrap::{CodeBlockWrap, split_code_blocks};
+use crate::content_renderer::ContentRenderers;
+use crate::utils::{format_relative_time, highlight_matches, timestamp_from_meta};
+
+/// Icon for an agent by name; `None` shows the agent's initials instead
+pub type AgentIconProvider = Arc<dyn Fn(&str) -> Option<Icon> + Send + Sync>;
+
+/// Reading speed used for the reading-time estimate
+const WORDS_PER_MINUTE: usize = 200;
+/// Completed messages with fewer words get no stats footer
+const STATS_MIN_WORDS: usize = 150;
+/// Avatar next to the agent name
+const AVATAR_SIZE: Pixels = px(20.);
+/// Message content starts under the agent name: avatar plus gap
+const CONTENT_INDENT: Pixels = px(28.);
+/// Typing caret at the end of a message that is still streaming
+const CARET: &str = "▍";
+/// One on/off cycle of the typing caret
+const CARET_BLINK: Duration = Duration::from_millis(1000);
 
 #[derive(Clone)]
 pub struct AgentMessageOptions {
     pub icon_provider: AgentIconProvider,
+    /// How content blocks other than text are shown
+    pub content_renderers: Arc<ContentRenderers>,
+    /// Render text as markdown; off shows the raw text
+    pub markdown: bool,
 }
 
 impl Default for AgentMessageOptions {
     fn default() -> Self {
         Self {
-            icon_provider: Arc::new(|_| Icon::new(IconName::Bot)),
+            icon_provider: Arc::new(|_| None),
+            content_renderers: Arc::new(ContentRenderers::default()),
+            markdown: true,
         }
     }
 }
@@ -44,6 +82,8 @@ pub struct AgentMessageData {
     pub chunks: Vec<ContentChunk>,
     /// Extended metadata (agent_name, is_complete, etc.)
     pub meta: AgentMessageMeta,
+    /// When the first chunk that says so was sent (see `timestamp_from_meta`)
+    pub timestamp: Option<SystemTime>,
     /// Cached full text to avoid reconstruction on every render
     cached_text: SharedString,
 }
@@ -54,6 +94,7 @@ impl AgentMessageData {
             session_id: session_id.into(),
             chunks: Vec::new(),
             meta: AgentMessageMeta::default(),
+            timestamp: None,
             cached_text: SharedString::default(),
         }
     }
@@ -64,14 +105,21 @@ impl AgentMessageData {
     }
 
     pub fn with_chunks(mut self, chunks: Vec<ContentChunk>) -> Self {
+        self.timestamp = chunks
+            .iter()
+            .find_map(|chunk| timestamp_from_meta(chunk.meta.as_ref()));
         self.chunks = chunks;
         self.update_cache();
         self
     }
 
     pub fn add_chunk(mut self, chunk: ContentChunk) -> Self {
-        self.chunks.push(chunk);
-        self.update_cache();
+        self.push_chunk(chunk);
+        self
+    }
+
+    pub fn with_timestamp(mut self, timestamp: SystemTime) -> Self {
+        self.timestamp = Some(timestamp);
         self
     }
 
@@ -85,6 +133,9 @@ impl AgentMessageData {
 
     /// Append a chunk in place and update cache
     pub fn push_chunk(&mut self, chunk: ContentChunk) {
+        if self.timestamp.is_none() {
+            self.timestamp = timestamp_from_meta(chunk.meta.as_ref());
+        }
         self.chunks.push(chunk);
         self.update_cache();
     }
@@ -144,28 +195,144 @@ impl AgentMessageData {
     }
 }
 
+/// Word count and reading-time estimate of an agent message
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MessageStats {
+    pub words: usize,
+    /// Estimated reading time, rounded up to whole minutes
+    pub minutes: usize,
+}
+
+impl MessageStats {
+    /// Whether the message is long enough for the stats footer
+    pub fn is_worth_showing(&self) -> bool {
+        self.words >= STATS_MIN_WORDS
+    }
+
+    /// Footer text, e.g. "420 words · ~3 min"
+    pub fn label(&self) -> String {
+        format!("{} words · ~{} min", self.words, self.minutes)
+    }
+}
+
+/// Count the words in the message's text chunks and estimate reading time
+pub fn message_stats(data: &AgentMessageData) -> MessageStats {
+    let words = data.full_text().split_whitespace().count();
+    MessageStats {
+        words,
+        minutes: words.div_ceil(WORDS_PER_MINUTE).max(1),
+    }
+}
+
+/// Make a still-streaming Markdown tail safe to render.
+///
+/// An unclosed ``` fence is closed so the partial code renders as a code
+/// block instead of swallowing the rest of the document, a trailing run of
+/// emphasis/code markers (`**`, `_`, a half-typed fence) is held back until
+/// more text arrives, and an unbalanced inline code span on the last line is
+/// closed. Text that is already well formed is borrowed unchanged.
+pub fn streaming_markdown(text: &str) -> Cow<'_, str> {
+    let fence_open = text
+        .lines()
+        .filter(|line| line.trim_start().starts_with("```"))
+        .count()
+        % 2
+        == 1;
+    if fence_open {
+        let separator = if text.ends_with('\n') { "" } else { "\n" };
+        return Cow::Owned(format!("{}{}```", text, separator));
+    }
+
+    let body = text.trim_end_matches(['*', '_', '~', '`']);
+    let last_line = body.rsplit('\n').next().unwrap_or_default();
+    if last_line.matches('`').count() % 2 == 1 {
+        return Cow::Owned(format!("{}`", body));
+    }
+
+    if body.len() == text.len() {
+        Cow::Borrowed(text)
+    } else {
+        Cow::Borrowed(body)
+    }
+}
+
+/// The typing caret after a streaming message. It blinks on its own, so the
+/// message text above it isn't rebuilt on every frame.
+fn render_caret(id: SharedString, color: Hsla) -> impl IntoElement {
+    div()
+        .text_sm()
+        .text_color(color)
+        .child(CARET)
+        .with_animation(id, Animation::new(CARET_BLINK).repeat(), |this, delta| {
+            this.opacity(if delta < 0.5 { 1. } else { 0. })
+        })
+}
+
+/// Hover group of a message; its copy button shows while it is hovered
+pub(crate) const MESSAGE_HOVER_GROUP: &str = "message";
+
+/// Button copying `text` to the clipboard, shown while the message is hovered;
+/// `None` for a message with nothing to copy
+pub(crate) fn render_copy_button(id: impl Into<ElementId>, text: String) -> Option<AnyElement> {
+    if text.trim().is_empty() {
+        return None;
+    }
+    Some(
+        div()
+            .invisible()
+            .group_hover(MESSAGE_HOVER_GROUP, |style| style.visible())
+            .child(
+                Button::new(id)
+                    .icon(IconName::Copy)
+                    .ghost()
+                    .xsmall()
+                    .tooltip("Copy message")
+                    .on_click(move |_, _, cx| {
+                        cx.write_to_clipboard(ClipboardItem::new_string(text.clone()));
+                    }),
+            )
+            .into_any_element(),
+    )
+}
+
+/// "5m ago" label in a message header
+pub(crate) fn render_message_time(timestamp: SystemTime, cx: &App) -> impl IntoElement {
+    div()
+        .flex_none()
+        .text_xs()
+        .text_color(cx.theme().muted_foreground)
+        .child(format_relative_time(timestamp))
+}
+
 /// Agent message component
 #[derive(IntoElement)]
 pub struct AgentMessage {
     id: ElementId,
-    data: AgentMessageData,
+    /// Shared with the owner so rendering doesn't copy every chunk
+    data: Rc<AgentMessageData>,
     options: AgentMessageOptions,
+    /// Whether an incomplete message may show the typing caret
+    streaming: bool,
+    /// Transcript search query this message matches; empty when it doesn't
+    highlight: String,
 }
 
 impl AgentMessage {
-    pub fn new(id: impl Into<ElementId>, data: AgentMessageData) -> Self {
+    pub fn new(id: impl Into<ElementId>, data: impl Into<Rc<AgentMessageData>>) -> Self {
         Self::with_options(id, data, AgentMessageOptions::default())
     }
 
     pub fn with_options(
         id: impl Into<ElementId>,
-        data: AgentMessageData,
+        data: impl Into<Rc<AgentMessageData>>,
         options: AgentMessageOptions,
     ) -> Self {
         Self {
             id: id.into(),
-            data,
+            data: data.into(),
             options,
+            streaming: true,
+            highlight: String::new(),
         }
     }
 
@@ -173,46 +340,240 @@ impl AgentMessage {
         self.options.icon_provider = icon_provider;
         self
     }
+
+    pub fn content_renderers(mut self, content_renderers: Arc<ContentRenderers>) -> Self {
+        self.options.content_renderers = content_renderers;
+        self
+    }
+
+    pub fn markdown(mut self, markdown: bool) -> Self {
+        self.options.markdown = markdown;
+        self
+    }
+
+    /// Allow the typing caret while the message is incomplete (the default).
+    /// Transcripts pass `false` for all but their last item, so an earlier
+    /// message that was never marked complete doesn't keep blinking.
+    pub fn streaming(mut self, streaming: bool) -> Self {
+        self.streaming = streaming;
+        self
+    }
+
+    /// Whether the typing caret is drawn: only while an incomplete message
+    /// is streaming, so a completed one never shows it
+    fn shows_caret(&self) -> bool {
+        self.streaming && !self.data.is_complete()
+    }
+
+    /// Show that the message matches the search `query`: raw text highlights
+    /// each match, markdown (which can't carry highlights) tints the message
+    pub fn highlight(mut self, query: impl Into<String>) -> Self {
+        self.highlight = query.into();
+        self
+    }
 }
 
 impl RenderOnce for AgentMessage {
-    fn render(self, _window: &mut Window, cx: &mut App) -> impl IntoElement {
+    fn render(self, window: &mut Window, cx: &mut App) -> impl IntoElement {
         let agent_name = self.data.agent_name().unwrap_or("Agent");
-        let full_text = self.data.full_text();
+        let text = self.data.full_text();
+        let markdown = self.options.markdown;
+        let full_text = if self.data.is_complete() || !markdown {
+            text
+        } else {
+            match streaming_markdown(&text) {
+                Cow::Borrowed(tolerant) if tolerant.len() == text.len() => text.clone(),
+                tolerant => SharedString::from(tolerant.into_owned()),
+            }
+        };
         let markdown_id = SharedString::from(format!("{}-markdown", self.id));
+        let code_wraps: Vec<CodeBlockWrap> = if markdown {
+            split_code_blocks(&full_text)
+                .into_iter()
+                .filter(|(_, code)| *code)
+                .enumerate()
+                .map(|(ix, _)| {
+                    let id = SharedString::from(format!("{}-code-{}", markdown_id, ix));
+                    CodeBlockWrap::new(id, window, cx)
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+        let stats = Some(message_stats(&self.data))
+            .filter(|stats| self.data.is_complete() && stats.is_worth_showing());
+        let text_color = cx.theme().foreground;
+
+        // Message content with markdown rendering, aligned with the name
+        let matched = !self.highlight.is_empty();
+        let content = div()
+            .w_full()
+            .pl(CONTENT_INDENT)
+            .when(matched && markdown, |this| {
+                this.rounded(cx.theme().radius)
+                    .bg(cx.theme().yellow.opacity(0.12))
+            });
+        let content = if matched && !markdown {
+            content.child(
+                div()
+                    .text_sm()
+                    .text_color(text_color)
+                    .child(highlight_matches(&text, &self.highlight, cx)),
+            )
+        } else {
+            content.child(message_text(
+                markdown_id,
+                full_text,
+                text_color,
+                markdown,
+                &code_wraps,
+            ))
+        }
+        .when(self.shows_caret(), |this| {
+            this.child(render_caret(
+                SharedString::from(format!("{}-caret", self.id)),
+                text_color,
+            ))
+        });
 
-        // Get icon based on agent name
         let icon = (self.options.icon_provider)(agent_name);
+        // Text chunks are the markdown above; other blocks render on their own
+        let blocks: Vec<AnyElement> = self
+            .data
+            .chunks
+            .iter()
+            .enumerate()
+            .filter(|(_, chunk)| !matches!(chunk.content, ContentBlock::Text(_)))
+            .filter_map(|(ix, chunk)| {
+                self.options.content_renderers.render(
+                    SharedString::from(format!("{}-block-{}", self.id, ix)),
+                    &chunk.content,
+                    cx,
+                )
+            })
+            .collect();
+
+        let copy_button = render_copy_button(
+            SharedString::from(format!("{}-copy", self.id)),
+            self.options
+                .content_renderers
+                .plain_text(self.data.chunks.iter().map(|chunk| &chunk.content)),
+        );
 
         v_flex()
-            .gap_3()
+            .group(MESSAGE_HOVER_GROUP)
+            .gap_1()
             .w_full()
-            // Agent icon and message content
+            .pr_3()
+            // Author: avatar and name, left-aligned; copy at the far end
             .child(
                 h_flex()
-                    .items_start()
+                    .w_full()
                     .gap_2()
-                    .child(icon.size(px(16.)).mt_1().text_color(cx.theme().foreground))
-                    // Message content with markdown rendering
+                    .child(
+                        Avatar::new(agent_name.to_string())
+                            .icon(icon)
+                            .size(AVATAR_SIZE),
+                    )
                     .child(
                         div()
-                            .w_full()
-                            .child(
-                                TextView::markdown(markdown_id, full_text)
-                                    .text_sm()
-                                    .text_color(cx.theme().foreground)
-                                    .selectable(true)
-                                    .pr_3(),
-                            )
-                            .pr_3(),
-                    ),
+                            .text_size(px(13.))
+                            .font_weight(gpui::FontWeight::SEMIBOLD)
+                            .text_color(cx.theme().foreground)
+                            .child(agent_name.to_string()),
+                    )
+                    .child(div().flex_1())
+                    .children(copy_button)
+                    .when_some(self.data.timestamp, |this, timestamp| {
+                        this.child(render_message_time(timestamp, cx))
+                    }),
             )
+            .child(content)
+            .when(!blocks.is_empty(), |this| {
+                this.child(v_flex().pl(CONTENT_INDENT).gap_1().children(blocks))
+            })
+            .when_some(stats, |this, stats| {
+                this.child(
+                    div()
+                        .pl(CONTENT_INDENT)
+                        .pt_2()
+                        .text_xs()
+                        .text_color(cx.theme().muted_foreground)
+                        .child(stats.label()),
+                )
+            })
+    }
+}
+
+/// The message text; markdown code blocks get a wrap toggle each, from
+/// `code_wraps` in order
+fn message_text(
+    id: SharedString,
+    text: SharedString,
+    color: gpui::Hsla,
+    markdown: bool,
+    code_wraps: &[CodeBlockWrap],
+) -> AnyElement {
+    let markdown_view = |id: SharedString, text: SharedString| {
+        TextView::markdown(id, text)
+            .text_sm()
+            .text_color(color)
+            .selectable(true)
+    };
+    if markdown && code_wraps.is_empty() {
+        markdown_view(id, text).into_any_element()
+    } else if markdown {
+        let mut code_wraps = code_wraps.iter();
+        v_flex()
+            .w_full()
+            .gap_2()
+            .children(split_code_blocks(&text).into_iter().enumerate().map(
+                |(ix, (range, code))| {
+                    let view = markdown_view(
+                        SharedString::from(format!("{}-{}", id, ix)),
+                        SharedString::from(text[range].to_string()),
+                    );
+                    match code.then(|| code_wraps.next()).flatten() {
+                        Some(wrap) => render_code_block(view, wrap),
+                        None => view.into_any_element(),
+                    }
+                },
+            ))
+            .into_any_element()
+    } else {
+        div()
+            .text_sm()
+            .text_color(color)
+            .child(text)
+            .into_any_element()
     }
 }
 
+/// A markdown code block under its wrap toggle; unwrapped, it keeps lines
+/// whole and scrolls horizontally
+fn render_code_block(view: TextView, wrap: &CodeBlockWrap) -> AnyElement {
+    v_flex()
+        .w_full()
+        .child(h_flex().justify_end().child(wrap.toggle()))
+        .map(|this| {
+            if wrap.wrapped {
+                this.child(view)
+            } else {
+                this.child(
+                    div()
+                        .id(SharedString::from(format!("{}-scroll", wrap.id())))
+                        .w_full()
+                        .overflow_x_scroll()
+                        .child(div().flex_none().whitespace_nowrap().child(view)),
+                )
+            }
+        })
+        .into_any_element()
+}
+
 /// A stateful wrapper for AgentMessage that can be used as a GPUI view
 pub struct AgentMessageView {
-    data: Entity<AgentMessageData>,
+    data: Entity<Rc<AgentMessageData>>,
     options: AgentMessageOptions,
 }
 
@@ -228,7 +589,7 @@ impl AgentMessageView {
         cx: &mut App,
     ) -> Entity<Self> {
         cx.new(|cx| {
-            let data_entity = cx.new(|_| data);
+            let data_entity = cx.new(|_| Rc::new(data));
             Self {
                 data: data_entity,
                 options,
@@ -238,7 +599,7 @@ impl AgentMessageView {
 
     fn update_message(&mut self, cx: &mut Context<Self>, f: impl FnOnce(&mut AgentMessageData)) {
         self.data.update(cx, move |data, cx| {
-            f(data);
+            f(Rc::make_mut(data));
             cx.notify();
         });
         cx.notify();
@@ -315,6 +676,73 @@ mod tests {
         assert_eq!(data.full_text().as_ref(), "Hello world");
     }
 
+    #[test]
+    fn caret_only_shows_while_streaming() {
+        let data = AgentMessageData::new("session-1").add_text("Thinking");
+        assert!(AgentMessage::new("message", data.clone()).shows_caret());
+        assert!(
+            !AgentMessage::new("message", data.clone())
+                .streaming(false)
+                .shows_caret()
+        );
+        assert!(
+            !AgentMessage::new("message", data.complete())
+                .streaming(true)
+                .shows_caret()
+        );
+    }
+
+    #[test]
+    fn message_stats_count_words_across_chunks() {
+        let data = AgentMessageData::new("session-1")
+            .add_text("word ".repeat(250))
+            .add_chunk(ContentChunk::new(ContentBlock::Image(
+                agent_client_protocol::ImageContent::new("", "image/png"),
+            )))
+            .add_text("word ".repeat(170))
+            .complete();
+        let stats = message_stats(&data);
+        assert_eq!(stats.words, 420);
+        assert_eq!(stats.minutes, 3);
+        assert_eq!(stats.label(), "420 words · ~3 min");
+        assert!(stats.is_worth_showing());
+
+        let short = message_stats(&AgentMessageData::new("session-1").add_text("Done."));
+        assert_eq!(short.minutes, 1);
+        assert!(!short.is_worth_showing());
+    }
+
+    #[test]
+    fn streaming_markdown_tolerates_every_prefix() {
+        let source = "Here is the fix:\n\n```rust\nfn retry() {\n    backoff();\n}\n```\n\nThis is **important** and uses `retry` _once_.\n";
+        let boundaries = source
+            .char_indices()
+            .map(|(ix, _)| ix)
+            .chain([source.len()]);
+
+        let mut previous_len = 0;
+        for end in boundaries {
+            let prefix = &source[..end];
+            let rendered = streaming_markdown(prefix);
+
+            let fences = rendered
+                .lines()
+                .filter(|line| line.trim_start().starts_with("```"))
+                .count();
+            assert_eq!(fences % 2, 0, "unbalanced fence for {:?}", prefix);
+
+            // Only a few held-back marker characters may be missing
+            let kept = prefix.trim_end_matches(['*', '_', '~', '`']);
+            assert!(rendered.starts_with(kept), "lost text for {:?}", prefix);
+            assert!(kept.len() + 3 >= previous_len);
+            previous_len = kept.len();
+        }
+
+        assert!(matches!(streaming_markdown(source), Cow::Borrowed(text) if text == source));
+        assert_eq!(streaming_markdown("uses `ret"), "uses `ret`");
+        assert_eq!(streaming_markdown("This is **"), "This is ");
+    }
+
     #[test]
     fn agent_message_data_handles_non_text_chunks() {
         let mut data = AgentMessageData::new("session-1");
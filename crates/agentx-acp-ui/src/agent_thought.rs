@@ -1,15 +1,21 @@
-use gpui::{Context, IntoElement, ParentElement, Render, Styled, Window, div, prelude::*, px};
+use gpui::{
+    Context, IntoElement, ParentElement, Render, SharedString, Styled, Window, div, prelude::*, px,
+};
 use gpui_component::{
     ActiveTheme, Icon, IconName, Sizable,
     button::{Button, ButtonVariants},
     collapsible::Collapsible,
-    h_flex, v_flex,
+    h_flex,
+    text::TextView,
+    v_flex,
 };
 
 /// Agent thought item for streaming "thinking" output.
 pub struct AgentThoughtItem {
     text: String,
     open: bool,
+    /// Render markdown in the thought; off shows the raw text
+    markdown: bool,
 }
 
 impl AgentThoughtItem {
@@ -17,9 +23,21 @@ impl AgentThoughtItem {
         Self {
             text: text.into(),
             open: false,
+            markdown: true,
         }
     }
 
+    pub fn with_markdown(mut self, markdown: bool) -> Self {
+        self.markdown = markdown;
+        self
+    }
+
+    /// Turn markdown rendering on or off
+    pub fn set_markdown(&mut self, markdown: bool, cx: &mut Context<Self>) {
+        self.markdown = markdown;
+        cx.notify();
+    }
+
     /// Append more text to the thought (for streaming updates)
     pub fn append_text(&mut self, text: impl Into<String>, cx: &mut Context<Self>) {
         self.text.push_str(&text.into());
@@ -81,17 +99,60 @@ impl Render for AgentThoughtItem {
                         ),
                 )
                 .when(has_content, |this| {
-                    this.content(
-                        div()
-                            .mt_2()
-                            .p_3()
-                            .pl_6()
-                            .text_sm()
-                            .italic()
-                            .text_color(cx.theme().foreground.opacity(0.8))
-                            .child(self.text.clone()),
-                    )
+                    let color = cx.theme().foreground.opacity(0.8);
+                    let content = div()
+                        .mt_2()
+                        .p_3()
+                        .pl_6()
+                        .text_sm()
+                        .italic()
+                        .text_color(color);
+                    this.content(if self.markdown && has_markdown_syntax(&self.text) {
+                        let id = SharedString::from(format!(
+                            "agent-thought-{}-markdown",
+                            cx.entity_id()
+                        ));
+                        content.child(
+                            TextView::markdown(id, SharedString::from(self.text.clone()))
+                                .text_sm()
+                                .text_color(color)
+                                .selectable(true),
+                        )
+                    } else {
+                        content.child(self.text.clone())
+                    })
                 }),
         )
     }
 }
+
+/// Whether `text` uses any markdown syntax. Text without it skips the
+/// markdown renderer, so plain thoughts look exactly as they always have.
+fn has_markdown_syntax(text: &str) -> bool {
+    text.contains(['`', '*', '_', '[', '#', '>', '|', '~'])
+        || text.lines().any(|line| {
+            let line = line.trim_start();
+            line.starts_with("- ")
+                || line.starts_with("+ ")
+                || line.split_once(". ").is_some_and(|(number, _)| {
+                    !number.is_empty() && number.chars().all(|c| c.is_ascii_digit())
+                })
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_marked_up_thoughts_use_markdown() {
+        assert!(!has_markdown_syntax(
+            "Let me look at the failing test first."
+        ));
+        assert!(!has_markdown_syntax("Version 2. Then 3."));
+        assert!(has_markdown_syntax("Check `Config::load` next"));
+        assert!(has_markdown_syntax("Plan:\n- read\n- fix"));
+        assert!(has_markdown_syntax("Steps:\n1. read\n2. fix"));
+        assert!(has_markdown_syntax("```rust\nfn main() {}\n```"));
+    }
+}
@@ -1,6 +1,9 @@
+use std::collections::{HashMap, VecDeque};
+
 use gpui::{
-    App, AppContext, Context, ElementId, Entity, InteractiveElement, IntoElement, ParentElement,
-    Render, RenderOnce, SharedString, Styled, Window, div, px,
+    App, AppContext, Context, ElementId, Entity, Hsla, InteractiveElement, IntoElement,
+    ParentElement, Render, RenderOnce, SharedString, Styled, Window, div,
+    prelude::FluentBuilder as _, px,
 };
 
 use agent_client_protocol::{Plan, PlanEntry, PlanEntryPriority, PlanEntryStatus};
@@ -16,11 +19,108 @@ pub struct PlanMeta {
     pub title: Option<String>,
 }
 
+/// How an entry differs from the same entry in the previous plan
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PlanEntryChange {
+    /// Not in the previous plan
+    Added,
+    /// Was pending, now in progress
+    Started,
+    /// Completed since the previous plan
+    Completed,
+}
+
+impl PlanEntryChange {
+    fn label(self) -> &'static str {
+        match self {
+            PlanEntryChange::Added => "new",
+            PlanEntryChange::Started => "started",
+            PlanEntryChange::Completed => "done now",
+        }
+    }
+}
+
+/// What changed between a plan and the one it replaces
+#[derive(Clone, Debug, Default)]
+pub struct PlanDelta {
+    /// Change of each entry of the new plan, by index
+    pub changes: Vec<Option<PlanEntryChange>>,
+    /// Entries of the previous plan that are gone
+    pub removed: Vec<PlanEntry>,
+}
+
+impl PlanDelta {
+    /// Compare `current` with `previous`. Entries are matched by their text,
+    /// in order when the same text appears more than once.
+    pub fn between(previous: &Plan, current: &Plan) -> Self {
+        let mut unmatched: HashMap<&str, VecDeque<&PlanEntry>> = HashMap::new();
+        for entry in &previous.entries {
+            unmatched
+                .entry(entry.content.trim())
+                .or_default()
+                .push_back(entry);
+        }
+
+        let changes = current
+            .entries
+            .iter()
+            .map(|entry| {
+                let Some(before) = unmatched
+                    .get_mut(entry.content.trim())
+                    .and_then(|entries| entries.pop_front())
+                else {
+                    return Some(PlanEntryChange::Added);
+                };
+                match (&before.status, &entry.status) {
+                    (PlanEntryStatus::Completed, _) | (_, PlanEntryStatus::Pending) => None,
+                    (_, PlanEntryStatus::Completed) => Some(PlanEntryChange::Completed),
+                    (PlanEntryStatus::Pending, PlanEntryStatus::InProgress) => {
+                        Some(PlanEntryChange::Started)
+                    }
+                    _ => None,
+                }
+            })
+            .collect();
+
+        // What is left unmatched, in its previous order
+        let removed = previous
+            .entries
+            .iter()
+            .filter(|entry| {
+                unmatched
+                    .get(entry.content.trim())
+                    .is_some_and(|left| left.iter().any(|left| std::ptr::eq(*left, *entry)))
+            })
+            .cloned()
+            .collect();
+
+        Self { changes, removed }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.removed.is_empty() && self.changes.iter().all(Option::is_none)
+    }
+}
+
+/// Small tag next to an entry that changed
+fn change_badge(label: &'static str, color: Hsla) -> impl IntoElement {
+    div()
+        .flex_none()
+        .px_1()
+        .rounded(px(4.))
+        .text_size(px(11.))
+        .line_height(px(16.))
+        .text_color(color)
+        .bg(color.opacity(0.12))
+        .child(label)
+}
+
 /// A list item component for displaying a plan entry
 #[derive(IntoElement)]
 struct PlanEntryItem {
     id: ElementId,
     entry: PlanEntry,
+    change: Option<PlanEntryChange>,
 }
 
 impl PlanEntryItem {
@@ -28,8 +128,15 @@ impl PlanEntryItem {
         Self {
             id: id.into(),
             entry,
+            change: None,
         }
     }
+
+    /// Mark the entry as changed since the previous plan
+    pub fn change(mut self, change: Option<PlanEntryChange>) -> Self {
+        self.change = change;
+        self
+    }
 }
 
 impl RenderOnce for PlanEntryItem {
@@ -48,6 +155,11 @@ impl RenderOnce for PlanEntryItem {
             _ => (Icon::new(IconName::Dash), cx.theme().muted_foreground),
         };
 
+        let badge_color = match self.change {
+            Some(PlanEntryChange::Completed) => cx.theme().green,
+            _ => cx.theme().primary,
+        };
+
         div().id(self.id).child(
             h_flex()
                 .items_start()
@@ -64,11 +176,52 @@ impl RenderOnce for PlanEntryItem {
                         .text_color(text_color)
                         .line_height(px(20.))
                         .child(self.entry.content.clone()),
-                ),
+                )
+                .when_some(self.change, |this, change| {
+                    this.child(
+                        div()
+                            .mt(px(2.))
+                            .child(change_badge(change.label(), badge_color)),
+                    )
+                }),
         )
     }
 }
 
+/// An entry the newer plan dropped, struck through
+#[derive(IntoElement)]
+struct RemovedPlanEntryItem {
+    entry: PlanEntry,
+}
+
+impl RenderOnce for RemovedPlanEntryItem {
+    fn render(self, _: &mut Window, cx: &mut App) -> impl IntoElement {
+        let muted = cx.theme().muted_foreground;
+        h_flex()
+            .items_start()
+            .gap_2()
+            .child(
+                div()
+                    .mt(px(1.))
+                    .child(Icon::new(IconName::Minus).text_color(muted).size(px(16.))),
+            )
+            .child(
+                div()
+                    .flex_1()
+                    .text_size(px(14.))
+                    .text_color(muted)
+                    .line_height(px(20.))
+                    .line_through()
+                    .child(self.entry.content),
+            )
+            .child(
+                div()
+                    .mt(px(2.))
+                    .child(change_badge("removed", cx.theme().red)),
+            )
+    }
+}
+
 /// Agent Todo List component for displaying plan execution progress
 /// Based on ACP's Plan structure from SessionUpdate::Plan
 pub struct AgentTodoList {
@@ -76,6 +229,8 @@ pub struct AgentTodoList {
     plan: Plan,
     /// Extended metadata (title, etc.) - extracted from plan.meta
     meta: PlanMeta,
+    /// Changes since the plan this one replaced, marked on the entries
+    delta: Option<PlanDelta>,
 }
 
 impl AgentTodoList {
@@ -83,6 +238,7 @@ impl AgentTodoList {
         Self {
             plan: Plan::new(Vec::new()),
             meta: PlanMeta::default(),
+            delta: None,
         }
     }
 
@@ -97,7 +253,17 @@ impl AgentTodoList {
             })
             .unwrap_or_default();
 
-        Self { plan, meta }
+        Self {
+            plan,
+            meta,
+            delta: None,
+        }
+    }
+
+    /// Mark what changed since the previous plan
+    pub fn delta(mut self, delta: Option<PlanDelta>) -> Self {
+        self.delta = delta;
+        self
     }
 
     /// Set the title of the todo list (stored in meta)
@@ -175,6 +341,7 @@ impl IntoElement for AgentTodoList {
         let title = self.display_title().to_string();
         let completed = self.completed_count();
         let total = self.total_count();
+        let PlanDelta { changes, removed } = self.delta.unwrap_or_default();
 
         v_flex()
             .gap_3()
@@ -210,7 +377,13 @@ impl IntoElement for AgentTodoList {
                     .w_full()
                     .children(self.plan.entries.into_iter().enumerate().map(|(i, entry)| {
                         PlanEntryItem::new(SharedString::from(format!("plan-entry-{}", i)), entry)
-                    })),
+                            .change(changes.get(i).copied().flatten())
+                    }))
+                    .children(
+                        removed
+                            .into_iter()
+                            .map(|entry| RemovedPlanEntryItem { entry }),
+                    ),
             )
     }
 }
@@ -381,4 +554,51 @@ mod tests {
         assert_eq!(list.completed_count(), 1);
         assert_eq!(list.total_count(), 2);
     }
+
+    #[test]
+    fn plan_delta_marks_additions_transitions_and_removals() {
+        let entry =
+            |content: &str, status| PlanEntry::new(content, PlanEntryPriority::Medium, status);
+        let previous = Plan::new(vec![
+            entry("Read the config", PlanEntryStatus::InProgress),
+            entry("Add the field", PlanEntryStatus::Pending),
+            entry("Write docs", PlanEntryStatus::Pending),
+            entry("Run tests", PlanEntryStatus::Pending),
+        ]);
+        let current = Plan::new(vec![
+            entry("Read the config", PlanEntryStatus::Completed),
+            entry("Add the field", PlanEntryStatus::InProgress),
+            entry("Update the tests", PlanEntryStatus::Pending),
+            entry("Run tests", PlanEntryStatus::Pending),
+        ]);
+
+        let delta = PlanDelta::between(&previous, &current);
+        assert_eq!(
+            delta.changes,
+            vec![
+                Some(PlanEntryChange::Completed),
+                Some(PlanEntryChange::Started),
+                Some(PlanEntryChange::Added),
+                None,
+            ]
+        );
+        assert_eq!(delta.removed.len(), 1);
+        assert_eq!(delta.removed[0].content, "Write docs");
+
+        assert!(PlanDelta::between(&current, &current).is_empty());
+    }
+
+    #[test]
+    fn plan_delta_matches_repeated_entries_in_order() {
+        let entry = |status| PlanEntry::new("Fix lint", PlanEntryPriority::Low, status);
+        let previous = Plan::new(vec![
+            entry(PlanEntryStatus::Completed),
+            entry(PlanEntryStatus::Pending),
+        ]);
+        let current = Plan::new(vec![entry(PlanEntryStatus::Completed)]);
+
+        let delta = PlanDelta::between(&previous, &current);
+        assert_eq!(delta.changes, vec![None]);
+        assert_eq!(delta.removed[0].status, PlanEntryStatus::Pending);
+    }
 }
@@ -0,0 +1,49 @@
+//! `Audio` content blocks, shown as a chip naming the clip's format. There is
+//! no player yet, so the duration is a placeholder.
+
+use agent_client_protocol::AudioContent;
+use gpui::{
+    AnyElement, App, ElementId, InteractiveElement as _, IntoElement, ParentElement, Styled, div,
+    px,
+};
+use gpui_component::{ActiveTheme, Icon, h_flex};
+
+/// Icon of the chip, from the app's bundled icons
+const AUDIO_ICON: &str = "icons2/audio-lines.svg";
+/// Shown where the clip's length will go
+const DURATION_PLACEHOLDER: &str = "--:--";
+
+/// Chip for an audio clip: icon, mime type and duration
+pub fn render_audio_chip(id: impl Into<ElementId>, audio: &AudioContent, cx: &App) -> AnyElement {
+    let theme = cx.theme();
+    h_flex()
+        .id(id)
+        .flex_none()
+        .gap_1p5()
+        .items_center()
+        .px_2()
+        .py_1()
+        .rounded(px(6.))
+        .border_1()
+        .border_color(theme.border)
+        .bg(theme.muted.opacity(0.5))
+        .child(
+            Icon::empty()
+                .path(AUDIO_ICON)
+                .size(px(14.))
+                .text_color(theme.muted_foreground),
+        )
+        .child(
+            div()
+                .text_xs()
+                .text_color(theme.foreground)
+                .child(audio.mime_type.clone()),
+        )
+        .child(
+            div()
+                .text_xs()
+                .text_color(theme.muted_foreground)
+                .child(DURATION_PLACEHOLDER),
+        )
+        .into_any_element()
+}
@@ -0,0 +1,122 @@
+//! Author avatars for the message list.
+
+use gpui::{
+    App, Hsla, IntoElement, ParentElement, Pixels, RenderOnce, SharedString, Styled, Window, div,
+    px,
+};
+use gpui_component::{ActiveTheme, Icon};
+
+/// Round avatar for a message author: their icon when there is one, else
+/// the initials of their name on a color picked from the name
+#[derive(IntoElement)]
+pub struct Avatar {
+    name: SharedString,
+    icon: Option<Icon>,
+    /// Overrides the color picked from the name
+    color: Option<Hsla>,
+    size: Pixels,
+}
+
+impl Avatar {
+    pub fn new(name: impl Into<SharedString>) -> Self {
+        Self {
+            name: name.into(),
+            icon: None,
+            color: None,
+            size: px(20.),
+        }
+    }
+
+    pub fn icon(mut self, icon: Option<Icon>) -> Self {
+        self.icon = icon;
+        self
+    }
+
+    pub fn color(mut self, color: Hsla) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    pub fn size(mut self, size: Pixels) -> Self {
+        self.size = size;
+        self
+    }
+}
+
+impl RenderOnce for Avatar {
+    fn render(self, _: &mut Window, cx: &mut App) -> impl IntoElement {
+        let theme = cx.theme();
+        let palette: [Hsla; 6] = [
+            theme.blue,
+            theme.green,
+            theme.magenta,
+            theme.cyan,
+            theme.yellow,
+            theme.red,
+        ];
+        let color = self
+            .color
+            .unwrap_or_else(|| palette[palette_index(&self.name, palette.len())]);
+
+        let avatar = div()
+            .size(self.size)
+            .flex_none()
+            .flex()
+            .items_center()
+            .justify_center()
+            .rounded_full()
+            .bg(color.opacity(0.15))
+            .text_color(color);
+
+        match self.icon {
+            Some(icon) => avatar.child(icon.size(self.size * 0.7)),
+            None => avatar
+                .text_size(self.size * 0.45)
+                .font_weight(gpui::FontWeight::SEMIBOLD)
+                .child(initials(&self.name)),
+        }
+    }
+}
+
+/// Up to two initials from the words of `name` (split on spaces, `-`, `_` and `.`)
+pub fn initials(name: &str) -> String {
+    let initials: String = name
+        .split(|c: char| c.is_whitespace() || matches!(c, '-' | '_' | '.'))
+        .filter_map(|word| word.chars().find(|c| c.is_alphanumeric()))
+        .take(2)
+        .flat_map(char::to_uppercase)
+        .collect();
+    if initials.is_empty() {
+        "?".to_string()
+    } else {
+        initials
+    }
+}
+
+/// Stable pick of one of `len` colors for `name`
+fn palette_index(name: &str, len: usize) -> usize {
+    name.bytes().fold(0usize, |hash, b| {
+        hash.wrapping_mul(31).wrapping_add(b as usize)
+    }) % len
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn initials_come_from_the_first_two_words() {
+        assert_eq!(initials("claude-code"), "CC");
+        assert_eq!(initials("Gemini"), "G");
+        assert_eq!(initials("open ai codex"), "OA");
+        assert_eq!(initials("  __ "), "?");
+        assert_eq!(initials("ünal"), "Ü");
+    }
+
+    #[test]
+    fn palette_index_is_stable() {
+        assert_eq!(palette_index("claude", 6), palette_index("claude", 6));
+        assert!(palette_index("gemini", 6) < 6);
+        assert_eq!(palette_index("", 6), 0);
+    }
+}
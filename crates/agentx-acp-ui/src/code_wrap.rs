@@ -0,0 +1,137 @@
+//! Line wrapping of code-like blocks (diffs, tool output, markdown code
+//! blocks). The host app sets the default as a gpui global; each block can
+//! override it with a toggle, and scrolls horizontally while unwrapped.
+
+use std::ops::Range;
+
+use gpui::{App, ElementId, Entity, Global, SharedString, Window};
+use gpui_component::{
+    Sizable,
+    button::{Button, ButtonVariants},
+};
+
+/// Whether code-like blocks wrap long lines unless toggled per block
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CodeWrap(pub bool);
+
+impl Default for CodeWrap {
+    fn default() -> Self {
+        Self(true)
+    }
+}
+
+impl Global for CodeWrap {}
+
+impl CodeWrap {
+    /// Current default (wrapping if the host never set one)
+    pub fn global(cx: &App) -> bool {
+        cx.try_global::<CodeWrap>().copied().unwrap_or_default().0
+    }
+}
+
+/// The per-block wrap toggle; selected while the block wraps
+pub fn wrap_toggle(id: impl Into<ElementId>, wrapped: bool) -> Button {
+    Button::new(id)
+        .label("Wrap")
+        .ghost()
+        .xsmall()
+        .selected(wrapped)
+}
+
+/// A markdown code block's wrap override, kept across renders under `id`
+#[derive(Clone)]
+pub(crate) struct CodeBlockWrap {
+    id: SharedString,
+    state: Entity<Option<bool>>,
+    pub wrapped: bool,
+}
+
+impl CodeBlockWrap {
+    pub fn new(id: SharedString, window: &mut Window, cx: &mut App) -> Self {
+        let state = window.use_keyed_state(
+            SharedString::from(format!("{}-wrap-state", id)),
+            cx,
+            |_, _| None::<bool>,
+        );
+        let wrapped = state.read(cx).unwrap_or_else(|| CodeWrap::global(cx));
+        Self { id, state, wrapped }
+    }
+
+    pub fn id(&self) -> &SharedString {
+        &self.id
+    }
+
+    /// The toggle flipping this block's wrapping
+    pub fn toggle(&self) -> Button {
+        let state = self.state.clone();
+        let wrapped = self.wrapped;
+        wrap_toggle(SharedString::from(format!("{}-wrap", self.id)), wrapped).on_click(
+            move |_, window, cx| {
+                state.update(cx, |wrap, _| *wrap = Some(!wrapped));
+                window.refresh();
+            },
+        )
+    }
+}
+
+/// `markdown` split into byte ranges of prose and of fenced code blocks (the
+/// `bool`), in order. A fence left open runs to the end, as while streaming.
+pub(crate) fn split_code_blocks(markdown: &str) -> Vec<(Range<usize>, bool)> {
+    let mut segments = Vec::new();
+    let mut start = 0;
+    let mut offset = 0;
+    // The opening fence's character while inside a code block
+    let mut fence: Option<char> = None;
+    for line in markdown.split_inclusive('\n') {
+        let trimmed = line.trim_start_matches(' ');
+        let marker = ["```", "~~~"]
+            .into_iter()
+            .find(|marker| trimmed.starts_with(marker))
+            .filter(|_| line.len() - trimmed.len() <= 3)
+            .and_then(|marker| marker.chars().next());
+        match (fence, marker) {
+            (None, Some(c)) => {
+                if offset > start {
+                    segments.push((start..offset, false));
+                }
+                start = offset;
+                fence = Some(c);
+            }
+            (Some(open), Some(c)) if c == open && trimmed.trim_end().trim_matches(c).is_empty() => {
+                segments.push((start..offset + line.len(), true));
+                start = offset + line.len();
+                fence = None;
+            }
+            _ => {}
+        }
+        offset += line.len();
+    }
+    if offset > start {
+        segments.push((start..offset, fence.is_some()));
+    }
+    segments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fenced_code_blocks_are_split_from_prose() {
+        let text = "Run this:\n```sh\ncargo test\n```\nThen ~~~ is prose.\n~~~\nopen";
+        let segments: Vec<(&str, bool)> = split_code_blocks(text)
+            .into_iter()
+            .map(|(range, code)| (&text[range], code))
+            .collect();
+        assert_eq!(
+            segments,
+            vec![
+                ("Run this:\n", false),
+                ("```sh\ncargo test\n```\n", true),
+                ("Then ~~~ is prose.\n", false),
+                ("~~~\nopen", true),
+            ]
+        );
+        assert_eq!(split_code_blocks("plain"), vec![(0..5, false)]);
+    }
+}
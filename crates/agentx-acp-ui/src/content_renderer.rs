@@ -0,0 +1,367 @@
+//! One place that knows how each kind of ACP content block is shown: its
+//! plain-text form (thoughts, previews) and, for blocks that get more than
+//! the message markdown, an element. Adding a content type means registering
+//! one renderer here.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use agent_client_protocol::{ContentBlock, EmbeddedResourceResource};
+use gpui::{AnyElement, App, ElementId};
+
+use crate::audio_chip::render_audio_chip;
+use crate::resource_link::{ResourceOpenHandler, render_resource_link};
+use crate::unsupported::render_unsupported;
+use crate::utils::{decode_base64_content, truncate_chars};
+
+/// Characters of an embedded text resource kept in its plain-text form
+const RESOURCE_EXCERPT_CHARS: usize = 200;
+
+/// The variant of a `ContentBlock`, which picks its renderer
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ContentKind {
+    Text,
+    Image,
+    Audio,
+    ResourceLink,
+    Resource,
+}
+
+impl ContentKind {
+    /// `None` for variants added to ACP after this list
+    pub fn of(block: &ContentBlock) -> Option<Self> {
+        match block {
+            ContentBlock::Text(_) => Some(Self::Text),
+            ContentBlock::Image(_) => Some(Self::Image),
+            ContentBlock::Audio(_) => Some(Self::Audio),
+            ContentBlock::ResourceLink(_) => Some(Self::ResourceLink),
+            ContentBlock::Resource(_) => Some(Self::Resource),
+            _ => None,
+        }
+    }
+}
+
+/// Shows one kind of content block
+pub trait ContentRenderer: Send + Sync {
+    /// Plain-text form, e.g. `[Image: image/png]`
+    fn text(&self, block: &ContentBlock) -> String;
+
+    /// Element shown in a message; `None` when the block has no visual form
+    /// beyond its text
+    fn render(&self, id: ElementId, block: &ContentBlock, cx: &App) -> Option<AnyElement> {
+        let _ = (id, block, cx);
+        None
+    }
+
+    /// Text shown instead of `block` when its data can't be used, e.g. image
+    /// data that isn't valid base64; `None` when it's fine
+    fn invalid_placeholder(&self, block: &ContentBlock) -> Option<String> {
+        let _ = block;
+        None
+    }
+}
+
+/// Renderers by content kind; `default()` has one for every kind
+#[derive(Clone)]
+pub struct ContentRenderers {
+    renderers: HashMap<ContentKind, Arc<dyn ContentRenderer>>,
+}
+
+impl Default for ContentRenderers {
+    fn default() -> Self {
+        Self {
+            renderers: HashMap::new(),
+        }
+        .register(ContentKind::Text, TextRenderer)
+        .register(ContentKind::Image, MediaRenderer)
+        .register(ContentKind::Audio, MediaRenderer)
+        .register(ContentKind::ResourceLink, ResourceLinkRenderer::default())
+        .register(ContentKind::Resource, EmbeddedResourceRenderer)
+    }
+}
+
+impl ContentRenderers {
+    /// Show blocks of `kind` with `renderer`, replacing the previous one
+    pub fn register(mut self, kind: ContentKind, renderer: impl ContentRenderer + 'static) -> Self {
+        self.renderers.insert(kind, Arc::new(renderer));
+        self
+    }
+
+    /// Open files that resource links point at with `handler`
+    pub fn on_open_resource(self, handler: ResourceOpenHandler) -> Self {
+        self.register(
+            ContentKind::ResourceLink,
+            ResourceLinkRenderer {
+                on_open_file: Some(handler),
+            },
+        )
+    }
+
+    fn renderer(&self, block: &ContentBlock) -> Option<&Arc<dyn ContentRenderer>> {
+        ContentKind::of(block).and_then(|kind| self.renderers.get(&kind))
+    }
+
+    pub fn text(&self, block: &ContentBlock) -> String {
+        match self.renderer(block) {
+            Some(renderer) => renderer.text(block),
+            None => "[Unknown Content]".to_string(),
+        }
+    }
+
+    /// Plain text of a whole message: text blocks run together as they were
+    /// streamed, other blocks go on lines of their own as `[Image: …]` and
+    /// the like
+    pub fn plain_text<'a>(&self, blocks: impl IntoIterator<Item = &'a ContentBlock>) -> String {
+        let mut text = String::new();
+        let mut previous_is_text = true;
+        for block in blocks {
+            let part = self.text(block);
+            if part.is_empty() {
+                continue;
+            }
+            let is_text = matches!(block, ContentBlock::Text(_));
+            if !text.is_empty() && !(is_text && previous_is_text) && !text.ends_with('\n') {
+                text.push('\n');
+            }
+            text.push_str(&part);
+            previous_is_text = is_text;
+        }
+        text
+    }
+
+    /// `block`, or a text block saying it was dropped when its data can't
+    /// be used
+    pub fn validated(&self, block: ContentBlock) -> ContentBlock {
+        match self
+            .renderer(&block)
+            .and_then(|renderer| renderer.invalid_placeholder(&block))
+        {
+            Some(placeholder) => ContentBlock::from(placeholder),
+            None => block,
+        }
+    }
+
+    /// Element for `block`; blocks of kinds this version doesn't know get
+    /// the unsupported badge
+    pub fn render(
+        &self,
+        id: impl Into<ElementId>,
+        block: &ContentBlock,
+        cx: &App,
+    ) -> Option<AnyElement> {
+        match self.renderer(block) {
+            Some(renderer) => renderer.render(id.into(), block, cx),
+            None => Some(render_unsupported(id, serde_json::to_value(block).ok()?)),
+        }
+    }
+}
+
+/// Text blocks; messages render them together as markdown
+struct TextRenderer;
+
+impl ContentRenderer for TextRenderer {
+    fn text(&self, block: &ContentBlock) -> String {
+        match block {
+            ContentBlock::Text(text) => text.text.clone(),
+            _ => String::new(),
+        }
+    }
+}
+
+/// Images and audio, named by their mime type; audio is also shown as a chip
+struct MediaRenderer;
+
+impl ContentRenderer for MediaRenderer {
+    fn text(&self, block: &ContentBlock) -> String {
+        match block {
+            ContentBlock::Image(image) => format!("[Image: {}]", image.mime_type),
+            ContentBlock::Audio(audio) => format!("[Audio: {}]", audio.mime_type),
+            _ => String::new(),
+        }
+    }
+
+    fn render(&self, id: ElementId, block: &ContentBlock, cx: &App) -> Option<AnyElement> {
+        match block {
+            ContentBlock::Audio(audio) => Some(render_audio_chip(id, audio, cx)),
+            _ => None,
+        }
+    }
+
+    fn invalid_placeholder(&self, block: &ContentBlock) -> Option<String> {
+        let (kind, data, mime_type) = match block {
+            ContentBlock::Image(image) => ("image", &image.data, &image.mime_type),
+            ContentBlock::Audio(audio) => ("audio", &audio.data, &audio.mime_type),
+            _ => return None,
+        };
+        let e = decode_base64_content(data).err()?;
+        log::warn!("Dropping {} {}: {:#}", mime_type, kind, e);
+        Some(format!("[invalid {} data]", kind))
+    }
+}
+
+/// Resource links, shown as clickable links
+#[derive(Default)]
+struct ResourceLinkRenderer {
+    on_open_file: Option<ResourceOpenHandler>,
+}
+
+impl ContentRenderer for ResourceLinkRenderer {
+    fn text(&self, block: &ContentBlock) -> String {
+        match block {
+            ContentBlock::ResourceLink(link) => format!("[Resource: {}]", link.name),
+            _ => String::new(),
+        }
+    }
+
+    fn render(&self, id: ElementId, block: &ContentBlock, cx: &App) -> Option<AnyElement> {
+        match block {
+            ContentBlock::ResourceLink(link) => Some(render_resource_link(
+                id,
+                link,
+                self.on_open_file.clone(),
+                cx,
+            )),
+            _ => None,
+        }
+    }
+}
+
+/// Embedded resources: the URI and, for text, an excerpt
+struct EmbeddedResourceRenderer;
+
+impl ContentRenderer for EmbeddedResourceRenderer {
+    fn text(&self, block: &ContentBlock) -> String {
+        let ContentBlock::Resource(resource) = block else {
+            return String::new();
+        };
+        match &resource.resource {
+            EmbeddedResourceResource::TextResourceContents(text_res) => {
+                let (excerpt, truncated) = truncate_chars(&text_res.text, RESOURCE_EXCERPT_CHARS);
+                format!(
+                    "[Resource: {}]\n{}{}",
+                    text_res.uri,
+                    excerpt,
+                    if truncated { "…" } else { "" }
+                )
+            }
+            EmbeddedResourceResource::BlobResourceContents(blob_res) => {
+                format!("[Binary Resource: {}]", blob_res.uri)
+            }
+            _ => "[Unknown Resource]".to_string(),
+        }
+    }
+
+    fn render(&self, id: ElementId, block: &ContentBlock, _: &App) -> Option<AnyElement> {
+        let ContentBlock::Resource(resource) = block else {
+            return None;
+        };
+        match &resource.resource {
+            EmbeddedResourceResource::TextResourceContents(_)
+            | EmbeddedResourceResource::BlobResourceContents(_) => None,
+            unknown => Some(render_unsupported(id, serde_json::to_value(unknown).ok()?)),
+        }
+    }
+
+    fn invalid_placeholder(&self, block: &ContentBlock) -> Option<String> {
+        let ContentBlock::Resource(resource) = block else {
+            return None;
+        };
+        let EmbeddedResourceResource::BlobResourceContents(blob) = &resource.resource else {
+            return None;
+        };
+        let e = decode_base64_content(&blob.blob).err()?;
+        log::warn!("Dropping resource {}: {:#}", blob.uri, e);
+        Some(format!("[invalid resource data: {}]", blob.uri))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use agent_client_protocol::{
+        AudioContent, EmbeddedResource, ImageContent, ResourceLink, TextResourceContents,
+    };
+
+    #[test]
+    fn plain_text_joins_chunks_and_marks_other_blocks() {
+        let renderers = ContentRenderers::default();
+        let blocks = [
+            ContentBlock::from("Here is ".to_string()),
+            ContentBlock::from("the chart:".to_string()),
+            ContentBlock::Image(ImageContent::new("AAEC", "image/png")),
+            ContentBlock::from("Done.".to_string()),
+        ];
+        assert_eq!(
+            renderers.plain_text(&blocks),
+            "Here is the chart:\n[Image: image/png]\nDone."
+        );
+        assert_eq!(renderers.plain_text(&[] as &[ContentBlock]), "");
+    }
+
+    #[test]
+    fn default_text_forms_are_unchanged() {
+        let renderers = ContentRenderers::default();
+        assert_eq!(
+            renderers.text(&ContentBlock::from("hello".to_string())),
+            "hello"
+        );
+        assert_eq!(
+            renderers.text(&ContentBlock::Image(ImageContent::new("AAEC", "image/png"))),
+            "[Image: image/png]"
+        );
+        assert_eq!(
+            renderers.text(&ContentBlock::Audio(AudioContent::new("AAEC", "audio/wav"))),
+            "[Audio: audio/wav]"
+        );
+        assert_eq!(
+            renderers.text(&ContentBlock::ResourceLink(ResourceLink::new(
+                "notes",
+                "file:///notes.md"
+            ))),
+            "[Resource: notes]"
+        );
+    }
+
+    #[test]
+    fn registered_renderers_replace_the_default() {
+        struct Redacted;
+        impl ContentRenderer for Redacted {
+            fn text(&self, _: &ContentBlock) -> String {
+                "[redacted]".to_string()
+            }
+        }
+
+        let renderers = ContentRenderers::default().register(ContentKind::Image, Redacted);
+        let image = ContentBlock::Image(ImageContent::new("AAEC", "image/png"));
+        assert_eq!(renderers.text(&image), "[redacted]");
+        assert_eq!(renderers.text(&ContentBlock::from("hi".to_string())), "hi");
+    }
+
+    #[test]
+    fn blocks_with_undecodable_data_are_replaced_by_text() {
+        let renderers = ContentRenderers::default();
+        let valid = ContentBlock::Image(ImageContent::new("AAEC", "image/png"));
+        assert!(matches!(renderers.validated(valid), ContentBlock::Image(_)));
+        let invalid = ContentBlock::Audio(AudioContent::new("not*base64", "audio/wav"));
+        assert!(matches!(
+            renderers.validated(invalid),
+            ContentBlock::Text(text) if text.text == "[invalid audio data]"
+        ));
+    }
+
+    #[test]
+    fn resource_excerpt_cuts_at_char_boundary() {
+        // 199 ASCII bytes, then multibyte chars straddling byte 200
+        let text = format!("{}é日本語", "a".repeat(199));
+        let block = ContentBlock::Resource(EmbeddedResource::new(
+            EmbeddedResourceResource::TextResourceContents(TextResourceContents::new(
+                text,
+                "file:///notes.md",
+            )),
+        ));
+
+        let extracted = ContentRenderers::default().text(&block);
+        let excerpt = extracted.lines().nth(1).unwrap();
+        assert_eq!(excerpt, format!("{}é…", "a".repeat(199)));
+    }
+}
@@ -0,0 +1,141 @@
+//! Semantic colors for added/deleted lines and counts, derived from the theme.
+
+use gpui::{App, Hsla, Rgba};
+use gpui_component::ActiveTheme;
+
+/// WCAG AA contrast for normal-size text
+pub const MIN_TEXT_CONTRAST: f32 = 4.5;
+
+/// Background tint opacity in light themes
+const LIGHT_BG_OPACITY: f32 = 0.12;
+/// Dark backgrounds need a stronger tint for the shade to read at all
+const DARK_BG_OPACITY: f32 = 0.18;
+
+/// Colors for diff content: tinted line backgrounds and text that keeps
+/// [`MIN_TEXT_CONTRAST`] against those backgrounds
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DiffColors {
+    pub added_bg: Hsla,
+    pub added_fg: Hsla,
+    pub deleted_bg: Hsla,
+    pub deleted_fg: Hsla,
+}
+
+impl DiffColors {
+    /// Resolve from the active theme; diffs render on the `secondary` surface
+    pub fn from_theme(cx: &App) -> Self {
+        let theme = cx.theme();
+        Self::derive(
+            theme.green,
+            theme.red,
+            theme.secondary,
+            theme.mode.is_dark(),
+        )
+    }
+
+    /// Derive from the theme's green/red and the surface the diff sits on
+    pub fn derive(green: Hsla, red: Hsla, surface: Hsla, is_dark: bool) -> Self {
+        let opacity = if is_dark {
+            DARK_BG_OPACITY
+        } else {
+            LIGHT_BG_OPACITY
+        };
+        let added_bg = green.opacity(opacity);
+        let deleted_bg = red.opacity(opacity);
+
+        Self {
+            added_bg,
+            added_fg: ensure_contrast(green, composite(added_bg, surface), MIN_TEXT_CONTRAST),
+            deleted_bg,
+            deleted_fg: ensure_contrast(red, composite(deleted_bg, surface), MIN_TEXT_CONTRAST),
+        }
+    }
+}
+
+/// WCAG contrast ratio between two opaque colors (1.0 to 21.0)
+pub fn contrast_ratio(a: Hsla, b: Hsla) -> f32 {
+    let (a, b) = (relative_luminance(a), relative_luminance(b));
+    let (lighter, darker) = if a > b { (a, b) } else { (b, a) };
+    (lighter + 0.05) / (darker + 0.05)
+}
+
+/// Shift `fg`'s lightness away from `bg` until the pair reaches `min_ratio`,
+/// keeping its hue and saturation
+pub fn ensure_contrast(fg: Hsla, bg: Hsla, min_ratio: f32) -> Hsla {
+    let fg = Hsla { a: 1.0, ..fg };
+    if contrast_ratio(fg, bg) >= min_ratio {
+        return fg;
+    }
+
+    let step = if relative_luminance(bg) > 0.18 {
+        -0.02
+    } else {
+        0.02
+    };
+    let mut adjusted = fg;
+    while (0.0..=1.0).contains(&(adjusted.l + step)) {
+        adjusted.l += step;
+        if contrast_ratio(adjusted, bg) >= min_ratio {
+            break;
+        }
+    }
+    adjusted
+}
+
+/// `top` alpha-blended over an opaque `bottom`
+fn composite(top: Hsla, bottom: Hsla) -> Hsla {
+    let (t, b) = (Rgba::from(top), Rgba::from(bottom));
+    let mix = |top: f32, bottom: f32| top * t.a + bottom * (1.0 - t.a);
+    Rgba {
+        r: mix(t.r, b.r),
+        g: mix(t.g, b.g),
+        b: mix(t.b, b.b),
+        a: 1.0,
+    }
+    .into()
+}
+
+fn relative_luminance(color: Hsla) -> f32 {
+    let rgb = Rgba::from(color);
+    let channel = |c: f32| {
+        if c <= 0.03928 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    0.2126 * channel(rgb.r) + 0.7152 * channel(rgb.g) + 0.0722 * channel(rgb.b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::hsla;
+
+    #[test]
+    fn derived_colors_meet_contrast_in_both_modes() {
+        // Bright, low-contrast greens and reds like many themes ship
+        let green = hsla(142. / 360., 0.7, 0.55, 1.0);
+        let red = hsla(0., 0.75, 0.6, 1.0);
+
+        for (surface, is_dark) in [
+            (hsla(0., 0., 0.96, 1.0), false),
+            (hsla(0., 0., 0.12, 1.0), true),
+        ] {
+            let colors = DiffColors::derive(green, red, surface, is_dark);
+            let added = composite(colors.added_bg, surface);
+            let deleted = composite(colors.deleted_bg, surface);
+            assert!(contrast_ratio(colors.added_fg, added) >= MIN_TEXT_CONTRAST);
+            assert!(contrast_ratio(colors.deleted_fg, deleted) >= MIN_TEXT_CONTRAST);
+            assert_ne!(colors.added_bg, colors.deleted_bg);
+        }
+    }
+
+    #[test]
+    fn contrast_ratio_spans_black_to_white() {
+        let black = hsla(0., 0., 0., 1.0);
+        let white = hsla(0., 0., 1., 1.0);
+        assert!((contrast_ratio(black, white) - 21.0).abs() < 0.01);
+        assert!((contrast_ratio(white, white) - 1.0).abs() < 0.01);
+    }
+}
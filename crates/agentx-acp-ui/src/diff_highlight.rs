@@ -0,0 +1,135 @@
+//! Syntax colors for diff lines. Each side of a diff is highlighted as a whole
+//! file, in the language its path names, and the colors are then split per
+//! line, so a line inside a multi-line string or comment still reads as one.
+
+use std::ops::Range;
+use std::path::Path;
+
+use agent_client_protocol::Diff;
+use gpui::{App, HighlightStyle, StyledText};
+use gpui_component::ActiveTheme;
+use gpui_component::highlighter::{HighlightTheme, Language, LanguageRegistry, SyntaxHighlighter};
+use gpui_component::input::Rope;
+
+use crate::diff_view::DiffLine;
+
+/// Sides larger than this are shown without syntax colors
+const MAX_HIGHLIGHT_BYTES: usize = 512 * 1024;
+
+/// Styled byte ranges of one line
+pub(crate) type LineHighlights = Vec<(Range<usize>, HighlightStyle)>;
+
+/// Syntax colors of both sides of a diff, by line
+#[derive(Default)]
+pub(crate) struct DiffHighlights {
+    old: Vec<LineHighlights>,
+    new: Vec<LineHighlights>,
+}
+
+impl DiffHighlights {
+    /// Highlight `diff` in the language of its path; empty when the path
+    /// names no language the registry knows
+    pub fn new(diff: &Diff, cx: &App) -> Self {
+        let Some(language) = language_for(&diff.path) else {
+            return Self::default();
+        };
+        let theme = &cx.theme().highlight_theme;
+        let side = |text: &str| split_lines(text, &highlight(&language, text, theme));
+        Self {
+            old: diff.old_text.as_deref().map(side).unwrap_or_default(),
+            new: side(&diff.new_text),
+        }
+    }
+
+    /// Colors of `line`; unchanged lines take the side they are shown on
+    pub fn line(&self, line: &DiffLine, old_side: bool) -> &[(Range<usize>, HighlightStyle)] {
+        let (lines, num) = match line {
+            DiffLine::Context { old_num, .. } if old_side => (&self.old, *old_num),
+            DiffLine::Context { new_num, .. } => (&self.new, *new_num),
+            DiffLine::Insert { new_num, .. } => (&self.new, *new_num),
+            DiffLine::Delete { old_num, .. } => (&self.old, *old_num),
+        };
+        num.checked_sub(1)
+            .and_then(|ix| lines.get(ix))
+            .map_or(&[], Vec::as_slice)
+    }
+}
+
+/// `line` with its syntax colors
+pub(crate) fn styled_line(line: &str, highlights: &[(Range<usize>, HighlightStyle)]) -> StyledText {
+    // Shown lines lose a trailing `\r`, which a token may still cover
+    let highlights = highlights.iter().filter_map(|(range, style)| {
+        let range = range.start.min(line.len())..range.end.min(line.len());
+        (!range.is_empty()).then_some((range, *style))
+    });
+    StyledText::new(line.to_string()).with_highlights(highlights)
+}
+
+/// Registered language for `path`: its extension when a language is
+/// registered under that name, otherwise the built-in language for it
+fn language_for(path: &Path) -> Option<String> {
+    let extension = path.extension()?.to_str()?;
+    let registry = LanguageRegistry::singleton();
+    if registry.language(extension).is_some() {
+        return Some(extension.to_string());
+    }
+    let name = Language::from_str(extension).name();
+    (name != "text" && registry.language(name).is_some()).then(|| name.to_string())
+}
+
+/// Styled ranges of all of `text`
+fn highlight(
+    language: &str,
+    text: &str,
+    theme: &HighlightTheme,
+) -> Vec<(Range<usize>, HighlightStyle)> {
+    if text.is_empty() || text.len() > MAX_HIGHLIGHT_BYTES {
+        return Vec::new();
+    }
+    let mut highlighter = SyntaxHighlighter::new(language);
+    highlighter.update(None, &Rope::from_str(text));
+    highlighter.styles(&(0..text.len()), theme)
+}
+
+/// Split styled ranges of `text` into per-line ranges, relative to each line.
+/// Lines are split at `\n` only, as the diff does.
+fn split_lines(text: &str, highlights: &[(Range<usize>, HighlightStyle)]) -> Vec<LineHighlights> {
+    let mut lines = Vec::new();
+    let mut start = 0;
+    for line in text.split('\n') {
+        let end = start + line.len();
+        lines.push(
+            highlights
+                .iter()
+                .filter(|(range, _)| range.start < end && range.end > start)
+                .map(|(range, style)| {
+                    (
+                        range.start.max(start) - start..range.end.min(end) - start,
+                        *style,
+                    )
+                })
+                .collect(),
+        );
+        start = end + 1;
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn highlights_are_split_per_line() {
+        let text = "let s = \"a\nb\";\nfn f() {}";
+        let style = HighlightStyle::default();
+        // A keyword, a string spanning two lines, and another keyword
+        let highlights = vec![(0..3, style), (8..13, style), (15..17, style)];
+
+        let lines: Vec<Vec<Range<usize>>> = split_lines(text, &highlights)
+            .into_iter()
+            .map(|line| line.into_iter().map(|(range, _)| range).collect())
+            .collect();
+        assert_eq!(lines, vec![vec![0..3, 8..10], vec![0..2], vec![0..2]]);
+    }
+}
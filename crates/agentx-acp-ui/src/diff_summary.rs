@@ -1,21 +1,176 @@
-use std::collections::HashMap;
-use std::path::PathBuf;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
-use agent_client_protocol::{Diff, ToolCall, ToolCallContent, ToolCallId, ToolCallStatus};
-use gpui::{App, Context, IntoElement, ParentElement, Render, Styled, Window, div, prelude::*, px};
+use agent_client_protocol::{
+    Diff, SessionUpdate, ToolCall, ToolCallContent, ToolCallId, ToolCallStatus,
+};
+use gpui::{
+    App, ClipboardItem, Context, Entity, IntoElement, ParentElement, Render, SharedString, Styled,
+    Subscription, Window, div, prelude::*, px,
+};
 use gpui_component::{
-    ActiveTheme, Icon, IconName, Sizable,
+    ActiveTheme, Disableable, Icon, IconName, Selectable, Sizable,
     button::{Button, ButtonVariants},
-    h_flex, v_flex,
+    h_flex,
+    input::{Input, InputEvent, InputState},
+    v_flex,
 };
 use similar::{ChangeTag, TextDiff};
 
+use crate::diff_colors::DiffColors;
+use crate::diff_view::{DiffView, DiffViewConfig};
+use crate::tool_call_item::DiffApplyRequested;
+use crate::ui_scale::scaled;
+
 pub type DiffSummaryToolCallHandler = Arc<dyn Fn(ToolCall, &mut Window, &mut App) + Send + Sync>;
+/// Called with the path of a clicked file row
+pub type DiffSummaryFileHandler = Arc<dyn Fn(&PathBuf, &mut Window, &mut App) + Send + Sync>;
+
+/// Batches above this many files ask for confirmation before applying
+pub const CONFIRM_APPLY_ALL_ABOVE: usize = 5;
+/// Width of the additions/deletions bar in file rows, before UI scaling
+const CHANGE_BAR_WIDTH: f32 = 48.;
+/// A deleted and an added file more similar than this (`TextDiff::ratio`) are
+/// shown as one rename
+pub const RENAME_SIMILARITY_THRESHOLD: f32 = 0.8;
+/// Characters of a file checked when telling binary from text
+const BINARY_SNIFF_CHARS: usize = 8000;
+/// Share of non-printable characters above which content counts as binary
+const BINARY_NON_PRINTABLE_RATIO: f32 = 0.1;
+
+/// Asks the host to write every changed file of the session at once
+#[derive(Clone, Debug, PartialEq)]
+pub struct DiffBatchApplyRequested {
+    pub changes: Vec<DiffApplyRequested>,
+}
+
+pub type DiffBatchApplyHandler =
+    Arc<dyn Fn(DiffBatchApplyRequested, &mut Window, &mut App) + Send + Sync>;
+
+/// Result of applying one file of a batch
+#[derive(Debug, Clone, PartialEq)]
+pub enum DiffApplyOutcome {
+    Applied,
+    /// The file changed on disk since the agent produced the diff; left untouched
+    Conflict,
+    Failed(String),
+}
+
+/// Progress of an "Apply all" batch, reported back by the host
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DiffApplyProgress {
+    pub total: usize,
+    pub applied: usize,
+    pub conflicts: Vec<PathBuf>,
+    pub failed: Vec<(PathBuf, String)>,
+}
+
+impl DiffApplyProgress {
+    pub fn new(total: usize) -> Self {
+        Self {
+            total,
+            ..Default::default()
+        }
+    }
+
+    pub fn record(&mut self, path: PathBuf, outcome: DiffApplyOutcome) {
+        match outcome {
+            DiffApplyOutcome::Applied => self.applied += 1,
+            DiffApplyOutcome::Conflict => self.conflicts.push(path),
+            DiffApplyOutcome::Failed(error) => self.failed.push((path, error)),
+        }
+    }
+
+    /// Files processed so far
+    pub fn done(&self) -> usize {
+        self.applied + self.conflicts.len() + self.failed.len()
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.done() >= self.total
+    }
+
+    /// "Applying 3 of 9…" while running, then "Applied 7 of 9; 2 failed"
+    pub fn label(&self) -> String {
+        if !self.is_finished() {
+            return format!("Applying {} of {}…", self.done() + 1, self.total);
+        }
+
+        let mut label = format!("Applied {} of {}", self.applied, self.total);
+        if !self.failed.is_empty() {
+            label.push_str(&format!("; {} failed", self.failed.len()));
+        }
+        if !self.conflicts.is_empty() {
+            label.push_str(&format!("; {} changed on disk", self.conflicts.len()));
+        }
+        label
+    }
+}
 
 #[derive(Clone, Default)]
 pub struct DiffSummaryOptions {
     pub on_open_tool_call: Option<DiffSummaryToolCallHandler>,
+    /// Handles clicks on file rows, e.g. to open the file; takes the place of
+    /// `on_open_tool_call` when both are set
+    pub on_file_click: Option<DiffSummaryFileHandler>,
+    /// Shows an "Apply all N changes" button in the header when set
+    pub on_apply_all: Option<DiffBatchApplyHandler>,
+    /// Whether header totals reflect the active filter instead of the whole session
+    pub totals_follow_filter: bool,
+    /// List files under collapsible headers per top-level directory instead
+    /// of in one flat list
+    pub group_by_dir: bool,
+}
+
+/// Kind of change made to a file, shown as a git-status style letter badge
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ChangeKind {
+    Added,
+    #[default]
+    Modified,
+    Deleted,
+    Renamed,
+}
+
+impl ChangeKind {
+    pub const ALL: [ChangeKind; 4] = [
+        ChangeKind::Added,
+        ChangeKind::Modified,
+        ChangeKind::Deleted,
+        ChangeKind::Renamed,
+    ];
+
+    /// Classify a change from the initial and final file contents
+    pub fn classify(old_text: Option<&str>, new_text: &str) -> Self {
+        match old_text {
+            None => ChangeKind::Added,
+            Some(old) if new_text.is_empty() && !old.is_empty() => ChangeKind::Deleted,
+            Some(_) => ChangeKind::Modified,
+        }
+    }
+
+    /// Single-letter badge label (A/M/D/R)
+    pub fn letter(&self) -> &'static str {
+        match self {
+            ChangeKind::Added => "A",
+            ChangeKind::Modified => "M",
+            ChangeKind::Deleted => "D",
+            ChangeKind::Renamed => "R",
+        }
+    }
+}
+
+/// What the diff summary counts, besides changed lines
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DiffGranularity {
+    /// Changed lines only
+    #[default]
+    Line,
+    /// Changed words too, which tell more for one-line edits
+    Word,
 }
 
 /// Statistics for a single file's changes
@@ -25,12 +180,32 @@ pub struct FileChangeStats {
     pub additions: usize,
     pub deletions: usize,
     pub is_new_file: bool,
+    pub kind: ChangeKind,
+    /// Previous path for renamed files
+    pub renamed_from: Option<PathBuf>,
+    /// Binary content: no line counts, which would be meaningless
+    pub is_binary: bool,
+    /// Words added or removed, counted at `DiffGranularity::Word`
+    pub changed_words: Option<usize>,
 }
 
 impl FileChangeStats {
     /// Calculate statistics from old and new text
     pub fn from_diff(path: PathBuf, old_text: Option<&str>, new_text: &str) -> Self {
+        Self::from_diff_with(path, old_text, new_text, DiffGranularity::Line)
+    }
+
+    /// Calculate statistics from old and new text, also counting changed
+    /// words at `DiffGranularity::Word`
+    pub fn from_diff_with(
+        path: PathBuf,
+        old_text: Option<&str>,
+        new_text: &str,
+        granularity: DiffGranularity,
+    ) -> Self {
+        let is_binary = looks_binary(new_text) || old_text.is_some_and(looks_binary);
         let (additions, deletions, is_new_file) = match old_text {
+            _ if is_binary => (0, 0, old_text.is_none()),
             Some(old) => {
                 let diff = TextDiff::from_lines(old, new_text);
                 let (mut adds, mut dels) = (0, 0);
@@ -45,12 +220,45 @@ impl FileChangeStats {
             }
             None => (new_text.lines().count(), 0, true),
         };
+        let changed_words = (granularity == DiffGranularity::Word && !is_binary)
+            .then(|| count_changed_words(old_text.unwrap_or_default(), new_text));
 
         Self {
             path,
             additions,
             deletions,
             is_new_file,
+            kind: ChangeKind::classify(old_text, new_text),
+            renamed_from: None,
+            is_binary,
+            changed_words,
+        }
+    }
+
+    /// Mark the file as renamed from `old_path`
+    ///
+    /// ACP diffs carry a single path, so renames are only known to callers.
+    pub fn renamed_from(mut self, old_path: PathBuf) -> Self {
+        self.kind = ChangeKind::Renamed;
+        self.renamed_from = Some(old_path);
+        self
+    }
+
+    /// File name shown in the summary; `old → new` for renames, with whole
+    /// paths when the file also moved to another directory
+    pub fn display_name(&self) -> String {
+        let file_name = |path: &Path| {
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or("unknown")
+                .to_string()
+        };
+        match &self.renamed_from {
+            Some(old) if old.parent() == self.path.parent() => {
+                format!("{} → {}", file_name(old), file_name(&self.path))
+            }
+            Some(old) => format!("{} → {}", old.display(), self.path.display()),
+            None => file_name(&self.path),
         }
     }
 
@@ -58,6 +266,90 @@ impl FileChangeStats {
     pub fn total_changes(&self) -> usize {
         self.additions + self.deletions
     }
+
+    /// Widths of the green and red segments of a `width`-wide change bar,
+    /// proportional to additions and deletions. Both are zero without changes.
+    pub fn change_bar_widths(&self, width: f32) -> (f32, f32) {
+        let total = self.total_changes();
+        if total == 0 {
+            return (0., 0.);
+        }
+        let added = width * self.additions as f32 / total as f32;
+        (added, width - added)
+    }
+}
+
+/// Words inserted or deleted between `old` and `new`, not counting whitespace
+fn count_changed_words(old: &str, new: &str) -> usize {
+    TextDiff::from_words(old, new)
+        .iter_all_changes()
+        .filter(|change| change.tag() != ChangeTag::Equal && !change.value().trim().is_empty())
+        .count()
+}
+
+/// Group `files` by the first directory below the one they all share, keeping
+/// their order within each group. Files directly in the shared directory go
+/// under `"."`. Agents report absolute paths, so grouping by the very first
+/// component would put everything under `/`.
+pub fn group_by_dir<'a>(
+    files: impl IntoIterator<Item = &'a FileChangeStats>,
+) -> BTreeMap<String, Vec<&'a FileChangeStats>> {
+    let files: Vec<_> = files.into_iter().collect();
+    let common = common_dir(files.iter().map(|stats| stats.path.as_path()));
+
+    let mut groups: BTreeMap<String, Vec<&FileChangeStats>> = BTreeMap::new();
+    for stats in files {
+        let dir = stats
+            .path
+            .strip_prefix(&common)
+            .ok()
+            .and_then(|relative| {
+                let mut components = relative.components();
+                let first = components.next()?;
+                components
+                    .next()
+                    .is_some()
+                    .then(|| first.as_os_str().to_string_lossy().into_owned())
+            })
+            .unwrap_or_else(|| ".".to_string());
+        groups.entry(dir).or_default().push(stats);
+    }
+    groups
+}
+
+/// Deepest directory containing all of `paths`
+fn common_dir<'a>(paths: impl IntoIterator<Item = &'a Path>) -> PathBuf {
+    let mut common: Option<PathBuf> = None;
+    for path in paths {
+        let dir = path.parent().unwrap_or(Path::new(""));
+        common = Some(match common {
+            None => dir.to_path_buf(),
+            Some(common) => common
+                .components()
+                .zip(dir.components())
+                .take_while(|(a, b)| a == b)
+                .map(|(a, _)| a)
+                .collect(),
+        });
+    }
+    common.unwrap_or_default()
+}
+
+/// Whether `text` holds binary data: it has a NUL, or too many non-printable
+/// characters (lossily decoded bytes included) near its start
+fn looks_binary(text: &str) -> bool {
+    if text.contains('\0') {
+        return true;
+    }
+    let (mut total, mut non_printable) = (0, 0);
+    for c in text.chars().take(BINARY_SNIFF_CHARS) {
+        total += 1;
+        let printable = !c.is_control() || matches!(c, '\n' | '\r' | '\t' | '\x0c');
+        if !printable || c == char::REPLACEMENT_CHARACTER {
+            non_printable += 1;
+        }
+    }
+    total > 0 && non_printable as f32 / total as f32 > BINARY_NON_PRINTABLE_RATIO
 }
 
 /// Summary of all file changes in a session
@@ -69,6 +361,77 @@ pub struct DiffSummaryData {
     pub tool_calls: Vec<ToolCall>,
     /// Merged file states: (initial old_text, final new_text) for multi-edit files
     merged_states: HashMap<PathBuf, (Option<String>, String)>,
+    /// What the file statistics count
+    granularity: DiffGranularity,
+    /// Renames found for the current deleted and added files
+    renames: RenameCache,
+}
+
+/// Renames found for one set of deleted and added files, so rebuilding the
+/// summary after an unrelated update doesn't diff every pair again
+#[derive(Debug, Clone, Default)]
+struct RenameCache {
+    /// Fingerprint of the deleted and added paths and their contents
+    key: Option<u64>,
+    /// (old path, new path) of each rename
+    pairs: Vec<(PathBuf, PathBuf)>,
+}
+
+/// Apply a tool call or tool call update to `tool_calls`, returning the index
+/// of the call it changed
+fn fold_tool_call_update(tool_calls: &mut Vec<ToolCall>, update: &SessionUpdate) -> Option<usize> {
+    match update {
+        SessionUpdate::ToolCall(tool_call) => {
+            match tool_calls
+                .iter()
+                .position(|tc| tc.tool_call_id == tool_call.tool_call_id)
+            {
+                Some(ix) => {
+                    tool_calls[ix] = tool_call.clone();
+                    Some(ix)
+                }
+                None => {
+                    tool_calls.push(tool_call.clone());
+                    Some(tool_calls.len() - 1)
+                }
+            }
+        }
+        SessionUpdate::ToolCallUpdate(tool_call_update) => {
+            if let Some(ix) = tool_calls
+                .iter()
+                .position(|tc| tc.tool_call_id == tool_call_update.tool_call_id)
+            {
+                tool_calls[ix].update(tool_call_update.fields.clone());
+                Some(ix)
+            } else if let Ok(tool_call) = ToolCall::try_from(tool_call_update.clone()) {
+                tool_calls.push(tool_call);
+                Some(tool_calls.len() - 1)
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Paths of the diffs in `tool_call`
+fn diff_paths(tool_call: &ToolCall) -> impl Iterator<Item = PathBuf> + '_ {
+    tool_call
+        .content
+        .iter()
+        .filter_map(|content| match content {
+            ToolCallContent::Diff(diff) => Some(diff.path.clone()),
+            _ => None,
+        })
+}
+
+/// Best `TextDiff::ratio` possible between texts of `a` and `b` lines, when
+/// every line of the shorter one is kept
+fn max_similarity(a: usize, b: usize) -> f32 {
+    if a + b == 0 {
+        return 1.;
+    }
+    2. * a.min(b) as f32 / (a + b) as f32
 }
 
 impl DiffSummaryData {
@@ -111,13 +474,224 @@ impl DiffSummaryData {
             merged_states.insert(path, (first_old, final_new));
         }
 
-        Self {
+        let mut data = Self {
             files,
             tool_calls: tool_calls.to_vec(),
             merged_states,
+            granularity: DiffGranularity::Line,
+            renames: RenameCache::default(),
+        };
+        data.detect_renames();
+        data
+    }
+
+    /// Recount every file at `granularity`
+    pub fn with_granularity(mut self, granularity: DiffGranularity) -> Self {
+        if granularity == self.granularity {
+            return self;
+        }
+        self.granularity = granularity;
+        self.files = self
+            .merged_states
+            .iter()
+            .map(|(path, (old_text, new_text))| {
+                let stats = FileChangeStats::from_diff_with(
+                    path.clone(),
+                    old_text.as_deref(),
+                    new_text,
+                    granularity,
+                );
+                (path.clone(), stats)
+            })
+            .collect();
+        self.detect_renames();
+        self
+    }
+
+    /// Collapse each deleted file and the added file most similar to it into
+    /// one renamed file, when they are similar enough. ACP has no rename, so
+    /// agents move a file by deleting it and writing it elsewhere. The pairs
+    /// are only searched again when the deleted or added files change.
+    pub fn detect_renames(&mut self) {
+        let mut deleted: Vec<PathBuf> = self.paths_of_kind(ChangeKind::Deleted);
+        let mut added: Vec<PathBuf> = self.paths_of_kind(ChangeKind::Added);
+        deleted.sort();
+        added.sort();
+
+        let key = self.rename_key(&deleted, &added);
+        if self.renames.key != Some(key) {
+            self.renames = RenameCache {
+                key: Some(key),
+                pairs: self.find_renames(deleted, added),
+            };
+        }
+
+        for (old_path, new_path) in &self.renames.pairs {
+            let (Some((Some(old_text), _)), Some((_, new_text))) = (
+                self.merged_states.get(old_path),
+                self.merged_states.get(new_path),
+            ) else {
+                continue;
+            };
+            let stats = FileChangeStats::from_diff_with(
+                new_path.clone(),
+                Some(old_text.as_str()),
+                new_text,
+                self.granularity,
+            )
+            .renamed_from(old_path.clone());
+            self.files.remove(old_path);
+            self.files.insert(new_path.clone(), stats);
         }
     }
 
+    fn rename_key(&self, deleted: &[PathBuf], added: &[PathBuf]) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        deleted.len().hash(&mut hasher);
+        for path in deleted.iter().chain(added) {
+            path.hash(&mut hasher);
+            self.merged_states.get(path).hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+
+    /// Pair each deleted file with the most similar added one. Identical
+    /// texts match without a diff, and pairs whose line counts are too far
+    /// apart to reach the threshold are skipped.
+    fn find_renames(
+        &self,
+        deleted: Vec<PathBuf>,
+        mut added: Vec<PathBuf>,
+    ) -> Vec<(PathBuf, PathBuf)> {
+        let mut added_lines: Vec<usize> = added
+            .iter()
+            .map(|path| self.merged_states[path].1.lines().count())
+            .collect();
+        let mut pairs = Vec::new();
+
+        for old_path in deleted {
+            let Some((Some(old_text), _)) = self.merged_states.get(&old_path) else {
+                continue;
+            };
+            let old_lines = old_text.lines().count();
+            let best = added
+                .iter()
+                .zip(&added_lines)
+                .enumerate()
+                .filter_map(|(ix, (new_path, &new_lines))| {
+                    let (_, new_text) = self.merged_states.get(new_path)?;
+                    if new_text == old_text {
+                        return Some((ix, 1.));
+                    }
+                    if max_similarity(old_lines, new_lines) <= RENAME_SIMILARITY_THRESHOLD {
+                        return None;
+                    }
+                    let ratio = TextDiff::from_lines(old_text.as_str(), new_text.as_str()).ratio();
+                    (ratio > RENAME_SIMILARITY_THRESHOLD).then_some((ix, ratio))
+                })
+                .max_by(|a, b| a.1.total_cmp(&b.1));
+            let Some((ix, _)) = best else {
+                continue;
+            };
+
+            added_lines.remove(ix);
+            pairs.push((old_path, added.remove(ix)));
+        }
+        pairs
+    }
+
+    fn paths_of_kind(&self, kind: ChangeKind) -> Vec<PathBuf> {
+        self.files
+            .values()
+            .filter(|stats| stats.kind == kind)
+            .map(|stats| stats.path.clone())
+            .collect()
+    }
+
+    /// Extract diff statistics from a stream of session updates
+    /// Tool call updates are applied to their tool call first, so each file keeps
+    /// the most recent `new_text` seen across the session
+    pub fn from_session_updates(updates: &[SessionUpdate]) -> Self {
+        let mut tool_calls: Vec<ToolCall> = Vec::new();
+        for update in updates {
+            fold_tool_call_update(&mut tool_calls, update);
+        }
+        Self::from_tool_calls(&tool_calls)
+    }
+
+    /// Fold further session updates into the summary, recounting only the
+    /// files whose diffs they change
+    pub fn apply_updates(&mut self, updates: &[SessionUpdate]) {
+        let mut touched: HashSet<PathBuf> = HashSet::new();
+        for update in updates {
+            let content_changed = match update {
+                SessionUpdate::ToolCall(_) => true,
+                SessionUpdate::ToolCallUpdate(update) => update.fields.content.is_some(),
+                _ => continue,
+            };
+            if !content_changed {
+                fold_tool_call_update(&mut self.tool_calls, update);
+                continue;
+            }
+            let id = match update {
+                SessionUpdate::ToolCall(tool_call) => &tool_call.tool_call_id,
+                SessionUpdate::ToolCallUpdate(update) => &update.tool_call_id,
+                _ => continue,
+            };
+            if let Some(existing) = self.tool_calls.iter().find(|tc| &tc.tool_call_id == id) {
+                touched.extend(diff_paths(existing));
+            }
+            if let Some(ix) = fold_tool_call_update(&mut self.tool_calls, update) {
+                touched.extend(diff_paths(&self.tool_calls[ix]));
+            }
+        }
+        if touched.is_empty() {
+            return;
+        }
+
+        // Renamed files are paired again below, from their plain statistics
+        touched.extend(
+            self.renames
+                .pairs
+                .iter()
+                .flat_map(|(old_path, new_path)| [old_path.clone(), new_path.clone()]),
+        );
+        for path in touched {
+            match self.merged_state(&path) {
+                Some((old_text, new_text)) => {
+                    let stats = FileChangeStats::from_diff_with(
+                        path.clone(),
+                        old_text.as_deref(),
+                        &new_text,
+                        self.granularity,
+                    );
+                    self.files.insert(path.clone(), stats);
+                    self.merged_states.insert(path, (old_text, new_text));
+                }
+                None => {
+                    self.files.remove(&path);
+                    self.merged_states.remove(&path);
+                }
+            }
+        }
+        self.detect_renames();
+    }
+
+    /// The first `old_text` and the last `new_text` of every diff of `path`
+    fn merged_state(&self, path: &Path) -> Option<(Option<String>, String)> {
+        let mut diffs = self
+            .tool_calls
+            .iter()
+            .flat_map(|tc| &tc.content)
+            .filter_map(|content| match content {
+                ToolCallContent::Diff(diff) if diff.path == path => Some(diff),
+                _ => None,
+            });
+        let first = diffs.next()?;
+        let last = diffs.last().unwrap_or(first);
+        Some((first.old_text.clone(), last.new_text.clone()))
+    }
+
     /// Find or create a ToolCall for the given file path
     /// For files edited multiple times, returns a synthetic ToolCall with merged diff (initial -> final)
     /// For files edited once, returns the original ToolCall
@@ -161,19 +735,129 @@ impl DiffSummaryData {
         Some(tool_call)
     }
 
+    /// The file's change from its initial to its latest text; a renamed file
+    /// starts from the text at its old path
+    pub fn merged_diff(&self, path: &PathBuf) -> Option<Diff> {
+        let (first_old, final_new) = self.merged_states.get(path)?;
+        let old_text = match self.files.get(path).and_then(|f| f.renamed_from.as_ref()) {
+            Some(old_path) => self.merged_states.get(old_path)?.0.clone(),
+            None => first_old.clone(),
+        };
+        Some(Diff::new(path.clone(), final_new.clone()).old_text(old_text))
+    }
+
+    /// All changes as one patch in unified diff format, files ordered by path.
+    /// Paths are given below the directory all files share, with git's `a/`
+    /// and `b/` prefixes. Binary files and renames get git's extended headers
+    /// in place of line hunks they can't have.
+    pub fn to_unified_diff(&self) -> String {
+        let mut files: Vec<&FileChangeStats> = self.files.values().collect();
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        let root = common_dir(files.iter().flat_map(|stats| {
+            std::iter::once(stats.path.as_path()).chain(stats.renamed_from.as_deref())
+        }));
+        let relative = |path: &Path| {
+            path.strip_prefix(&root)
+                .unwrap_or(path)
+                .to_string_lossy()
+                .into_owned()
+        };
+
+        let mut patch = String::new();
+        for stats in files {
+            let Some(diff) = self.merged_diff(&stats.path) else {
+                continue;
+            };
+            let new_path = relative(&stats.path);
+            let old_path = stats
+                .renamed_from
+                .as_deref()
+                .map_or(new_path.clone(), relative);
+            let old_label = match diff.old_text {
+                Some(_) => format!("a/{}", old_path),
+                None => "/dev/null".to_string(),
+            };
+            let new_label = match stats.kind {
+                ChangeKind::Deleted => "/dev/null".to_string(),
+                _ => format!("b/{}", new_path),
+            };
+
+            patch.push_str(&format!("diff --git a/{} b/{}\n", old_path, new_path));
+            match stats.kind {
+                ChangeKind::Added => patch.push_str("new file mode 100644\n"),
+                ChangeKind::Deleted => patch.push_str("deleted file mode 100644\n"),
+                ChangeKind::Renamed => patch.push_str(&format!(
+                    "rename from {}\nrename to {}\n",
+                    old_path, new_path
+                )),
+                ChangeKind::Modified => {}
+            }
+            if stats.is_binary {
+                patch.push_str(&format!(
+                    "Binary files {} and {} differ\n",
+                    old_label, new_label
+                ));
+                continue;
+            }
+            let old_text = diff.old_text.as_deref().unwrap_or_default();
+            patch.push_str(
+                &TextDiff::from_lines(old_text, diff.new_text.as_str())
+                    .unified_diff()
+                    .context_radius(3)
+                    .header(&old_label, &new_label)
+                    .to_string(),
+            );
+        }
+        patch
+    }
+
+    /// Every changed file as a request from its initial to its latest text,
+    /// ordered by path. Deleted files (including the old path of a rename)
+    /// are removed rather than written empty.
+    pub fn pending_changes(&self) -> Vec<DiffApplyRequested> {
+        let mut changes: Vec<DiffApplyRequested> = self
+            .merged_states
+            .iter()
+            .map(|(path, (old_text, new_text))| {
+                let deleted =
+                    ChangeKind::classify(old_text.as_deref(), new_text) == ChangeKind::Deleted;
+                DiffApplyRequested {
+                    path: path.clone(),
+                    old_text: old_text.clone(),
+                    new_text: (!deleted).then(|| new_text.clone()),
+                }
+            })
+            .collect();
+        changes.sort_by(|a, b| a.path.cmp(&b.path));
+        changes
+    }
+
+    /// Number of [`Self::pending_changes`], without copying their texts
+    pub fn pending_change_count(&self) -> usize {
+        self.merged_states.len()
+    }
+
     /// Get total number of files changed
     pub fn total_files(&self) -> usize {
         self.files.len()
     }
 
-    /// Get total additions across all files
+    /// Get total additions across all text files
     pub fn total_additions(&self) -> usize {
-        self.files.values().map(|f| f.additions).sum()
+        self.files
+            .values()
+            .filter(|f| !f.is_binary)
+            .map(|f| f.additions)
+            .sum()
     }
 
-    /// Get total deletions across all files
+    /// Get total deletions across all text files
     pub fn total_deletions(&self) -> usize {
-        self.files.values().map(|f| f.deletions).sum()
+        self.files
+            .values()
+            .filter(|f| !f.is_binary)
+            .map(|f| f.deletions)
+            .sum()
     }
 
     /// Get files sorted by total changes (descending)
@@ -183,6 +867,26 @@ impl DiffSummaryData {
         files
     }
 
+    /// Get files matching a path substring and change kinds, sorted like `sorted_files`
+    ///
+    /// An empty query matches every path and an empty `kinds` matches every kind.
+    pub fn filtered(&self, query: &str, kinds: &[ChangeKind]) -> Vec<&FileChangeStats> {
+        let query = query.trim().to_lowercase();
+        self.sorted_files()
+            .into_iter()
+            .filter(|stats| kinds.is_empty() || kinds.contains(&stats.kind))
+            .filter(|stats| {
+                query.is_empty() || stats.path.to_string_lossy().to_lowercase().contains(&query)
+            })
+            .collect()
+    }
+
+    /// Files by top-level directory (see [`group_by_dir`]), each group sorted
+    /// like `sorted_files`
+    pub fn grouped_by_dir(&self) -> BTreeMap<String, Vec<&FileChangeStats>> {
+        group_by_dir(self.sorted_files())
+    }
+
     /// Check if there are any changes
     pub fn has_changes(&self) -> bool {
         !self.files.is_empty()
@@ -192,16 +896,43 @@ impl DiffSummaryData {
 /// UI component to display diff summary
 pub struct DiffSummary {
     data: DiffSummaryData,
+    /// What file rows count; applied to data passed to `update_data` too
+    granularity: DiffGranularity,
     collapsed: bool,
+    /// Files whose changes are shown inline under their row
+    expanded_files: HashSet<PathBuf>,
+    /// Directory groups folded away, when grouping by directory
+    collapsed_dirs: HashSet<String>,
     options: DiffSummaryOptions,
+    /// Whether the filter bar is shown
+    show_filter: bool,
+    /// Path filter input, created the first time the filter bar is shown
+    filter_input: Option<Entity<InputState>>,
+    filter_query: String,
+    filter_kinds: Vec<ChangeKind>,
+    /// Waiting for the user to confirm a large "Apply all"
+    confirming_apply_all: bool,
+    /// Progress of the last "Apply all", as reported by the host
+    apply_progress: Option<DiffApplyProgress>,
+    _subscriptions: Vec<Subscription>,
 }
 
 impl DiffSummary {
     pub fn new(data: DiffSummaryData) -> Self {
         Self {
             data,
+            granularity: DiffGranularity::Line,
             collapsed: false,
+            expanded_files: HashSet::new(),
+            collapsed_dirs: HashSet::new(),
             options: DiffSummaryOptions::default(),
+            show_filter: false,
+            filter_input: None,
+            filter_query: String::new(),
+            filter_kinds: Vec::new(),
+            confirming_apply_all: false,
+            apply_progress: None,
+            _subscriptions: Vec::new(),
         }
     }
 
@@ -215,18 +946,324 @@ impl DiffSummary {
         self
     }
 
+    /// Call `handler` with the file's path when a file row is clicked
+    pub fn on_file_click(
+        mut self,
+        handler: impl Fn(&PathBuf, &mut Window, &mut App) + Send + Sync + 'static,
+    ) -> Self {
+        self.options.on_file_click = Some(Arc::new(handler));
+        self
+    }
+
+    pub fn on_apply_all(mut self, handler: DiffBatchApplyHandler) -> Self {
+        self.options.on_apply_all = Some(handler);
+        self
+    }
+
+    /// Count changed words as well as lines, shown under each file name
+    pub fn granularity(mut self, granularity: DiffGranularity) -> Self {
+        self.granularity = granularity;
+        self.data = self.data.with_granularity(granularity);
+        self
+    }
+
+    /// Set the initial collapsed state
+    pub fn collapsed(mut self, collapsed: bool) -> Self {
+        self.collapsed = collapsed;
+        self
+    }
+
+    pub fn totals_follow_filter(mut self, follow: bool) -> Self {
+        self.options.totals_follow_filter = follow;
+        self
+    }
+
+    /// Group files under their top-level directory; `false` keeps the flat list
+    pub fn group_by_dir(mut self, group: bool) -> Self {
+        self.options.group_by_dir = group;
+        self
+    }
+
     /// Toggle collapsed state
     pub fn toggle(&mut self, cx: &mut Context<Self>) {
         self.collapsed = !self.collapsed;
+        if self.collapsed {
+            self.expanded_files.clear();
+        }
         cx.notify();
     }
 
+    /// Put all changes on the clipboard as one unified diff
+    pub fn copy_patch(&mut self, cx: &mut Context<Self>) {
+        cx.write_to_clipboard(ClipboardItem::new_string(self.data.to_unified_diff()));
+    }
+
+    /// Fold or unfold the files of one directory group
+    pub fn toggle_dir(&mut self, dir: String, cx: &mut Context<Self>) {
+        if !self.collapsed_dirs.remove(&dir) {
+            self.collapsed_dirs.insert(dir);
+        }
+        cx.notify();
+    }
+
+    /// Show or hide the changes of one file under its row
+    pub fn toggle_file(&mut self, path: PathBuf, cx: &mut Context<Self>) {
+        if !self.expanded_files.remove(&path) {
+            self.expanded_files.insert(path);
+        }
+        cx.notify();
+    }
+
+    /// Show or hide the filter bar
+    pub fn toggle_filter(&mut self, cx: &mut Context<Self>) {
+        self.show_filter = !self.show_filter;
+        cx.notify();
+    }
+
+    /// Include or exclude a change kind from the filter
+    pub fn toggle_kind(&mut self, kind: ChangeKind, cx: &mut Context<Self>) {
+        if let Some(ix) = self.filter_kinds.iter().position(|k| *k == kind) {
+            self.filter_kinds.remove(ix);
+        } else {
+            self.filter_kinds.push(kind);
+        }
+        cx.notify();
+    }
+
+    /// Clear the path query and kind toggles, restoring the full list
+    pub fn clear_filter(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.filter_query.clear();
+        self.filter_kinds.clear();
+        if let Some(input) = &self.filter_input {
+            input.update(cx, |state, cx| state.set_value("", window, cx));
+        }
+        cx.notify();
+    }
+
+    fn is_filtering(&self) -> bool {
+        !self.filter_query.trim().is_empty() || !self.filter_kinds.is_empty()
+    }
+
+    fn filter_input(&mut self, window: &mut Window, cx: &mut Context<Self>) -> Entity<InputState> {
+        if let Some(input) = &self.filter_input {
+            return input.clone();
+        }
+
+        let input = cx.new(|cx| InputState::new(window, cx).placeholder("Filter by path"));
+        self._subscriptions
+            .push(cx.subscribe(&input, |this, input, event: &InputEvent, cx| {
+                if let InputEvent::Change = event {
+                    this.filter_query = input.read(cx).value().to_string();
+                    cx.notify();
+                }
+            }));
+        self.filter_input = Some(input.clone());
+        input
+    }
+
+    /// Render the path input and change kind chips
+    fn render_filter_bar(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let input = self.filter_input(window, cx);
+        let is_filtering = self.is_filtering();
+
+        h_flex()
+            .w_full()
+            .gap_1()
+            .items_center()
+            .child(div().flex_1().child(Input::new(&input).xsmall()))
+            .children(ChangeKind::ALL.into_iter().map(|kind| {
+                Button::new(("diff-summary-kind", kind as usize))
+                    .label(kind.letter())
+                    .ghost()
+                    .xsmall()
+                    .selected(self.filter_kinds.contains(&kind))
+                    .on_click(cx.listener(move |this, _ev, _window, cx| {
+                        this.toggle_kind(kind, cx);
+                    }))
+            }))
+            .when(is_filtering, |this| {
+                this.child(
+                    Button::new("diff-summary-clear-filter")
+                        .icon(IconName::Close)
+                        .ghost()
+                        .xsmall()
+                        .on_click(cx.listener(|this, _ev, window, cx| {
+                            this.clear_filter(window, cx);
+                        })),
+                )
+            })
+    }
+
+    /// Current summary data
+    pub fn data(&self) -> &DiffSummaryData {
+        &self.data
+    }
+
     /// Update the summary data
     pub fn update_data(&mut self, data: DiffSummaryData, cx: &mut Context<Self>) {
-        self.data = data;
+        self.data = data.with_granularity(self.granularity);
+        let files = &self.data.files;
+        self.expanded_files.retain(|path| files.contains_key(path));
+        cx.notify();
+    }
+
+    /// Fold new tool call updates into the summary, see
+    /// [`DiffSummaryData::apply_updates`]
+    pub fn apply_updates(&mut self, updates: &[SessionUpdate], cx: &mut Context<Self>) {
+        self.data.apply_updates(updates);
+        let files = &self.data.files;
+        self.expanded_files.retain(|path| files.contains_key(path));
         cx.notify();
     }
 
+    /// Show the progress of an "Apply all" batch
+    pub fn set_apply_progress(&mut self, progress: DiffApplyProgress, cx: &mut Context<Self>) {
+        self.apply_progress = Some(progress);
+        cx.notify();
+    }
+
+    fn is_applying(&self) -> bool {
+        self.apply_progress
+            .as_ref()
+            .is_some_and(|progress| !progress.is_finished())
+    }
+
+    /// Ask the host to apply every changed file, confirming large batches first
+    fn apply_all(&mut self, confirmed: bool, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(handler) = self.options.on_apply_all.clone() else {
+            return;
+        };
+        if self.is_applying() {
+            return;
+        }
+
+        let changes = self.data.pending_changes();
+        if changes.len() > CONFIRM_APPLY_ALL_ABOVE && !confirmed {
+            self.confirming_apply_all = true;
+            cx.notify();
+            return;
+        }
+
+        self.confirming_apply_all = false;
+        self.apply_progress = Some(DiffApplyProgress::new(changes.len()));
+        handler(DiffBatchApplyRequested { changes }, window, cx);
+        cx.notify();
+    }
+
+    fn cancel_apply_all(&mut self, cx: &mut Context<Self>) {
+        self.confirming_apply_all = false;
+        cx.notify();
+    }
+
+    /// Render the "Apply all" button, its confirmation, or the batch result
+    fn render_apply_all(&self, cx: &mut Context<Self>) -> Option<gpui::AnyElement> {
+        self.options.on_apply_all.as_ref()?;
+        // What "Apply all" writes: a rename is a removal and a write
+        let total = self.data.pending_change_count();
+
+        if self.confirming_apply_all {
+            return Some(
+                h_flex()
+                    .gap_1()
+                    .items_center()
+                    .child(
+                        div()
+                            .text_size(scaled(12., cx))
+                            .text_color(cx.theme().warning)
+                            .child(format!("Overwrite {} files?", total)),
+                    )
+                    .child(
+                        Button::new("diff-summary-apply-all-confirm")
+                            .label("Apply")
+                            .primary()
+                            .xsmall()
+                            .on_click(cx.listener(|this, _ev, window, cx| {
+                                this.apply_all(true, window, cx);
+                            })),
+                    )
+                    .child(
+                        Button::new("diff-summary-apply-all-cancel")
+                            .label("Cancel")
+                            .ghost()
+                            .xsmall()
+                            .on_click(cx.listener(|this, _ev, _window, cx| {
+                                this.cancel_apply_all(cx);
+                            })),
+                    )
+                    .into_any_element(),
+            );
+        }
+
+        Some(
+            Button::new("diff-summary-apply-all")
+                .label(format!(
+                    "Apply all {} change{}",
+                    total,
+                    if total == 1 { "" } else { "s" }
+                ))
+                .ghost()
+                .xsmall()
+                .disabled(self.is_applying())
+                .on_click(cx.listener(|this, _ev, window, cx| {
+                    this.apply_all(false, window, cx);
+                }))
+                .into_any_element(),
+        )
+    }
+
+    /// Render the batch progress line and any files that were not written
+    fn render_apply_progress(&self, cx: &Context<Self>) -> Option<gpui::AnyElement> {
+        let progress = self.apply_progress.as_ref()?;
+        let has_problems = !progress.failed.is_empty() || !progress.conflicts.is_empty();
+        let color = if !progress.is_finished() {
+            cx.theme().muted_foreground
+        } else if has_problems {
+            cx.theme().warning
+        } else {
+            cx.theme().green
+        };
+        let problem_rows = progress
+            .conflicts
+            .iter()
+            .map(|path| {
+                format!(
+                    "{}: changed on disk since the agent's edit, not overwritten",
+                    path.display()
+                )
+            })
+            .chain(
+                progress
+                    .failed
+                    .iter()
+                    .map(|(path, error)| format!("{}: {}", path.display(), error)),
+            )
+            .map(|text| {
+                div()
+                    .pl_2()
+                    .text_size(scaled(11., cx))
+                    .text_color(cx.theme().muted_foreground)
+                    .child(text)
+            });
+
+        Some(
+            v_flex()
+                .w_full()
+                .gap_0p5()
+                .child(
+                    div()
+                        .text_size(scaled(12., cx))
+                        .text_color(color)
+                        .child(progress.label()),
+                )
+                .children(problem_rows)
+                .into_any_element(),
+        )
+    }
+
     /// Render change statistics (additions/deletions)
     fn render_stats(
         &self,
@@ -234,29 +1271,85 @@ impl DiffSummary {
         deletions: usize,
         cx: &Context<Self>,
     ) -> impl IntoElement {
+        let colors = DiffColors::from_theme(cx);
         h_flex()
             .gap_1()
             .items_center()
             .when(additions > 0, |this| {
                 this.child(
                     div()
-                        .text_size(px(11.))
+                        .text_size(scaled(11., cx))
                         .font_weight(gpui::FontWeight::MEDIUM)
-                        .text_color(cx.theme().green)
+                        .text_color(colors.added_fg)
                         .child(format!("+{}", additions)),
                 )
             })
             .when(deletions > 0, |this| {
                 this.child(
                     div()
-                        .text_size(px(11.))
+                        .text_size(scaled(11., cx))
                         .font_weight(gpui::FontWeight::MEDIUM)
-                        .text_color(cx.theme().red)
+                        .text_color(colors.deleted_fg)
                         .child(format!("-{}", deletions)),
                 )
             })
     }
 
+    /// GitHub-style bar splitting a file's changed lines into additions and deletions
+    fn render_change_bar(&self, stats: &FileChangeStats, cx: &Context<Self>) -> impl IntoElement {
+        let colors = DiffColors::from_theme(cx);
+        let (added, deleted) = stats.change_bar_widths(CHANGE_BAR_WIDTH);
+
+        h_flex()
+            .flex_none()
+            .w(scaled(CHANGE_BAR_WIDTH, cx))
+            .h(px(6.))
+            .rounded(px(3.))
+            .overflow_hidden()
+            .bg(cx.theme().muted)
+            .when(added > 0., |this| {
+                this.child(div().h_full().w(scaled(added, cx)).bg(colors.added_fg))
+            })
+            .when(deleted > 0., |this| {
+                this.child(div().h_full().w(scaled(deleted, cx)).bg(colors.deleted_fg))
+            })
+    }
+
+    /// Shown instead of line counts for binary files
+    fn render_binary_badge(&self, cx: &Context<Self>) -> impl IntoElement {
+        div()
+            .flex_none()
+            .px_1()
+            .rounded(px(3.))
+            .bg(cx.theme().muted)
+            .text_size(scaled(10., cx))
+            .font_weight(gpui::FontWeight::SEMIBOLD)
+            .text_color(cx.theme().muted_foreground)
+            .child("BINARY")
+    }
+
+    /// Render the git-status style badge for a change kind
+    fn render_kind_badge(&self, kind: ChangeKind, cx: &Context<Self>) -> impl IntoElement {
+        let colors = DiffColors::from_theme(cx);
+        let color = match kind {
+            ChangeKind::Added => colors.added_fg,
+            ChangeKind::Modified => cx.theme().yellow,
+            ChangeKind::Deleted => colors.deleted_fg,
+            ChangeKind::Renamed => cx.theme().accent,
+        };
+
+        div()
+            .w(scaled(16., cx))
+            .flex_shrink_0()
+            .text_center()
+            .rounded(px(3.))
+            .bg(color.opacity(0.2))
+            .text_size(scaled(10., cx))
+            .font_weight(gpui::FontWeight::SEMIBOLD)
+            .text_color(color)
+            .child(kind.letter())
+    }
+
     /// Render a single file change row
     fn render_file_row(
         &self,
@@ -264,20 +1357,25 @@ impl DiffSummary {
         _window: &mut Window,
         cx: &Context<Self>,
     ) -> gpui::AnyElement {
-        let filename = stats
-            .path
-            .file_name()
-            .and_then(|n| n.to_str())
-            .unwrap_or("unknown")
-            .to_string();
+        let filename = stats.display_name();
+        let expanded = self.expanded_files.contains(&stats.path);
+        // Binary files have no lines to show
+        let changes = (expanded && !stats.is_binary)
+            .then(|| self.data.merged_diff(&stats.path))
+            .flatten();
 
         let file_path = stats.path.clone();
-        let data = self.data.clone();
-        let handler = self.options.on_open_tool_call.clone();
+        let on_file_click = self.options.on_file_click.clone();
+        let handler = self
+            .options
+            .on_open_tool_call
+            .clone()
+            .filter(|_| on_file_click.is_none());
 
         let tool_call = handler
             .as_ref()
-            .and_then(|_| data.find_tool_call_for_file(&file_path));
+            .and_then(|_| self.data.find_tool_call_for_file(&file_path));
+        let clickable = on_file_click.is_some() || tool_call.is_some();
 
         let row = div().w_full().child(
             h_flex()
@@ -288,47 +1386,165 @@ impl DiffSummary {
                 .py_1()
                 .rounded(px(4.))
                 .hover(|this| this.bg(cx.theme().muted.opacity(0.3)))
-                .when(tool_call.is_some(), |this| this.cursor_pointer())
+                .when(clickable, |this| this.cursor_pointer())
+                .child(self.render_kind_badge(stats.kind, cx))
                 .child(
                     Icon::new(IconName::File)
-                        .size(px(14.))
+                        .size(scaled(14., cx))
                         .text_color(cx.theme().muted_foreground),
                 )
                 .child(
-                    div()
+                    v_flex()
                         .flex_1()
-                        .text_size(px(12.))
-                        .text_color(cx.theme().foreground)
-                        .child(filename),
+                        .child(
+                            div()
+                                .text_size(scaled(12., cx))
+                                .text_color(cx.theme().foreground)
+                                .child(filename),
+                        )
+                        .when_some(stats.changed_words, |this, words| {
+                            this.child(
+                                div()
+                                    .text_size(scaled(11., cx))
+                                    .text_color(cx.theme().muted_foreground)
+                                    .child(format!(
+                                        "±{} word{}",
+                                        words,
+                                        if words == 1 { "" } else { "s" }
+                                    )),
+                            )
+                        }),
                 )
-                .when(stats.is_new_file, |this| {
-                    this.child(
-                        div()
-                            .px_1p5()
-                            .py(px(1.))
-                            .rounded(px(3.))
-                            .bg(cx.theme().green.opacity(0.2))
-                            .text_size(px(10.))
-                            .text_color(cx.theme().green)
-                            .child("NEW"),
-                    )
+                .map(|this| {
+                    if stats.is_binary {
+                        this.child(self.render_binary_badge(cx))
+                    } else {
+                        this.child(self.render_stats(stats.additions, stats.deletions, cx))
+                            .child(self.render_change_bar(stats, cx))
+                    }
                 })
-                .child(self.render_stats(stats.additions, stats.deletions, cx))
-                .child(
-                    Icon::new(IconName::ChevronRight)
-                        .size(px(12.))
-                        .text_color(cx.theme().muted_foreground),
-                ),
+                .when(!stats.is_binary, |this| {
+                    this.child(self.render_file_toggle(&stats.path, expanded, cx))
+                }),
         );
+        let row = match on_file_click {
+            Some(on_file_click) => row
+                .id(SharedString::from(format!(
+                    "diff-summary-file-{}",
+                    file_path.display()
+                )))
+                .on_click(move |_event, window, cx| {
+                    cx.stop_propagation();
+                    on_file_click(&file_path, window, cx);
+                })
+                .into_any_element(),
+            None => match (tool_call, handler) {
+                (Some(tool_call), Some(handler)) => row
+                    .on_mouse_down(gpui::MouseButton::Left, move |_event, window, cx| {
+                        handler(tool_call.clone(), window, cx);
+                    })
+                    .into_any_element(),
+                _ => row.into_any_element(),
+            },
+        };
 
-        if let (Some(tool_call), Some(handler)) = (tool_call, handler) {
-            row.on_mouse_down(gpui::MouseButton::Left, move |_event, window, cx| {
-                handler(tool_call.clone(), window, cx);
+        v_flex()
+            .w_full()
+            .child(row)
+            .when_some(changes, |this, diff| {
+                this.child(div().w_full().pl_6().py_1().child(DiffView::with_config(
+                    diff,
+                    DiffViewConfig {
+                        show_file_header: false,
+                        ..Default::default()
+                    },
+                )))
             })
             .into_any_element()
-        } else {
-            row.into_any_element()
-        }
+    }
+
+    /// Header of a directory group: its name, file count and summed changes
+    fn render_dir_header(
+        &self,
+        dir: &str,
+        files: &[&FileChangeStats],
+        collapsed: bool,
+        cx: &Context<Self>,
+    ) -> gpui::AnyElement {
+        let text_files = files.iter().filter(|f| !f.is_binary);
+        let additions = text_files.clone().map(|f| f.additions).sum();
+        let deletions = text_files.map(|f| f.deletions).sum();
+        let key = dir.to_string();
+
+        h_flex()
+            .id(SharedString::from(format!("diff-summary-dir-{}", dir)))
+            .w_full()
+            .items_center()
+            .gap_2()
+            .px_2()
+            .py_1()
+            .rounded(px(4.))
+            .cursor_pointer()
+            .hover(|this| this.bg(cx.theme().muted.opacity(0.3)))
+            .on_click(cx.listener(move |this, _, _, cx| {
+                this.toggle_dir(key.clone(), cx);
+            }))
+            .child(
+                Icon::new(if collapsed {
+                    IconName::ChevronRight
+                } else {
+                    IconName::ChevronDown
+                })
+                .size(scaled(12., cx))
+                .text_color(cx.theme().muted_foreground),
+            )
+            .child(
+                Icon::new(IconName::Folder)
+                    .size(scaled(14., cx))
+                    .text_color(cx.theme().muted_foreground),
+            )
+            .child(
+                div()
+                    .flex_1()
+                    .text_size(scaled(12., cx))
+                    .font_weight(gpui::FontWeight::MEDIUM)
+                    .text_color(cx.theme().foreground)
+                    .child(format!("{}/ ({})", dir, files.len())),
+            )
+            .child(self.render_stats(additions, deletions, cx))
+            .into_any_element()
+    }
+
+    /// Chevron showing and flipping whether the file's changes are expanded.
+    /// It swallows the press so the row's own handler doesn't also run.
+    fn render_file_toggle(
+        &self,
+        path: &PathBuf,
+        expanded: bool,
+        cx: &Context<Self>,
+    ) -> impl IntoElement {
+        let path = path.clone();
+        div()
+            .id(SharedString::from(format!(
+                "diff-summary-file-toggle-{}",
+                path.display()
+            )))
+            .flex_none()
+            .cursor_pointer()
+            .on_mouse_down(gpui::MouseButton::Left, |_, _, cx| cx.stop_propagation())
+            .on_click(cx.listener(move |this, _, _, cx| {
+                cx.stop_propagation();
+                this.toggle_file(path.clone(), cx);
+            }))
+            .child(
+                Icon::new(if expanded {
+                    IconName::ChevronDown
+                } else {
+                    IconName::ChevronRight
+                })
+                .size(scaled(12., cx))
+                .text_color(cx.theme().muted_foreground),
+            )
     }
 }
 
@@ -338,22 +1554,52 @@ impl Render for DiffSummary {
             return div().into_any_element();
         }
 
-        let total_files = self.data.total_files();
-        let total_additions = self.data.total_additions();
-        let total_deletions = self.data.total_deletions();
         let is_collapsed = self.collapsed;
+        let visible_files: Vec<FileChangeStats> = self
+            .data
+            .filtered(&self.filter_query, &self.filter_kinds)
+            .into_iter()
+            .cloned()
+            .collect();
 
-        // Pre-render all file rows before entering the builder chain
-        let file_rows: Vec<_> = if !is_collapsed {
-            self.data
-                .sorted_files()
-                .into_iter()
-                .map(|stats| self.render_file_row(stats, window, cx))
-                .collect()
+        let (total_files, total_additions, total_deletions) = if self.options.totals_follow_filter {
+            (
+                visible_files.len(),
+                visible_files.iter().map(|f| f.additions).sum(),
+                visible_files.iter().map(|f| f.deletions).sum(),
+            )
         } else {
-            Vec::new()
+            (
+                self.data.total_files(),
+                self.data.total_additions(),
+                self.data.total_deletions(),
+            )
         };
 
+        // Pre-render all file rows before entering the builder chain
+        let mut file_rows = Vec::new();
+        if is_collapsed {
+            // Only the header is shown
+        } else if self.options.group_by_dir {
+            for (dir, files) in group_by_dir(&visible_files) {
+                let dir_collapsed = self.collapsed_dirs.contains(&dir);
+                file_rows.push(self.render_dir_header(&dir, &files, dir_collapsed, cx));
+                if !dir_collapsed {
+                    for stats in files {
+                        file_rows.push(self.render_file_row(stats, window, cx));
+                    }
+                }
+            }
+        } else {
+            for stats in &visible_files {
+                file_rows.push(self.render_file_row(stats, window, cx));
+            }
+        }
+        let filter_bar = (self.show_filter && !is_collapsed)
+            .then(|| self.render_filter_bar(window, cx).into_any_element());
+        let apply_all = self.render_apply_all(cx);
+        let apply_progress = self.render_apply_progress(cx);
+
         v_flex()
             .w_full()
             .gap_1()
@@ -370,13 +1616,13 @@ impl Render for DiffSummary {
                     .gap_2()
                     .child(
                         Icon::new(IconName::Asterisk)
-                            .size(px(14.))
+                            .size(scaled(14., cx))
                             .text_color(cx.theme().accent),
                     )
                     .child(
                         div()
                             .flex_1()
-                            .text_size(px(13.))
+                            .text_size(scaled(13., cx))
                             .font_weight(gpui::FontWeight::SEMIBOLD)
                             .text_color(cx.theme().foreground)
                             .child(format!(
@@ -386,6 +1632,26 @@ impl Render for DiffSummary {
                             )),
                     )
                     .child(self.render_stats(total_additions, total_deletions, cx))
+                    .children(apply_all)
+                    .child(
+                        Button::new("diff-summary-copy-patch")
+                            .label("Copy patch")
+                            .ghost()
+                            .xsmall()
+                            .on_click(cx.listener(|this, _ev, _window, cx| {
+                                this.copy_patch(cx);
+                            })),
+                    )
+                    .child(
+                        Button::new("diff-summary-filter")
+                            .icon(IconName::Search)
+                            .ghost()
+                            .small()
+                            .selected(self.show_filter)
+                            .on_click(cx.listener(|this, _ev, _window, cx| {
+                                this.toggle_filter(cx);
+                            })),
+                    )
                     .child(
                         Button::new("diff-summary-toggle")
                             .icon(if is_collapsed {
@@ -400,6 +1666,8 @@ impl Render for DiffSummary {
                             })),
                     ),
             )
+            .children(apply_progress)
+            .children(filter_bar)
             // File list (only shown when not collapsed)
             .when(!is_collapsed, |this| {
                 this.child(v_flex().w_full().gap_0p5().children(file_rows))
@@ -411,7 +1679,9 @@ impl Render for DiffSummary {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use agent_client_protocol::{ToolCallContent, ToolCallStatus};
+    use agent_client_protocol::{
+        ToolCallContent, ToolCallStatus, ToolCallUpdate, ToolCallUpdateFields,
+    };
 
     #[test]
     fn summary_merges_multi_edit_files() {
@@ -432,4 +1702,420 @@ mod tests {
         assert_eq!(merged.status, ToolCallStatus::Completed);
         assert!(merged.title.contains("file.txt"));
     }
+
+    #[test]
+    fn pending_changes_span_initial_to_latest_text() {
+        let path = PathBuf::from("b.rs");
+        let mut first = ToolCall::new("tc-1", "Edit b.rs");
+        first.content = vec![ToolCallContent::Diff(
+            Diff::new(path.clone(), "two".to_string()).old_text("one".to_string()),
+        )];
+        let mut second = ToolCall::new("tc-2", "Edit b.rs again");
+        second.content = vec![
+            ToolCallContent::Diff(
+                Diff::new(path.clone(), "three".to_string()).old_text("two".to_string()),
+            ),
+            ToolCallContent::Diff(Diff::new("a.rs", "new".to_string())),
+        ];
+
+        let changes = DiffSummaryData::from_tool_calls(&[first, second]).pending_changes();
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].path, PathBuf::from("a.rs"));
+        assert_eq!(changes[0].old_text, None);
+        assert_eq!(changes[1].old_text.as_deref(), Some("one"));
+        assert_eq!(changes[1].new_text.as_deref(), Some("three"));
+    }
+
+    #[test]
+    fn pending_changes_remove_deleted_files() {
+        let mut tool_call = ToolCall::new("tc-1", "Delete old.rs");
+        tool_call.content = vec![ToolCallContent::Diff(
+            Diff::new("old.rs", String::new()).old_text("fn old() {}\n".to_string()),
+        )];
+
+        let data = DiffSummaryData::from_tool_calls(&[tool_call]);
+        let changes = data.pending_changes();
+        assert_eq!(changes.len(), data.pending_change_count());
+        assert_eq!(changes[0].path, PathBuf::from("old.rs"));
+        assert_eq!(changes[0].new_text, None);
+    }
+
+    #[test]
+    fn apply_progress_reports_partial_failures() {
+        let mut progress = DiffApplyProgress::new(9);
+        for ix in 0..7 {
+            progress.record(
+                PathBuf::from(format!("{}.rs", ix)),
+                DiffApplyOutcome::Applied,
+            );
+        }
+        assert_eq!(progress.label(), "Applying 8 of 9…");
+
+        progress.record(
+            PathBuf::from("ro.rs"),
+            DiffApplyOutcome::Failed("permission denied".to_string()),
+        );
+        progress.record(
+            PathBuf::from("gone.rs"),
+            DiffApplyOutcome::Failed("no such file".to_string()),
+        );
+        assert!(progress.is_finished());
+        assert_eq!(progress.label(), "Applied 7 of 9; 2 failed");
+
+        let mut progress = DiffApplyProgress::new(2);
+        progress.record(PathBuf::from("a.rs"), DiffApplyOutcome::Applied);
+        progress.record(PathBuf::from("b.rs"), DiffApplyOutcome::Conflict);
+        assert_eq!(progress.label(), "Applied 1 of 2; 1 changed on disk");
+    }
+
+    #[test]
+    fn classifies_added_file() {
+        let stats = FileChangeStats::from_diff(PathBuf::from("new.rs"), None, "fn main() {}");
+        assert_eq!(stats.kind, ChangeKind::Added);
+        assert!(stats.is_new_file);
+    }
+
+    #[test]
+    fn classifies_modified_file() {
+        let stats = FileChangeStats::from_diff(PathBuf::from("lib.rs"), Some("a\nb"), "a\nc");
+        assert_eq!(stats.kind, ChangeKind::Modified);
+        assert_eq!(stats.kind.letter(), "M");
+    }
+
+    #[test]
+    fn classifies_deleted_file() {
+        let stats = FileChangeStats::from_diff(PathBuf::from("old.rs"), Some("a\nb\nc"), "");
+        assert_eq!(stats.kind, ChangeKind::Deleted);
+        assert_eq!(stats.deletions, 3);
+        assert_eq!(stats.additions, 0);
+    }
+
+    #[test]
+    fn change_bar_splits_by_additions_and_deletions() {
+        let new_file = FileChangeStats::from_diff(PathBuf::from("new.rs"), None, "a\nb\n");
+        assert_eq!(new_file.change_bar_widths(48.), (48., 0.));
+
+        let deleted = FileChangeStats::from_diff(PathBuf::from("old.rs"), Some("a\nb\n"), "");
+        assert_eq!(deleted.change_bar_widths(48.), (0., 48.));
+
+        let modified =
+            FileChangeStats::from_diff(PathBuf::from("lib.rs"), Some("a\nb\nc\n"), "a\nx\n");
+        assert_eq!((modified.additions, modified.deletions), (1, 2));
+        assert_eq!(modified.change_bar_widths(48.), (16., 32.));
+
+        let unchanged = FileChangeStats::from_diff(PathBuf::from("same.rs"), Some("a\n"), "a\n");
+        assert_eq!(unchanged.change_bar_widths(48.), (0., 0.));
+    }
+
+    #[test]
+    fn classifies_renamed_file() {
+        let stats = FileChangeStats::from_diff(PathBuf::from("b.rs"), Some("a"), "a")
+            .renamed_from(PathBuf::from("a.rs"));
+        assert_eq!(stats.kind, ChangeKind::Renamed);
+        assert_eq!(stats.renamed_from, Some(PathBuf::from("a.rs")));
+    }
+
+    #[test]
+    fn multi_edit_deleting_file_is_deleted() {
+        let path = PathBuf::from("gone.txt");
+        let mut edit = ToolCall::new("tc-1", "Edit file");
+        edit.content = vec![ToolCallContent::Diff(
+            Diff::new(path.clone(), "one".to_string()).old_text("one\ntwo".to_string()),
+        )];
+        let mut delete = ToolCall::new("tc-2", "Delete file");
+        delete.content = vec![ToolCallContent::Diff(
+            Diff::new(path.clone(), String::new()).old_text("one".to_string()),
+        )];
+
+        let summary = DiffSummaryData::from_tool_calls(&[edit, delete]);
+        assert_eq!(summary.files[&path].kind, ChangeKind::Deleted);
+    }
+
+    fn stats(path: &str, old_text: Option<&str>, new_text: &str) -> FileChangeStats {
+        FileChangeStats::from_diff(PathBuf::from(path), old_text, new_text)
+    }
+
+    #[test]
+    fn filtered_by_path_and_kind() {
+        let mut data = DiffSummaryData::default();
+        for stats in [
+            stats("src/main.rs", Some("a"), "b"),
+            stats("src/new.rs", None, "fn new() {}"),
+            stats("docs/README.md", None, "# Docs"),
+        ] {
+            data.files.insert(stats.path.clone(), stats);
+        }
+
+        assert_eq!(data.filtered("", &[]).len(), 3);
+        assert_eq!(data.filtered("SRC/", &[]).len(), 2);
+        assert_eq!(data.filtered("", &[ChangeKind::Added]).len(), 2);
+
+        let added_in_src = data.filtered("src/", &[ChangeKind::Added]);
+        assert_eq!(added_in_src.len(), 1);
+        assert_eq!(added_in_src[0].path, PathBuf::from("src/new.rs"));
+    }
+
+    #[test]
+    fn applied_updates_match_a_full_rebuild() {
+        let body: String = (1..=10).map(|n| format!("fn f{}() {{}}\n", n)).collect();
+        let updates = [
+            SessionUpdate::ToolCall(diff_call("tc-1", "a.rs", Some("one\n"), "two\n")),
+            SessionUpdate::ToolCall(diff_call("tc-2", "old.rs", Some(&body), "")),
+            SessionUpdate::ToolCallUpdate(ToolCallUpdate::new(
+                "tc-1",
+                ToolCallUpdateFields::new().status(ToolCallStatus::Completed),
+            )),
+            SessionUpdate::ToolCall(diff_call("tc-3", "new.rs", None, &body)),
+            SessionUpdate::ToolCall(diff_call("tc-4", "a.rs", Some("two\n"), "three\n")),
+        ];
+
+        let mut incremental = DiffSummaryData::default();
+        for batch in updates.chunks(2) {
+            incremental.apply_updates(batch);
+        }
+        let rebuilt = DiffSummaryData::from_session_updates(&updates);
+
+        assert_eq!(incremental.total_files(), rebuilt.total_files());
+        for (path, stats) in &rebuilt.files {
+            let applied = &incremental.files[path];
+            assert_eq!(applied.kind, stats.kind, "{:?}", path);
+            assert_eq!(
+                (applied.additions, applied.deletions),
+                (stats.additions, stats.deletions)
+            );
+            assert_eq!(applied.renamed_from, stats.renamed_from);
+        }
+        assert_eq!(
+            incremental.files[&PathBuf::from("new.rs")].kind,
+            ChangeKind::Renamed
+        );
+        assert_eq!(incremental.tool_calls[0].status, ToolCallStatus::Completed);
+    }
+
+    #[test]
+    fn session_updates_keep_latest_text_per_file() {
+        let path = PathBuf::from("src/lib.rs");
+        let mut tool_call = ToolCall::new("tc-1", "Edit lib.rs");
+        tool_call.content = vec![ToolCallContent::Diff(
+            Diff::new(path.clone(), "a\nb\n".to_string()).old_text("a\n".to_string()),
+        )];
+
+        let update = ToolCallUpdate::new(
+            "tc-1",
+            ToolCallUpdateFields::new()
+                .status(ToolCallStatus::Completed)
+                .content(vec![ToolCallContent::Diff(
+                    Diff::new(path.clone(), "a\nb\nc\n".to_string()).old_text("a\n".to_string()),
+                )]),
+        );
+
+        let mut second_edit = ToolCall::new("tc-2", "Edit lib.rs again");
+        second_edit.content = vec![ToolCallContent::Diff(
+            Diff::new(path.clone(), "a\nb\nc\nd\n".to_string()).old_text("a\nb\nc\n".to_string()),
+        )];
+
+        let summary = DiffSummaryData::from_session_updates(&[
+            SessionUpdate::ToolCall(tool_call),
+            SessionUpdate::ToolCallUpdate(update),
+            SessionUpdate::ToolCall(second_edit),
+        ]);
+
+        assert_eq!(summary.total_files(), 1);
+        assert_eq!(summary.files[&path].additions, 3);
+        assert_eq!(summary.files[&path].deletions, 0);
+
+        let merged = summary.find_tool_call_for_file(&path).unwrap();
+        let ToolCallContent::Diff(diff) = &merged.content[0] else {
+            panic!("expected a diff");
+        };
+        assert_eq!(diff.new_text, "a\nb\nc\nd\n");
+    }
+
+    fn diff_call(id: &str, path: &str, old_text: Option<&str>, new_text: &str) -> ToolCall {
+        let mut tool_call = ToolCall::new(id, format!("Edit {}", path));
+        tool_call.content = vec![ToolCallContent::Diff(
+            Diff::new(PathBuf::from(path), new_text.to_string())
+                .old_text(old_text.map(str::to_string)),
+        )];
+        tool_call
+    }
+
+    #[test]
+    fn similar_delete_and_add_become_a_rename() {
+        let body: String = (1..=10).map(|n| format!("fn f{}() {{}}\n", n)).collect();
+        let moved = body.replace("fn f10()", "fn last()");
+        let summary = DiffSummaryData::from_tool_calls(&[
+            diff_call("tc-1", "src/util.rs", Some(&body), ""),
+            diff_call("tc-2", "src/helpers.rs", None, &moved),
+            diff_call("tc-3", "src/other.rs", None, "unrelated\n"),
+        ]);
+
+        assert_eq!(summary.total_files(), 2);
+        let renamed = &summary.files[&PathBuf::from("src/helpers.rs")];
+        assert_eq!(renamed.kind, ChangeKind::Renamed);
+        assert_eq!(renamed.renamed_from, Some(PathBuf::from("src/util.rs")));
+        assert_eq!((renamed.additions, renamed.deletions), (1, 1));
+        assert_eq!(renamed.display_name(), "util.rs → helpers.rs");
+        let diff = summary
+            .merged_diff(&PathBuf::from("src/helpers.rs"))
+            .unwrap();
+        assert_eq!(diff.old_text.as_deref(), Some(body.as_str()));
+        assert_eq!(diff.new_text, moved);
+        assert_eq!(
+            summary.files[&PathBuf::from("src/other.rs")].kind,
+            ChangeKind::Added
+        );
+    }
+
+    #[test]
+    fn applying_a_rename_removes_the_old_path_and_creates_the_new_one() {
+        let body: String = (1..=10).map(|n| format!("fn f{}() {{}}\n", n)).collect();
+        let summary = DiffSummaryData::from_tool_calls(&[
+            diff_call("tc-1", "src/util.rs", Some(&body), ""),
+            diff_call("tc-2", "src/helpers.rs", None, &body),
+        ]);
+        assert_eq!(
+            summary.files[&PathBuf::from("src/helpers.rs")].kind,
+            ChangeKind::Renamed
+        );
+
+        let changes = summary.pending_changes();
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].path, PathBuf::from("src/helpers.rs"));
+        assert_eq!(changes[0].old_text, None);
+        assert_eq!(changes[0].new_text.as_deref(), Some(body.as_str()));
+        assert_eq!(changes[1].path, PathBuf::from("src/util.rs"));
+        assert_eq!(changes[1].new_text, None);
+    }
+
+    #[test]
+    fn dissimilar_delete_and_add_stay_apart() {
+        let summary = DiffSummaryData::from_tool_calls(&[
+            diff_call("tc-1", "a.rs", Some("one\ntwo\nthree\n"), ""),
+            diff_call("tc-2", "b.rs", None, "four\nfive\nsix\n"),
+        ]);
+
+        assert_eq!(
+            summary.files[&PathBuf::from("a.rs")].kind,
+            ChangeKind::Deleted
+        );
+        assert_eq!(
+            summary.files[&PathBuf::from("b.rs")].kind,
+            ChangeKind::Added
+        );
+    }
+
+    #[test]
+    fn moved_files_show_both_paths() {
+        let stats = FileChangeStats::from_diff(PathBuf::from("src/b.rs"), Some("a"), "a")
+            .renamed_from(PathBuf::from("lib/a.rs"));
+        assert_eq!(stats.display_name(), "lib/a.rs → src/b.rs");
+    }
+
+    #[test]
+    fn binary_files_have_no_line_counts() {
+        let png = "\u{89}PNG\r\n\u{1a}\n\0\0\0\rIHDR";
+        let stats = FileChangeStats::from_diff(PathBuf::from("logo.png"), None, png);
+        assert!(stats.is_binary);
+        assert_eq!((stats.additions, stats.deletions), (0, 0));
+
+        let lossy = "\u{fffd}\u{fffd}x\u{fffd}\u{1}\u{fffd}";
+        assert!(looks_binary(lossy));
+        assert!(!looks_binary("fn main() {\n\tprintln!(\"é\");\r\n}\n"));
+
+        let mut data = DiffSummaryData::default();
+        for stats in [stats, self::stats("src/lib.rs", Some("a\n"), "b\n")] {
+            data.files.insert(stats.path.clone(), stats);
+        }
+        assert_eq!((data.total_additions(), data.total_deletions()), (1, 1));
+    }
+
+    #[test]
+    fn word_granularity_counts_changed_words() {
+        let old = "let timeout = Duration::from_secs(30);\n";
+        let new = "let timeout = Duration::from_secs(60);\n";
+        let lines = FileChangeStats::from_diff(PathBuf::from("a.rs"), Some(old), new);
+        assert_eq!((lines.additions, lines.deletions), (1, 1));
+        assert_eq!(lines.changed_words, None);
+
+        let words = FileChangeStats::from_diff_with(
+            PathBuf::from("a.rs"),
+            Some(old),
+            new,
+            DiffGranularity::Word,
+        );
+        assert_eq!((words.additions, words.deletions), (1, 1));
+        // The one changed token, removed and added
+        assert_eq!(words.changed_words, Some(2));
+
+        let summary =
+            DiffSummaryData::from_tool_calls(&[diff_call("tc-1", "a.rs", Some(old), new)])
+                .with_granularity(DiffGranularity::Word);
+        assert_eq!(
+            summary.files[&PathBuf::from("a.rs")].changed_words,
+            words.changed_words
+        );
+    }
+
+    #[test]
+    fn files_group_below_their_shared_directory() {
+        let summary = DiffSummaryData::from_tool_calls(&[
+            diff_call("tc-1", "/repo/src/a.rs", Some("a"), "b"),
+            diff_call("tc-2", "/repo/src/b.rs", Some("a"), "b\nc\nd"),
+            diff_call("tc-3", "/repo/tests/t.rs", None, "t"),
+            diff_call("tc-4", "/repo/Cargo.toml", Some("x"), "y"),
+        ]);
+        let groups: Vec<(String, Vec<&str>)> = summary
+            .grouped_by_dir()
+            .into_iter()
+            .map(|(dir, files)| {
+                let names = files
+                    .iter()
+                    .map(|f| f.path.file_name().unwrap().to_str().unwrap())
+                    .collect();
+                (dir, names)
+            })
+            .collect();
+        // Largest change first within a group
+        assert_eq!(
+            groups,
+            vec![
+                (".".to_string(), vec!["Cargo.toml"]),
+                ("src".to_string(), vec!["b.rs", "a.rs"]),
+                ("tests".to_string(), vec!["t.rs"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn unified_diff_covers_every_kind_of_change() {
+        let body: String = (1..=10).map(|n| format!("fn f{}() {{}}\n", n)).collect();
+        let summary = DiffSummaryData::from_tool_calls(&[
+            diff_call("tc-1", "/repo/src/lib.rs", Some("a\nb\n"), "a\nc\n"),
+            diff_call("tc-2", "/repo/src/new.rs", None, "x\n"),
+            diff_call("tc-3", "/repo/old.rs", Some(&body), ""),
+            diff_call("tc-4", "/repo/src/moved.rs", None, &body),
+            diff_call(
+                "tc-5",
+                "/repo/logo.png",
+                Some("\u{89}PNG\0"),
+                "\u{89}PNG\0\0",
+            ),
+        ]);
+        let patch = summary.to_unified_diff();
+
+        assert!(patch.contains(
+            "diff --git a/src/lib.rs b/src/lib.rs\n--- a/src/lib.rs\n+++ b/src/lib.rs\n@@ -1,2 +1,2 @@\n a\n-b\n+c\n"
+        ));
+        assert!(patch.contains(
+            "new file mode 100644\n--- /dev/null\n+++ b/src/new.rs\n@@ -0,0 +1 @@\n+x\n"
+        ));
+        assert!(patch.contains(
+            "diff --git a/old.rs b/src/moved.rs\nrename from old.rs\nrename to src/moved.rs\n"
+        ));
+        assert!(!patch.contains("+fn f1()"));
+        assert!(patch.contains("Binary files a/logo.png and b/logo.png differ\n"));
+        // Files in path order
+        assert!(patch.find("logo.png") < patch.find("src/lib.rs"));
+    }
 }
@@ -1,11 +1,26 @@
+use std::collections::HashSet;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
 use std::path::PathBuf;
+use std::rc::Rc;
 
 use agent_client_protocol::Diff;
 use gpui::prelude::FluentBuilder;
-use gpui::{AnyElement, App, IntoElement, ParentElement, RenderOnce, Styled, Window, div, px};
-use gpui_component::{ActiveTheme, Icon, IconName, h_flex, v_flex};
+use gpui::{
+    AnyElement, App, Div, Entity, Global, InteractiveElement, IntoElement, ParentElement,
+    RenderOnce, SharedString, StatefulInteractiveElement, Styled, Window, div, px,
+};
+use gpui_component::{
+    ActiveTheme, Icon, IconName, Sizable,
+    button::{Button, ButtonVariants},
+    h_flex, v_flex,
+};
 use similar::{ChangeTag, TextDiff};
 
+use crate::code_wrap::{CodeWrap, wrap_toggle};
+use crate::diff_colors::DiffColors;
+use crate::diff_highlight::{DiffHighlights, styled_line};
+
 /// Represents a single line in a diff view
 #[derive(Debug, Clone)]
 pub enum DiffLine {
@@ -34,6 +49,98 @@ pub enum DiffDisplayItem {
     },
 }
 
+/// One row of the split layout: old side on the left, new side on the right.
+/// Unchanged lines appear on both sides; `None` pads the side without a
+/// counterpart for an insertion or deletion.
+#[derive(Debug, Clone)]
+pub enum SplitRow {
+    Pair {
+        old: Option<DiffLine>,
+        new: Option<DiffLine>,
+    },
+    Collapsed {
+        start_old: usize,
+        start_new: usize,
+        count: usize,
+    },
+}
+
+/// How diffs are laid out, set as a gpui global by the host app
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DiffViewMode {
+    /// Deletions and insertions interleaved in a single column
+    #[default]
+    Unified,
+    /// Old text on the left, new text on the right
+    Split,
+}
+
+impl Global for DiffViewMode {}
+
+impl DiffViewMode {
+    /// Current mode (unified if the host never set one)
+    pub fn global(cx: &App) -> Self {
+        cx.try_global::<DiffViewMode>().copied().unwrap_or_default()
+    }
+
+    pub fn toggled(self) -> Self {
+        match self {
+            Self::Unified => Self::Split,
+            Self::Split => Self::Unified,
+        }
+    }
+}
+
+/// Pair display items into split rows.
+///
+/// Within each run of changes, the i-th deleted line sits next to the i-th
+/// inserted line and the shorter side is padded, so the unchanged lines
+/// around the run stay aligned across both columns.
+pub fn split_rows(items: &[DiffDisplayItem]) -> Vec<SplitRow> {
+    fn flush(rows: &mut Vec<SplitRow>, deletes: &mut Vec<DiffLine>, inserts: &mut Vec<DiffLine>) {
+        let len = deletes.len().max(inserts.len());
+        let mut deletes = deletes.drain(..);
+        let mut inserts = inserts.drain(..);
+        for _ in 0..len {
+            rows.push(SplitRow::Pair {
+                old: deletes.next(),
+                new: inserts.next(),
+            });
+        }
+    }
+
+    let mut rows = Vec::with_capacity(items.len());
+    let mut deletes = Vec::new();
+    let mut inserts = Vec::new();
+    for item in items {
+        match item {
+            DiffDisplayItem::Line(line @ DiffLine::Delete { .. }) => deletes.push(line.clone()),
+            DiffDisplayItem::Line(line @ DiffLine::Insert { .. }) => inserts.push(line.clone()),
+            DiffDisplayItem::Line(line @ DiffLine::Context { .. }) => {
+                flush(&mut rows, &mut deletes, &mut inserts);
+                rows.push(SplitRow::Pair {
+                    old: Some(line.clone()),
+                    new: Some(line.clone()),
+                });
+            }
+            DiffDisplayItem::Collapsed {
+                start_old,
+                start_new,
+                count,
+            } => {
+                flush(&mut rows, &mut deletes, &mut inserts);
+                rows.push(SplitRow::Collapsed {
+                    start_old: *start_old,
+                    start_new: *start_new,
+                    count: *count,
+                });
+            }
+        }
+    }
+    flush(&mut rows, &mut deletes, &mut inserts);
+    rows
+}
+
 /// Configuration for DiffView rendering
 #[derive(Debug, Clone)]
 pub struct DiffViewConfig {
@@ -61,24 +168,68 @@ impl Default for DiffViewConfig {
     }
 }
 
+/// Called with the first old line number of the collapsed section to reveal
+pub type DiffExpandHandler = Rc<dyn Fn(usize, &mut Window, &mut App)>;
+
+/// Hash of a diff's path and texts, standing in for them in cache keys
+fn diff_content_hash(diff: &Diff) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    diff.path.hash(&mut hasher);
+    diff.old_text.hash(&mut hasher);
+    diff.new_text.hash(&mut hasher);
+    hasher.finish()
+}
+
 /// A reusable diff view component that displays file diffs with syntax highlighting
 pub struct DiffView {
-    diff: Diff,
+    diff: Rc<Diff>,
+    /// Keys the state kept across renders; the diff's content when unset
+    id: Option<SharedString>,
     config: DiffViewConfig,
+    /// Collapsed sections (by first old line number) shown in full
+    expanded: HashSet<usize>,
+    on_expand: Option<DiffExpandHandler>,
+    /// Whether unified lines wrap; resolved on render from this view's toggle
+    /// or the `CodeWrap` default
+    wrap: bool,
+    wrap_state: Option<Entity<Option<bool>>>,
+    /// Syntax colors of the lines; resolved on render
+    highlights: Rc<DiffHighlights>,
 }
 
 impl DiffView {
     /// Create a new DiffView with default configuration
-    pub fn new(diff: Diff) -> Self {
+    pub fn new(diff: impl Into<Rc<Diff>>) -> Self {
+        Self::with_config(diff, DiffViewConfig::default())
+    }
+
+    /// Create a new DiffView with custom configuration
+    pub fn with_config(diff: impl Into<Rc<Diff>>, config: DiffViewConfig) -> Self {
         Self {
-            diff,
-            config: DiffViewConfig::default(),
+            diff: diff.into(),
+            id: None,
+            config,
+            expanded: HashSet::new(),
+            on_expand: None,
+            wrap: true,
+            wrap_state: None,
+            highlights: Rc::default(),
         }
     }
 
-    /// Create a new DiffView with custom configuration
-    pub fn with_config(diff: Diff, config: DiffViewConfig) -> Self {
-        Self { diff, config }
+    /// Identify this diff across renders, e.g. by its tool call and content
+    /// index, so its cached state follows it when the text changes
+    pub fn id(mut self, id: impl Into<SharedString>) -> Self {
+        self.id = Some(id.into());
+        self
+    }
+
+    /// Key of this view's state across renders: its id, or its path when it
+    /// has none
+    fn state_key(&self) -> SharedString {
+        self.id
+            .clone()
+            .unwrap_or_else(|| self.diff.path.display().to_string().into())
     }
 
     /// Set maximum number of lines to display
@@ -111,6 +262,40 @@ impl DiffView {
         self
     }
 
+    /// Show these collapsed sections (by first old line number) in full
+    pub fn expanded_sections(mut self, sections: impl IntoIterator<Item = usize>) -> Self {
+        self.expanded = sections.into_iter().collect();
+        self
+    }
+
+    /// Make collapsed sections clickable; the owner records the section and
+    /// passes it back through [`Self::expanded_sections`]
+    pub fn on_expand(mut self, handler: impl Fn(usize, &mut Window, &mut App) + 'static) -> Self {
+        self.on_expand = Some(Rc::new(handler));
+        self
+    }
+
+    /// Push a run of unchanged lines as a collapsed placeholder, or as the
+    /// lines themselves when that section has been expanded
+    fn push_collapsed(&self, display_items: &mut Vec<DiffDisplayItem>, hidden: &[DiffLine]) {
+        let Some(DiffLine::Context {
+            old_num, new_num, ..
+        }) = hidden.first()
+        else {
+            return;
+        };
+
+        if self.expanded.contains(old_num) {
+            display_items.extend(hidden.iter().cloned().map(DiffDisplayItem::Line));
+        } else {
+            display_items.push(DiffDisplayItem::Collapsed {
+                start_old: *old_num,
+                start_new: *new_num,
+                count: hidden.len(),
+            });
+        }
+    }
+
     /// Compute line-by-line diff using similar crate
     fn compute_diff(&self, old_text: &str, new_text: &str) -> Vec<DiffLine> {
         let diff = TextDiff::from_lines(old_text, new_text);
@@ -183,18 +368,10 @@ impl DiffView {
 
                                 // Collapse the middle
                                 let collapsed_count = distance - context_lines * 2;
-                                if collapsed_count > 0 {
-                                    if let DiffLine::Context {
-                                        old_num, new_num, ..
-                                    } = &context_buffer[context_lines]
-                                    {
-                                        display_items.push(DiffDisplayItem::Collapsed {
-                                            start_old: *old_num,
-                                            start_new: *new_num,
-                                            count: collapsed_count,
-                                        });
-                                    }
-                                }
+                                self.push_collapsed(
+                                    &mut display_items,
+                                    &context_buffer[context_lines..context_lines + collapsed_count],
+                                );
 
                                 // Show context_lines before current change
                                 let start = context_buffer.len().saturating_sub(context_lines);
@@ -213,16 +390,10 @@ impl DiffView {
                                 // Collapse leading context, only show last context_lines
                                 if self.config.show_edge_collapsed {
                                     let collapsed_count = context_buffer.len() - context_lines;
-                                    if let DiffLine::Context {
-                                        old_num, new_num, ..
-                                    } = &context_buffer[0]
-                                    {
-                                        display_items.push(DiffDisplayItem::Collapsed {
-                                            start_old: *old_num,
-                                            start_new: *new_num,
-                                            count: collapsed_count,
-                                        });
-                                    }
+                                    self.push_collapsed(
+                                        &mut display_items,
+                                        &context_buffer[..collapsed_count],
+                                    );
                                 }
 
                                 let start = context_buffer.len() - context_lines;
@@ -255,17 +426,7 @@ impl DiffView {
                     display_items.push(DiffDisplayItem::Line(ctx.clone()));
                 }
                 if self.config.show_edge_collapsed {
-                    let collapsed_count = context_buffer.len() - context_lines;
-                    if let DiffLine::Context {
-                        old_num, new_num, ..
-                    } = &context_buffer[context_lines]
-                    {
-                        display_items.push(DiffDisplayItem::Collapsed {
-                            start_old: *old_num,
-                            start_new: *new_num,
-                            count: collapsed_count,
-                        });
-                    }
+                    self.push_collapsed(&mut display_items, &context_buffer[context_lines..]);
                 }
             } else {
                 // Show all trailing context
@@ -278,6 +439,63 @@ impl DiffView {
         display_items
     }
 
+    /// A unified diff line; unwrapped lines grow past the view to scroll
+    fn line_row(&self) -> Div {
+        if self.wrap {
+            h_flex().w_full()
+        } else {
+            h_flex().min_w_full()
+        }
+    }
+
+    /// A line's code, wrapped or kept on one line
+    fn code_cell(&self) -> Div {
+        if self.wrap {
+            div().flex_1().px_2()
+        } else {
+            div().flex_none().px_2().whitespace_nowrap()
+        }
+    }
+
+    /// Syntax colors for this diff, kept per id until its text changes. The
+    /// texts are only hashed when the view gets a diff it didn't render last.
+    fn cached_highlights(&self, window: &mut Window, cx: &mut App) -> Rc<DiffHighlights> {
+        let mut hash = None;
+        let key = match &self.id {
+            Some(id) => format!("diff-highlights-{}", id),
+            None => format!(
+                "diff-highlights-{:x}",
+                *hash.insert(diff_content_hash(&self.diff))
+            ),
+        };
+        let cache = window.use_keyed_state(SharedString::from(key), cx, |_, _| {
+            None::<(Rc<Diff>, u64, Rc<DiffHighlights>)>
+        });
+
+        let mut content_hash = || *hash.get_or_insert_with(|| diff_content_hash(&self.diff));
+        let cached = cache
+            .read(cx)
+            .as_ref()
+            .and_then(|(diff, cached_hash, highlights)| {
+                if Rc::ptr_eq(diff, &self.diff) {
+                    return Some((highlights.clone(), None));
+                }
+                let hash = content_hash();
+                (*cached_hash == hash).then(|| (highlights.clone(), Some(hash)))
+            });
+        let (highlights, hash) = match cached {
+            Some((highlights, None)) => return highlights,
+            Some((highlights, Some(hash))) => (highlights, hash),
+            None => (Rc::new(DiffHighlights::new(&self.diff, cx)), content_hash()),
+        };
+        // Remember this diff, so the next render can compare by pointer
+        let diff = self.diff.clone();
+        cache.update(cx, |cache, _| {
+            *cache = Some((diff, hash, highlights.clone()))
+        });
+        highlights
+    }
+
     /// Render a single diff line
     fn render_diff_line<'a>(
         &self,
@@ -285,13 +503,14 @@ impl DiffView {
         _window: &'a mut Window,
         cx: &'a mut App,
     ) -> impl IntoElement + 'a {
+        let colors = DiffColors::from_theme(cx);
         match diff_line {
             DiffLine::Context {
                 line,
                 old_num,
                 new_num,
-            } => h_flex()
-                .w_full()
+            } => self
+                .line_row()
                 .font_family("Monaco, 'Courier New', monospace")
                 .text_size(px(12.))
                 .line_height(px(18.))
@@ -305,17 +524,15 @@ impl DiffView {
                 )
                 .child(
                     // Code content
-                    div()
-                        .flex_1()
-                        .px_2()
+                    self.code_cell()
                         .text_color(cx.theme().foreground)
-                        .child(line.clone()),
+                        .child(styled_line(line, self.highlights.line(diff_line, false))),
                 ),
-            DiffLine::Insert { line, new_num } => h_flex()
-                .w_full()
-                .bg(cx.theme().green.opacity(0.1))
+            DiffLine::Insert { line, new_num } => self
+                .line_row()
+                .bg(colors.added_bg)
                 .border_l_2()
-                .border_color(cx.theme().green)
+                .border_color(colors.added_fg)
                 .font_family("Monaco, 'Courier New', monospace")
                 .text_size(px(12.))
                 .line_height(px(18.))
@@ -323,21 +540,19 @@ impl DiffView {
                     div()
                         .min_w(px(70.))
                         .px_2()
-                        .text_color(cx.theme().green)
+                        .text_color(colors.added_fg)
                         .child(format!("     {:>4} +", new_num)),
                 )
                 .child(
-                    div()
-                        .flex_1()
-                        .px_2()
-                        .text_color(cx.theme().green)
-                        .child(line.clone()),
+                    self.code_cell()
+                        .text_color(colors.added_fg)
+                        .child(styled_line(line, self.highlights.line(diff_line, false))),
                 ),
-            DiffLine::Delete { line, old_num } => h_flex()
-                .w_full()
-                .bg(cx.theme().red.opacity(0.1))
+            DiffLine::Delete { line, old_num } => self
+                .line_row()
+                .bg(colors.deleted_bg)
                 .border_l_2()
-                .border_color(cx.theme().red)
+                .border_color(colors.deleted_fg)
                 .font_family("Monaco, 'Courier New', monospace")
                 .text_size(px(12.))
                 .line_height(px(18.))
@@ -345,15 +560,13 @@ impl DiffView {
                     div()
                         .min_w(px(70.))
                         .px_2()
-                        .text_color(cx.theme().red)
+                        .text_color(colors.deleted_fg)
                         .child(format!("{:>4}      -", old_num)),
                 )
                 .child(
-                    div()
-                        .flex_1()
-                        .px_2()
-                        .text_color(cx.theme().red)
-                        .child(line.clone()),
+                    self.code_cell()
+                        .text_color(colors.deleted_fg)
+                        .child(styled_line(line, self.highlights.line(diff_line, true))),
                 ),
         }
     }
@@ -367,26 +580,49 @@ impl DiffView {
         _window: &'a mut Window,
         cx: &'a mut App,
     ) -> impl IntoElement + 'a {
+        let id = SharedString::from(format!(
+            "diff-expand-{}-{}",
+            self.diff.path.display(),
+            start_old
+        ));
+        let label = if self.on_expand.is_some() {
+            format!(
+                "Expand {} unchanged lines ({}..{}, {}..{})",
+                count,
+                start_old,
+                start_old + count - 1,
+                start_new,
+                start_new + count - 1
+            )
+        } else {
+            format!(
+                "... {} unchanged lines hidden ({}..{}, {}..{}) ...",
+                count,
+                start_old,
+                start_old + count - 1,
+                start_new,
+                start_new + count - 1
+            )
+        };
+
         h_flex()
+            .id(id)
             .w_full()
             .items_center()
             .justify_center()
+            .gap_1()
             .bg(cx.theme().muted.opacity(0.3))
             .border_y_1()
             .border_color(cx.theme().border)
-            .child(
-                div()
-                    .text_size(px(11.))
-                    .text_color(cx.theme().muted_foreground)
-                    .child(format!(
-                        "... {} unchanged lines hidden ({}..{}, {}..{}) ...",
-                        count,
-                        start_old,
-                        start_old + count - 1,
-                        start_new,
-                        start_new + count - 1
-                    )),
-            )
+            .text_size(px(11.))
+            .text_color(cx.theme().muted_foreground)
+            .when_some(self.on_expand.clone(), |this, on_expand| {
+                this.cursor_pointer()
+                    .hover(|style| style.bg(cx.theme().muted))
+                    .child(Icon::new(IconName::ChevronsUpDown).size(px(12.)))
+                    .on_click(move |_, window, cx| on_expand(start_old, window, cx))
+            })
+            .child(label)
     }
 
     /// Render a diff display item (either a line or a collapsed section)
@@ -410,14 +646,131 @@ impl DiffView {
         }
     }
 
+    /// Render one side of a split row; `old_side` picks the line number shown
+    /// for unchanged lines
+    fn render_split_cell(&self, line: Option<&DiffLine>, old_side: bool, cx: &App) -> AnyElement {
+        let cell = h_flex()
+            .w_1_2()
+            .min_w_0()
+            .overflow_hidden()
+            .self_stretch()
+            .font_family("Monaco, 'Courier New', monospace")
+            .text_size(px(12.))
+            .line_height(px(18.));
+
+        let colors = DiffColors::from_theme(cx);
+        // Changed lines carry their tint; unchanged ones use the plain surface
+        let Some(diff_line) = line else {
+            return cell.bg(cx.theme().muted.opacity(0.3)).into_any_element();
+        };
+        let highlights = self.highlights.line(diff_line, old_side);
+        let (num, text, color, tint) = match diff_line {
+            DiffLine::Context {
+                line,
+                old_num,
+                new_num,
+            } => (
+                if old_side { *old_num } else { *new_num },
+                line,
+                cx.theme().foreground,
+                None,
+            ),
+            DiffLine::Delete { line, old_num } => {
+                (*old_num, line, colors.deleted_fg, Some(colors.deleted_bg))
+            }
+            DiffLine::Insert { line, new_num } => {
+                (*new_num, line, colors.added_fg, Some(colors.added_bg))
+            }
+        };
+
+        cell.when_some(tint, |this, tint| {
+            this.bg(tint).border_l_2().border_color(color)
+        })
+        .child(
+            div()
+                .min_w(px(40.))
+                .px_2()
+                .text_color(if tint.is_some() {
+                    color
+                } else {
+                    cx.theme().muted_foreground
+                })
+                .child(format!("{:>4}", num)),
+        )
+        .child(
+            div()
+                .flex_1()
+                .px_2()
+                .text_color(color)
+                .child(styled_line(text, highlights)),
+        )
+        .into_any_element()
+    }
+
+    /// Render a split row; both sides share the row, so they scroll together
+    fn render_split_row(&self, row: &SplitRow, window: &mut Window, cx: &mut App) -> AnyElement {
+        match row {
+            SplitRow::Pair { old, new } => h_flex()
+                .w_full()
+                .items_start()
+                .child(self.render_split_cell(old.as_ref(), true, cx))
+                .child(div().w(px(1.)).self_stretch().bg(cx.theme().border))
+                .child(self.render_split_cell(new.as_ref(), false, cx))
+                .into_any_element(),
+            SplitRow::Collapsed {
+                start_old,
+                start_new,
+                count,
+            } => self
+                .render_collapsed_section(*start_old, *start_new, *count, window, cx)
+                .into_any_element(),
+        }
+    }
+
+    /// Render the unified/split toggle
+    fn render_mode_toggle(&self, mode: DiffViewMode) -> impl IntoElement {
+        let id = SharedString::from(format!("diff-mode-{}", self.diff.path.display()));
+        Button::new(id)
+            .label("Split")
+            .ghost()
+            .xsmall()
+            .selected(mode == DiffViewMode::Split)
+            .on_click(move |_, _window, cx| {
+                cx.set_global(mode.toggled());
+                cx.refresh_windows();
+            })
+    }
+
+    /// Render the wrap toggle (unified layout only) and the unified/split toggle
+    fn render_toggles(&self, mode: DiffViewMode) -> impl IntoElement {
+        let wrap_state = self
+            .wrap_state
+            .clone()
+            .filter(|_| mode == DiffViewMode::Unified);
+        let wrapped = self.wrap;
+        let id = SharedString::from(format!("diff-wrap-{}", self.state_key()));
+
+        h_flex()
+            .gap_1()
+            .when_some(wrap_state, |this, state| {
+                this.child(wrap_toggle(id, wrapped).on_click(move |_, window, cx| {
+                    state.update(cx, |wrap, _| *wrap = Some(!wrapped));
+                    window.refresh();
+                }))
+            })
+            .child(self.render_mode_toggle(mode))
+    }
+
     /// Render file header
     fn render_file_header<'a>(
         &self,
         path: &'a PathBuf,
         is_new_file: bool,
+        mode: DiffViewMode,
         _window: &'a mut Window,
         cx: &'a mut App,
     ) -> impl IntoElement + 'a {
+        let colors = DiffColors::from_theme(cx);
         h_flex()
             .items_center()
             .gap_2()
@@ -442,12 +795,14 @@ impl DiffView {
                         .px_2()
                         .py(px(2.))
                         .rounded(px(4.))
-                        .bg(cx.theme().green.opacity(0.2))
+                        .bg(colors.added_bg)
                         .text_size(px(11.))
-                        .text_color(cx.theme().green)
+                        .text_color(colors.added_fg)
                         .child("NEW FILE"),
                 )
             })
+            .child(div().flex_1())
+            .child(self.render_toggles(mode))
     }
 
     /// Render truncation warning
@@ -472,7 +827,19 @@ impl DiffView {
 }
 
 impl RenderOnce for DiffView {
-    fn render(self, window: &mut Window, cx: &mut App) -> impl IntoElement {
+    fn render(mut self, window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let wrap_state = window.use_keyed_state(
+            SharedString::from(format!("diff-wrap-state-{}", self.state_key())),
+            cx,
+            |_, _| None::<bool>,
+        );
+        let mode = DiffViewMode::global(cx);
+        // Split columns are half width and always wrap
+        self.wrap = mode == DiffViewMode::Split
+            || wrap_state.read(cx).unwrap_or_else(|| CodeWrap::global(cx));
+        self.wrap_state = Some(wrap_state);
+        self.highlights = self.cached_highlights(window, cx);
+
         // Compute diff
         let diff_lines = match &self.diff.old_text {
             Some(old_text) => {
@@ -502,14 +869,30 @@ impl RenderOnce for DiffView {
         let total_lines = display_items.len();
         let truncated = total_lines > self.config.max_lines;
         let is_new_file = self.diff.old_text.is_none();
+        let shown = &display_items[..total_lines.min(self.config.max_lines)];
+        let rows: Vec<AnyElement> = match mode {
+            DiffViewMode::Unified => shown
+                .iter()
+                .map(|item| self.render_diff_display_item(item, window, cx))
+                .collect(),
+            DiffViewMode::Split => split_rows(shown)
+                .iter()
+                .map(|row| self.render_split_row(row, window, cx))
+                .collect(),
+        };
 
         v_flex()
             .w_full()
             .gap_2()
             // File header
             .when(self.config.show_file_header, |this| {
-                this.child(self.render_file_header(&self.diff.path, is_new_file, window, cx))
+                this.child(self.render_file_header(&self.diff.path, is_new_file, mode, window, cx))
             })
+            // Without a header the toggle gets a row of its own
+            .when(
+                !self.config.show_file_header && !display_items.is_empty(),
+                |this| this.child(h_flex().justify_end().child(self.render_toggles(mode))),
+            )
             // Large file warning
             .when(truncated && self.config.show_truncation_warning, |this| {
                 this.child(self.render_truncation_warning(
@@ -522,13 +905,29 @@ impl RenderOnce for DiffView {
             // Diff content
             .child(
                 div()
+                    .id(SharedString::from(format!(
+                        "diff-content-{}",
+                        self.diff.path.display()
+                    )))
                     .w_full()
                     .rounded(cx.theme().radius)
                     .bg(cx.theme().secondary)
-                    .overflow_hidden()
+                    .map(|this| {
+                        if self.wrap {
+                            this.overflow_hidden()
+                        } else {
+                            this.overflow_x_scroll()
+                        }
+                    })
                     .child(
                         v_flex()
-                            .w_full()
+                            .map(|this| {
+                                if self.wrap {
+                                    this.w_full()
+                                } else {
+                                    this.min_w_full()
+                                }
+                            })
                             .when(display_items.is_empty(), |this| {
                                 this.child(
                                     div()
@@ -540,12 +939,7 @@ impl RenderOnce for DiffView {
                                         .child("No changes"),
                                 )
                             })
-                            .children(
-                                display_items
-                                    .iter()
-                                    .take(self.config.max_lines)
-                                    .map(|item| self.render_diff_display_item(item, window, cx)),
-                            ),
+                            .children(rows),
                     ),
             )
     }
@@ -563,6 +957,88 @@ mod tests {
         assert!(matches!(lines.last(), Some(DiffLine::Insert { .. })));
     }
 
+    #[test]
+    fn expanding_the_gap_merges_adjacent_hunks() {
+        let old_text: String = (1..=20).map(|n| format!("l{}\n", n)).collect();
+        let new_text = old_text
+            .replace("l3\n", "changed3\n")
+            .replace("l15\n", "changed15\n");
+        let diff = Diff::new("file.txt", new_text.clone()).old_text(old_text.clone());
+
+        let view = DiffView::new(diff.clone()).context_lines(1);
+        let lines = view.compute_diff(&old_text, &new_text);
+        let items = view.apply_context_collapsing(lines.clone());
+        let gap = items
+            .iter()
+            .find_map(|item| match item {
+                DiffDisplayItem::Collapsed {
+                    start_old, count, ..
+                } => Some((*start_old, *count)),
+                _ => None,
+            })
+            .expect("gap between hunks is collapsed");
+        assert_eq!(gap, (5, 9));
+
+        let view = DiffView::new(diff)
+            .context_lines(1)
+            .expanded_sections([gap.0]);
+        let items = view.apply_context_collapsing(lines);
+        let old_nums: Vec<usize> = items
+            .iter()
+            .filter_map(|item| match item {
+                DiffDisplayItem::Line(DiffLine::Context { old_num, .. })
+                | DiffDisplayItem::Line(DiffLine::Delete { old_num, .. }) => Some(*old_num),
+                DiffDisplayItem::Line(DiffLine::Insert { .. }) => None,
+                DiffDisplayItem::Collapsed { .. } => panic!("gap stayed collapsed"),
+            })
+            .collect();
+        assert_eq!(old_nums, (2..=16).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn split_rows_pad_uneven_changes() {
+        let old_text = "a\nb\nc\nd\n";
+        let new_text = "a\nB1\nB2\nB3\nd\ne\n";
+        let view = DiffView::new(Diff::new("file.txt", new_text.to_string()));
+        let items: Vec<DiffDisplayItem> = view
+            .compute_diff(old_text, new_text)
+            .into_iter()
+            .map(DiffDisplayItem::Line)
+            .collect();
+
+        let sides: Vec<(Option<usize>, Option<usize>)> =
+            split_rows(&items)
+                .iter()
+                .map(|row| match row {
+                    SplitRow::Pair { old, new } => (
+                        old.as_ref().map(|line| match line {
+                            DiffLine::Context { old_num, .. }
+                            | DiffLine::Delete { old_num, .. } => *old_num,
+                            DiffLine::Insert { .. } => panic!("insertion on the old side"),
+                        }),
+                        new.as_ref().map(|line| match line {
+                            DiffLine::Context { new_num, .. }
+                            | DiffLine::Insert { new_num, .. } => *new_num,
+                            DiffLine::Delete { .. } => panic!("deletion on the new side"),
+                        }),
+                    ),
+                    SplitRow::Collapsed { .. } => panic!("nothing to collapse"),
+                })
+                .collect();
+
+        assert_eq!(
+            sides,
+            [
+                (Some(1), Some(1)),
+                (Some(2), Some(2)),
+                (Some(3), Some(3)),
+                (None, Some(4)),
+                (Some(4), Some(5)),
+                (None, Some(6)),
+            ]
+        );
+    }
+
     #[test]
     fn apply_context_collapsing_shows_collapsed_items() {
         let diff = Diff::new("file.txt", "a\nb\nc\nd\ne\nf".to_string())
@@ -0,0 +1,133 @@
+//! Failed agent requests shown inline in the conversation, with a button to
+//! send the prompt again.
+
+use std::sync::Arc;
+
+use gpui::{
+    App, ClickEvent, ElementId, IntoElement, ParentElement, RenderOnce, SharedString, Styled,
+    Window, div, prelude::FluentBuilder as _, px,
+};
+use gpui_component::{
+    ActiveTheme, Icon, IconName, Sizable,
+    button::{Button, ButtonVariants},
+    h_flex, v_flex,
+};
+
+/// Called when the user asks to send the failed prompt again
+pub type ErrorRetryHandler = Arc<dyn Fn(&mut Window, &mut App) + Send + Sync>;
+
+/// Where the request broke down; decides the styling
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// The agent could not be reached
+    Transport,
+    /// The agent answered with an error
+    Agent,
+}
+
+impl ErrorKind {
+    pub fn title(self) -> &'static str {
+        match self {
+            Self::Transport => "Couldn't reach the agent",
+            Self::Agent => "The agent reported an error",
+        }
+    }
+
+    fn icon(self) -> IconName {
+        match self {
+            Self::Transport => IconName::CircleX,
+            Self::Agent => IconName::TriangleAlert,
+        }
+    }
+}
+
+/// Red-tinted block with the error and, until used, a retry button
+#[derive(IntoElement)]
+pub struct ErrorMessage {
+    id: ElementId,
+    kind: ErrorKind,
+    message: SharedString,
+    on_retry: Option<Box<dyn Fn(&ClickEvent, &mut Window, &mut App) + 'static>>,
+}
+
+impl ErrorMessage {
+    pub fn new(
+        id: impl Into<ElementId>,
+        kind: ErrorKind,
+        message: impl Into<SharedString>,
+    ) -> Self {
+        Self {
+            id: id.into(),
+            kind,
+            message: message.into(),
+            on_retry: None,
+        }
+    }
+
+    /// Show the retry button
+    pub fn on_retry(
+        mut self,
+        handler: impl Fn(&ClickEvent, &mut Window, &mut App) + 'static,
+    ) -> Self {
+        self.on_retry = Some(Box::new(handler));
+        self
+    }
+}
+
+impl RenderOnce for ErrorMessage {
+    fn render(self, _: &mut Window, cx: &mut App) -> impl IntoElement {
+        let theme = cx.theme();
+        let red = theme.red;
+        // A dead connection is the louder of the two: stronger tint and border
+        let (tint, border) = match self.kind {
+            ErrorKind::Transport => (0.12, red.opacity(0.5)),
+            ErrorKind::Agent => (0.06, theme.border),
+        };
+
+        h_flex()
+            .w_full()
+            .gap_3()
+            .items_start()
+            .p_3()
+            .rounded(theme.radius)
+            .bg(red.opacity(tint))
+            .border_1()
+            .border_color(border)
+            .child(
+                Icon::new(self.kind.icon())
+                    .size(px(16.))
+                    .flex_none()
+                    .text_color(red),
+            )
+            .child(
+                v_flex()
+                    .flex_1()
+                    .min_w_0()
+                    .gap_1()
+                    .child(
+                        div()
+                            .text_sm()
+                            .font_weight(gpui::FontWeight::MEDIUM)
+                            .text_color(red)
+                            .child(self.kind.title()),
+                    )
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(theme.muted_foreground)
+                            .child(self.message),
+                    ),
+            )
+            .when_some(self.on_retry, |this, on_retry| {
+                this.child(
+                    Button::new(self.id)
+                        .icon(Icon::new(IconName::Replace))
+                        .label("Retry")
+                        .outline()
+                        .xsmall()
+                        .flex_none()
+                        .on_click(on_retry),
+                )
+            })
+    }
+}
@@ -0,0 +1,283 @@
+//! Tool output too large to keep in memory (multi-MB file reads, long logs).
+//! The text is written to a temporary file and only a preview stays in the
+//! tool call until the user asks for the rest.
+
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+use agent_client_protocol::{SessionUpdate, ToolCallContent};
+use gpui::{BackgroundExecutor, Task};
+
+use crate::utils::truncate_chars;
+
+/// Thresholds below this are raised to it, so a preview never needs offloading itself
+pub const MIN_OFFLOAD_BYTES: usize = 64 * 1024;
+/// Characters of an offloaded text kept in memory
+const PREVIEW_CHARS: usize = 2000;
+
+static NEXT_FILE_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Text kept on disk, with its preview in memory. The file is removed on drop.
+pub struct LargeContent {
+    path: PathBuf,
+    size: usize,
+    /// Hash of the full text, to recognize it once loaded back
+    hash: u64,
+    preview: String,
+    /// The full text was read back and handed to its owner
+    loaded: bool,
+    /// The file being written, until a reader waits for it
+    write: Option<Task<io::Result<()>>>,
+    /// Set on drop, so a write still running removes the file it made
+    dropped: Arc<AtomicBool>,
+}
+
+impl LargeContent {
+    /// Whether `text` exceeds `max_inline_bytes` and should be offloaded (0 never offloads)
+    pub fn should_offload(text: &str, max_inline_bytes: usize) -> bool {
+        max_inline_bytes > 0 && text.len() > max_inline_bytes.max(MIN_OFFLOAD_BYTES)
+    }
+
+    /// Move `text` to a new file in `dir`. The file is written on `executor`,
+    /// so the caller doesn't wait on the disk.
+    pub fn offload(text: String, dir: PathBuf, executor: &BackgroundExecutor) -> Self {
+        let path = dir.join(format!(
+            "{}-{}.txt",
+            std::process::id(),
+            NEXT_FILE_ID.fetch_add(1, Ordering::Relaxed)
+        ));
+        let size = text.len();
+        let hash = hash_text(&text);
+        let preview = truncate_chars(&text, PREVIEW_CHARS).0.to_string();
+        let dropped = Arc::new(AtomicBool::new(false));
+        let write = executor.spawn({
+            let path = path.clone();
+            let dropped = dropped.clone();
+            async move {
+                let result = std::fs::create_dir_all(&dir)
+                    .and_then(|_| std::fs::write(&path, text))
+                    // Dropped while writing: its own removal came too early
+                    .and_then(|_| match dropped.load(Ordering::SeqCst) {
+                        true => std::fs::remove_file(&path),
+                        false => Ok(()),
+                    });
+                if let Err(e) = &result {
+                    log::warn!("Failed to move large tool output to {:?}: {}", path, e);
+                }
+                result
+            }
+        });
+        Self {
+            path,
+            size,
+            hash,
+            preview,
+            loaded: false,
+            write: Some(write),
+            dropped,
+        }
+    }
+
+    /// Directory offloaded tool output is written to
+    pub fn default_dir() -> PathBuf {
+        std::env::temp_dir().join("agentx-tool-output")
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Size of the full text in bytes
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn preview(&self) -> &str {
+        &self.preview
+    }
+
+    pub fn is_loaded(&self) -> bool {
+        self.loaded
+    }
+
+    /// Whether `text` is what this content currently stands for: the preview,
+    /// or the full text once loaded
+    pub fn matches(&self, text: &str) -> bool {
+        if self.loaded {
+            text.len() == self.size && hash_text(text) == self.hash
+        } else {
+            text == self.preview
+        }
+    }
+
+    /// The write of the file, if nobody has waited for it yet. Await it
+    /// before reading the file back.
+    pub fn take_write(&mut self) -> Option<Task<io::Result<()>>> {
+        self.write.take()
+    }
+
+    /// Read the full text from `path` (blocking; run it off the UI thread)
+    pub fn read(path: &Path) -> io::Result<String> {
+        std::fs::read_to_string(path)
+    }
+
+    pub fn mark_loaded(&mut self) {
+        self.loaded = true;
+    }
+}
+
+impl std::fmt::Debug for LargeContent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LargeContent")
+            .field("path", &self.path)
+            .field("size", &self.size)
+            .field("loaded", &self.loaded)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Drop for LargeContent {
+    fn drop(&mut self) {
+        self.dropped.store(true, Ordering::SeqCst);
+        // A write that hasn't finished removes the file itself
+        if let Some(write) = self.write.take() {
+            write.detach();
+        }
+        if let Err(e) = std::fs::remove_file(&self.path) {
+            log::debug!("Failed to remove {:?}: {}", self.path, e);
+        }
+    }
+}
+
+fn hash_text(text: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    text.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Cut the text outputs of a tool call update that [`LargeContent`] would
+/// offload at `max_inline_bytes` down to their preview, for copies of the
+/// update kept after it was shown. Returns whether anything was cut.
+pub fn truncate_large_tool_output(update: &mut SessionUpdate, max_inline_bytes: usize) -> bool {
+    let contents = match update {
+        SessionUpdate::ToolCall(tool_call) => &mut tool_call.content,
+        SessionUpdate::ToolCallUpdate(update) => match &mut update.fields.content {
+            Some(contents) => contents,
+            None => return false,
+        },
+        _ => return false,
+    };
+    let mut truncated = false;
+    for content in contents {
+        let ToolCallContent::Content(c) = content else {
+            continue;
+        };
+        let agent_client_protocol::ContentBlock::Text(text) = &mut c.content else {
+            continue;
+        };
+        if !LargeContent::should_offload(&text.text, max_inline_bytes) {
+            continue;
+        }
+        let (preview, _) = truncate_chars(&text.text, PREVIEW_CHARS);
+        text.text = format!(
+            "{}\n… ({} not kept)",
+            preview,
+            format_size(text.text.len() - preview.len())
+        );
+        truncated = true;
+    }
+    truncated
+}
+
+/// Human-readable byte size, e.g. `3.4 MB`
+pub fn format_size(bytes: usize) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[gpui::test]
+    fn offloaded_text_round_trips_and_is_removed_on_drop(cx: &mut gpui::TestAppContext) {
+        let dir = std::env::temp_dir().join(format!("agentx-large-content-{}", std::process::id()));
+        let text = "line of output\n".repeat(10_000);
+        assert!(LargeContent::should_offload(&text, 1));
+        assert!(!LargeContent::should_offload(&text, 0));
+        assert!(!LargeContent::should_offload(&text, text.len()));
+
+        let mut content = LargeContent::offload(text.clone(), dir.clone(), &cx.executor());
+        let path = content.path().to_path_buf();
+        assert_eq!(content.size(), text.len());
+        assert_eq!(content.preview().chars().count(), PREVIEW_CHARS);
+        assert!(text.starts_with(content.preview()));
+        assert!(content.matches(content.preview()));
+
+        cx.run_until_parked();
+        let full = LargeContent::read(&path).unwrap();
+        assert_eq!(full, text);
+        content.mark_loaded();
+        assert!(content.matches(&full));
+        assert!(!content.matches(content.preview()));
+        // Same length, different text
+        let mut edited = full.clone();
+        edited.replace_range(0..4, "LINE");
+        assert!(!content.matches(&edited));
+
+        drop(content);
+        assert!(!path.exists());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn kept_updates_lose_oversized_output() {
+        let text = |content: &ToolCallContent| match content {
+            ToolCallContent::Content(c) => match &c.content {
+                agent_client_protocol::ContentBlock::Text(text) => text.text.clone(),
+                _ => unreachable!(),
+            },
+            _ => unreachable!(),
+        };
+        let mut tool_call = agent_client_protocol::ToolCall::new("tc-1", "Read file");
+        tool_call.content = vec![
+            ToolCallContent::from(agent_client_protocol::ContentBlock::from(
+                "small".to_string(),
+            )),
+            ToolCallContent::from(agent_client_protocol::ContentBlock::from(
+                "x".repeat(200_000),
+            )),
+        ];
+        let mut update = SessionUpdate::ToolCall(tool_call);
+        assert!(!truncate_large_tool_output(&mut update, 0));
+        assert!(truncate_large_tool_output(&mut update, 100_000));
+
+        let SessionUpdate::ToolCall(tool_call) = &update else {
+            unreachable!()
+        };
+        assert_eq!(text(&tool_call.content[0]), "small");
+        let kept = text(&tool_call.content[1]);
+        assert!(kept.starts_with(&"x".repeat(PREVIEW_CHARS)));
+        assert!(kept.ends_with(&format!("({} not kept)", format_size(198_000))));
+    }
+
+    #[test]
+    fn sizes_are_human_readable() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(2048), "2.0 KB");
+        assert_eq!(format_size(3_565_158), "3.4 MB");
+    }
+}
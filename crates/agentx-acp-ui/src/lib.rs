@@ -1,34 +1,70 @@
 mod agent_message;
 mod agent_thought;
 mod agent_todo_list;
+mod audio_chip;
+mod avatar;
+mod code_wrap;
+mod content_renderer;
+mod diff_colors;
+mod diff_highlight;
 mod diff_summary;
 mod diff_view;
+mod error_message;
+mod large_content;
 mod message_stream;
 mod permission_request;
+mod resource_link;
+mod tool_call_group;
 mod tool_call_item;
+mod ui_scale;
+mod unsupported;
 mod user_message;
 mod utils;
 
 pub use agent_message::{
     AgentIconProvider, AgentMessage, AgentMessageData, AgentMessageMeta, AgentMessageOptions,
-    AgentMessageView,
+    AgentMessageView, MessageStats, message_stats,
 };
 pub use agent_thought::AgentThoughtItem;
-pub use agent_todo_list::{AgentTodoList, AgentTodoListView, PlanMeta};
+pub use agent_todo_list::{AgentTodoList, AgentTodoListView, PlanDelta, PlanEntryChange, PlanMeta};
+pub use audio_chip::render_audio_chip;
+pub use avatar::{Avatar, initials};
+pub use code_wrap::{CodeWrap, wrap_toggle};
+pub use content_renderer::{ContentKind, ContentRenderer, ContentRenderers};
+pub use diff_colors::{DiffColors, MIN_TEXT_CONTRAST, contrast_ratio, ensure_contrast};
 pub use diff_summary::{
-    DiffSummary, DiffSummaryData, DiffSummaryOptions, DiffSummaryToolCallHandler, FileChangeStats,
+    CONFIRM_APPLY_ALL_ABOVE, ChangeKind, DiffApplyOutcome, DiffApplyProgress,
+    DiffBatchApplyHandler, DiffBatchApplyRequested, DiffGranularity, DiffSummary, DiffSummaryData,
+    DiffSummaryFileHandler, DiffSummaryOptions, DiffSummaryToolCallHandler, FileChangeStats,
 };
-pub use diff_view::{DiffDisplayItem, DiffLine, DiffView, DiffViewConfig};
-pub use message_stream::{AcpMessageStream, AcpMessageStreamOptions};
+pub use diff_view::{
+    DiffDisplayItem, DiffExpandHandler, DiffLine, DiffView, DiffViewConfig, DiffViewMode, SplitRow,
+    split_rows,
+};
+pub use error_message::{ErrorKind, ErrorMessage, ErrorRetryHandler};
+pub use large_content::{LargeContent, MIN_OFFLOAD_BYTES, format_size, truncate_large_tool_output};
+pub use message_stream::{AcpMessageStream, AcpMessageStreamOptions, mode_display_name};
 pub use permission_request::{
     PermissionRequest, PermissionRequestOptions, PermissionRequestView, PermissionResponseHandler,
     permission_is_allow, permission_option_kind_to_icon,
 };
+pub use resource_link::{ResourceOpenHandler, ResourceTarget, render_resource_link};
+pub use tool_call_group::{
+    GroupToolCalls, MIN_TOOL_CALL_GROUP, group_kind, group_label, render_group_summary,
+    tool_call_runs,
+};
 pub use tool_call_item::{
-    ToolCallDetailHandler, ToolCallItem, ToolCallItemOptions, ToolCallItemView,
+    DiffApplyHandler, DiffApplyRequested, DiffRevertHandler, DiffRevertRequested,
+    TerminalOpenHandler, ToolCallDetailHandler, ToolCallItem, ToolCallItemOptions,
+    ToolCallItemView,
 };
+pub use ui_scale::{DEFAULT_UI_SCALE, MAX_UI_SCALE, MIN_UI_SCALE, UiScale, clamp_ui_scale, scaled};
+pub use unsupported::{UnsupportedContent, render_unsupported};
 pub use user_message::{
-    ResourceInfo, UserMessage, UserMessageData, UserMessageView, get_resource_info,
+    ResourceInfo, ResourceKind, UserMessage, UserMessageData, UserMessageView, get_resource_info,
 };
 
-pub use utils::{extract_terminal_output, extract_xml_content, truncate_lines};
+pub use utils::{
+    TIMESTAMP_META_KEY, decode_base64_content, extract_terminal_output, extract_xml_content,
+    find_matches, format_relative_time, timestamp_from_meta, truncate_chars, truncate_lines,
+};
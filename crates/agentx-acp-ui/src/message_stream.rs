@@ -1,26 +1,52 @@
-use std::collections::HashMap;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::Duration;
 
 use agent_client_protocol::{
-    ContentBlock, ContentChunk, Plan, PlanEntryStatus, SessionUpdate, ToolCall, ToolCallUpdate,
+    ContentBlock, ContentChunk, Plan, PlanEntryStatus, SessionUpdate, ToolCall, ToolCallStatus,
+    ToolCallUpdate,
 };
 use gpui::{
-    App, Context, Entity, IntoElement, ParentElement, Render, Styled, Window, div, prelude::*,
+    AnyElement, App, Bounds, Context, Entity, IntoElement, ParentElement, Pixels, Render, Styled,
+    Task, Window, div, prelude::*, px,
 };
-use gpui_component::{ActiveTheme, v_flex};
+use gpui_component::{ActiveTheme, Icon, IconName, h_flex, v_flex};
 
 use crate::agent_thought::AgentThoughtItem;
-use crate::user_message::{ResourceItem, get_resource_info};
+use crate::agent_todo_list::PlanDelta;
+use crate::content_renderer::ContentRenderers;
+use crate::error_message::{ErrorKind, ErrorMessage, ErrorRetryHandler};
+use crate::tool_call_group::{
+    GroupToolCalls, MIN_TOOL_CALL_GROUP, group_kind, render_group_summary, tool_call_runs,
+};
+use crate::unsupported::{UnsupportedContent, render_unsupported};
+use crate::utils::{find_matches, timestamp_from_meta};
 use crate::{
     AgentIconProvider, AgentMessage, AgentMessageData, AgentMessageOptions, AgentTodoList,
     DiffSummary, DiffSummaryData, DiffSummaryOptions, PermissionRequestView, ToolCallItem,
     ToolCallItemOptions, UserMessageData, UserMessageView,
 };
 
+/// How often relative message times ("5m ago") are redrawn
+const RELATIVE_TIME_REFRESH: Duration = Duration::from_secs(30);
+
+/// Session of messages created without one
+const DEFAULT_SESSION_ID: &str = "default-session";
+
 #[derive(Clone)]
 pub struct AcpMessageStreamOptions {
     pub agent_icon_provider: AgentIconProvider,
     pub tool_call_item_options: ToolCallItemOptions,
     pub diff_summary_options: DiffSummaryOptions,
+    /// Sends the last prompt again; without it error items have no retry button
+    pub on_retry: Option<ErrorRetryHandler>,
+    /// How content blocks other than text are shown
+    pub content_renderers: Arc<ContentRenderers>,
+    /// Render agent messages and thoughts as markdown
+    pub markdown: bool,
 }
 
 impl Default for AcpMessageStreamOptions {
@@ -29,6 +55,9 @@ impl Default for AcpMessageStreamOptions {
             agent_icon_provider: AgentMessageOptions::default().icon_provider,
             tool_call_item_options: ToolCallItemOptions::default(),
             diff_summary_options: DiffSummaryOptions::default(),
+            on_retry: None,
+            content_renderers: Arc::new(ContentRenderers::default()),
+            markdown: true,
         }
     }
 }
@@ -39,6 +68,26 @@ pub struct AcpMessageStream {
     index: UpdateStateIndex,
     next_index: usize,
     options: AcpMessageStreamOptions,
+    /// Latest mode reported by a `CurrentModeUpdate`
+    current_mode: Option<String>,
+    /// Collapsed tool call groups the user expanded, by their first tool call id
+    expanded_groups: HashSet<String>,
+    /// Anchor of each item, assigned once when the item is added
+    anchors: Vec<String>,
+    /// Sequence number of the next anchor
+    next_anchor: usize,
+    /// Items behind each rendered child; a collapsed group is one child
+    rendered_children: Vec<Range<usize>>,
+    /// Bounds of the rendered children as last painted
+    child_bounds: Rc<RefCell<Vec<Bounds<Pixels>>>>,
+    /// Transcript search; while set only matching items are shown
+    search_query: String,
+    /// Whether each item matches `search_query`, rechecked only for the items
+    /// an update changes
+    search_hits: Vec<bool>,
+    /// Repaints now and then so "5m ago" labels keep up; started by the
+    /// first update
+    _relative_time_refresh: Option<Task<()>>,
 }
 
 impl AcpMessageStream {
@@ -52,6 +101,15 @@ impl AcpMessageStream {
             index: UpdateStateIndex::new(),
             next_index: 0,
             options,
+            current_mode: None,
+            expanded_groups: HashSet::new(),
+            anchors: Vec::new(),
+            next_anchor: 0,
+            rendered_children: Vec::new(),
+            child_bounds: Rc::default(),
+            search_query: String::new(),
+            search_hits: Vec::new(),
+            _relative_time_refresh: None,
         }
     }
 
@@ -66,7 +124,7 @@ impl AcpMessageStream {
     /// Return the current in-progress plan entry (if any).
     pub fn current_todo_in_progress(&self) -> Option<String> {
         self.items.iter().rev().find_map(|item| {
-            if let RenderedItem::Plan(plan) = item {
+            if let RenderedItem::Plan(plan, _) = item {
                 plan.entries
                     .iter()
                     .find(|entry| entry.status == PlanEntryStatus::InProgress)
@@ -77,6 +135,35 @@ impl AcpMessageStream {
         })
     }
 
+    /// The agent's current mode id, if the session has reported one.
+    pub fn current_mode(&self) -> Option<&str> {
+        self.current_mode.as_deref()
+    }
+
+    /// Whether the agent is still producing output for the current turn: its
+    /// latest agent message is incomplete or one of its tool calls is in
+    /// progress. A turn starts at the last user message and ends at an error,
+    /// so calls an earlier turn left running don't count.
+    pub fn is_generating(&self, cx: &App) -> bool {
+        let turn = || {
+            self.items.iter().rev().take_while(|item| {
+                !matches!(item, RenderedItem::UserMessage(_) | RenderedItem::Error(_))
+            })
+        };
+        let message_streaming = turn().find_map(|item| match item {
+            RenderedItem::AgentMessage(_id, data) => Some(!data.meta.is_complete),
+            _ => None,
+        });
+        if message_streaming == Some(true) {
+            return true;
+        }
+
+        turn().any(|item| {
+            matches!(item, RenderedItem::ToolCall(entity)
+                if entity.read(cx).tool_call().status == ToolCallStatus::InProgress)
+        })
+    }
+
     /// Process a SessionUpdate and add/update items.
     pub fn process_update(
         &mut self,
@@ -85,9 +172,18 @@ impl AcpMessageStream {
         agent_name: Option<&str>,
         cx: &mut Context<Self>,
     ) {
+        // A chunk can only grow the last item, the streaming messages or its
+        // tool call, so only those are searched again
+        let first_changed = self.items.len().saturating_sub(1);
+        let tool_call_id = match &update {
+            SessionUpdate::ToolCall(tool_call) => Some(tool_call.tool_call_id.to_string()),
+            SessionUpdate::ToolCallUpdate(update) => Some(update.tool_call_id.to_string()),
+            _ => None,
+        };
         let mut processor = UpdateProcessor::new(
             &mut self.items,
             &mut self.index,
+            &mut self.current_mode,
             session_id,
             agent_name,
             self.next_index,
@@ -96,23 +192,292 @@ impl AcpMessageStream {
 
         processor.process_update(update, cx);
         self.next_index += 1;
+        self.assign_anchors(cx);
+        if !self.search_query.is_empty() {
+            self.highlight_search_query(first_changed, cx);
+            let changed = [
+                self.index.last_message(),
+                self.index.last_user_message(),
+                tool_call_id.and_then(|id| self.index.find_tool_call(&id)),
+            ];
+            self.refresh_search_hits(first_changed, changed.into_iter().flatten(), cx);
+        }
+        self.start_relative_time_refresh(cx);
         cx.notify();
     }
 
+    fn start_relative_time_refresh(&mut self, cx: &mut Context<Self>) {
+        if self._relative_time_refresh.is_some() {
+            return;
+        }
+        self._relative_time_refresh = Some(cx.spawn(async move |this, cx| {
+            loop {
+                cx.background_executor().timer(RELATIVE_TIME_REFRESH).await;
+                if this.update(cx, |_, cx| cx.notify()).is_err() {
+                    break;
+                }
+            }
+        }));
+    }
+
     pub fn add_permission_request(
         &mut self,
         request: Entity<PermissionRequestView>,
         cx: &mut Context<Self>,
     ) {
         self.items.push(RenderedItem::PermissionRequest(request));
+        self.assign_anchors(cx);
         cx.notify();
     }
 
     pub fn add_info_update(&mut self, text: impl Into<String>, cx: &mut Context<Self>) {
         self.items.push(RenderedItem::InfoUpdate(text.into()));
+        self.assign_anchors(cx);
+        cx.notify();
+    }
+
+    /// Collapse tool calls as they complete from now on (see
+    /// `ToolCallItemOptions::auto_collapse_completed`)
+    pub fn set_auto_collapse_completed(&mut self, auto_collapse: bool, cx: &mut Context<Self>) {
+        self.options.tool_call_item_options.auto_collapse_completed = auto_collapse;
+        for item in &self.items {
+            if let RenderedItem::ToolCall(entity) = item {
+                entity.update(cx, |item, _| {
+                    item.set_auto_collapse_completed(auto_collapse)
+                });
+            }
+        }
+    }
+
+    /// Render agent messages and thoughts as markdown, or as raw text
+    pub fn set_markdown(&mut self, markdown: bool, cx: &mut Context<Self>) {
+        self.options.markdown = markdown;
+        for item in &self.items {
+            if let RenderedItem::AgentThought(entity) = item {
+                entity.update(cx, |thought, cx| thought.set_markdown(markdown, cx));
+            }
+        }
+        cx.notify();
+    }
+
+    /// Anchors of all items, in transcript order
+    pub fn anchors(&self) -> &[String] {
+        &self.anchors
+    }
+
+    /// Where the item with `anchor` was last painted; a collapsed tool call
+    /// resolves to its group
+    pub fn item_bounds(&self, anchor: &str) -> Option<Bounds<Pixels>> {
+        let ix = anchor_position(&self.anchors, anchor)?;
+        let child = self
+            .rendered_children
+            .iter()
+            .position(|items| items.contains(&ix))?;
+        self.child_bounds.borrow().get(child).copied()
+    }
+
+    /// Show only the user and agent messages and tool calls whose text or
+    /// title contains `query` (ignoring ASCII case), with the matches
+    /// highlighted. An empty query shows every item again.
+    pub fn set_search_query(&mut self, query: &str, cx: &mut Context<Self>) {
+        if self.search_query == query {
+            return;
+        }
+        self.search_query = query.to_string();
+        self.search_hits.clear();
+        self.highlight_search_query(0, cx);
+        self.refresh_search_hits(0, [], cx);
         cx.notify();
     }
 
+    /// Search the items from `from` on, and those in `changed`, again
+    fn refresh_search_hits(
+        &mut self,
+        from: usize,
+        changed: impl IntoIterator<Item = usize>,
+        cx: &App,
+    ) {
+        if self.search_query.is_empty() {
+            return;
+        }
+        self.search_hits.resize(self.items.len(), false);
+        for ix in (from..self.items.len()).chain(changed) {
+            if ix < self.items.len() {
+                let hit = self.item_matches(ix, cx);
+                self.search_hits[ix] = hit;
+            }
+        }
+    }
+
+    fn is_search_hit(&self, ix: usize) -> bool {
+        self.search_hits.get(ix).copied().unwrap_or(false)
+    }
+
+    /// Anchors of the items matching the search query, in transcript order
+    pub fn search_matches(&self) -> Vec<String> {
+        if self.search_query.is_empty() {
+            return Vec::new();
+        }
+        self.anchors
+            .iter()
+            .enumerate()
+            .filter(|(ix, _)| self.is_search_hit(*ix))
+            .map(|(_, anchor)| anchor.clone())
+            .collect()
+    }
+
+    /// Number of items matching the search query, and the position among
+    /// them of the one with anchor `current`
+    pub fn search_match_position(&self, current: Option<&str>) -> (usize, Option<usize>) {
+        if self.search_query.is_empty() {
+            return (0, None);
+        }
+        let current = current.and_then(|anchor| anchor_position(&self.anchors, anchor));
+        let mut count = 0;
+        let mut position = None;
+        for ix in (0..self.anchors.len()).filter(|ix| self.is_search_hit(*ix)) {
+            if Some(ix) == current {
+                position = Some(count);
+            }
+            count += 1;
+        }
+        (count, position)
+    }
+
+    /// The match `delta` steps away from the one with anchor `current`,
+    /// wrapping around; without a current match, the first (or the last,
+    /// stepping back)
+    pub fn step_search_match(&self, current: Option<&str>, delta: isize) -> Option<String> {
+        let matches = self.search_matches();
+        let current = current.and_then(|anchor| anchor_position(&matches, anchor));
+        step_match(matches.len(), current, delta).map(|ix| matches[ix].clone())
+    }
+
+    /// Whether the item at `ix` contains the search query
+    fn item_matches(&self, ix: usize, cx: &App) -> bool {
+        let query = self.search_query.as_str();
+        match &self.items[ix] {
+            RenderedItem::UserMessage(entity) => {
+                let data = entity.read(cx).data.read(cx);
+                let text = self.options.content_renderers.plain_text(&data.contents);
+                !find_matches(&text, query).is_empty()
+            }
+            RenderedItem::AgentMessage(_, data) => {
+                !find_matches(&data.full_text(), query).is_empty()
+            }
+            RenderedItem::ToolCall(entity) => entity.read(cx).title_matches(query),
+            _ => false,
+        }
+    }
+
+    /// Pass the search query on to the items from `from` on that highlight it
+    /// themselves
+    fn highlight_search_query(&self, from: usize, cx: &mut Context<Self>) {
+        let query = self.search_query.as_str();
+        for item in self.items.iter().skip(from) {
+            match item {
+                RenderedItem::UserMessage(entity) => {
+                    entity.update(cx, |view, cx| view.set_search_query(query, cx));
+                }
+                RenderedItem::ToolCall(entity) => {
+                    entity.update(cx, |item, cx| item.set_title_highlight(query, cx));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Give the items added since the last call their anchors
+    fn assign_anchors(&mut self, cx: &App) {
+        for item in &self.items[self.anchors.len()..] {
+            self.anchors.push(item.anchor(self.next_anchor, cx));
+            self.next_anchor += 1;
+        }
+    }
+
+    /// Show a failed request inline, after whatever came before it
+    pub fn push_error(
+        &mut self,
+        kind: ErrorKind,
+        message: impl Into<String>,
+        cx: &mut Context<Self>,
+    ) {
+        // The failed turn is over: the next chunks start new messages
+        if let Some(last_item) = self.items.last_mut() {
+            last_item.mark_complete();
+        }
+        self.index.clear_streaming_state();
+        self.index.clear_user_message_state();
+
+        let id = format!("error-{}", self.next_index);
+        self.next_index += 1;
+        self.items.push(RenderedItem::Error(ErrorItem {
+            id,
+            kind,
+            message: message.into(),
+            retried: false,
+        }));
+        self.assign_anchors(cx);
+        cx.notify();
+    }
+
+    /// Note that a timed-out request is being sent again. Retries of one
+    /// request share an item, which counts up.
+    pub fn push_retry(
+        &mut self,
+        attempt: u32,
+        max_retries: u32,
+        message: impl Into<String>,
+        cx: &mut Context<Self>,
+    ) {
+        let retry = RetryItem {
+            attempt,
+            max_retries,
+            message: message.into(),
+        };
+        if let Some(RenderedItem::Retry(last)) = self.items.last_mut() {
+            *last = retry;
+            cx.notify();
+            return;
+        }
+
+        // The abandoned attempt is over: the next chunks start new messages
+        if let Some(last_item) = self.items.last_mut() {
+            last_item.mark_complete();
+        }
+        self.index.clear_streaming_state();
+        self.items.push(RenderedItem::Retry(retry));
+        self.assign_anchors(cx);
+        cx.notify();
+    }
+
+    /// Send the last prompt again from the error item at `ix`; each error
+    /// offers this once
+    fn retry(&mut self, ix: usize, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(RenderedItem::Error(error)) = self.items.get_mut(ix) else {
+            return;
+        };
+        error.retried = true;
+        if let Some(on_retry) = self.options.on_retry.clone() {
+            on_retry(window, cx);
+        }
+        cx.notify();
+    }
+
+    /// Index of the newest plan, the only one showing what changed
+    fn last_plan_index(&self) -> Option<usize> {
+        self.items
+            .iter()
+            .rposition(|item| matches!(item, RenderedItem::Plan(..)))
+    }
+
+    /// Index of the newest error item, the only one whose retry sends the last prompt
+    fn last_error_index(&self) -> Option<usize> {
+        self.items
+            .iter()
+            .rposition(|item| matches!(item, RenderedItem::Error(_)))
+    }
+
     pub fn mark_last_complete(&mut self, cx: &mut Context<Self>) {
         if let Some(last_item) = self.items.last_mut() {
             last_item.mark_complete();
@@ -129,6 +494,7 @@ impl AcpMessageStream {
             let options = self.options.diff_summary_options.clone();
             let diff_summary = cx.new(|_| DiffSummary::new(summary_data).with_options(options));
             self.items.push(RenderedItem::DiffSummary(diff_summary));
+            self.assign_anchors(cx);
             cx.notify();
         }
     }
@@ -147,56 +513,195 @@ impl AcpMessageStream {
     }
 }
 
-impl Render for AcpMessageStream {
-    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
-        let mut children = v_flex().gap_3().w_full();
+impl AcpMessageStream {
+    /// Expand or collapse the tool call group starting with `first_tool_call_id`
+    pub fn toggle_tool_call_group(&mut self, first_tool_call_id: String, cx: &mut Context<Self>) {
+        if !self.expanded_groups.remove(&first_tool_call_id) {
+            self.expanded_groups.insert(first_tool_call_id);
+        }
+        cx.notify();
+    }
 
-        for item in &self.items {
-            match item {
-                RenderedItem::UserMessage(entity) => {
-                    children = children.child(entity.clone());
-                }
-                RenderedItem::AgentMessage(id, data) => {
-                    let msg = AgentMessage::new(get_element_id(id), data.clone())
-                        .icon_provider(self.options.agent_icon_provider.clone());
-                    children = children.child(msg);
-                }
-                RenderedItem::AgentThought(entity) => {
-                    children = children.child(entity.clone());
-                }
-                RenderedItem::Plan(plan) => {
-                    let todo_list = AgentTodoList::from_plan(plan.clone());
-                    children = children.child(v_flex().pl_6().child(todo_list));
-                }
-                RenderedItem::ToolCall(entity) => {
-                    children = children.child(v_flex().pl_6().child(entity.clone()));
-                }
-                RenderedItem::PermissionRequest(entity) => {
-                    children = children.child(v_flex().pl_6().child(entity.clone()));
-                }
-                RenderedItem::DiffSummary(entity) => {
-                    children = children.child(entity.clone());
-                }
-                RenderedItem::InfoUpdate(text) => {
-                    children = children.child(
-                        div().pl_6().child(
+    /// Runs of same-kind finished tool calls to collapse, when grouping is on
+    fn tool_call_groups(&self, cx: &App) -> Vec<Range<usize>> {
+        if !GroupToolCalls::global(cx) {
+            return Vec::new();
+        }
+        let kinds: Vec<_> = self
+            .items
+            .iter()
+            .map(|item| match item {
+                RenderedItem::ToolCall(entity) => group_kind(entity.read(cx).tool_call()),
+                _ => None,
+            })
+            .collect();
+        tool_call_runs(&kinds, MIN_TOOL_CALL_GROUP)
+    }
+
+    fn render_tool_call_group(&self, items: &[RenderedItem], cx: &mut Context<Self>) -> AnyElement {
+        let entities: Vec<_> = items
+            .iter()
+            .filter_map(|item| match item {
+                RenderedItem::ToolCall(entity) => Some(entity),
+                _ => None,
+            })
+            .collect();
+        let tool_calls: Vec<_> = entities
+            .iter()
+            .map(|entity| entity.read(cx).tool_call())
+            .collect();
+        let Some(first) = tool_calls.first() else {
+            return div().into_any_element();
+        };
+        let key = first.tool_call_id.to_string();
+        let expanded = self.expanded_groups.contains(&key);
+        let group_id = get_element_id(&format!("tool-call-group-{}", key));
+        let summary = render_group_summary(group_id, &tool_calls, expanded, cx).on_click(
+            cx.listener(move |this, _, _, cx| {
+                this.toggle_tool_call_group(key.clone(), cx);
+            }),
+        );
+
+        v_flex()
+            .pl_6()
+            .gap_3()
+            .child(summary)
+            .when(expanded, |this| {
+                this.children(entities.into_iter().cloned())
+            })
+            .into_any_element()
+    }
+
+    /// Render the item at `ix`; only the last one may show a typing caret
+    fn render_item(&self, ix: usize, cx: &Context<Self>) -> AnyElement {
+        let is_last = ix + 1 == self.items.len();
+        match &self.items[ix] {
+            RenderedItem::UserMessage(entity) => entity.clone().into_any_element(),
+            RenderedItem::AgentMessage(id, data) => {
+                AgentMessage::new(get_element_id(id), Rc::clone(data))
+                    .icon_provider(self.options.agent_icon_provider.clone())
+                    .content_renderers(self.options.content_renderers.clone())
+                    .markdown(self.options.markdown)
+                    .streaming(is_last)
+                    .highlight(if self.is_search_hit(ix) {
+                        self.search_query.clone()
+                    } else {
+                        String::new()
+                    })
+                    .into_any_element()
+            }
+            RenderedItem::AgentThought(entity) => entity.clone().into_any_element(),
+            RenderedItem::Plan(plan, delta) => {
+                // Changes are marked until the next plan arrives
+                let delta = delta.clone().filter(|_| self.last_plan_index() == Some(ix));
+                let todo_list = AgentTodoList::from_plan(plan.clone()).delta(delta);
+                v_flex().pl_6().child(todo_list).into_any_element()
+            }
+            RenderedItem::ToolCall(entity) => {
+                v_flex().pl_6().child(entity.clone()).into_any_element()
+            }
+            RenderedItem::PermissionRequest(entity) => {
+                v_flex().pl_6().child(entity.clone()).into_any_element()
+            }
+            RenderedItem::DiffSummary(entity) => entity.clone().into_any_element(),
+            RenderedItem::ModeSwitch(mode) => h_flex()
+                .pl_6()
+                .gap_2()
+                .items_center()
+                .child(div().flex_1().h(px(1.)).bg(cx.theme().border))
+                .child(
+                    div()
+                        .text_xs()
+                        .text_color(cx.theme().muted_foreground)
+                        .child(format!("Switched to {} mode", mode_display_name(mode))),
+                )
+                .child(div().flex_1().h(px(1.)).bg(cx.theme().border))
+                .into_any_element(),
+            RenderedItem::Error(error) => {
+                let can_retry = !error.retried
+                    && self.options.on_retry.is_some()
+                    && self.last_error_index() == Some(ix);
+                let message =
+                    ErrorMessage::new(get_element_id(&error.id), error.kind, error.message.clone());
+                let message = if can_retry {
+                    message.on_retry(cx.listener(move |this, _, window, cx| {
+                        this.retry(ix, window, cx);
+                    }))
+                } else {
+                    message
+                };
+                div().pl_6().child(message).into_any_element()
+            }
+            RenderedItem::Unsupported(id, raw) => div()
+                .pl_6()
+                .child(render_unsupported(get_element_id(id), raw.clone()))
+                .into_any_element(),
+            RenderedItem::OrphanToolCallUpdate(id, raw) => div()
+                .pl_6()
+                .child(
+                    UnsupportedContent::new(get_element_id(id), raw.clone())
+                        .label("Update for an unknown tool call"),
+                )
+                .into_any_element(),
+            RenderedItem::Retry(retry) => h_flex()
+                .pl_6()
+                .gap_2()
+                .items_center()
+                .text_xs()
+                .text_color(cx.theme().muted_foreground)
+                .child(
+                    Icon::new(if is_last {
+                        IconName::Loader
+                    } else {
+                        IconName::TriangleAlert
+                    })
+                    .size(px(12.)),
+                )
+                .child(retry.label(is_last))
+                .into_any_element(),
+            RenderedItem::InfoUpdate(text) => div()
+                .pl_6()
+                .child(
+                    div()
+                        .p_2()
+                        .rounded(cx.theme().radius)
+                        .bg(cx.theme().muted.opacity(0.5))
+                        .border_1()
+                        .border_color(cx.theme().border.opacity(0.3))
+                        .child(
                             div()
-                                .p_2()
-                                .rounded(cx.theme().radius)
-                                .bg(cx.theme().muted.opacity(0.5))
-                                .border_1()
-                                .border_color(cx.theme().border.opacity(0.3))
-                                .child(
-                                    div()
-                                        .text_xs()
-                                        .text_color(cx.theme().muted_foreground)
-                                        .child(text.clone()),
-                                ),
+                                .text_xs()
+                                .text_color(cx.theme().muted_foreground)
+                                .child(text.clone()),
                         ),
-                    );
-                }
-            }
+                )
+                .into_any_element(),
+        }
+    }
+}
+
+impl Render for AcpMessageStream {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let child_bounds = self.child_bounds.clone();
+        let mut children = v_flex()
+            .gap_3()
+            .w_full()
+            .on_children_prepainted(move |bounds, _, _| *child_bounds.borrow_mut() = bounds);
+        // Items added without an update (errors, notices) never match
+        self.search_hits.resize(self.items.len(), false);
+        let matches = (!self.search_query.is_empty()).then_some(self.search_hits.as_slice());
+        let layout = layout_children(self.items.len(), self.tool_call_groups(cx), matches);
+
+        let mut rendered_children = Vec::with_capacity(layout.len());
+        for (range, is_group) in layout {
+            children = children.child(if is_group {
+                self.render_tool_call_group(&self.items[range.clone()], cx)
+            } else {
+                self.render_item(range.start, cx)
+            });
+            rendered_children.push(range);
         }
+        self.rendered_children = rendered_children;
 
         children
     }
@@ -208,22 +713,93 @@ impl Render for AcpMessageStream {
 
 enum RenderedItem {
     UserMessage(Entity<UserMessageView>),
-    /// Agent message with unique ID and mutable data (supports chunk merging)
-    AgentMessage(String, AgentMessageData),
+    /// Agent message with unique ID and mutable data (supports chunk merging).
+    /// Shared with the rendered element, and copied on write only while a
+    /// frame still holds it.
+    AgentMessage(String, Rc<AgentMessageData>),
     /// Agent thought with entity (supports chunk merging and expand/collapse)
     AgentThought(Entity<AgentThoughtItem>),
-    Plan(Plan),
+    /// A plan and, when it replaced an earlier one, what changed
+    Plan(Plan, Option<PlanDelta>),
     ToolCall(Entity<ToolCallItem>),
     InfoUpdate(String),
+    /// Separator marking a mid-conversation mode change
+    ModeSwitch(String),
     PermissionRequest(Entity<PermissionRequestView>),
     DiffSummary(Entity<DiffSummary>),
+    /// A request to the agent that failed
+    Error(ErrorItem),
+    /// A request that timed out and was sent again
+    Retry(RetryItem),
+    /// An update this version doesn't understand, with its id and raw JSON
+    Unsupported(String, serde_json::Value),
+    /// A tool call update for an id never seen, too incomplete to show as a
+    /// tool call, with its id and raw JSON
+    OrphanToolCallUpdate(String, serde_json::Value),
+}
+
+struct ErrorItem {
+    id: String,
+    kind: ErrorKind,
+    message: String,
+    /// The retry button was used
+    retried: bool,
+}
+
+struct RetryItem {
+    attempt: u32,
+    max_retries: u32,
+    /// Why the previous attempt failed
+    message: String,
+}
+
+impl RetryItem {
+    /// `pending` while nothing has arrived since the retry was sent
+    fn label(&self, pending: bool) -> String {
+        if pending {
+            format!(
+                "{}, retrying ({}/{})…",
+                self.message, self.attempt, self.max_retries
+            )
+        } else {
+            format!(
+                "{}, retried ({}/{})",
+                self.message, self.attempt, self.max_retries
+            )
+        }
+    }
 }
 
 impl RenderedItem {
+    /// The item's anchor. Agent messages and tool calls are named by their
+    /// ids; other items by `seq`, the order they were added in, which
+    /// replaying the same updates reproduces. Neither depends on the item's
+    /// position, so anchors don't shift as items are added around them.
+    fn anchor(&self, seq: usize, cx: &App) -> String {
+        let kind = match self {
+            RenderedItem::AgentMessage(id, _) => return id.clone(),
+            RenderedItem::ToolCall(entity) => {
+                return format!("tool-call-{}", entity.read(cx).tool_call().tool_call_id);
+            }
+            RenderedItem::UserMessage(_) => "user",
+            RenderedItem::AgentThought(_) => "thought",
+            RenderedItem::Plan(..) => "plan",
+            RenderedItem::InfoUpdate(_) => "info",
+            RenderedItem::ModeSwitch(_) => "mode",
+            RenderedItem::PermissionRequest(_) => "permission",
+            RenderedItem::DiffSummary(_) => "diff-summary",
+            RenderedItem::Error(_) => "error",
+            RenderedItem::Retry(_) => "retry",
+            RenderedItem::Unsupported(..) => "unsupported",
+            RenderedItem::OrphanToolCallUpdate(..) => "orphan-update",
+        };
+        format!("{}-{}", kind, seq)
+    }
+
     /// Try to append an AgentMessageChunk to this item (returns true if successful)
     pub fn try_append_agent_message_chunk(&mut self, chunk: ContentChunk) -> bool {
         if let RenderedItem::AgentMessage(_id, data) = self {
-            data.push_chunk(chunk);
+            Rc::make_mut(data).push_chunk(chunk);
             true
         } else {
             false
@@ -249,12 +825,14 @@ impl RenderedItem {
     /// Mark an AgentMessage as complete (no more chunks expected)
     pub fn mark_complete(&mut self) {
         if let RenderedItem::AgentMessage(_id, data) = self {
-            data.meta.is_complete = true;
+            Rc::make_mut(data).meta.is_complete = true;
         }
     }
 
-    pub fn can_accept_agent_message_chunk(&self) -> bool {
-        matches!(self, RenderedItem::AgentMessage(..))
+    /// Whether a chunk from `session_id` continues this message. Chunks of
+    /// another session start a message of their own.
+    pub fn can_accept_agent_message_chunk(&self, session_id: &str) -> bool {
+        matches!(self, RenderedItem::AgentMessage(_, data) if &*data.session_id.0 == session_id)
     }
 
     pub fn can_accept_agent_thought_chunk(&self) -> bool {
@@ -380,6 +958,7 @@ impl UpdateStateIndex {
 struct UpdateProcessor<'a> {
     items: &'a mut Vec<RenderedItem>,
     index: &'a mut UpdateStateIndex,
+    current_mode: &'a mut Option<String>,
     session_id: Option<&'a str>,
     agent_name: Option<&'a str>,
     next_index: usize,
@@ -390,6 +969,7 @@ impl<'a> UpdateProcessor<'a> {
     pub fn new(
         items: &'a mut Vec<RenderedItem>,
         index: &'a mut UpdateStateIndex,
+        current_mode: &'a mut Option<String>,
         session_id: Option<&'a str>,
         agent_name: Option<&'a str>,
         next_index: usize,
@@ -398,6 +978,7 @@ impl<'a> UpdateProcessor<'a> {
         Self {
             items,
             index,
+            current_mode,
             session_id,
             agent_name,
             next_index,
@@ -433,34 +1014,37 @@ impl<'a> UpdateProcessor<'a> {
                 self.process_plan(plan);
             }
             SessionUpdate::AvailableCommandsUpdate(commands_update) => {
-                self.complete_last_item();
-                self.index.clear_streaming_state();
+                // Commands feed the input's `/` palette, not the transcript
                 log::debug!(
                     "  └─ Commands update: {} available",
                     commands_update.available_commands.len()
                 );
-                self.items.push(RenderedItem::InfoUpdate(format!(
-                    "📋 Available Commands: {} commands",
-                    commands_update.available_commands.len()
-                )));
             }
             SessionUpdate::CurrentModeUpdate(mode_update) => {
-                self.complete_last_item();
-                self.index.clear_streaming_state();
-                log::debug!("  └─ Mode changed to: {}", mode_update.current_mode_id);
-                self.items.push(RenderedItem::InfoUpdate(format!(
-                    "🔄 Mode: {}",
-                    mode_update.current_mode_id
-                )));
+                let mode = mode_update.current_mode_id.to_string();
+                log::debug!("  └─ Mode changed to: {}", mode);
+                let previous = self.current_mode.replace(mode.clone());
+                if is_mid_conversation_switch(previous.as_deref(), &mode, self.items.is_empty()) {
+                    self.complete_last_item();
+                    self.index.clear_streaming_state();
+                    self.items.push(RenderedItem::ModeSwitch(mode));
+                }
             }
             _ => {
                 log::warn!(
                     "⚠️  UNHANDLED SessionUpdate type: {}\n\
-                     This update will be ignored. Consider implementing support for this type.\n\
+                     Showing it as unsupported. Consider implementing support for this type.\n\
                      Update details: {:?}",
                     update_type,
                     update
                 );
+                let raw = serde_json::to_value(&update).unwrap_or_default();
+                self.complete_last_item();
+                self.index.clear_streaming_state();
+                self.items.push(RenderedItem::Unsupported(
+                    format!("unsupported-{}", self.next_index),
+                    raw,
+                ));
             }
         }
     }
@@ -508,13 +1092,15 @@ impl<'a> UpdateProcessor<'a> {
         if let Some(last_idx) = self.index.last_message() {
             if last_idx < self.items.len() {
                 if let Some(last_item) = self.items.get_mut(last_idx) {
-                    if last_item.can_accept_agent_message_chunk() {
+                    if last_item.can_accept_agent_message_chunk(
+                        self.session_id.unwrap_or(DEFAULT_SESSION_ID),
+                    ) {
                         if last_item.try_append_agent_message_chunk(chunk.clone()) {
                             if let (Some(name), RenderedItem::AgentMessage(_, data)) =
                                 (resolved_agent_name.as_deref(), last_item)
                             {
                                 if data.meta.agent_name.is_none() {
-                                    data.meta.agent_name = Some(name.to_string());
+                                    Rc::make_mut(data).meta.agent_name = Some(name.to_string());
                                 }
                             }
                             log::debug!(
@@ -537,7 +1123,7 @@ impl<'a> UpdateProcessor<'a> {
         let new_index = self.items.len();
         self.items.push(RenderedItem::AgentMessage(
             format!("agent-msg-{}", self.next_index),
-            data,
+            Rc::new(data),
         ));
         self.index.set_last_message(new_index);
         self.index.set_last_thought(new_index);
@@ -549,7 +1135,7 @@ impl<'a> UpdateProcessor<'a> {
         cx: &mut Context<AcpMessageStream>,
     ) {
         self.index.clear_user_message_state();
-        let text = extract_text_from_content(&chunk.content);
+        let text = self.options.content_renderers.text(&chunk.content);
 
         if let Some(last_idx) = self.index.last_thought() {
             if last_idx < self.items.len() {
@@ -571,7 +1157,8 @@ impl<'a> UpdateProcessor<'a> {
         }
 
         log::debug!("  └─ Creating new AgentThought");
-        let entity = cx.new(|_| AgentThoughtItem::new(text));
+        let markdown = self.options.markdown;
+        let entity = cx.new(|_| AgentThoughtItem::new(text).with_markdown(markdown));
         let new_index = self.items.len();
         self.items.push(RenderedItem::AgentThought(entity));
         self.index.set_last_thought(new_index);
@@ -606,7 +1193,12 @@ impl<'a> UpdateProcessor<'a> {
         log::debug!("  └─ Creating new ToolCall: {}", tool_call.tool_call_id);
         let tool_call_id = tool_call.tool_call_id.to_string();
         let options = self.options.tool_call_item_options.clone();
-        let entity = cx.new(|_| ToolCallItem::with_options(tool_call, options));
+        let open = starts_open(&tool_call);
+        let entity = cx.new(|cx| {
+            let mut item = ToolCallItem::with_options_and_open(tool_call, open, options);
+            item.offload_large_content(cx);
+            item
+        });
         let new_index = self.items.len();
         self.items.push(RenderedItem::ToolCall(entity));
         self.index.register_tool_call(tool_call_id, new_index);
@@ -631,7 +1223,19 @@ impl<'a> UpdateProcessor<'a> {
                             tool_call_update.tool_call_id,
                             tool_call_update.fields.status
                         );
-                        state.apply_update(tool_call_update.fields.clone(), cx);
+                        let fields = tool_call_update.fields.clone();
+                        // Output streamed into a running call only needs a
+                        // repaint; status changes also open or close the item
+                        let streaming = fields.status.is_none()
+                            && matches!(
+                                state.tool_call().status,
+                                ToolCallStatus::Pending | ToolCallStatus::InProgress
+                            );
+                        if streaming {
+                            state.queue_updates(|tool_call| tool_call.update(fields), cx);
+                        } else {
+                            state.apply_update(fields, cx);
+                        }
                     });
                     return;
                 }
@@ -643,18 +1247,29 @@ impl<'a> UpdateProcessor<'a> {
             tool_call_update.tool_call_id
         );
 
-        match ToolCall::try_from(tool_call_update) {
+        match tool_call_from_update(tool_call_update) {
             Ok(tool_call) => {
                 log::debug!("     ✓ Successfully created ToolCall from update");
                 let tool_call_id = tool_call.tool_call_id.to_string();
                 let options = self.options.tool_call_item_options.clone();
-                let entity = cx.new(|_| ToolCallItem::with_options(tool_call, options));
+                let open = starts_open(&tool_call);
+                let entity = cx.new(|cx| {
+                    let mut item = ToolCallItem::with_options_and_open(tool_call, open, options);
+                    item.offload_large_content(cx);
+                    item
+                });
                 let new_index = self.items.len();
                 self.items.push(RenderedItem::ToolCall(entity));
                 self.index.register_tool_call(tool_call_id, new_index);
             }
-            Err(e) => {
-                log::error!("     ✗ Failed to create ToolCall from update: {:?}", e);
+            Err(raw) => {
+                // Keep the update visible rather than dropping it
+                self.complete_last_item();
+                self.index.clear_streaming_state();
+                self.items.push(RenderedItem::OrphanToolCallUpdate(
+                    format!("orphan-update-{}", self.next_index),
+                    raw,
+                ));
             }
         }
     }
@@ -664,7 +1279,11 @@ impl<'a> UpdateProcessor<'a> {
         self.complete_last_item();
         self.index.clear_streaming_state();
         log::debug!("  └─ Creating Plan with {} entries", plan.entries.len());
-        self.items.push(RenderedItem::Plan(plan));
+        let delta = self.items.iter().rev().find_map(|item| match item {
+            RenderedItem::Plan(previous, _) => Some(PlanDelta::between(previous, &plan)),
+            _ => None,
+        });
+        self.items.push(RenderedItem::Plan(plan, delta));
     }
 
     fn complete_last_item(&mut self) {
@@ -683,36 +1302,34 @@ fn create_user_message<T>(
     session_id: Option<&str>,
     cx: &mut Context<T>,
 ) -> RenderedItem {
-    let content_vec = vec![chunk.content.clone()];
     let session_id = session_id
         .map(str::to_string)
-        .unwrap_or_else(|| "default-session".to_string());
-    let user_data = UserMessageData::new(session_id).with_contents(content_vec.clone());
+        .unwrap_or_else(|| DEFAULT_SESSION_ID.to_string());
+    let user_data = UserMessageData::new(session_id)
+        .with_timestamp(timestamp_from_meta(chunk.meta.as_ref()))
+        .with_contents(vec![chunk.content]);
 
-    let entity = cx.new(|cx| {
-        let data_entity = cx.new(|_| user_data);
-
-        let resource_items: Vec<Entity<ResourceItem>> = content_vec
-            .iter()
-            .filter_map(|content| get_resource_info(content))
-            .map(|resource_info| cx.new(|_| ResourceItem::new(resource_info)))
-            .collect();
-
-        UserMessageView {
-            data: data_entity,
-            resource_items,
-        }
-    });
+    let entity = cx.new(|cx| UserMessageView::from_data(user_data, cx));
 
     RenderedItem::UserMessage(entity)
 }
 
+/// A tool call from an update for an id not seen before, or the update as raw
+/// JSON when it lacks the fields a tool call needs
+fn tool_call_from_update(update: ToolCallUpdate) -> Result<ToolCall, serde_json::Value> {
+    let raw = serde_json::to_value(&update).unwrap_or_default();
+    ToolCall::try_from(update).map_err(|e| {
+        log::error!("     ✗ Failed to create ToolCall from update: {:?}", e);
+        raw
+    })
+}
+
 fn create_agent_message_data(
     chunk: ContentChunk,
     session_id: Option<&str>,
     agent_name: Option<&str>,
 ) -> AgentMessageData {
-    let session_id = session_id.unwrap_or("default-session").to_string();
+    let session_id = session_id.unwrap_or(DEFAULT_SESSION_ID).to_string();
     let mut data = AgentMessageData::new(session_id).add_chunk(chunk);
     if let Some(agent_name) = agent_name {
         data = data.with_agent_name(agent_name);
@@ -720,27 +1337,69 @@ fn create_agent_message_data(
     data
 }
 
-fn extract_text_from_content(content: &ContentBlock) -> String {
-    match content {
-        ContentBlock::Text(text_content) => text_content.text.clone(),
-        ContentBlock::Image(img) => format!("[Image: {}]", img.mime_type),
-        ContentBlock::Audio(audio) => format!("[Audio: {}]", audio.mime_type),
-        ContentBlock::ResourceLink(link) => format!("[Resource: {}]", link.name),
-        ContentBlock::Resource(resource) => match &resource.resource {
-            agent_client_protocol::EmbeddedResourceResource::TextResourceContents(text_res) => {
-                format!(
-                    "[Resource: {}]\n{}",
-                    text_res.uri,
-                    &text_res.text[..text_res.text.len().min(200)]
-                )
-            }
-            agent_client_protocol::EmbeddedResourceResource::BlobResourceContents(blob_res) => {
-                format!("[Binary Resource: {}]", blob_res.uri)
-            }
-            _ => "[Unknown Resource]".to_string(),
-        },
-        _ => "[Unknown Content]".to_string(),
+/// Items behind each rendered child, and whether it is a collapsed tool call
+/// group: each of `groups` is one child, every other item its own. With
+/// `matches` (one per item), children without a matching item are left out.
+fn layout_children(
+    len: usize,
+    groups: Vec<Range<usize>>,
+    matches: Option<&[bool]>,
+) -> Vec<(Range<usize>, bool)> {
+    let mut groups = groups.into_iter().peekable();
+    let mut layout = Vec::new();
+    let mut ix = 0;
+    while ix < len {
+        let child = match groups.next_if(|group| group.start == ix) {
+            Some(group) => (group, true),
+            None => (ix..ix + 1, false),
+        };
+        ix = child.0.end;
+        if matches.is_none_or(|matches| matches[child.0.clone()].contains(&true)) {
+            layout.push(child);
+        }
+    }
+    layout
+}
+
+/// Index `delta` steps from `current` among `len` matches, wrapping around
+fn step_match(len: usize, current: Option<usize>, delta: isize) -> Option<usize> {
+    if len == 0 {
+        return None;
     }
+    Some(match current {
+        Some(ix) => (ix as isize + delta).rem_euclid(len as isize) as usize,
+        None if delta < 0 => len - 1,
+        None => 0,
+    })
+}
+
+/// Position of the item with `anchor`
+fn anchor_position(anchors: &[String], anchor: &str) -> Option<usize> {
+    anchors.iter().position(|candidate| candidate == anchor)
+}
+
+/// Human-readable name for a mode id, e.g. `plan` -> `Plan`
+pub fn mode_display_name(mode_id: &str) -> String {
+    let mut chars = mode_id.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// A mode update earns a transcript separator only when it changes an already
+/// known mode after the conversation has started
+fn is_mid_conversation_switch(
+    previous: Option<&str>,
+    current: &str,
+    transcript_empty: bool,
+) -> bool {
+    !transcript_empty && previous.is_some_and(|previous| previous != current)
+}
+
+/// Failed tool calls start expanded so their error output is visible
+fn starts_open(tool_call: &ToolCall) -> bool {
+    tool_call.status == ToolCallStatus::Failed && !tool_call.content.is_empty()
 }
 
 fn session_update_type_name(update: &SessionUpdate) -> &'static str {
@@ -769,6 +1428,142 @@ fn get_element_id(id: &str) -> gpui::ElementId {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use agent_client_protocol::{ToolCallContent, ToolCallUpdateFields};
+
+    #[gpui::test]
+    fn tool_call_updates_change_the_call_or_stay_visible(cx: &mut gpui::TestAppContext) {
+        let stream = cx.new(|_| AcpMessageStream::new());
+        let (status, title, output, kinds) = stream.update(cx, |stream, cx| {
+            let mut tool_call = ToolCall::new("tc-1", "cargo test");
+            tool_call.status = ToolCallStatus::InProgress;
+            let updates = [
+                SessionUpdate::ToolCall(tool_call),
+                // Output streamed into the running call is applied right away
+                SessionUpdate::ToolCallUpdate(ToolCallUpdate::new(
+                    "tc-1",
+                    ToolCallUpdateFields::new().content(vec![ToolCallContent::from(
+                        ContentBlock::from("running 3 tests".to_string()),
+                    )]),
+                )),
+                SessionUpdate::ToolCallUpdate(ToolCallUpdate::new(
+                    "tc-1",
+                    ToolCallUpdateFields::new().status(ToolCallStatus::Completed),
+                )),
+                // An unknown id with a title becomes a tool call of its own
+                SessionUpdate::ToolCallUpdate(ToolCallUpdate::new(
+                    "tc-2",
+                    ToolCallUpdateFields::new().title("ls".to_string()),
+                )),
+                // Without one it is kept as raw JSON
+                SessionUpdate::ToolCallUpdate(ToolCallUpdate::new(
+                    "tc-3",
+                    ToolCallUpdateFields::new().status(ToolCallStatus::Completed),
+                )),
+            ];
+            for update in updates {
+                stream.process_update(update, Some("sess"), None, cx);
+            }
+
+            let RenderedItem::ToolCall(first) = &stream.items[0] else {
+                panic!("expected a tool call");
+            };
+            let first = first.read(cx).tool_call();
+            let kinds: Vec<String> = stream
+                .items
+                .iter()
+                .map(|item| match item {
+                    RenderedItem::ToolCall(entity) => entity.read(cx).tool_call().title.clone(),
+                    RenderedItem::OrphanToolCallUpdate(_, raw) => raw["toolCallId"].to_string(),
+                    _ => "other".to_string(),
+                })
+                .collect();
+            (
+                first.status,
+                first.title.clone(),
+                first.content.len(),
+                kinds,
+            )
+        });
+        assert_eq!(status, ToolCallStatus::Completed);
+        assert_eq!(title, "cargo test");
+        assert_eq!(output, 1);
+        assert_eq!(kinds, ["cargo test", "ls", "\"tc-3\""]);
+    }
+
+    #[test]
+    fn search_hides_children_without_a_match() {
+        let groups = vec![1..4];
+        assert_eq!(
+            layout_children(5, groups.clone(), None),
+            vec![(0..1, false), (1..4, true), (4..5, false)]
+        );
+
+        // A group stays while any of its tool calls matches
+        let matches = [false, false, true, false, true];
+        assert_eq!(
+            layout_children(5, groups.clone(), Some(&matches)),
+            vec![(1..4, true), (4..5, false)]
+        );
+        assert!(layout_children(5, groups, Some(&[false; 5])).is_empty());
+    }
+
+    #[gpui::test]
+    fn search_hits_follow_streamed_text(cx: &mut gpui::TestAppContext) {
+        let stream = cx.new(|_| AcpMessageStream::new());
+        stream.update(cx, |stream, cx| {
+            replay_turn(stream, cx);
+            stream.set_search_query("look", cx);
+            assert_eq!(stream.search_matches(), ["agent-msg-1"]);
+            assert_eq!(
+                stream.search_match_position(Some("agent-msg-1")),
+                (1, Some(0))
+            );
+
+            // The streaming message only matches once the chunk arrives
+            let chunk = ContentChunk::new(ContentBlock::from(", look again".to_string()));
+            stream.process_update(
+                SessionUpdate::AgentMessageChunk(chunk),
+                Some("sess"),
+                None,
+                cx,
+            );
+            assert_eq!(stream.search_matches(), ["agent-msg-1", "agent-msg-3"]);
+            assert_eq!(
+                stream.search_match_position(Some("agent-msg-3")),
+                (2, Some(1))
+            );
+
+            stream.set_search_query("", cx);
+            assert!(stream.search_matches().is_empty());
+        });
+    }
+
+    #[test]
+    fn search_navigation_wraps_around() {
+        assert_eq!(step_match(3, None, 0), Some(0));
+        assert_eq!(step_match(3, None, -1), Some(2));
+        assert_eq!(step_match(3, Some(2), 1), Some(0));
+        assert_eq!(step_match(3, Some(0), -1), Some(2));
+        assert_eq!(step_match(3, Some(1), 0), Some(1));
+        assert_eq!(step_match(0, Some(1), 1), None);
+    }
+
+    #[test]
+    fn agent_chunks_coalesce_per_session() {
+        let chunk = |text: &str| ContentChunk::new(ContentBlock::from(text.to_string()));
+        let mut item = RenderedItem::AgentMessage(
+            "agent-msg-0".to_string(),
+            Rc::new(create_agent_message_data(chunk("Hel"), Some("s1"), None)),
+        );
+
+        assert!(item.can_accept_agent_message_chunk("s1"));
+        assert!(item.try_append_agent_message_chunk(chunk("lo")));
+        assert!(!item.can_accept_agent_message_chunk("s2"));
+        let RenderedItem::AgentMessage(_, data) = &item else {
+            unreachable!();
+        };
+        assert_eq!(data.chunks.len(), 2);
+    }
 
     #[test]
     fn test_index_tool_call_operations() {
@@ -802,4 +1597,146 @@ mod tests {
         assert_eq!(index.last_message(), None);
         assert_eq!(index.last_thought(), None);
     }
+
+    fn replay_turn(stream: &mut AcpMessageStream, cx: &mut Context<AcpMessageStream>) {
+        let updates = [
+            SessionUpdate::UserMessageChunk(ContentChunk::new(ContentBlock::from(
+                "hi".to_string(),
+            ))),
+            SessionUpdate::AgentMessageChunk(ContentChunk::new(ContentBlock::from(
+                "Let me look".to_string(),
+            ))),
+            SessionUpdate::ToolCall(ToolCall::new("call_1", "Read file")),
+            SessionUpdate::AgentMessageChunk(ContentChunk::new(ContentBlock::from(
+                "Done".to_string(),
+            ))),
+        ];
+        for update in updates {
+            stream.process_update(update, Some("sess"), None, cx);
+        }
+    }
+
+    #[gpui::test]
+    fn anchors_follow_items_not_positions(cx: &mut gpui::TestAppContext) {
+        let stream = cx.new(|_| AcpMessageStream::new());
+        let anchors = stream.update(cx, |stream, cx| {
+            replay_turn(stream, cx);
+            stream.anchors().to_vec()
+        });
+        assert_eq!(
+            anchors,
+            ["user-0", "agent-msg-1", "tool-call-call_1", "agent-msg-3"]
+        );
+
+        // Replayed after an item of its own, the turn sits one further down
+        // but its messages and tool call keep their anchors
+        let shifted = cx.new(|_| AcpMessageStream::new());
+        let shifted_anchors = shifted.update(cx, |stream, cx| {
+            stream.add_info_update("Resumed", cx);
+            replay_turn(stream, cx);
+            stream.anchors().to_vec()
+        });
+        assert_eq!(shifted_anchors.len(), anchors.len() + 1);
+        for anchor in ["agent-msg-1", "tool-call-call_1", "agent-msg-3"] {
+            let ix = anchor_position(&anchors, anchor).unwrap();
+            assert_eq!(anchor_position(&shifted_anchors, anchor), Some(ix + 1));
+        }
+    }
+
+    #[gpui::test]
+    fn opened_tool_call_stays_open_across_renders(cx: &mut gpui::TestAppContext) {
+        cx.update(gpui_component::init);
+        let (stream, cx) = cx.add_window_view(|_, _| AcpMessageStream::new());
+        let draw = |cx: &mut gpui::VisualTestContext| {
+            let view = stream.clone();
+            cx.draw(
+                gpui::point(px(0.), px(0.)),
+                gpui::size(px(800.), px(600.)),
+                |_, _| view,
+            );
+        };
+
+        let tool_call = stream.update(cx, |stream, cx| {
+            replay_turn(stream, cx);
+            let RenderedItem::ToolCall(tool_call) = &stream.items[2] else {
+                panic!("expected a tool call");
+            };
+            tool_call.update(cx, |item, cx| item.set_open(true, cx));
+            tool_call.clone()
+        });
+        draw(cx);
+
+        // Items appended later reuse the views already built
+        stream.update(cx, |stream, cx| {
+            let chunk = ContentChunk::new(ContentBlock::from("More".to_string()));
+            stream.process_update(
+                SessionUpdate::UserMessageChunk(chunk),
+                Some("sess"),
+                None,
+                cx,
+            );
+        });
+        draw(cx);
+        draw(cx);
+
+        stream.update(cx, |stream, cx| {
+            let RenderedItem::ToolCall(same) = &stream.items[2] else {
+                panic!("expected a tool call");
+            };
+            assert_eq!(same.entity_id(), tool_call.entity_id());
+            assert!(same.read(cx).open());
+        });
+    }
+
+    #[gpui::test]
+    fn only_the_current_turn_counts_as_generating(cx: &mut gpui::TestAppContext) {
+        let stream = cx.new(|_| AcpMessageStream::new());
+        let prompt = || {
+            SessionUpdate::UserMessageChunk(ContentChunk::new(ContentBlock::from("go".to_string())))
+        };
+        let (running, abandoned) = stream.update(cx, |stream, cx| {
+            let mut tool_call = ToolCall::new("call_1", "Run tests");
+            tool_call.status = ToolCallStatus::InProgress;
+            stream.process_update(prompt(), Some("sess"), None, cx);
+            stream.process_update(SessionUpdate::ToolCall(tool_call), Some("sess"), None, cx);
+            let running = stream.is_generating(cx);
+            stream.process_update(prompt(), Some("sess"), None, cx);
+            (running, stream.is_generating(cx))
+        });
+        assert!(running);
+        // The agent never finished the call, but the next prompt started a new turn
+        assert!(!abandoned);
+    }
+
+    #[test]
+    fn mode_separator_only_on_real_switches() {
+        // Initial mode report, before or during the conversation
+        assert!(!is_mid_conversation_switch(None, "code", true));
+        assert!(!is_mid_conversation_switch(None, "code", false));
+        // Repeated report of the same mode
+        assert!(!is_mid_conversation_switch(Some("code"), "code", false));
+        // Switch before anything was said
+        assert!(!is_mid_conversation_switch(Some("code"), "plan", true));
+
+        assert!(is_mid_conversation_switch(Some("code"), "plan", false));
+        assert_eq!(mode_display_name("plan"), "Plan");
+        assert_eq!(mode_display_name(""), "");
+    }
+
+    #[test]
+    fn retry_label_counts_attempts() {
+        let retry = RetryItem {
+            attempt: 2,
+            max_retries: 3,
+            message: "Request timed out after 60s".to_string(),
+        };
+        assert_eq!(
+            retry.label(true),
+            "Request timed out after 60s, retrying (2/3)…"
+        );
+        assert_eq!(
+            retry.label(false),
+            "Request timed out after 60s, retried (2/3)"
+        );
+    }
 }
@@ -0,0 +1,242 @@
+//! `ResourceLink` blocks in agent messages, rendered as links: files open in
+//! the host's editor, web pages in the system browser.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use agent_client_protocol::ResourceLink;
+use gpui::{
+    AnyElement, App, ElementId, InteractiveElement as _, IntoElement, ParentElement,
+    StatefulInteractiveElement as _, Styled, Window, div, prelude::FluentBuilder as _, px,
+};
+use gpui_component::{ActiveTheme, Icon, IconName, h_flex};
+
+/// Opens a linked local file (e.g. in the code editor)
+pub type ResourceOpenHandler = Arc<dyn Fn(PathBuf, &mut Window, &mut App) + Send + Sync>;
+
+/// Where a resource link leads, from its URI scheme
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ResourceTarget {
+    /// A `file://` URI or a bare path
+    File(PathBuf),
+    /// An `http(s)://` URL
+    Web(String),
+    /// Any other scheme, or a URI that doesn't parse; shown as text
+    Other,
+}
+
+impl ResourceTarget {
+    pub fn parse(uri: &str) -> Self {
+        let uri = uri.trim();
+        if let Some(rest) = uri.strip_prefix("file://") {
+            // Only local files: `file:///path` or `file://localhost/path`
+            let path = rest.strip_prefix("localhost").unwrap_or(rest);
+            return match percent_decode(path) {
+                Some(path) if path.starts_with('/') => {
+                    Self::File(PathBuf::from(strip_drive_slash(&path)))
+                }
+                _ => Self::Other,
+            };
+        }
+        for scheme in ["http://", "https://"] {
+            if let Some(rest) = uri.strip_prefix(scheme) {
+                let host = rest.split(['/', '?', '#']).next().unwrap_or_default();
+                return if host.is_empty() || uri.contains(char::is_whitespace) {
+                    Self::Other
+                } else {
+                    Self::Web(uri.to_string())
+                };
+            }
+        }
+        if uri.is_empty() || has_scheme(uri) {
+            Self::Other
+        } else {
+            Self::File(PathBuf::from(uri))
+        }
+    }
+}
+
+/// Whether `uri` starts with `scheme:`; a Windows drive (`C:\`) is a path
+fn has_scheme(uri: &str) -> bool {
+    let Some((scheme, _)) = uri.split_once(':') else {
+        return false;
+    };
+    scheme.len() > 1
+        && scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+}
+
+/// `C:/x` for the `/C:/x` path of a Windows `file:///C:/x` URI
+fn strip_drive_slash(path: &str) -> &str {
+    let bytes = path.as_bytes();
+    let is_drive = bytes.len() >= 3
+        && bytes[1].is_ascii_alphabetic()
+        && bytes[2] == b':'
+        && matches!(bytes.get(3), None | Some(b'/'));
+    if is_drive { &path[1..] } else { path }
+}
+
+/// Decode `%XX` escapes; `None` for a broken escape or non-UTF-8 result
+fn percent_decode(text: &str) -> Option<String> {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = std::str::from_utf8(bytes.get(i + 1..i + 3)?).ok()?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
+
+/// A resource link: icon, name and mime type badge. Files go to
+/// `on_open_file` (plain text without one), web pages to the browser; other
+/// URIs are shown as text.
+pub fn render_resource_link(
+    id: impl Into<ElementId>,
+    link: &ResourceLink,
+    on_open_file: Option<ResourceOpenHandler>,
+    cx: &App,
+) -> AnyElement {
+    let theme = cx.theme();
+    let name = if link.name.trim().is_empty() {
+        link.uri.clone()
+    } else {
+        link.name.clone()
+    };
+    let badge = link.mime_type.clone().map(|mime_type| {
+        div()
+            .flex_none()
+            .px_1()
+            .rounded(px(4.))
+            .bg(theme.muted)
+            .text_xs()
+            .text_color(theme.muted_foreground)
+            .child(mime_type)
+    });
+
+    let (icon, on_click): (IconName, Option<Box<dyn Fn(&mut Window, &mut App)>>) =
+        match ResourceTarget::parse(&link.uri) {
+            ResourceTarget::File(path) => (
+                IconName::File,
+                on_open_file.map(|on_open_file| {
+                    Box::new(move |window: &mut Window, cx: &mut App| {
+                        on_open_file(path.clone(), window, cx)
+                    }) as Box<dyn Fn(&mut Window, &mut App)>
+                }),
+            ),
+            ResourceTarget::Web(url) => (
+                IconName::Globe,
+                Some(
+                    Box::new(move |_: &mut Window, cx: &mut App| cx.open_url(&url))
+                        as Box<dyn Fn(&mut Window, &mut App)>,
+                ),
+            ),
+            ResourceTarget::Other => (IconName::File, None),
+        };
+
+    let row = h_flex()
+        .id(id)
+        .gap_1p5()
+        .items_center()
+        .max_w_full()
+        .text_sm()
+        .child(
+            Icon::new(icon)
+                .size(px(14.))
+                .flex_none()
+                .text_color(theme.muted_foreground),
+        );
+
+    match on_click {
+        Some(on_click) => row
+            .cursor_pointer()
+            .child(
+                div()
+                    .min_w_0()
+                    .truncate()
+                    .text_color(theme.blue)
+                    .hover(|this| this.underline())
+                    .child(name),
+            )
+            .when_some(badge, |this, badge| this.child(badge))
+            .on_click(move |_, window, cx| on_click(window, cx))
+            .into_any_element(),
+        None => row
+            .child(
+                div()
+                    .min_w_0()
+                    .truncate()
+                    .text_color(theme.muted_foreground)
+                    .child(link.uri.clone()),
+            )
+            .when_some(badge, |this, badge| this.child(badge))
+            .into_any_element(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn targets_follow_the_scheme() {
+        assert_eq!(
+            ResourceTarget::parse("file:///tmp/my%20notes.md"),
+            ResourceTarget::File(PathBuf::from("/tmp/my notes.md"))
+        );
+        assert_eq!(
+            ResourceTarget::parse("file://localhost/etc/hosts"),
+            ResourceTarget::File(PathBuf::from("/etc/hosts"))
+        );
+        assert_eq!(
+            ResourceTarget::parse("file:///C:/work/main.rs"),
+            ResourceTarget::File(PathBuf::from("C:/work/main.rs"))
+        );
+        assert_eq!(
+            ResourceTarget::parse("file:///d%3A/notes.md"),
+            ResourceTarget::File(PathBuf::from("d:/notes.md"))
+        );
+        assert_eq!(
+            ResourceTarget::parse("src/main.rs"),
+            ResourceTarget::File(PathBuf::from("src/main.rs"))
+        );
+        assert_eq!(
+            ResourceTarget::parse("C:\\work\\main.rs"),
+            ResourceTarget::File(PathBuf::from("C:\\work\\main.rs"))
+        );
+        assert_eq!(
+            ResourceTarget::parse("https://example.com/docs?q=1"),
+            ResourceTarget::Web("https://example.com/docs?q=1".to_string())
+        );
+        assert_eq!(
+            ResourceTarget::parse("mcp://server/resource"),
+            ResourceTarget::Other
+        );
+    }
+
+    #[test]
+    fn malformed_uris_are_not_links() {
+        assert_eq!(ResourceTarget::parse(""), ResourceTarget::Other);
+        assert_eq!(ResourceTarget::parse("https://"), ResourceTarget::Other);
+        assert_eq!(
+            ResourceTarget::parse("https://exa mple.com"),
+            ResourceTarget::Other
+        );
+        assert_eq!(
+            ResourceTarget::parse("file:///tmp/%zz"),
+            ResourceTarget::Other
+        );
+        assert_eq!(
+            ResourceTarget::parse("file://server/share"),
+            ResourceTarget::Other
+        );
+    }
+}
@@ -0,0 +1,193 @@
+//! Collapsing runs of consecutive tool calls of the same kind (an agent
+//! reading file after file) into one summary entry. The host app turns the
+//! grouping on as a gpui global; each group expands to its individual items.
+
+use std::ops::Range;
+
+use agent_client_protocol::{ToolCall, ToolCallStatus, ToolKind};
+use gpui::{
+    App, ElementId, Global, InteractiveElement as _, IntoElement, ParentElement, Stateful,
+    StatefulInteractiveElement as _, Styled, div, prelude::FluentBuilder as _, px,
+};
+use gpui_component::{ActiveTheme, Icon, IconName, h_flex};
+
+use crate::tool_call_item::tool_kind_icon;
+
+/// Shortest run of same-kind tool calls that gets collapsed
+pub const MIN_TOOL_CALL_GROUP: usize = 3;
+
+/// Whether runs of same-kind tool calls are collapsed into a summary entry
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct GroupToolCalls(pub bool);
+
+impl Global for GroupToolCalls {}
+
+impl GroupToolCalls {
+    /// Current setting (off if the host never set one)
+    pub fn global(cx: &App) -> bool {
+        cx.try_global::<GroupToolCalls>()
+            .copied()
+            .unwrap_or_default()
+            .0
+    }
+}
+
+/// Ranges of at least `min_len` adjacent entries with the same kind. `None`
+/// marks an entry that is not a tool call and ends any run.
+pub fn tool_call_runs<K: PartialEq>(kinds: &[Option<K>], min_len: usize) -> Vec<Range<usize>> {
+    let mut runs = Vec::new();
+    let mut start = 0;
+    for end in 1..=kinds.len() {
+        let continues = end < kinds.len() && kinds[end].is_some() && kinds[end] == kinds[start];
+        if continues {
+            continue;
+        }
+        if kinds[start].is_some() && end - start >= min_len.max(2) {
+            runs.push(start..end);
+        }
+        start = end;
+    }
+    runs
+}
+
+/// Kind `tool_call` is grouped by, or `None` while it runs so it stays on
+/// its own until it finishes
+pub fn group_kind(tool_call: &ToolCall) -> Option<ToolKind> {
+    match tool_call.status {
+        ToolCallStatus::Pending | ToolCallStatus::InProgress => None,
+        _ => Some(tool_call.kind.clone()),
+    }
+}
+
+/// Summary line for `count` tool calls of `kind`, e.g. "Read 8 files"
+pub fn group_label(kind: &ToolKind, count: usize) -> String {
+    match kind {
+        ToolKind::Read => format!("Read {} files", count),
+        ToolKind::Edit => format!("Edited {} files", count),
+        ToolKind::Delete => format!("Deleted {} files", count),
+        ToolKind::Move => format!("Moved {} files", count),
+        ToolKind::Search => format!("Ran {} searches", count),
+        ToolKind::Execute => format!("Ran {} commands", count),
+        ToolKind::Think => format!("Thought {} times", count),
+        ToolKind::Fetch => format!("Fetched {} URLs", count),
+        _ => format!("{} tool calls", count),
+    }
+}
+
+/// Clickable summary of a run of tool calls: icon, count, status and the
+/// first and last titles
+pub fn render_group_summary(
+    id: impl Into<ElementId>,
+    tool_calls: &[&ToolCall],
+    expanded: bool,
+    cx: &App,
+) -> Stateful<gpui::Div> {
+    let (Some(first), Some(last)) = (tool_calls.first(), tool_calls.last()) else {
+        return div().id(id);
+    };
+    let failed = tool_calls
+        .iter()
+        .filter(|tool_call| tool_call.status == ToolCallStatus::Failed)
+        .count();
+    let theme = cx.theme();
+
+    h_flex()
+        .id(id)
+        .w_full()
+        .gap_2()
+        .items_center()
+        .px_2()
+        .py_1()
+        .rounded(theme.radius)
+        .border_1()
+        .border_color(theme.border)
+        .bg(theme.muted.opacity(0.3))
+        .cursor_pointer()
+        .hover(|this| this.bg(theme.muted.opacity(0.6)))
+        .child(
+            tool_kind_icon(&first.kind)
+                .size(px(14.))
+                .text_color(theme.muted_foreground),
+        )
+        .child(
+            div()
+                .flex_none()
+                .text_size(px(13.))
+                .font_weight(gpui::FontWeight::MEDIUM)
+                .text_color(theme.foreground)
+                .child(group_label(&first.kind, tool_calls.len())),
+        )
+        .when(failed > 0, |this| {
+            this.child(
+                div()
+                    .flex_none()
+                    .text_xs()
+                    .text_color(theme.red)
+                    .child(format!("{} failed", failed)),
+            )
+        })
+        .child(
+            div()
+                .flex_1()
+                .min_w_0()
+                .overflow_hidden()
+                .whitespace_nowrap()
+                .text_ellipsis()
+                .text_xs()
+                .text_color(theme.muted_foreground)
+                .child(format!("{} … {}", first.title, last.title)),
+        )
+        .child(
+            Icon::new(if expanded {
+                IconName::ChevronUp
+            } else {
+                IconName::ChevronDown
+            })
+            .size(px(14.))
+            .text_color(theme.muted_foreground),
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn runs_need_adjacent_entries_of_one_kind() {
+        let (read, edit) = (Some("read"), Some("edit"));
+        let kinds = [
+            read, read, read, None, read, read, edit, edit, edit, edit, read,
+        ];
+        assert_eq!(tool_call_runs(&kinds, 3), vec![0..3, 6..10]);
+        assert_eq!(tool_call_runs(&kinds, 2), vec![0..3, 4..6, 6..10]);
+        assert_eq!(tool_call_runs(&[None::<&str>, None, None], 2), vec![]);
+        assert!(tool_call_runs::<&str>(&[], 3).is_empty());
+    }
+
+    #[test]
+    fn running_calls_are_never_grouped() {
+        let mut tool_call = ToolCall::new("tc-1", "Read main.rs");
+        tool_call.kind = ToolKind::Read;
+        assert_eq!(group_kind(&tool_call), None);
+        tool_call.status = ToolCallStatus::InProgress;
+        assert_eq!(group_kind(&tool_call), None);
+        tool_call.status = ToolCallStatus::Failed;
+        assert_eq!(group_kind(&tool_call), Some(ToolKind::Read));
+
+        // A running call splits the run it sits in
+        let read = Some(ToolKind::Read);
+        let kinds = [read.clone(), read.clone(), None, read.clone(), read];
+        assert!(
+            tool_call_runs(&kinds, 2)
+                .iter()
+                .all(|run| !run.contains(&2))
+        );
+    }
+
+    #[test]
+    fn labels_name_the_kind() {
+        assert_eq!(group_label(&ToolKind::Read, 8), "Read 8 files");
+        assert_eq!(group_label(&ToolKind::Execute, 3), "Ran 3 commands");
+        assert_eq!(group_label(&ToolKind::Other, 4), "4 tool calls");
+    }
+}
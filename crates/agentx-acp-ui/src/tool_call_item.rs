@@ -1,38 +1,109 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
 use std::sync::Arc;
+use std::time::Duration;
 
+use crate::code_wrap::{CodeWrap, wrap_toggle};
+use crate::content_renderer::ContentRenderers;
+use crate::diff_colors::DiffColors;
+use crate::diff_summary::FileChangeStats;
+use crate::diff_view::DiffView;
+use crate::large_content::{LargeContent, format_size};
+use crate::ui_scale::scaled;
+use crate::unsupported::render_unsupported;
+use crate::utils::{
+    extract_terminal_output, extract_xml_content, find_matches, highlight_matches, truncate_chars,
+    truncate_lines,
+};
 use agent_client_protocol::{
-    self as acp, ToolCall, ToolCallContent, ToolCallId, ToolCallStatus, ToolCallUpdateFields,
+    self as acp, Diff, ToolCall, ToolCallContent, ToolCallId, ToolCallStatus, ToolCallUpdateFields,
     ToolKind,
 };
 use gpui::{
-    AnyElement, App, AppContext, Context, Entity, IntoElement, ParentElement, Render, RenderOnce,
-    SharedString, Styled, Window, div, prelude::FluentBuilder as _, px,
+    AnyElement, App, AppContext, Context, Entity, InteractiveElement, IntoElement, KeyDownEvent,
+    ParentElement, Render, RenderOnce, SharedString, StatefulInteractiveElement, Styled,
+    Subscription, Task, Window, div, prelude::FluentBuilder as _, px,
 };
 use gpui_component::{
     ActiveTheme, Icon, IconName, Sizable,
     button::{Button, ButtonVariants},
     collapsible::Collapsible,
-    h_flex, v_flex,
+    h_flex,
+    input::{Input, InputEvent, InputState},
+    scroll::ScrollableElement as _,
+    v_flex,
 };
-use similar::{ChangeTag, TextDiff};
-
-use crate::diff_view::DiffView;
-use crate::utils::{extract_terminal_output, extract_xml_content, truncate_lines};
 
 pub type ToolCallDetailHandler =
     Arc<dyn Fn(ToolCall, &mut Window, &mut Context<ToolCallItem>) + Send + Sync>;
 
+/// Asks the host to write the new text of an agent-proposed edit to `path`;
+/// `new_text` is `None` when the edit deletes the file
+#[derive(Clone, Debug, PartialEq)]
+pub struct DiffApplyRequested {
+    pub path: PathBuf,
+    pub old_text: Option<String>,
+    pub new_text: Option<String>,
+}
+
+/// Asks the host to restore the old text at `path`; `old_text` is `None`
+/// when the edit created the file
+#[derive(Clone, Debug, PartialEq)]
+pub struct DiffRevertRequested {
+    pub path: PathBuf,
+    pub old_text: Option<String>,
+    pub new_text: String,
+}
+
+/// Shows the output of a terminal, by id, in the host's output panel
+pub type TerminalOpenHandler = Arc<dyn Fn(String, &mut Window, &mut App) + Send + Sync>;
+
+/// Writes an applied diff, resolving once the file is written
+pub type DiffApplyHandler = Arc<
+    dyn Fn(DiffApplyRequested, &mut Window, &mut App) -> Task<anyhow::Result<()>> + Send + Sync,
+>;
+/// Writes a reverted diff, resolving once the file is written
+pub type DiffRevertHandler = Arc<
+    dyn Fn(DiffRevertRequested, &mut Window, &mut App) -> Task<anyhow::Result<()>> + Send + Sync,
+>;
+
 #[derive(Clone)]
 pub struct ToolCallItemOptions {
     pub preview_max_lines: usize,
+    /// Maximum height of the expanded content area before it scrolls internally
+    pub max_content_height: f32,
+    /// Text output longer than this (in characters) is cut behind a "show full output" toggle
+    pub max_output_chars: usize,
+    /// Text output larger than this (in bytes) is moved to a temporary file and
+    /// loaded on request (0 keeps everything in memory)
+    pub max_inline_content_bytes: usize,
     pub on_open_detail: Option<ToolCallDetailHandler>,
+    /// Shows an "Open output" link under terminal content when set
+    pub on_open_terminal: Option<TerminalOpenHandler>,
+    /// Shows Apply/Revert buttons under diffs when set
+    pub on_apply_diff: Option<DiffApplyHandler>,
+    pub on_revert_diff: Option<DiffRevertHandler>,
+    /// Collapse the item when the tool call completes, unless the user
+    /// expanded or collapsed it by hand
+    pub auto_collapse_completed: bool,
+    /// How content blocks other than text are shown
+    pub content_renderers: Arc<ContentRenderers>,
 }
 
 impl Default for ToolCallItemOptions {
     fn default() -> Self {
         Self {
             preview_max_lines: 10,
+            max_content_height: 300.,
+            max_output_chars: 4000,
+            max_inline_content_bytes: 0,
             on_open_detail: None,
+            on_open_terminal: None,
+            on_apply_diff: None,
+            on_revert_diff: None,
+            auto_collapse_completed: false,
+            content_renderers: Arc::new(ContentRenderers::default()),
         }
     }
 }
@@ -43,60 +114,119 @@ impl ToolCallItemOptions {
         self
     }
 
+    pub fn max_content_height(mut self, height: f32) -> Self {
+        self.max_content_height = height;
+        self
+    }
+
+    pub fn max_output_chars(mut self, max_chars: usize) -> Self {
+        self.max_output_chars = max_chars;
+        self
+    }
+
+    pub fn max_inline_content_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_inline_content_bytes = max_bytes;
+        self
+    }
+
     pub fn on_open_detail(mut self, handler: ToolCallDetailHandler) -> Self {
         self.on_open_detail = Some(handler);
         self
     }
+
+    pub fn on_open_terminal(mut self, handler: TerminalOpenHandler) -> Self {
+        self.on_open_terminal = Some(handler);
+        self
+    }
+
+    pub fn on_apply_diff(mut self, handler: DiffApplyHandler) -> Self {
+        self.on_apply_diff = Some(handler);
+        self
+    }
+
+    pub fn on_revert_diff(mut self, handler: DiffRevertHandler) -> Self {
+        self.on_revert_diff = Some(handler);
+        self
+    }
+
+    pub fn auto_collapse_completed(mut self, auto_collapse: bool) -> Self {
+        self.auto_collapse_completed = auto_collapse;
+        self
+    }
+
+    pub fn content_renderers(mut self, content_renderers: Arc<ContentRenderers>) -> Self {
+        self.content_renderers = content_renderers;
+        self
+    }
 }
 
-/// Diff statistics
-#[derive(Debug, Clone, Default)]
-struct DiffStats {
-    additions: usize,
-    deletions: usize,
+/// Outputs get a wrap toggle once a line is longer than this (in characters)
+const WRAP_TOGGLE_MIN_LINE_CHARS: usize = 80;
+
+/// Queued updates repaint at most once per this interval (one 60 Hz frame)
+const COALESCE_INTERVAL: Duration = Duration::from_millis(16);
+
+/// What the user last did with a diff's Apply/Revert buttons
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DiffState {
+    Applying,
+    Applied,
+    Reverting,
+    Reverted,
 }
 
-/// Calculate diff statistics from old and new text
-fn calculate_diff_stats(old_text: &str, new_text: &str) -> DiffStats {
-    let diff = TextDiff::from_lines(old_text, new_text);
-    let mut additions = 0;
-    let mut deletions = 0;
-
-    for change in diff.iter_all_changes() {
-        match change.tag() {
-            ChangeTag::Insert => additions += 1,
-            ChangeTag::Delete => deletions += 1,
-            ChangeTag::Equal => {}
-        }
+impl DiffState {
+    fn is_writing(self) -> bool {
+        matches!(self, Self::Applying | Self::Reverting)
     }
+}
+
+/// Change stats of the first diff in a tool call, shown under its title
+fn extract_diff_stats_from_tool_call(tool_call: &ToolCall) -> Option<FileChangeStats> {
+    tool_call.content.iter().find_map(|content| match content {
+        ToolCallContent::Diff(diff) => Some(FileChangeStats::from_diff(
+            diff.path.clone(),
+            diff.old_text.as_deref(),
+            &diff.new_text,
+        )),
+        _ => None,
+    })
+}
 
-    DiffStats {
-        additions,
-        deletions,
+/// Text of a plain text content block
+fn content_text(content: &ToolCallContent) -> Option<&str> {
+    match content {
+        ToolCallContent::Content(c) => match &c.content {
+            acp::ContentBlock::Text(text) => Some(&text.text),
+            _ => None,
+        },
+        _ => None,
     }
 }
 
-/// Extract diff statistics from tool call content
-fn extract_diff_stats_from_tool_call(tool_call: &ToolCall) -> Option<DiffStats> {
-    // Find the first Diff content in the tool call
-    for content in &tool_call.content {
-        if let ToolCallContent::Diff(diff) = content {
-            return Some(match &diff.old_text {
-                Some(old_text) => calculate_diff_stats(old_text, &diff.new_text),
-                None => {
-                    // New file - all lines are additions
-                    DiffStats {
-                        additions: diff.new_text.lines().count(),
-                        deletions: 0,
-                    }
-                }
-            });
-        }
+/// Build the visible part of a text output.
+/// Returns the text to display and the number of characters left hidden.
+fn output_preview(text: &str, max_chars: usize, expanded: bool) -> (&str, usize) {
+    if expanded {
+        return (text, 0);
+    }
+
+    match truncate_chars(text, max_chars) {
+        (visible, true) => (visible, text[visible.len()..].chars().count()),
+        (visible, false) => (visible, 0),
     }
-    None
 }
 
-fn tool_kind_icon(kind: &ToolKind) -> Icon {
+/// Whether `query` matches past the preview of `text`, which then has to be
+/// shown in full for every counted match to be on screen
+fn matches_past_preview(text: &str, query: &str, max_chars: usize) -> bool {
+    let (visible, _) = output_preview(text, max_chars, false);
+    find_matches(text, query)
+        .iter()
+        .any(|range| range.end > visible.len())
+}
+
+pub(crate) fn tool_kind_icon(kind: &ToolKind) -> Icon {
     match kind {
         ToolKind::Read => Icon::new(IconName::Eye),
         ToolKind::Edit => Icon::new(IconName::Replace),
@@ -125,7 +255,32 @@ fn tool_call_status_icon(status: &ToolCallStatus) -> Icon {
 pub struct ToolCallItem {
     tool_call: ToolCall,
     open: bool,
+    /// The user expanded or collapsed the item; auto-collapse leaves it alone
+    toggled_by_user: bool,
+    /// Whether over-long text output is shown in full
+    show_full_output: bool,
+    /// Diffs applied or reverted from this item, by path
+    diff_states: HashMap<PathBuf, DiffState>,
+    /// Why the last write of a diff failed, by path
+    diff_errors: HashMap<PathBuf, SharedString>,
+    /// Diff contents shared with the rendered diff views, by content index.
+    /// Filled on render and dropped whenever the tool call changes.
+    diffs: HashMap<usize, Rc<Diff>>,
+    /// Text outputs kept on disk, by content index; the tool call holds their preview
+    large_contents: HashMap<usize, LargeContent>,
+    /// Text outputs toggled away from the `CodeWrap` default, by content index
+    wrap_overrides: HashMap<usize, bool>,
+    /// Whether the find bar above the output is shown
+    show_search: bool,
+    /// Find input, created the first time the find bar is shown
+    search_input: Option<Entity<InputState>>,
+    search_query: String,
+    /// Transcript search query, highlighted in the title
+    title_highlight: String,
+    /// A coalesced repaint is scheduled by `queue_updates`
+    notify_pending: bool,
     options: ToolCallItemOptions,
+    _subscriptions: Vec<Subscription>,
 }
 
 impl ToolCallItem {
@@ -134,19 +289,11 @@ impl ToolCallItem {
     }
 
     pub fn with_options(tool_call: ToolCall, options: ToolCallItemOptions) -> Self {
-        Self {
-            tool_call,
-            open: false,
-            options,
-        }
+        Self::with_options_and_open(tool_call, false, options)
     }
 
     pub fn new_with_open(tool_call: ToolCall, open: bool) -> Self {
-        Self {
-            tool_call,
-            open,
-            options: ToolCallItemOptions::default(),
-        }
+        Self::with_options_and_open(tool_call, open, ToolCallItemOptions::default())
     }
 
     pub fn with_options_and_open(
@@ -157,7 +304,20 @@ impl ToolCallItem {
         Self {
             tool_call,
             open,
+            toggled_by_user: false,
+            show_full_output: false,
+            diff_states: HashMap::new(),
+            diff_errors: HashMap::new(),
+            diffs: HashMap::new(),
+            large_contents: HashMap::new(),
+            wrap_overrides: HashMap::new(),
+            show_search: false,
+            search_input: None,
+            search_query: String::new(),
+            title_highlight: String::new(),
+            notify_pending: false,
             options,
+            _subscriptions: Vec::new(),
         }
     }
 
@@ -169,23 +329,202 @@ impl ToolCallItem {
         &self.tool_call.tool_call_id
     }
 
+    /// Whether the content area is expanded
+    pub fn open(&self) -> bool {
+        self.open
+    }
+
     /// Toggle the open state
     pub fn toggle(&mut self, cx: &mut Context<Self>) {
         self.open = !self.open;
+        self.toggled_by_user = true;
         cx.notify();
     }
 
+    /// Turn auto-collapse on or off for completions from now on
+    pub fn set_auto_collapse_completed(&mut self, auto_collapse: bool) {
+        self.options.auto_collapse_completed = auto_collapse;
+    }
+
+    /// Whether auto-collapse keeps this item closed: it is on, the tool call
+    /// completed, and the user hasn't toggled the item by hand
+    fn auto_collapses(&self) -> bool {
+        self.options.auto_collapse_completed
+            && !self.toggled_by_user
+            && self.tool_call.status == ToolCallStatus::Completed
+    }
+
     /// Set the open state
     pub fn set_open(&mut self, open: bool, cx: &mut Context<Self>) {
         self.open = open;
         cx.notify();
     }
 
+    /// Toggle between the truncated and the full text output
+    pub fn toggle_full_output(&mut self, cx: &mut Context<Self>) {
+        self.show_full_output = !self.show_full_output;
+        cx.notify();
+    }
+
+    fn search_input(&mut self, window: &mut Window, cx: &mut Context<Self>) -> Entity<InputState> {
+        if let Some(input) = &self.search_input {
+            return input.clone();
+        }
+
+        let input = cx.new(|cx| InputState::new(window, cx).placeholder("Find in output"));
+        self._subscriptions
+            .push(cx.subscribe(&input, |this, input, event: &InputEvent, cx| {
+                if let InputEvent::Change = event {
+                    this.search_query = input.read(cx).value().to_string();
+                    cx.notify();
+                }
+            }));
+        self.search_input = Some(input.clone());
+        input
+    }
+
+    /// Show the find bar (expanding the item) and focus it, or close it
+    pub fn toggle_search(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.show_search {
+            self.clear_search(window, cx);
+            return;
+        }
+        self.show_search = true;
+        self.open = true;
+        let input = self.search_input(window, cx);
+        input.update(cx, |state, cx| state.focus(window, cx));
+        cx.notify();
+    }
+
+    /// Drop the query and hide the find bar
+    pub fn clear_search(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.show_search = false;
+        self.search_query.clear();
+        if let Some(input) = &self.search_input {
+            input.update(cx, |state, cx| state.set_value("", window, cx));
+        }
+        cx.notify();
+    }
+
+    /// Highlight matches of the transcript search `query` in the title
+    pub fn set_title_highlight(&mut self, query: &str, cx: &mut Context<Self>) {
+        if self.title_highlight != query {
+            self.title_highlight = query.to_string();
+            cx.notify();
+        }
+    }
+
+    /// Whether the title as shown contains `query`
+    pub(crate) fn title_matches(&self, query: &str) -> bool {
+        !find_matches(&self.get_display_title(), query).is_empty()
+    }
+
+    /// Matches of the find query across this tool call's text outputs
+    fn search_match_count(&self) -> usize {
+        self.tool_call
+            .content
+            .iter()
+            .filter_map(content_text)
+            .map(|text| find_matches(text, &self.search_query).len())
+            .sum()
+    }
+
+    /// Record `state` for the diff at `path`; false if it is already in that
+    /// state, so a repeated click never writes the same edit twice
+    fn mark_diff(&mut self, path: &Path, state: DiffState) -> bool {
+        if self.diff_states.get(path) == Some(&state) {
+            return false;
+        }
+        self.diff_states.insert(path.to_path_buf(), state);
+        true
+    }
+
+    /// Ask the host to write the diff's new text
+    fn apply_diff(&mut self, diff: &Diff, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(handler) = self.options.on_apply_diff.clone() else {
+            return;
+        };
+        let previous = self.diff_states.get(&diff.path).copied();
+        if previous.is_some_and(DiffState::is_writing)
+            || !self.mark_diff(&diff.path, DiffState::Applying)
+        {
+            return;
+        }
+        let request = DiffApplyRequested {
+            path: diff.path.clone(),
+            old_text: diff.old_text.clone(),
+            new_text: Some(diff.new_text.clone()),
+        };
+        let write = handler(request, window, cx);
+        self.finish_diff_write(diff.path.clone(), write, DiffState::Applied, previous, cx);
+    }
+
+    /// Ask the host to restore the diff's old text
+    fn revert_diff(&mut self, diff: &Diff, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(handler) = self.options.on_revert_diff.clone() else {
+            return;
+        };
+        let previous = self.diff_states.get(&diff.path).copied();
+        if previous.is_some_and(DiffState::is_writing)
+            || !self.mark_diff(&diff.path, DiffState::Reverting)
+        {
+            return;
+        }
+        let request = DiffRevertRequested {
+            path: diff.path.clone(),
+            old_text: diff.old_text.clone(),
+            new_text: diff.new_text.clone(),
+        };
+        let write = handler(request, window, cx);
+        self.finish_diff_write(diff.path.clone(), write, DiffState::Reverted, previous, cx);
+    }
+
+    /// Mark the diff at `path` as `done` once `write` succeeds; if it fails,
+    /// go back to `previous` and show why
+    fn finish_diff_write(
+        &mut self,
+        path: PathBuf,
+        write: Task<anyhow::Result<()>>,
+        done: DiffState,
+        previous: Option<DiffState>,
+        cx: &mut Context<Self>,
+    ) {
+        self.diff_errors.remove(&path);
+        cx.notify();
+        cx.spawn(async move |this, cx| {
+            let result = write.await;
+            let _ = this.update(cx, |this, cx| {
+                match result {
+                    Ok(()) => {
+                        this.diff_states.insert(path, done);
+                    }
+                    Err(e) => {
+                        log::error!("Failed to write diff to {}: {:#}", path.display(), e);
+                        match previous {
+                            Some(state) => this.diff_states.insert(path.clone(), state),
+                            None => this.diff_states.remove(&path),
+                        };
+                        this.diff_errors.insert(path, format!("{:#}", e).into());
+                    }
+                }
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
     /// Update the tool call data
     pub fn update_tool_call(&mut self, tool_call: ToolCall, cx: &mut Context<Self>) {
         log::debug!("tool_call: {:?}", &tool_call);
+        let was_completed = self.tool_call.status == ToolCallStatus::Completed;
         self.tool_call = tool_call;
-        if self.has_content() {
+        self.diffs.clear();
+        self.offload_large_content(cx);
+        if self.auto_collapses() {
+            if !was_completed {
+                self.open = false;
+            }
+        } else if self.has_content() {
             self.open = true;
         }
         cx.notify();
@@ -194,10 +533,19 @@ impl ToolCallItem {
     /// Update this tool call with fields from a ToolCallUpdate
     pub fn apply_update(&mut self, update_fields: ToolCallUpdateFields, cx: &mut Context<Self>) {
         log::debug!("Applying update to tool call: {:?}", update_fields);
+        let was_completed = self.tool_call.status == ToolCallStatus::Completed;
         self.tool_call.update(update_fields);
+        self.diffs.clear();
+        self.offload_large_content(cx);
 
-        // Auto-open when tool call completes or fails (so user can see result)
+        // Auto-open when tool call completes or fails (so user can see result),
+        // unless completed calls auto-collapse
         match self.tool_call.status {
+            _ if self.auto_collapses() => {
+                if !was_completed {
+                    self.open = false;
+                }
+            }
             ToolCallStatus::Completed | ToolCallStatus::Failed => {
                 if self.has_content() {
                     self.open = true;
@@ -211,13 +559,40 @@ impl ToolCallItem {
 
     /// Update the status
     pub fn update_status(&mut self, status: ToolCallStatus, cx: &mut Context<Self>) {
-        self.tool_call.status = status;
+        self.apply_updates(|tool_call| tool_call.status = status, cx);
+    }
+
+    /// Mutate the tool call in place and repaint once
+    pub fn apply_updates(&mut self, f: impl FnOnce(&mut ToolCall), cx: &mut Context<Self>) {
+        f(&mut self.tool_call);
+        self.diffs.clear();
+        self.offload_large_content(cx);
         cx.notify();
     }
 
+    /// Like [`Self::apply_updates`], but for high-frequency streams: changes
+    /// take effect immediately while repaints are coalesced to one per frame
+    pub fn queue_updates(&mut self, f: impl FnOnce(&mut ToolCall), cx: &mut Context<Self>) {
+        f(&mut self.tool_call);
+        self.diffs.clear();
+        self.offload_large_content(cx);
+        if std::mem::replace(&mut self.notify_pending, true) {
+            return;
+        }
+        cx.spawn(async move |this, cx| {
+            cx.background_executor().timer(COALESCE_INTERVAL).await;
+            let _ = this.update(cx, |this, cx| {
+                this.notify_pending = false;
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
     /// Add content to the tool call
     pub fn add_content(&mut self, content: ToolCallContent, cx: &mut Context<Self>) {
         self.tool_call.content.push(content);
+        self.offload_large_content(cx);
         cx.notify();
     }
 
@@ -225,6 +600,88 @@ impl ToolCallItem {
         !self.tool_call.content.is_empty()
     }
 
+    /// Move text outputs over `max_inline_content_bytes` to temporary files,
+    /// leaving their preview in the tool call. The files are written in the
+    /// background.
+    pub fn offload_large_content(&mut self, cx: &App) {
+        let contents = &self.tool_call.content;
+        self.large_contents.retain(|ix, large| {
+            contents
+                .get(*ix)
+                .and_then(content_text)
+                .is_some_and(|text| large.matches(text))
+        });
+
+        let max_bytes = self.options.max_inline_content_bytes;
+        for (ix, content) in self.tool_call.content.iter_mut().enumerate() {
+            let ToolCallContent::Content(c) = content else {
+                continue;
+            };
+            let acp::ContentBlock::Text(text) = &mut c.content else {
+                continue;
+            };
+            if self.large_contents.contains_key(&ix)
+                || !LargeContent::should_offload(&text.text, max_bytes)
+            {
+                continue;
+            }
+            let large = LargeContent::offload(
+                std::mem::take(&mut text.text),
+                LargeContent::default_dir(),
+                cx.background_executor(),
+            );
+            text.text = large.preview().to_string();
+            self.large_contents.insert(ix, large);
+        }
+    }
+
+    /// Read an offloaded output back into the tool call
+    fn load_large_content(&mut self, ix: usize, cx: &mut Context<Self>) {
+        let Some(large) = self.large_contents.get_mut(&ix) else {
+            return;
+        };
+        let path = large.path().to_path_buf();
+        let write = large.take_write();
+        cx.spawn(async move |this, cx| {
+            if let Some(write) = write
+                && let Err(e) = write.await
+            {
+                log::error!("Failed to load tool output: {}", e);
+                return;
+            }
+            let result = cx
+                .background_executor()
+                .spawn(async move { LargeContent::read(&path) })
+                .await;
+            let _ = this.update(cx, |this, cx| match result {
+                Ok(full_text) => {
+                    if let Some(large) = this.large_contents.get_mut(&ix)
+                        && let Some(ToolCallContent::Content(c)) =
+                            this.tool_call.content.get_mut(ix)
+                        && let acp::ContentBlock::Text(text) = &mut c.content
+                    {
+                        large.mark_loaded();
+                        text.text = full_text;
+                        cx.notify();
+                    }
+                }
+                Err(e) => log::error!("Failed to load tool output: {}", e),
+            });
+        })
+        .detach();
+    }
+
+    /// Share each diff's content once, so frames don't copy file texts
+    fn cache_diffs(&mut self) {
+        for (ix, content) in self.tool_call.content.iter().enumerate() {
+            if let ToolCallContent::Diff(diff) = content {
+                self.diffs
+                    .entry(ix)
+                    .or_insert_with(|| Rc::new(diff.clone()));
+            }
+        }
+    }
+
     /// Get formatted display title for the tool call
     /// For Read tools, formats as: filename#L<offset>-<offset+limit>
     /// For other tools, returns the original title
@@ -256,45 +713,330 @@ impl ToolCallItem {
         self.tool_call.title.clone()
     }
 
+    /// Flip wrapping of the text output at `ix`
+    fn toggle_wrap(&mut self, ix: usize, cx: &mut Context<Self>) {
+        let wrapped = self
+            .wrap_overrides
+            .get(&ix)
+            .copied()
+            .unwrap_or_else(|| CodeWrap::global(cx));
+        self.wrap_overrides.insert(ix, !wrapped);
+        cx.notify();
+    }
+
+    /// Render a block of text output, cut at `max_output_chars` unless the
+    /// find query matches past the cut. It soft-wraps, or scrolls
+    /// horizontally when wrapping is toggled off.
+    fn render_text_output(&self, ix: usize, text: String, cx: &mut Context<Self>) -> AnyElement {
+        let max_chars = self.options.max_output_chars;
+        let revealed_by_search = matches_past_preview(&text, &self.search_query, max_chars);
+        let is_long = truncate_chars(&text, max_chars).1 && !revealed_by_search;
+        let (visible, hidden_chars) = output_preview(
+            &text,
+            max_chars,
+            self.show_full_output || revealed_by_search,
+        );
+        let has_long_lines = visible
+            .lines()
+            .any(|line| line.chars().count() > WRAP_TOGGLE_MIN_LINE_CHARS);
+        let wrap = self
+            .wrap_overrides
+            .get(&ix)
+            .copied()
+            .unwrap_or_else(|| CodeWrap::global(cx));
+        let id = &self.tool_call.tool_call_id;
+
+        let output = div()
+            .text_size(scaled(12., cx))
+            .text_color(cx.theme().muted_foreground)
+            .line_height(scaled(18., cx))
+            .child(highlight_matches(visible, &self.search_query, cx));
+
+        v_flex()
+            .w_full()
+            .min_w(px(0.))
+            .gap_1()
+            .map(|this| {
+                if wrap {
+                    this.child(output.w_full().min_w(px(0.)).whitespace_normal())
+                } else {
+                    this.child(
+                        div()
+                            .id(SharedString::from(format!(
+                                "tool-call-{}-output-{}",
+                                id, ix
+                            )))
+                            .w_full()
+                            .overflow_x_scroll()
+                            .child(output.flex_none().whitespace_nowrap()),
+                    )
+                }
+            })
+            .when(is_long || has_long_lines, |this| {
+                this.child(
+                    h_flex()
+                        .w_full()
+                        .items_center()
+                        .when(is_long, |this| {
+                            let label = if self.show_full_output {
+                                "Show less".to_string()
+                            } else {
+                                format!("Show full output ({} more characters)", hidden_chars)
+                            };
+                            this.child(
+                                Button::new(SharedString::from(format!(
+                                    "tool-call-{}-full-output-{}",
+                                    id, ix
+                                )))
+                                .label(label)
+                                .ghost()
+                                .xsmall()
+                                .on_click(cx.listener(
+                                    |this, _ev, _window, cx| {
+                                        this.toggle_full_output(cx);
+                                    },
+                                )),
+                            )
+                        })
+                        .child(div().flex_1())
+                        .when(has_long_lines, |this| {
+                            this.child(
+                                wrap_toggle(
+                                    SharedString::from(format!("tool-call-{}-wrap-{}", id, ix)),
+                                    wrap,
+                                )
+                                .on_click(cx.listener(
+                                    move |this, _ev, _window, cx| {
+                                        this.toggle_wrap(ix, cx);
+                                    },
+                                )),
+                            )
+                        }),
+                )
+            })
+            .into_any_element()
+    }
+
+    /// Render the find input and match count; Escape clears and closes it
+    fn render_search_bar(&mut self, window: &mut Window, cx: &mut Context<Self>) -> AnyElement {
+        let input = self.search_input(window, cx);
+        let match_count = self.search_match_count();
+
+        h_flex()
+            .w_full()
+            .gap_1()
+            .items_center()
+            .child(
+                div()
+                    .flex_1()
+                    .capture_key_down(cx.listener(|this, event: &KeyDownEvent, window, cx| {
+                        if event.keystroke.key == "escape" {
+                            this.clear_search(window, cx);
+                            cx.stop_propagation();
+                        }
+                    }))
+                    .child(Input::new(&input).xsmall()),
+            )
+            .when(!self.search_query.is_empty(), |this| {
+                this.child(
+                    div()
+                        .flex_shrink_0()
+                        .text_size(scaled(11., cx))
+                        .text_color(cx.theme().muted_foreground)
+                        .child(match match_count {
+                            1 => "1 match".to_string(),
+                            count => format!("{} matches", count),
+                        }),
+                )
+            })
+            .child(
+                Button::new(SharedString::from(format!(
+                    "tool-call-{}-close-search",
+                    self.tool_call.tool_call_id
+                )))
+                .icon(IconName::Close)
+                .ghost()
+                .xsmall()
+                .on_click(cx.listener(|this, _ev, window, cx| {
+                    this.clear_search(window, cx);
+                })),
+            )
+            .into_any_element()
+    }
+
+    /// Render the Apply/Revert buttons under a diff, or its applied/reverted state
+    fn render_diff_actions(
+        &self,
+        ix: usize,
+        diff: &Rc<Diff>,
+        cx: &mut Context<Self>,
+    ) -> AnyElement {
+        let state = self.diff_states.get(&diff.path).copied();
+        let writing = state.is_some_and(DiffState::is_writing);
+        let error = self.diff_errors.get(&diff.path).cloned();
+        let id = &self.tool_call.tool_call_id;
+
+        h_flex()
+            .w_full()
+            .gap_1()
+            .justify_end()
+            .items_center()
+            .when_some(error, |this, error| {
+                this.child(
+                    div()
+                        .flex_1()
+                        .min_w_0()
+                        .px_1()
+                        .truncate()
+                        .text_size(scaled(12., cx))
+                        .text_color(cx.theme().red)
+                        .child(error),
+                )
+            })
+            .when_some(state, |this, state| {
+                let (label, color) = match state {
+                    DiffState::Applying => ("Applying…", cx.theme().muted_foreground),
+                    DiffState::Applied => ("Applied ✓", cx.theme().green),
+                    DiffState::Reverting => ("Reverting…", cx.theme().muted_foreground),
+                    DiffState::Reverted => ("Reverted", cx.theme().muted_foreground),
+                };
+                this.child(
+                    div()
+                        .px_1()
+                        .text_size(scaled(12., cx))
+                        .text_color(color)
+                        .child(label),
+                )
+            })
+            .when(
+                self.options.on_apply_diff.is_some()
+                    && !writing
+                    && state != Some(DiffState::Applied),
+                |this| {
+                    let diff = Rc::clone(diff);
+                    this.child(
+                        Button::new(SharedString::from(format!("tool-call-{}-apply-{}", id, ix)))
+                            .label("Apply")
+                            .ghost()
+                            .xsmall()
+                            .on_click(cx.listener(move |this, _ev, window, cx| {
+                                this.apply_diff(&diff, window, cx);
+                            })),
+                    )
+                },
+            )
+            .when(
+                self.options.on_revert_diff.is_some()
+                    && !writing
+                    && state != Some(DiffState::Reverted),
+                |this| {
+                    let diff = Rc::clone(diff);
+                    this.child(
+                        Button::new(SharedString::from(format!(
+                            "tool-call-{}-revert-{}",
+                            id, ix
+                        )))
+                        .label("Revert")
+                        .ghost()
+                        .xsmall()
+                        .on_click(cx.listener(
+                            move |this, _ev, window, cx| {
+                                this.revert_diff(&diff, window, cx);
+                            },
+                        )),
+                    )
+                },
+            )
+            .into_any_element()
+    }
+
     /// Render content based on type
     fn render_content(
         &self,
+        ix: usize,
         content: &ToolCallContent,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) -> AnyElement {
         match content {
             ToolCallContent::Diff(diff) => {
+                let diff = self
+                    .diffs
+                    .get(&ix)
+                    .cloned()
+                    .unwrap_or_else(|| Rc::new(diff.clone()));
                 // Use DiffView component for diff content, limited to 10 lines
-                let diff_view = DiffView::new(diff.clone())
+                let diff_view = DiffView::new(Rc::clone(&diff))
+                    .id(format!("{}-{}", self.tool_call.tool_call_id, ix))
                     .max_lines(8)
                     .context_lines(1)
                     .show_file_header(false); // Hide file header in compact view
 
-                diff_view.render(window, cx).into_any_element()
+                let has_actions =
+                    self.options.on_apply_diff.is_some() || self.options.on_revert_diff.is_some();
+                v_flex()
+                    .w_full()
+                    .gap_1()
+                    .child(diff_view.render(window, cx))
+                    .when(has_actions && !diff.path.as_os_str().is_empty(), |this| {
+                        this.child(self.render_diff_actions(ix, &diff, cx))
+                    })
+                    .into_any_element()
             }
-            ToolCallContent::Content(c) => match &c.content {
-                acp::ContentBlock::Text(text) => {
-                    let cleaned_text = extract_xml_content(&text.text, &self.tool_call.kind);
-                    let display_text = if cleaned_text.lines().count() > 20 {
-                        let max_lines = self.options.preview_max_lines;
-                        truncate_lines(&cleaned_text, max_lines)
-                    } else {
-                        cleaned_text
-                    };
-                    div()
-                        .text_size(px(12.))
-                        .text_color(cx.theme().muted_foreground)
-                        .line_height(px(18.))
-                        .child(display_text)
-                        .into_any_element()
+            ToolCallContent::Content(c) => {
+                match &c.content {
+                    acp::ContentBlock::Text(text) => {
+                        let cleaned_text = extract_xml_content(&text.text, &self.tool_call.kind);
+                        let display_text = if cleaned_text.lines().count() > 20 {
+                            let max_lines = self.options.preview_max_lines;
+                            truncate_lines(&cleaned_text, max_lines)
+                        } else {
+                            cleaned_text
+                        };
+                        let output = self.render_text_output(ix, display_text, cx);
+                        match self.large_contents.get(&ix) {
+                            Some(large) if !large.is_loaded() => v_flex()
+                                .w_full()
+                                .gap_1()
+                                .child(output)
+                                .child(
+                                    Button::new(SharedString::from(format!(
+                                        "tool-call-{}-load-{}",
+                                        self.tool_call.tool_call_id, ix
+                                    )))
+                                    .label(format!("Load full ({})", format_size(large.size())))
+                                    .ghost()
+                                    .xsmall()
+                                    .on_click(cx.listener(move |this, _ev, _window, cx| {
+                                        this.load_large_content(ix, cx);
+                                    })),
+                                )
+                                .into_any_element(),
+                            _ => output,
+                        }
+                    }
+                    block => self
+                        .options
+                        .content_renderers
+                        .render(
+                            SharedString::from(format!(
+                                "tool-call-{}-content-{}",
+                                self.tool_call.tool_call_id, ix
+                            )),
+                            block,
+                            cx,
+                        )
+                        .unwrap_or_else(|| {
+                            render_unsupported(
+                                SharedString::from(format!(
+                                    "tool-call-{}-unsupported-{}",
+                                    self.tool_call.tool_call_id, ix
+                                )),
+                                serde_json::to_value(block).unwrap_or_default(),
+                            )
+                        }),
                 }
-                _ => div()
-                    .text_size(px(12.))
-                    .text_color(cx.theme().muted_foreground)
-                    .child("Unsupported content type")
-                    .into_any_element(),
-            },
+            }
             ToolCallContent::Terminal(terminal) => {
                 let max_lines = self.options.preview_max_lines;
                 let output = extract_terminal_output(terminal).and_then(|text| {
@@ -311,25 +1053,49 @@ impl ToolCallItem {
                     }
                     None => format!("Terminal: {}", terminal.terminal_id),
                 };
-                div()
-                    .text_size(px(12.))
-                    .text_color(cx.theme().muted_foreground)
-                    .line_height(px(18.))
-                    .child(display_text)
+                let terminal_id = terminal.terminal_id.to_string();
+                v_flex()
+                    .w_full()
+                    .gap_1()
+                    .child(self.render_text_output(ix, display_text, cx))
+                    .when_some(self.options.on_open_terminal.clone(), |this, on_open| {
+                        this.child(
+                            Button::new(SharedString::from(format!(
+                                "tool-call-{}-open-terminal-{}",
+                                self.tool_call.tool_call_id, ix
+                            )))
+                            .icon(IconName::SquareTerminal)
+                            .label("Open output")
+                            .ghost()
+                            .xsmall()
+                            .on_click(move |_, window, cx| {
+                                on_open(terminal_id.clone(), window, cx);
+                            }),
+                        )
+                    })
                     .into_any_element()
             }
-            _ => div()
-                .text_size(px(12.))
-                .text_color(cx.theme().muted_foreground)
-                .child("Unknown content type")
-                .into_any_element(),
+            unknown => render_unsupported(
+                SharedString::from(format!(
+                    "tool-call-{}-unsupported-{}",
+                    self.tool_call.tool_call_id, ix
+                )),
+                serde_json::to_value(unknown).unwrap_or_default(),
+            ),
         }
     }
 }
 
 impl Render for ToolCallItem {
     fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        self.cache_diffs();
         let has_content = self.has_content();
+        let searchable = self
+            .tool_call
+            .content
+            .iter()
+            .any(|c| content_text(c).is_some());
+        let search_bar = self.show_search.then(|| self.render_search_bar(window, cx));
         let status_color = match self.tool_call.status {
             ToolCallStatus::Completed => cx.theme().green,
             ToolCallStatus::Failed => cx.theme().red,
@@ -345,6 +1111,7 @@ impl Render for ToolCallItem {
 
         // Extract diff stats if this is a diff tool call
         let diff_stats = extract_diff_stats_from_tool_call(&self.tool_call);
+        let diff_colors = DiffColors::from_theme(cx);
         let detail_handler = self.options.on_open_detail.clone();
 
         Collapsible::new()
@@ -360,46 +1127,55 @@ impl Render for ToolCallItem {
                     .bg(cx.theme().secondary)
                     .child(
                         kind_icon
-                            .size(px(16.))
+                            .size(scaled(16., cx))
                             .text_color(cx.theme().muted_foreground),
                     )
                     .child(
-                        div()
+                        v_flex()
                             .flex_1()
                             .min_w(px(0.))
-                            .text_size(px(13.))
-                            .text_color(cx.theme().foreground)
-                            .line_height(px(18.))
-                            .whitespace_normal()
-                            .child(title),
-                    )
-                    // Show diff stats if available
-                    .when_some(diff_stats, |this, stats| {
-                        this.child(
-                            h_flex()
-                                .gap_1()
-                                .items_center()
-                                .child(
-                                    // Additions
-                                    div()
-                                        .text_size(px(11.))
-                                        .font_weight(gpui::FontWeight::MEDIUM)
-                                        .text_color(cx.theme().green)
-                                        .child(format!("+{}", stats.additions)),
+                            .child(
+                                div()
+                                    .text_size(scaled(13., cx))
+                                    .text_color(cx.theme().foreground)
+                                    .line_height(scaled(18., cx))
+                                    .whitespace_normal()
+                                    .child(highlight_matches(&title, &self.title_highlight, cx)),
+                            )
+                            // Diffs get a "path +added −deleted" subtitle
+                            .when_some(diff_stats, |this, stats| {
+                                this.child(
+                                    h_flex()
+                                        .gap_1()
+                                        .items_center()
+                                        .min_w(px(0.))
+                                        .text_size(scaled(11., cx))
+                                        .child(
+                                            div()
+                                                .min_w(px(0.))
+                                                .truncate()
+                                                .text_color(cx.theme().muted_foreground)
+                                                .child(stats.path.display().to_string()),
+                                        )
+                                        .child(
+                                            div()
+                                                .flex_shrink_0()
+                                                .font_weight(gpui::FontWeight::MEDIUM)
+                                                .text_color(diff_colors.added_fg)
+                                                .child(format!("+{}", stats.additions)),
+                                        )
+                                        .child(
+                                            div()
+                                                .flex_shrink_0()
+                                                .font_weight(gpui::FontWeight::MEDIUM)
+                                                .text_color(diff_colors.deleted_fg)
+                                                .child(format!("\u{2212}{}", stats.deletions)),
+                                        ),
                                 )
-                                .child(
-                                    // Deletions
-                                    div()
-                                        .text_size(px(11.))
-                                        .font_weight(gpui::FontWeight::MEDIUM)
-                                        .text_color(cx.theme().red)
-                                        .child(format!("-{}", stats.deletions)),
-                                ),
-                        )
-                    })
-                    .child(status_icon.size(px(14.)).text_color(status_color))
+                            }),
+                    )
+                    .child(status_icon.size(scaled(14., cx)).text_color(status_color))
                     .when(has_content, |this| {
-                        let tool_call_clone_for_detail = self.tool_call.clone();
                         let detail_handler = detail_handler.clone();
                         this.child(
                             h_flex()
@@ -420,6 +1196,23 @@ impl Render for ToolCallItem {
                                         this.toggle(cx);
                                     })),
                                 )
+                                .when(searchable, |this| {
+                                    this.child(
+                                        Button::new(SharedString::from(format!(
+                                            "tool-call-{}-search",
+                                            tool_call_id
+                                        )))
+                                        .icon(IconName::Search)
+                                        .ghost()
+                                        .xsmall()
+                                        .selected(self.show_search)
+                                        .on_click(
+                                            cx.listener(|this, _ev, window, cx| {
+                                                this.toggle_search(window, cx);
+                                            }),
+                                        ),
+                                    )
+                                })
                                 .when(detail_handler.is_some(), |this| {
                                     let tool_call_id = tool_call_id.clone();
                                     this.child(
@@ -431,13 +1224,9 @@ impl Render for ToolCallItem {
                                         .ghost()
                                         .xsmall()
                                         .on_click(
-                                            cx.listener(move |_, _ev, window, cx| {
+                                            cx.listener(move |this, _ev, window, cx| {
                                                 if let Some(handler) = detail_handler.as_ref() {
-                                                    handler(
-                                                        tool_call_clone_for_detail.clone(),
-                                                        window,
-                                                        cx,
-                                                    );
+                                                    handler(this.tool_call.clone(), window, cx);
                                                 }
                                             }),
                                         ),
@@ -449,20 +1238,31 @@ impl Render for ToolCallItem {
             // Content - only visible when open and has content
             .when(has_content, |this| {
                 this.content(
-                    v_flex().gap_2().pl_8().children(
-                        self.tool_call
-                            .content
-                            .iter()
-                            .map(|content| self.render_content(content, window, cx)),
-                    ),
+                    v_flex()
+                        .w_full()
+                        .min_w(px(0.))
+                        .gap_2()
+                        .pl_8()
+                        .children(search_bar)
+                        .children(
+                            self.tool_call
+                                .content
+                                .iter()
+                                .enumerate()
+                                .map(|(ix, content)| self.render_content(ix, content, window, cx)),
+                        )
+                        .max_h(px(self.options.max_content_height))
+                        .overflow_y_scrollbar(),
                 )
-                .max_h(px(300.))
                 .overflow_hidden()
             })
     }
 }
 
-/// A stateful wrapper for ToolCallItem that can be used as a GPUI view
+/// A stateful wrapper for ToolCallItem that can be used as a GPUI view.
+///
+/// The view renders the inner item entity as-is, so updates only notify the
+/// item; notifying the wrapper as well would repaint twice.
 pub struct ToolCallItemView {
     item: Entity<ToolCallItem>,
 }
@@ -487,12 +1287,43 @@ impl ToolCallItemView {
         })
     }
 
+    /// Create a view that starts expanded or collapsed
+    pub fn new_with_open(
+        tool_call: ToolCall,
+        open: bool,
+        _window: &mut Window,
+        cx: &mut App,
+    ) -> Entity<Self> {
+        cx.new(|cx| {
+            let item = cx.new(|_| ToolCallItem::new_with_open(tool_call, open));
+            Self { item }
+        })
+    }
+
+    /// Create a view with custom options that starts expanded or collapsed
+    pub fn with_options_and_open(
+        tool_call: ToolCall,
+        open: bool,
+        options: ToolCallItemOptions,
+        _window: &mut Window,
+        cx: &mut App,
+    ) -> Entity<Self> {
+        cx.new(|cx| {
+            let item = cx.new(|_| ToolCallItem::with_options_and_open(tool_call, open, options));
+            Self { item }
+        })
+    }
+
+    /// Whether the content area is expanded
+    pub fn open(&self, cx: &App) -> bool {
+        self.item.read(cx).open()
+    }
+
     /// Update the tool call data
     pub fn update_tool_call(&mut self, tool_call: ToolCall, cx: &mut Context<Self>) {
         self.item.update(cx, |item, cx| {
             item.update_tool_call(tool_call, cx);
         });
-        cx.notify();
     }
 
     /// Update this tool call with fields from a ToolCallUpdate
@@ -500,7 +1331,6 @@ impl ToolCallItemView {
         self.item.update(cx, |item, cx| {
             item.apply_update(update_fields, cx);
         });
-        cx.notify();
     }
 
     /// Update the status
@@ -508,7 +1338,6 @@ impl ToolCallItemView {
         self.item.update(cx, |item, cx| {
             item.update_status(status, cx);
         });
-        cx.notify();
     }
 
     /// Add content to the tool call
@@ -516,7 +1345,6 @@ impl ToolCallItemView {
         self.item.update(cx, |item, cx| {
             item.add_content(content, cx);
         });
-        cx.notify();
     }
 
     /// Set content for the tool call
@@ -525,7 +1353,6 @@ impl ToolCallItemView {
             item.tool_call.content = content;
             cx.notify();
         });
-        cx.notify();
     }
 
     /// Toggle the open state
@@ -533,7 +1360,6 @@ impl ToolCallItemView {
         self.item.update(cx, |item, cx| {
             item.toggle(cx);
         });
-        cx.notify();
     }
 
     /// Set the open state
@@ -541,7 +1367,6 @@ impl ToolCallItemView {
         self.item.update(cx, |item, cx| {
             item.set_open(open, cx);
         });
-        cx.notify();
     }
 }
 
@@ -557,6 +1382,29 @@ mod tests {
     use agent_client_protocol::{Diff, ToolCallContent, ToolCallLocation};
     use std::path::PathBuf;
 
+    #[test]
+    fn only_untouched_completed_calls_auto_collapse() {
+        let options = ToolCallItemOptions::default().auto_collapse_completed(true);
+        let mut tool_call = ToolCall::new("tc-1", "cargo test");
+        tool_call.status = ToolCallStatus::Completed;
+
+        let mut item = ToolCallItem::with_options_and_open(tool_call, true, options.clone());
+        assert!(item.auto_collapses());
+        // Expanded by hand after collapsing: stays the user's choice
+        item.toggled_by_user = true;
+        assert!(!item.auto_collapses());
+
+        let mut failed = ToolCall::new("tc-2", "cargo build");
+        failed.status = ToolCallStatus::Failed;
+        let item = ToolCallItem::with_options_and_open(failed, true, options);
+        assert!(!item.auto_collapses());
+
+        let mut completed = ToolCall::new("tc-3", "ls");
+        completed.status = ToolCallStatus::Completed;
+        let item = ToolCallItem::with_options_and_open(completed, true, Default::default());
+        assert!(!item.auto_collapses());
+    }
+
     #[test]
     fn display_title_formats_read_with_ranges() {
         let mut tool_call = ToolCall::new("tc-1", "Read file");
@@ -572,6 +1420,115 @@ mod tests {
         assert_eq!(item.get_display_title(), "Read (test.txt#L5-L14)");
     }
 
+    #[test]
+    fn output_preview_bounds_long_single_line() {
+        let text = "x".repeat(50_000);
+        let (visible, hidden) = output_preview(&text, 4000, false);
+        assert_eq!(visible.len(), 4000);
+        assert_eq!(hidden, 46_000);
+
+        let (visible, hidden) = output_preview(&text, 4000, true);
+        assert_eq!(visible.len(), 50_000);
+        assert_eq!(hidden, 0);
+    }
+
+    #[test]
+    fn find_reveals_matches_past_the_preview() {
+        let text = format!("start{}needle", "x".repeat(5000));
+        assert!(matches_past_preview(&text, "needle", 4000));
+        assert!(!matches_past_preview(&text, "start", 4000));
+        assert!(!matches_past_preview(&text, "", 4000));
+        assert!(!matches_past_preview("short needle", "needle", 4000));
+    }
+
+    #[test]
+    fn open_state_is_set_at_construction() {
+        let mut failed = ToolCall::new("tc-4", "Run tests");
+        failed.status = ToolCallStatus::Failed;
+
+        assert!(!ToolCallItem::new(failed.clone()).open());
+        assert!(ToolCallItem::new_with_open(failed.clone(), true).open());
+        assert!(
+            ToolCallItem::with_options_and_open(failed, true, ToolCallItemOptions::default())
+                .open()
+        );
+    }
+
+    #[test]
+    fn applying_twice_is_ignored() {
+        let mut item = ToolCallItem::new(ToolCall::new("tc-3", "Edit file"));
+        let path = PathBuf::from("src/lib.rs");
+
+        assert!(item.mark_diff(&path, DiffState::Applied));
+        assert!(!item.mark_diff(&path, DiffState::Applied));
+        assert!(item.mark_diff(&path, DiffState::Reverted));
+        assert!(item.mark_diff(&path, DiffState::Applied));
+        assert!(item.mark_diff(Path::new("src/main.rs"), DiffState::Applied));
+    }
+
+    #[gpui::test]
+    fn diffs_are_applied_only_once_written(cx: &mut gpui::TestAppContext) {
+        let item = cx.new(|_| ToolCallItem::new(ToolCall::new("tc-4", "Edit file")));
+        let written = PathBuf::from("src/lib.rs");
+        let denied = PathBuf::from("/etc/hosts");
+
+        item.update(cx, |item, cx| {
+            for (path, write) in [
+                (&written, Task::ready(Ok(()))),
+                (
+                    &denied,
+                    Task::ready(Err(anyhow::anyhow!("permission denied"))),
+                ),
+            ] {
+                item.mark_diff(path, DiffState::Applying);
+                item.finish_diff_write(path.clone(), write, DiffState::Applied, None, cx);
+            }
+        });
+        cx.run_until_parked();
+
+        item.update(cx, |item, _| {
+            assert_eq!(item.diff_states.get(&written), Some(&DiffState::Applied));
+            assert!(!item.diff_errors.contains_key(&written));
+            // A failed write is pending again, with the reason shown
+            assert_eq!(item.diff_states.get(&denied), None);
+            assert_eq!(
+                item.diff_errors.get(&denied).map(|e| e.as_ref()),
+                Some("permission denied")
+            );
+        });
+    }
+
+    #[gpui::test]
+    fn oversized_output_is_kept_on_disk(cx: &mut gpui::TestAppContext) {
+        let big = "x".repeat(200_000);
+        let mut tool_call = ToolCall::new("tc-5", "Read file");
+        tool_call.content = vec![
+            ToolCallContent::from(acp::ContentBlock::from("small".to_string())),
+            ToolCallContent::from(acp::ContentBlock::from(big.clone())),
+        ];
+
+        let mut kept = ToolCallItem::new(tool_call.clone());
+        cx.update(|cx| kept.offload_large_content(cx));
+        assert!(kept.large_contents.is_empty());
+
+        let options = ToolCallItemOptions::default().max_inline_content_bytes(100_000);
+        let mut item = ToolCallItem::with_options(tool_call, options);
+        cx.update(|cx| item.offload_large_content(cx));
+        cx.run_until_parked();
+        let large = &item.large_contents[&1];
+        assert_eq!(large.size(), big.len());
+        assert_eq!(
+            content_text(&item.tool_call.content[1]),
+            Some(large.preview())
+        );
+        assert_eq!(content_text(&item.tool_call.content[0]), Some("small"));
+        assert_eq!(LargeContent::read(large.path()).unwrap(), big);
+
+        let path = large.path().to_path_buf();
+        drop(item);
+        assert!(!path.exists());
+    }
+
     #[test]
     fn diff_stats_handles_new_files() {
         let diff = Diff::new("file.txt", "line1\nline2".to_string());
@@ -582,4 +1539,22 @@ mod tests {
         assert_eq!(stats.additions, 2);
         assert_eq!(stats.deletions, 0);
     }
+
+    #[test]
+    fn diff_stats_come_from_the_first_diff() {
+        let mut tool_call = ToolCall::new("tc-4", "Edit file");
+        assert!(extract_diff_stats_from_tool_call(&tool_call).is_none());
+
+        tool_call.content = vec![
+            ToolCallContent::from(acp::ContentBlock::from("Applied edit".to_string())),
+            ToolCallContent::Diff(
+                Diff::new("src/foo.rs", "a\nB\nc\nd\n".to_string())
+                    .old_text("a\nb\nc\n".to_string()),
+            ),
+            ToolCallContent::Diff(Diff::new("src/bar.rs", "x".to_string())),
+        ];
+        let stats = extract_diff_stats_from_tool_call(&tool_call).unwrap();
+        assert_eq!(stats.path, PathBuf::from("src/foo.rs"));
+        assert_eq!((stats.additions, stats.deletions), (2, 1));
+    }
 }
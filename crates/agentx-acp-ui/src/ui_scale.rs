@@ -0,0 +1,63 @@
+//! Global UI scale factor for the fixed text and icon sizes used by components.
+
+use gpui::{App, Global, Pixels, px};
+
+pub const DEFAULT_UI_SCALE: f32 = 1.0;
+pub const MIN_UI_SCALE: f32 = 0.75;
+pub const MAX_UI_SCALE: f32 = 2.0;
+
+/// UI scale factor, set as a gpui global by the host app
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UiScale(f32);
+
+impl Global for UiScale {}
+
+impl Default for UiScale {
+    fn default() -> Self {
+        Self(DEFAULT_UI_SCALE)
+    }
+}
+
+impl UiScale {
+    /// Create a scale factor, clamped to `MIN_UI_SCALE..=MAX_UI_SCALE`
+    pub fn new(factor: f32) -> Self {
+        Self(clamp_ui_scale(factor))
+    }
+
+    pub fn factor(&self) -> f32 {
+        self.0
+    }
+
+    /// Current scale factor (1.0 if the host never set one)
+    pub fn global(cx: &App) -> f32 {
+        cx.try_global::<UiScale>()
+            .map_or(DEFAULT_UI_SCALE, UiScale::factor)
+    }
+}
+
+/// Clamp a scale factor to the supported range, treating NaN/inf as the default
+pub fn clamp_ui_scale(factor: f32) -> f32 {
+    if factor.is_finite() {
+        factor.clamp(MIN_UI_SCALE, MAX_UI_SCALE)
+    } else {
+        DEFAULT_UI_SCALE
+    }
+}
+
+/// Scale a base pixel size by the global UI scale factor
+pub fn scaled(base: f32, cx: &App) -> Pixels {
+    px(base * UiScale::global(cx))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scale_factor_is_clamped() {
+        assert_eq!(UiScale::new(0.5).factor(), MIN_UI_SCALE);
+        assert_eq!(UiScale::new(3.0).factor(), MAX_UI_SCALE);
+        assert_eq!(UiScale::new(1.25).factor(), 1.25);
+        assert_eq!(UiScale::new(f32::NAN).factor(), DEFAULT_UI_SCALE);
+    }
+}
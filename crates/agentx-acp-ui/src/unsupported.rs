@@ -0,0 +1,145 @@
+//! Fallback for content and updates this version doesn't understand: a
+//! warning badge with the raw JSON behind an expander, so what the agent sent
+//! can still be inspected.
+
+use gpui::{
+    AnyElement, App, ElementId, InteractiveElement as _, IntoElement, ParentElement, RenderOnce,
+    SharedString, StatefulInteractiveElement as _, Styled, Window, div,
+    prelude::FluentBuilder as _, px,
+};
+use gpui_component::{ActiveTheme, Icon, IconName, h_flex, v_flex};
+
+use crate::utils::truncate_chars;
+
+/// Raw JSON beyond this many characters is cut (blobs can be megabytes)
+const MAX_RAW_CHARS: usize = 4000;
+
+/// Badge for an unknown variant, expandable to its raw JSON
+#[derive(IntoElement)]
+pub struct UnsupportedContent {
+    id: ElementId,
+    raw: serde_json::Value,
+    label: SharedString,
+}
+
+impl UnsupportedContent {
+    pub fn new(id: impl Into<ElementId>, raw: serde_json::Value) -> Self {
+        Self {
+            id: id.into(),
+            raw,
+            label: "Unsupported content (update your client)".into(),
+        }
+    }
+
+    /// Say why the content is shown raw, instead of asking for a newer client
+    pub fn label(mut self, label: impl Into<SharedString>) -> Self {
+        self.label = label.into();
+        self
+    }
+}
+
+/// The one fallback for unknown content blocks, tool call content and
+/// session updates
+pub fn render_unsupported(id: impl Into<ElementId>, raw: serde_json::Value) -> AnyElement {
+    UnsupportedContent::new(id, raw).into_any_element()
+}
+
+/// Pretty-printed `raw`, cut to `MAX_RAW_CHARS`
+fn raw_json(raw: &serde_json::Value) -> String {
+    let json = serde_json::to_string_pretty(raw).unwrap_or_else(|_| raw.to_string());
+    let (shown, truncated) = truncate_chars(&json, MAX_RAW_CHARS);
+    if truncated {
+        format!("{}\n…", shown)
+    } else {
+        shown.to_string()
+    }
+}
+
+impl RenderOnce for UnsupportedContent {
+    fn render(self, window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let expanded = window.use_keyed_state(
+            SharedString::from(format!("{}-expanded", self.id)),
+            cx,
+            |_, _| false,
+        );
+        let is_expanded = *expanded.read(cx);
+        let theme = cx.theme();
+        let yellow = theme.yellow;
+
+        v_flex()
+            .w_full()
+            .gap_1()
+            .p_2()
+            .rounded(theme.radius)
+            .border_1()
+            .border_color(yellow.opacity(0.5))
+            .bg(yellow.opacity(0.08))
+            .child(
+                h_flex()
+                    .id(self.id)
+                    .gap_2()
+                    .items_center()
+                    .cursor_pointer()
+                    .on_click(move |_, _, cx| {
+                        expanded.update(cx, |expanded, cx| {
+                            *expanded = !*expanded;
+                            cx.notify();
+                        });
+                    })
+                    .child(
+                        Icon::new(IconName::TriangleAlert)
+                            .size(px(14.))
+                            .text_color(yellow),
+                    )
+                    .child(
+                        div()
+                            .flex_1()
+                            .text_xs()
+                            .font_weight(gpui::FontWeight::MEDIUM)
+                            .text_color(theme.foreground)
+                            .child(self.label),
+                    )
+                    .child(
+                        Icon::new(if is_expanded {
+                            IconName::ChevronUp
+                        } else {
+                            IconName::ChevronDown
+                        })
+                        .size(px(14.))
+                        .text_color(theme.muted_foreground),
+                    ),
+            )
+            .when(is_expanded, |this| {
+                this.child(
+                    div()
+                        .w_full()
+                        .p_2()
+                        .rounded(theme.radius)
+                        .bg(theme.muted.opacity(0.5))
+                        .font_family("Monaco, 'Courier New', monospace")
+                        .text_xs()
+                        .text_color(theme.muted_foreground)
+                        .child(raw_json(&self.raw)),
+                )
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn raw_json_is_pretty_and_capped() {
+        let raw = serde_json::json!({ "sessionUpdate": "future_thing", "value": 1 });
+        assert_eq!(
+            raw_json(&raw),
+            "{\n  \"sessionUpdate\": \"future_thing\",\n  \"value\": 1\n}"
+        );
+
+        let big = serde_json::Value::String("x".repeat(MAX_RAW_CHARS * 2));
+        let shown = raw_json(&big);
+        assert!(shown.ends_with('…'));
+        assert_eq!(shown.chars().count(), MAX_RAW_CHARS + 2);
+    }
+}
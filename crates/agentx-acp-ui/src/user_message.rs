@@ -1,18 +1,77 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::SystemTime;
+
 use agent_client_protocol::{
-    ContentBlock, EmbeddedResource, EmbeddedResourceResource, ResourceLink, SessionId,
-    TextResourceContents,
+    BlobResourceContents, ContentBlock, EmbeddedResource, EmbeddedResourceResource, ResourceLink,
+    SessionId, TextResourceContents,
 };
 use gpui::{
-    App, AppContext, Context, ElementId, Entity, IntoElement, ParentElement, Render, RenderOnce,
-    SharedString, Styled, Window, div, prelude::FluentBuilder as _, px,
+    AnyElement, App, AppContext, Context, Div, ElementId, Entity, Hsla, Image, ImageFormat,
+    InteractiveElement as _, IntoElement, ParentElement, Render, RenderOnce, SharedString,
+    StatefulInteractiveElement as _, Styled, Window, div, img, linear_color_stop, linear_gradient,
+    prelude::FluentBuilder as _, px, relative,
 };
 use gpui_component::{
     ActiveTheme, Icon, IconName, Sizable,
     button::{Button, ButtonVariants},
     collapsible::Collapsible,
-    h_flex, v_flex,
+    h_flex,
+    tooltip::Tooltip,
+    v_flex,
 };
 
+use crate::agent_message::{MESSAGE_HOVER_GROUP, render_copy_button, render_message_time};
+use crate::audio_chip::render_audio_chip;
+use crate::avatar::Avatar;
+use crate::content_renderer::ContentRenderers;
+use crate::utils::{decode_base64_content, highlight_matches, truncate_chars, truncate_lines};
+
+/// Lines kept in the preview of a collapsed user message block
+const USER_MESSAGE_PREVIEW_LINES: usize = 10;
+/// Blocks longer than this collapse even if they have few lines
+const USER_MESSAGE_PREVIEW_CHARS: usize = 1200;
+/// Lines shown in the hover preview of a resource
+const RESOURCE_PREVIEW_LINES: usize = 8;
+
+/// Preview of a long user message block, or `None` if it is short enough to show in full
+fn collapsed_preview(text: &str) -> Option<String> {
+    let too_many_lines = text.lines().nth(USER_MESSAGE_PREVIEW_LINES).is_some();
+    let (prefix, too_long) = truncate_chars(text, USER_MESSAGE_PREVIEW_CHARS);
+    if !too_many_lines && !too_long {
+        return None;
+    }
+    Some(truncate_lines(prefix, USER_MESSAGE_PREVIEW_LINES))
+}
+
+/// Gradient that fades the bottom of a collapsed preview into `background`
+fn render_preview_fade(background: Hsla) -> impl IntoElement {
+    div()
+        .absolute()
+        .left_0()
+        .right_0()
+        .bottom_0()
+        .h(px(36.))
+        .bg(linear_gradient(
+            180.,
+            linear_color_stop(background.opacity(0.), 0.),
+            linear_color_stop(background, 1.),
+        ))
+}
+
+/// "Show more" / "Show less" toggle for a collapsed block
+fn render_show_more_button(id: impl Into<ElementId>, expanded: bool) -> Button {
+    Button::new(id)
+        .label(if expanded { "Show less" } else { "Show more" })
+        .icon(if expanded {
+            IconName::ChevronUp
+        } else {
+            IconName::ChevronDown
+        })
+        .ghost()
+        .xsmall()
+}
+
 /// User message data structure based on ACP's PromptRequest format
 #[derive(Clone, Debug)]
 pub struct UserMessageData {
@@ -20,6 +79,8 @@ pub struct UserMessageData {
     pub session_id: SessionId,
     /// Message content blocks (following ACP ContentBlock format)
     pub contents: Vec<ContentBlock>,
+    /// When the message was sent, if its chunk's `_meta` says
+    pub timestamp: Option<SystemTime>,
 }
 
 impl UserMessageData {
@@ -27,9 +88,15 @@ impl UserMessageData {
         Self {
             session_id: session_id.into(),
             contents: Vec::new(),
+            timestamp: None,
         }
     }
 
+    pub fn with_timestamp(mut self, timestamp: Option<SystemTime>) -> Self {
+        self.timestamp = timestamp;
+        self
+    }
+
     pub fn with_contents(mut self, contents: Vec<ContentBlock>) -> Self {
         self.contents = contents;
         self
@@ -72,27 +139,45 @@ impl UserMessageData {
     }
 }
 
-/// Helper to extract display information from ContentBlock
+/// Display information for a content block that refers to a resource.
+///
+/// `ResourceLink` and embedded `Resource` blocks (text and blob) yield the same
+/// chip for the same URI: links keep their own name unless it is empty,
+/// embedded resources are named after the last URI segment. Text, image and
+/// audio blocks are not resources and yield `None`.
 pub fn get_resource_info(content: &ContentBlock) -> Option<ResourceInfo> {
     match content {
-        ContentBlock::ResourceLink(link) => Some(ResourceInfo {
-            uri: link.uri.clone().into(),
-            name: link.name.clone().into(),
-            mime_type: link.mime_type.clone().map(|s| s.into()),
-            text: None,
-        }),
+        ContentBlock::ResourceLink(link) => {
+            let name = if link.name.trim().is_empty() {
+                extract_filename(&link.uri)
+            } else {
+                link.name.clone()
+            };
+            Some(ResourceInfo {
+                uri: link.uri.clone().into(),
+                name: name.into(),
+                mime_type: link.mime_type.clone().map(|s| s.into()),
+                kind: ResourceKind::Link,
+                text: None,
+                blob: None,
+            })
+        }
         ContentBlock::Resource(embedded) => match &embedded.resource {
             EmbeddedResourceResource::TextResourceContents(text_res) => Some(ResourceInfo {
                 uri: text_res.uri.clone().into(),
                 name: extract_filename(&text_res.uri).into(),
                 mime_type: text_res.mime_type.clone().map(|s| s.into()),
+                kind: ResourceKind::Text,
                 text: Some(text_res.text.clone().into()),
+                blob: None,
             }),
             EmbeddedResourceResource::BlobResourceContents(blob_res) => Some(ResourceInfo {
                 uri: blob_res.uri.clone().into(),
                 name: extract_filename(&blob_res.uri).into(),
                 mime_type: blob_res.mime_type.clone().map(|s| s.into()),
+                kind: ResourceKind::Blob,
                 text: None, // Blob content is not displayable as text
+                blob: Some(blob_res.blob.clone().into()),
             }),
             // Handle future variants
             _ => None,
@@ -101,22 +186,105 @@ pub fn get_resource_info(content: &ContentBlock) -> Option<ResourceInfo> {
     }
 }
 
-/// Extract filename from URI
+/// Last path segment of a URI, ignoring a trailing slash, query and fragment;
+/// the whole URI if there is no usable segment
 fn extract_filename(uri: &str) -> String {
-    uri.split('/').next_back().unwrap_or("unknown").to_string()
+    let path = uri.split(['?', '#']).next().unwrap_or(uri);
+    path.trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .filter(|name| !name.is_empty() && !name.ends_with(':'))
+        .unwrap_or(uri)
+        .to_string()
+}
+
+/// One `ResourceItem` per resource block in `contents`, in order
+fn resource_items(contents: &[ContentBlock], cx: &mut App) -> Vec<Entity<ResourceItem>> {
+    contents
+        .iter()
+        .filter_map(get_resource_info)
+        .map(|resource_info| cx.new(|_| ResourceItem::new(resource_info)))
+        .collect()
+}
+
+/// Which kind of content block a resource came from
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ResourceKind {
+    /// A `ResourceLink`, referenced by URI only
+    Link,
+    /// Embedded `TextResourceContents`, previewed and expanded inline
+    Text,
+    /// Embedded `BlobResourceContents`, offered for saving, never rendered as text
+    Blob,
+}
+
+impl ResourceKind {
+    /// Whether the resource can be expanded to show its contents inline
+    pub fn is_expandable(self) -> bool {
+        self == Self::Text
+    }
+
+    /// Whether the resource offers a save action for its decoded bytes
+    pub fn is_saveable(self) -> bool {
+        self == Self::Blob
+    }
 }
 
 /// Resource information for display
+#[derive(Clone)]
 pub struct ResourceInfo {
     pub uri: SharedString,
     pub name: SharedString,
     pub mime_type: Option<SharedString>,
+    pub kind: ResourceKind,
     pub text: Option<SharedString>,
+    /// Base64 contents of a blob resource
+    pub blob: Option<SharedString>,
 }
 
 impl ResourceInfo {
-    /// Get icon based on MIME type
+    /// First lines of the resource text, for the hover preview
+    pub fn preview_text(&self) -> Option<String> {
+        self.text
+            .as_ref()
+            .map(|text| truncate_lines(text, RESOURCE_PREVIEW_LINES))
+    }
+
+    /// Decoded bytes of a blob resource; `None` if missing or not valid base64
+    pub fn blob_bytes(&self) -> Option<Vec<u8>> {
+        match decode_base64_content(self.blob.as_deref()?) {
+            Ok(bytes) => Some(bytes),
+            Err(e) => {
+                log::warn!("Resource {}: {:#}", self.uri, e);
+                None
+            }
+        }
+    }
+
+    /// Whether the resource is a blob with an image MIME type
+    fn is_image(&self) -> bool {
+        self.kind == ResourceKind::Blob
+            && self
+                .mime_type
+                .as_deref()
+                .is_some_and(|mime| mime.starts_with("image/"))
+    }
+
+    /// Decode an image blob into a thumbnail, if the resource is a supported image
+    fn thumbnail(&self) -> Option<Arc<Image>> {
+        let format = ImageFormat::from_mime_type(self.mime_type.as_deref()?)?;
+        Some(Arc::new(Image::from_bytes(format, self.blob_bytes()?)))
+    }
+
+    /// Get icon based on resource kind and MIME type
     fn icon(&self) -> IconName {
+        if self.kind == ResourceKind::Blob {
+            return if self.is_image() {
+                IconName::Frame
+            } else {
+                IconName::Inbox
+            };
+        }
         if let Some(ref mime) = self.mime_type {
             if mime.contains("python")
                 || mime.contains("javascript")
@@ -135,16 +303,110 @@ impl ResourceInfo {
 pub struct ResourceItem {
     resource: ResourceInfo,
     open: bool,
+    /// Whether long content is shown in full instead of as a preview
+    show_full: bool,
+    /// Decoded image for the hover preview of image blobs
+    thumbnail: Option<Arc<Image>>,
 }
 
 impl ResourceItem {
     pub fn new(resource: ResourceInfo) -> Self {
+        let thumbnail = resource.thumbnail();
         Self {
             resource,
             open: false,
+            show_full: false,
+            thumbnail,
         }
     }
 
+    /// Hover preview: text excerpt, image thumbnail, or URI and MIME type
+    fn render_preview(
+        resource: &ResourceInfo,
+        thumbnail: Option<Arc<Image>>,
+        cx: &App,
+    ) -> impl IntoElement {
+        v_flex()
+            .gap_1()
+            .max_w(px(420.))
+            .child(
+                div()
+                    .text_size(px(12.))
+                    .font_weight(gpui::FontWeight::MEDIUM)
+                    .child(resource.name.clone()),
+            )
+            .child(
+                div()
+                    .text_size(px(11.))
+                    .text_color(cx.theme().muted_foreground)
+                    .child(resource.uri.clone()),
+            )
+            .when_some(resource.mime_type.clone(), |this, mime| {
+                this.child(
+                    div()
+                        .text_size(px(11.))
+                        .text_color(cx.theme().muted_foreground)
+                        .child(mime),
+                )
+            })
+            .when_some(resource.preview_text(), |this, text| {
+                this.child(
+                    div()
+                        .pt_1()
+                        .text_size(px(11.))
+                        .font_family("Monaco, 'Courier New', monospace")
+                        .line_height(px(16.))
+                        .child(text),
+                )
+            })
+            .map(|this| match thumbnail {
+                Some(image) => this.child(img(image).max_w(px(240.)).max_h(px(160.))),
+                None if resource.is_image() => this.child(
+                    div()
+                        .text_size(px(11.))
+                        .text_color(cx.theme().red)
+                        .child("Invalid image data"),
+                ),
+                None => this,
+            })
+    }
+
+    /// Ask where to save a blob resource and write its decoded bytes there
+    pub fn save_blob(&mut self, cx: &mut Context<Self>) {
+        let Some(bytes) = self.resource.blob_bytes() else {
+            log::warn!("Resource {} has no decodable blob", self.resource.uri);
+            return;
+        };
+        let file_name = self.resource.name.to_string();
+
+        cx.spawn(async move |_this, cx| {
+            let Some(file) = rfd::AsyncFileDialog::new()
+                .set_file_name(&file_name)
+                .save_file()
+                .await
+            else {
+                return;
+            };
+            let path = file.path().to_path_buf();
+            cx.background_spawn(async move {
+                match std::fs::write(&path, bytes) {
+                    Ok(()) => log::info!("Saved resource {} to {:?}", file_name, path),
+                    Err(e) => {
+                        log::error!("Failed to save resource {} to {:?}: {}", file_name, path, e)
+                    }
+                }
+            })
+            .await;
+        })
+        .detach();
+    }
+
+    /// Toggle between the preview and the full content
+    pub fn toggle_full(&mut self, cx: &mut Context<Self>) {
+        self.show_full = !self.show_full;
+        cx.notify();
+    }
+
     /// Toggle the open/close state
     pub fn toggle(&mut self, cx: &mut Context<Self>) {
         self.open = !self.open;
@@ -168,16 +430,42 @@ impl Render for ResourceItem {
             .unwrap_or(0);
 
         let is_open = self.open;
-        let has_content = self.resource.text.is_some();
+        let kind = self.resource.kind;
+        // Blobs never render inline; their base64 is only decoded for saving
+        let has_content = kind.is_expandable() && self.resource.text.is_some();
         let resource_name = self.resource.name.clone();
+        let full_text = self.resource.text.clone().unwrap_or_default();
+        let preview = collapsed_preview(&full_text);
+        let is_collapsible = preview.is_some();
+        let show_full = self.show_full;
+        let display_text: SharedString = match preview {
+            Some(preview) if !show_full => preview.into(),
+            _ => full_text,
+        };
 
         Collapsible::new()
             .open(is_open)
             .w_full()
             .gap_2()
-            // Header - with toggle button
+            // Header - with toggle button, previewing the resource on hover
             .child(
                 h_flex()
+                    .id(SharedString::from(format!(
+                        "resource-header-{}",
+                        resource_name
+                    )))
+                    .tooltip({
+                        let resource = self.resource.clone();
+                        let thumbnail = self.thumbnail.clone();
+                        move |window, cx| {
+                            let resource = resource.clone();
+                            let thumbnail = thumbnail.clone();
+                            Tooltip::element(move |_, cx| {
+                                Self::render_preview(&resource, thumbnail.clone(), cx)
+                            })
+                            .build(window, cx)
+                        }
+                    })
                     .items_center()
                     .gap_2()
                     .p_2()
@@ -206,6 +494,23 @@ impl Render for ResourceItem {
                                 .child(format!("{} lines", line_count)),
                         )
                     })
+                    .when(kind.is_saveable(), |this| {
+                        this.child(
+                            Button::new(SharedString::from(format!(
+                                "resource-save-{}",
+                                resource_name
+                            )))
+                            .label("Save")
+                            .icon(IconName::ArrowDown)
+                            .ghost()
+                            .xsmall()
+                            .on_click(cx.listener(
+                                |this, _ev, _window, cx| {
+                                    this.save_blob(cx);
+                                },
+                            )),
+                        )
+                    })
                     .when(has_content, |this| {
                         // Add toggle button only if there's content
                         this.child(
@@ -231,21 +536,47 @@ impl Render for ResourceItem {
             // Content - code display (only if we have text)
             .when(has_content, |this| {
                 this.content(
-                    div()
+                    v_flex()
                         .w_full()
-                        .p_3()
-                        .rounded(cx.theme().radius)
-                        .bg(cx.theme().secondary)
-                        .border_1()
-                        .border_color(cx.theme().border)
+                        .gap_1()
                         .child(
                             div()
-                                .text_size(px(12.))
-                                .font_family("Monaco, 'Courier New', monospace")
-                                .text_color(cx.theme().foreground)
-                                .line_height(px(18.))
-                                .child(self.resource.text.clone().unwrap_or_default()),
-                        ),
+                                .relative()
+                                .w_full()
+                                .p_3()
+                                .rounded(cx.theme().radius)
+                                .bg(cx.theme().secondary)
+                                .border_1()
+                                .border_color(cx.theme().border)
+                                .overflow_hidden()
+                                .child(
+                                    div()
+                                        .text_size(px(12.))
+                                        .font_family("Monaco, 'Courier New', monospace")
+                                        .text_color(cx.theme().foreground)
+                                        .line_height(px(18.))
+                                        .child(display_text),
+                                )
+                                .when(is_collapsible && !show_full, |this| {
+                                    this.child(render_preview_fade(cx.theme().secondary))
+                                }),
+                        )
+                        .when(is_collapsible, |this| {
+                            this.child(
+                                render_show_more_button(
+                                    SharedString::from(format!(
+                                        "resource-show-more-{}",
+                                        resource_name
+                                    )),
+                                    show_full,
+                                )
+                                .on_click(cx.listener(
+                                    |this, _ev, _window, cx| {
+                                        this.toggle_full(cx);
+                                    },
+                                )),
+                            )
+                        }),
                 )
             })
     }
@@ -329,73 +660,120 @@ impl UserMessage {
 
 impl RenderOnce for UserMessage {
     fn render(self, _: &mut Window, cx: &mut App) -> impl IntoElement {
+        let copy_button = render_copy_button(
+            SharedString::from(format!("{}-copy", self.id)),
+            ContentRenderers::default().plain_text(&self.data.contents),
+        );
+
         v_flex()
-            .gap_3()
+            .group(MESSAGE_HOVER_GROUP)
+            .gap_1()
             .w_full()
-            // User icon and label
+            .items_end()
+            .child(render_user_header(copy_button, self.data.timestamp, cx))
+            // Message content
             .child(
-                h_flex()
-                    .items_center()
-                    .gap_2()
-                    .child(
-                        Icon::new(IconName::User)
-                            .size(px(16.))
-                            .text_color(cx.theme().accent),
-                    )
-                    .child(
-                        div()
-                            .text_size(px(13.))
-                            .font_weight(gpui::FontWeight::SEMIBOLD)
-                            .text_color(cx.theme().foreground)
-                            .child("You"),
-                    ),
+                user_bubble(cx).children(self.data.contents.into_iter().enumerate().filter_map(
+                    |(index, content)| {
+                        match &content {
+                            ContentBlock::Text(text_content) => Some(
+                                div()
+                                    .text_size(px(14.))
+                                    .text_color(cx.theme().foreground)
+                                    .line_height(px(22.))
+                                    .child(text_content.text.clone())
+                                    .into_any_element(),
+                            ),
+                            // Skip resources in simple render - use UserMessageView for interactive resources
+                            ContentBlock::ResourceLink(_) | ContentBlock::Resource(_) => None,
+                            ContentBlock::Audio(audio) => Some(render_audio_chip(
+                                SharedString::from(format!("{}-audio-{}", self.id, index)),
+                                audio,
+                                cx,
+                            )),
+                            // Skip other content types for now (Image)
+                            _ => None,
+                        }
+                    },
+                )),
             )
-            // Message content
-            .child(v_flex().gap_3().pl_6().w_full().children(
-                self.data.contents.into_iter().filter_map(|content| {
-                    match &content {
-                        ContentBlock::Text(text_content) => Some(
-                            div()
-                                .text_size(px(14.))
-                                .text_color(cx.theme().foreground)
-                                .line_height(px(22.))
-                                .child(text_content.text.clone())
-                                .into_any_element(),
-                        ),
-                        // Skip resources in simple render - use UserMessageView for interactive resources
-                        ContentBlock::ResourceLink(_) | ContentBlock::Resource(_) => None,
-                        // Skip other content types for now (Image, Audio)
-                        _ => None,
-                    }
-                }),
-            ))
     }
 }
 
+/// "You" and the user avatar, right-aligned above the message bubble, after
+/// the copy button and when the message was sent
+fn render_user_header(
+    copy_button: Option<AnyElement>,
+    timestamp: Option<SystemTime>,
+    cx: &App,
+) -> impl IntoElement {
+    h_flex()
+        .items_center()
+        .gap_2()
+        .children(copy_button)
+        .when_some(timestamp, |this, timestamp| {
+            this.child(render_message_time(timestamp, cx))
+        })
+        .child(
+            div()
+                .text_size(px(13.))
+                .font_weight(gpui::FontWeight::SEMIBOLD)
+                .text_color(cx.theme().foreground)
+                .child("You"),
+        )
+        .child(
+            Avatar::new("You")
+                .icon(Some(Icon::new(IconName::User)))
+                .color(cx.theme().primary)
+                .size(px(20.)),
+        )
+}
+
+/// Background of the user message bubble: the panel background tinted with
+/// the primary color, kept opaque so previews can fade into it
+fn user_bubble_color(cx: &App) -> Hsla {
+    cx.theme()
+        .background
+        .blend(cx.theme().primary.opacity(0.08))
+}
+
+/// Right-aligned bubble holding the user message content
+fn user_bubble(cx: &App) -> Div {
+    v_flex()
+        .gap_3()
+        .max_w(relative(0.85))
+        .px_3()
+        .py_2()
+        .rounded(px(10.))
+        .bg(user_bubble_color(cx))
+        .border_1()
+        .border_color(cx.theme().primary.opacity(0.2))
+}
+
 /// A stateful wrapper for UserMessage that can be used as a GPUI view
 pub struct UserMessageView {
     pub(crate) data: Entity<UserMessageData>,
     pub(crate) resource_items: Vec<Entity<ResourceItem>>,
+    /// Indices of long text blocks the user expanded with "show more"
+    expanded_blocks: HashSet<usize>,
+    /// Transcript search query, highlighted in the text
+    search_query: String,
 }
 
 impl UserMessageView {
     pub fn new(data: UserMessageData, _window: &mut Window, cx: &mut App) -> Entity<Self> {
-        cx.new(|cx| {
-            let data_entity = cx.new(|_| data.clone());
-
-            // Create ResourceItem entities for each resource in the data
-            let resource_items: Vec<Entity<ResourceItem>> = data
-                .contents
-                .iter()
-                .filter_map(|content| get_resource_info(content))
-                .map(|resource_info| cx.new(|_| ResourceItem::new(resource_info)))
-                .collect();
-
-            Self {
-                data: data_entity,
-                resource_items,
-            }
-        })
+        cx.new(|cx| Self::from_data(data, cx))
+    }
+
+    /// Build the view state, with a `ResourceItem` for each resource in `data`
+    pub(crate) fn from_data(data: UserMessageData, cx: &mut App) -> Self {
+        let resource_items = resource_items(&data.contents, cx);
+        Self {
+            data: cx.new(|_| data),
+            resource_items,
+            expanded_blocks: HashSet::new(),
+            search_query: String::new(),
+        }
     }
 
     /// Update the message data
@@ -405,35 +783,80 @@ impl UserMessageView {
             cx.notify();
         });
 
-        // Recreate resource items
-        self.resource_items = data
-            .contents
-            .iter()
-            .filter_map(|content| get_resource_info(content))
-            .map(|resource_info| cx.new(|_| ResourceItem::new(resource_info)))
-            .collect();
+        // Recreate resource items and reset expansion state
+        self.expanded_blocks.clear();
+        self.resource_items = resource_items(&data.contents, cx);
+
+        cx.notify();
+    }
 
+    /// Toggle a long text block between its preview and full text
+    pub fn toggle_block_expanded(&mut self, index: usize, cx: &mut Context<Self>) {
+        if !self.expanded_blocks.remove(&index) {
+            self.expanded_blocks.insert(index);
+        }
         cx.notify();
     }
 
+    /// Highlight matches of the transcript search `query` in the text
+    pub fn set_search_query(&mut self, query: &str, cx: &mut Context<Self>) {
+        if self.search_query != query {
+            self.search_query = query.to_string();
+            cx.notify();
+        }
+    }
+
+    /// Render a text block, collapsed to a preview when it is long
+    fn render_text_block(&self, index: usize, text: &str, cx: &Context<Self>) -> AnyElement {
+        let expanded = self.expanded_blocks.contains(&index);
+        let preview = collapsed_preview(text);
+        let is_collapsible = preview.is_some();
+        let display_text = match &preview {
+            Some(preview) if !expanded => preview.as_str(),
+            _ => text,
+        };
+
+        v_flex()
+            .w_full()
+            .gap_1()
+            .child(
+                div()
+                    .relative()
+                    .w_full()
+                    .overflow_hidden()
+                    .text_size(px(14.))
+                    .text_color(cx.theme().foreground)
+                    .line_height(px(22.))
+                    .child(highlight_matches(display_text, &self.search_query, cx))
+                    .when(is_collapsible && !expanded, |this| {
+                        this.child(render_preview_fade(user_bubble_color(cx)))
+                    }),
+            )
+            .when(is_collapsible, |this| {
+                this.child(
+                    render_show_more_button(("user-message-show-more", index), expanded).on_click(
+                        cx.listener(move |this, _ev, _window, cx| {
+                            this.toggle_block_expanded(index, cx);
+                        }),
+                    ),
+                )
+            })
+            .into_any_element()
+    }
+
     /// Add content to the message
     pub fn add_content(&mut self, content: ContentBlock, cx: &mut Context<Self>) {
-        let is_resource = matches!(
-            content,
-            ContentBlock::ResourceLink(_) | ContentBlock::Resource(_)
-        );
+        let resource_info = get_resource_info(&content);
 
         self.data.update(cx, |d, cx| {
-            d.contents.push(content.clone());
+            d.contents.push(content);
             cx.notify();
         });
 
         // If it's a resource, create a new ResourceItem entity
-        if is_resource {
-            if let Some(resource_info) = get_resource_info(&content) {
-                let item = cx.new(|_| ResourceItem::new(resource_info));
-                self.resource_items.push(item);
-            }
+        if let Some(resource_info) = resource_info {
+            let item = cx.new(|_| ResourceItem::new(resource_info));
+            self.resource_items.push(item);
         }
 
         cx.notify();
@@ -460,16 +883,16 @@ impl UserMessageView {
 
 impl Render for UserMessageView {
     fn render(&mut self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
-        let data = self.data.read(cx).clone();
+        let data = self.data.read(cx);
         let mut resource_index = 0;
-        let theme = cx.theme().clone();
+        let theme = cx.theme();
 
         // Separate code selection blocks from other content
         let mut code_chips: Vec<CodeSelectionChip> = Vec::new();
-        let mut other_contents: Vec<ContentBlock> = Vec::new();
+        let mut other_contents: Vec<&ContentBlock> = Vec::new();
 
-        for content in data.contents.into_iter() {
-            if let ContentBlock::Text(ref text_content) = content {
+        for content in &data.contents {
+            if let ContentBlock::Text(text_content) = content {
                 if let Some(chip) = parse_code_selection_text(&text_content.text) {
                     code_chips.push(chip);
                     continue;
@@ -479,47 +902,28 @@ impl Render for UserMessageView {
         }
 
         let has_chips = !code_chips.is_empty();
+        let copy_button = render_copy_button(
+            SharedString::from(format!("user-message-{}-copy", cx.entity_id())),
+            ContentRenderers::default().plain_text(&data.contents),
+        );
 
         v_flex()
-            .gap_3()
+            .group(MESSAGE_HOVER_GROUP)
+            .gap_1()
             .w_full()
-            // User icon and label
-            .child(
-                h_flex()
-                    .items_center()
-                    .gap_2()
-                    .child(
-                        Icon::new(IconName::User)
-                            .size(px(16.))
-                            .text_color(cx.theme().accent),
-                    )
-                    .child(
-                        div()
-                            .text_size(px(13.))
-                            .font_weight(gpui::FontWeight::SEMIBOLD)
-                            .text_color(cx.theme().foreground)
-                            .child("You"),
-                    ),
-            )
+            .items_end()
+            .child(render_user_header(copy_button, data.timestamp, cx))
             // Message content
             .child(
-                v_flex()
-                    .gap_3()
-                    .pl_6()
-                    .w_full()
+                user_bubble(cx)
                     // Render text and resource blocks
-                    .children(other_contents.into_iter().filter_map(|content| {
-                        match &content {
-                            ContentBlock::Text(text_content) => Some(
-                                div()
-                                    .text_size(px(14.))
-                                    .text_color(theme.foreground)
-                                    .line_height(px(22.))
-                                    .child(text_content.text.clone())
-                                    .into_any_element(),
-                            ),
+                    .children(other_contents.into_iter().enumerate().filter_map(
+                        |(index, content)| match content {
+                            ContentBlock::Text(text_content) => {
+                                Some(self.render_text_block(index, &text_content.text, cx))
+                            }
                             ContentBlock::ResourceLink(_) | ContentBlock::Resource(_) => {
-                                if get_resource_info(&content).is_some() {
+                                if get_resource_info(content).is_some() {
                                     let current_index = resource_index;
                                     resource_index += 1;
 
@@ -532,10 +936,19 @@ impl Render for UserMessageView {
                                     None
                                 }
                             }
-                            // Skip other content types for now (Image, Audio)
+                            ContentBlock::Audio(audio) => Some(render_audio_chip(
+                                SharedString::from(format!(
+                                    "user-message-{}-audio-{}",
+                                    cx.entity_id(),
+                                    index
+                                )),
+                                audio,
+                                cx,
+                            )),
+                            // Skip other content types for now (Image)
                             _ => None,
-                        }
-                    }))
+                        },
+                    ))
                     // Render code selection chips
                     .when(has_chips, |this| {
                         this.child(h_flex().gap_1p5().items_center().flex_wrap().children(
@@ -592,4 +1005,110 @@ mod tests {
         assert_eq!(info.name.as_ref(), "a.txt");
         assert_eq!(info.text.as_ref().unwrap().as_ref(), "text");
     }
+
+    #[test]
+    fn short_messages_are_not_collapsed() {
+        assert!(collapsed_preview("fix the login bug").is_none());
+        assert!(collapsed_preview(&"line\n".repeat(USER_MESSAGE_PREVIEW_LINES)).is_none());
+    }
+
+    #[test]
+    fn long_messages_collapse_to_first_lines() {
+        let log: String = (0..200).map(|i| format!("log line {i}\n")).collect();
+        let preview = collapsed_preview(&log).unwrap();
+        assert_eq!(preview.lines().count(), USER_MESSAGE_PREVIEW_LINES);
+        assert!(preview.starts_with("log line 0"));
+
+        let single_line = "x".repeat(USER_MESSAGE_PREVIEW_CHARS * 2);
+        let preview = collapsed_preview(&single_line).unwrap();
+        assert_eq!(preview.len(), USER_MESSAGE_PREVIEW_CHARS);
+    }
+
+    #[test]
+    fn resource_preview_shows_first_lines() {
+        let text: String = (0..20).map(|i| format!("line {i}\n")).collect();
+        let resource = TextResourceContents::new(text, "file:///tmp/log.txt");
+        let content = ContentBlock::Resource(EmbeddedResource::new(
+            EmbeddedResourceResource::TextResourceContents(resource),
+        ));
+        let preview = get_resource_info(&content).unwrap().preview_text().unwrap();
+        assert_eq!(preview.lines().count(), RESOURCE_PREVIEW_LINES);
+
+        let link = ContentBlock::ResourceLink(ResourceLink::new("file", "file:///tmp/a.txt"));
+        assert!(get_resource_info(&link).unwrap().preview_text().is_none());
+    }
+
+    #[test]
+    fn blob_resources_are_saved_not_rendered_as_text() {
+        let text = ContentBlock::Resource(EmbeddedResource::new(
+            EmbeddedResourceResource::TextResourceContents(TextResourceContents::new(
+                "fn main() {}",
+                "file:///tmp/main.rs",
+            )),
+        ));
+        let mut blob = BlobResourceContents::new("AAEC", "file:///tmp/data.bin");
+        blob.mime_type = Some("application/octet-stream".into());
+        let blob = ContentBlock::Resource(EmbeddedResource::new(
+            EmbeddedResourceResource::BlobResourceContents(blob),
+        ));
+
+        let text = get_resource_info(&text).unwrap();
+        assert_eq!(text.kind, ResourceKind::Text);
+        assert!(text.kind.is_expandable() && !text.kind.is_saveable());
+
+        let blob = get_resource_info(&blob).unwrap();
+        assert_eq!(blob.kind, ResourceKind::Blob);
+        assert!(blob.kind.is_saveable() && !blob.kind.is_expandable());
+        assert!(blob.text.is_none());
+        assert!(blob.preview_text().is_none());
+        assert_eq!(blob.blob_bytes(), Some(vec![0, 1, 2]));
+    }
+    #[test]
+    fn only_resource_blocks_yield_resource_info() {
+        use agent_client_protocol::{AudioContent, ImageContent};
+
+        let uri = "file:///tmp/report.csv";
+        let blocks = [
+            ContentBlock::from("hello".to_string()),
+            ContentBlock::Image(ImageContent::new("AAEC", "image/png")),
+            ContentBlock::Audio(AudioContent::new("AAEC", "audio/wav")),
+            ContentBlock::ResourceLink(ResourceLink::new("report.csv", uri)),
+            ContentBlock::Resource(EmbeddedResource::new(
+                EmbeddedResourceResource::TextResourceContents(TextResourceContents::new(
+                    "a,b", uri,
+                )),
+            )),
+            ContentBlock::Resource(EmbeddedResource::new(
+                EmbeddedResourceResource::BlobResourceContents(BlobResourceContents::new(
+                    "AAEC", uri,
+                )),
+            )),
+        ];
+
+        let infos: Vec<Option<ResourceInfo>> = blocks.iter().map(get_resource_info).collect();
+        assert!(infos[..3].iter().all(Option::is_none));
+        let kinds: Vec<ResourceKind> = infos[3..].iter().flatten().map(|i| i.kind).collect();
+        assert_eq!(
+            kinds,
+            [ResourceKind::Link, ResourceKind::Text, ResourceKind::Blob]
+        );
+        // The same file reads the same whether linked or embedded
+        for info in infos[3..].iter().flatten() {
+            assert_eq!(info.name.as_ref(), "report.csv");
+            assert_eq!(info.uri.as_ref(), uri);
+        }
+    }
+
+    #[test]
+    fn resource_names_fall_back_to_the_uri() {
+        let unnamed = ContentBlock::ResourceLink(ResourceLink::new("", "file:///tmp/notes.md"));
+        assert_eq!(
+            get_resource_info(&unnamed).unwrap().name.as_ref(),
+            "notes.md"
+        );
+
+        assert_eq!(extract_filename("file:///tmp/dir/"), "dir");
+        assert_eq!(extract_filename("https://x.dev/a.png?v=2#top"), "a.png");
+        assert_eq!(extract_filename("file:///"), "file:///");
+    }
 }
@@ -1,6 +1,26 @@
+use std::ops::Range;
+use std::time::{Duration, SystemTime};
+
 use agent_client_protocol::{self as acp, ToolKind};
+use anyhow::{Context as _, Result};
+use gpui::{AnyElement, App, HighlightStyle, IntoElement, StyledText};
+use gpui_component::ActiveTheme;
 use serde_json::Value;
 
+/// Decode base64 image/blob data sent by an agent.
+///
+/// Line breaks and other ASCII whitespace are ignored (some agents wrap long
+/// payloads); anything else outside the standard alphabet, or missing
+/// padding, is rejected rather than rendered or written to disk.
+pub fn decode_base64_content(data: &str) -> Result<Vec<u8>> {
+    use base64::Engine as _;
+
+    let compact: String = data.chars().filter(|c| !c.is_ascii_whitespace()).collect();
+    base64::engine::general_purpose::STANDARD
+        .decode(compact)
+        .with_context(|| format!("Invalid base64 content ({} bytes)", data.len()))
+}
+
 pub fn truncate_lines(text: &str, max_lines: usize) -> String {
     if max_lines == 0 {
         return text.to_string();
@@ -17,6 +37,49 @@ pub fn truncate_lines(text: &str, max_lines: usize) -> String {
     result
 }
 
+/// Truncate text to at most `max_chars` characters without splitting a UTF-8
+/// sequence. Returns the kept prefix and whether anything was cut off.
+pub fn truncate_chars(text: &str, max_chars: usize) -> (&str, bool) {
+    if max_chars == 0 {
+        return (text, false);
+    }
+
+    match text.char_indices().nth(max_chars) {
+        Some((byte_index, _)) => (&text[..byte_index], true),
+        None => (text, false),
+    }
+}
+
+/// Byte ranges of `query` in `text`, ignoring ASCII case. Matches don't overlap.
+pub fn find_matches(text: &str, query: &str) -> Vec<Range<usize>> {
+    if query.is_empty() {
+        return Vec::new();
+    }
+    // ASCII lowercasing keeps byte offsets, so ranges map back onto `text`
+    let haystack = text.to_ascii_lowercase();
+    let needle = query.to_ascii_lowercase();
+    haystack
+        .match_indices(&needle)
+        .map(|(start, matched)| start..start + matched.len())
+        .collect()
+}
+
+/// `text` with matches of `query` highlighted
+pub(crate) fn highlight_matches(text: &str, query: &str, cx: &App) -> AnyElement {
+    let matches = find_matches(text, query);
+    if matches.is_empty() {
+        return text.to_string().into_any_element();
+    }
+
+    let style = HighlightStyle {
+        background_color: Some(cx.theme().yellow.opacity(0.4)),
+        ..Default::default()
+    };
+    StyledText::new(text.to_string())
+        .with_highlights(matches.into_iter().map(|range| (range, style)))
+        .into_any_element()
+}
+
 pub fn extract_terminal_output(terminal: &acp::Terminal) -> Option<String> {
     let meta = terminal.meta.as_ref()?;
     extract_terminal_output_from_meta(meta)
@@ -177,10 +240,58 @@ fn value_to_string(value: &Value) -> Option<String> {
     }
 }
 
+/// Key in a chunk's `_meta` holding when it was sent, in Unix milliseconds
+pub const TIMESTAMP_META_KEY: &str = "timestamp";
+
+/// When a chunk was sent, from its `_meta`
+pub fn timestamp_from_meta(meta: Option<&serde_json::Map<String, Value>>) -> Option<SystemTime> {
+    let millis = meta?.get(TIMESTAMP_META_KEY)?.as_u64()?;
+    SystemTime::UNIX_EPOCH.checked_add(Duration::from_millis(millis))
+}
+
+/// How long ago `time` was: "just now", "42s ago", "5m ago", "3h ago", "2d ago"
+pub fn format_relative_time(time: SystemTime) -> String {
+    // A clock running behind the sender's reads as "just now"
+    relative_time_label(time.elapsed().unwrap_or_default())
+}
+
+fn relative_time_label(elapsed: Duration) -> String {
+    const MINUTE: u64 = 60;
+    const HOUR: u64 = 60 * MINUTE;
+    const DAY: u64 = 24 * HOUR;
+
+    match elapsed.as_secs() {
+        secs if secs < 10 => "just now".to_string(),
+        secs if secs < MINUTE => format!("{}s ago", secs),
+        secs if secs < HOUR => format!("{}m ago", secs / MINUTE),
+        secs if secs < DAY => format!("{}h ago", secs / HOUR),
+        secs => format!("{}d ago", secs / DAY),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn decode_base64_content_accepts_valid_data() {
+        assert_eq!(decode_base64_content("aGVsbG8=").unwrap(), b"hello");
+        assert_eq!(decode_base64_content("aGVs\nbG8=\n").unwrap(), b"hello");
+        assert!(decode_base64_content("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn decode_base64_content_rejects_invalid_characters() {
+        assert!(decode_base64_content("aGVs*G8=").is_err());
+        assert!(decode_base64_content("aGVsbG8-").is_err());
+    }
+
+    #[test]
+    fn decode_base64_content_rejects_truncated_padding() {
+        assert!(decode_base64_content("aGVsbG8").is_err());
+        assert!(decode_base64_content("aGVsbG").is_err());
+    }
+
     #[test]
     fn truncate_lines_limits_output() {
         let text = "line1\nline2\nline3";
@@ -188,6 +299,29 @@ mod tests {
         assert_eq!(truncate_lines(text, 0), text);
     }
 
+    #[test]
+    fn find_matches_ignores_ascii_case() {
+        let text = "Error: café ERROR error";
+        assert_eq!(find_matches(text, "error"), vec![0..5, 13..18, 19..24]);
+        assert_eq!(find_matches(text, "CAFÉ"), Vec::<Range<usize>>::new());
+        assert_eq!(find_matches(text, "café"), vec![7..12]);
+        assert!(find_matches(text, "").is_empty());
+        assert_eq!(find_matches("aaaa", "aa"), vec![0..2, 2..4]);
+    }
+
+    #[test]
+    fn truncate_chars_respects_char_boundaries() {
+        let (kept, truncated) = truncate_chars("héllo wörld", 4);
+        assert_eq!(kept, "héll");
+        assert!(truncated);
+
+        let (kept, truncated) = truncate_chars("short", 10);
+        assert_eq!(kept, "short");
+        assert!(!truncated);
+
+        assert_eq!(truncate_chars("abc", 0), ("abc", false));
+    }
+
     #[test]
     fn extract_xml_content_strips_code_fences() {
         let text = "```\ncontent\n```";
@@ -205,4 +339,36 @@ mod tests {
         let output = extract_terminal_output(&terminal).unwrap();
         assert_eq!(output, "line1\nline2");
     }
+
+    #[test]
+    fn relative_time_steps_up_at_each_unit() {
+        let label = |secs| relative_time_label(Duration::from_secs(secs));
+        assert_eq!(label(0), "just now");
+        assert_eq!(label(9), "just now");
+        assert_eq!(label(10), "10s ago");
+        assert_eq!(label(59), "59s ago");
+        assert_eq!(label(60), "1m ago");
+        assert_eq!(label(3599), "59m ago");
+        assert_eq!(label(3600), "1h ago");
+        assert_eq!(label(86_399), "23h ago");
+        assert_eq!(label(86_400), "1d ago");
+        assert_eq!(label(10 * 86_400), "10d ago");
+
+        let future = SystemTime::now() + Duration::from_secs(30);
+        assert_eq!(format_relative_time(future), "just now");
+        let earlier = SystemTime::now() - Duration::from_secs(2 * 3600 + 5);
+        assert_eq!(format_relative_time(earlier), "2h ago");
+    }
+
+    #[test]
+    fn timestamps_are_read_from_meta() {
+        let meta = serde_json::json!({ "timestamp": 1_700_000_000_000u64 });
+        assert_eq!(
+            timestamp_from_meta(meta.as_object()),
+            Some(SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000))
+        );
+        let meta = serde_json::json!({ "timestamp": "yesterday" });
+        assert_eq!(timestamp_from_meta(meta.as_object()), None);
+        assert_eq!(timestamp_from_meta(None), None);
+    }
 }
@@ -136,6 +136,7 @@ impl AcpUiStory {
                 on_open_tool_call: Some(Arc::new(|tool_call, _window, _cx| {
                     log::info!("Open diff summary tool call: {}", tool_call.tool_call_id);
                 })),
+                ..Default::default()
             })
         });
 
@@ -24,13 +24,18 @@ use tokio::{
 };
 
 use agentx_event_bus::{EventHub, PermissionRequestEvent, SessionUpdateEvent};
-use agentx_types::{AgentProcessConfig, ProxyConfig};
+use agentx_types::{
+    AgentConfigEvent, AgentProcessConfig, AgentStatus, AgentStatusInfo, ProxyConfig,
+};
 
 use tokio_util::compat::{TokioAsyncReadCompatExt, TokioAsyncWriteCompatExt};
 
 #[derive(Clone)]
 pub struct AgentManager {
     agents: Arc<RwLock<HashMap<String, Arc<AgentHandle>>>>,
+    /// Connection state of every agent the manager was asked to start,
+    /// including ones still connecting or that failed
+    statuses: Arc<RwLock<HashMap<String, AgentStatus>>>,
     permission_store: Arc<PermissionStore>,
     event_hub: EventHub,
     proxy_config: Arc<RwLock<ProxyConfig>>,
@@ -47,6 +52,7 @@ impl AgentManager {
         let _ = configs;
         Self {
             agents: Arc::new(RwLock::new(HashMap::new())),
+            statuses: Arc::new(RwLock::new(HashMap::new())),
             permission_store,
             event_hub,
             proxy_config: Arc::new(RwLock::new(proxy_config)),
@@ -65,6 +71,7 @@ impl AgentManager {
         let proxy_config = Arc::new(RwLock::new(proxy_config));
         let manager = Arc::new(Self {
             agents: Arc::new(RwLock::new(HashMap::new())),
+            statuses: Arc::new(RwLock::new(HashMap::new())),
             permission_store,
             event_hub,
             proxy_config,
@@ -98,6 +105,59 @@ impl AgentManager {
         list
     }
 
+    /// All agents the manager was asked to start, with their connection state
+    pub async fn list_agents_with_status(&self) -> Vec<AgentStatusInfo> {
+        let statuses = self.statuses.read().await;
+        let mut list: Vec<_> = statuses
+            .iter()
+            .map(|(name, status)| AgentStatusInfo {
+                name: name.clone(),
+                status: status.clone(),
+            })
+            .collect();
+        list.sort_by(|a, b| a.name.cmp(&b.name));
+        list
+    }
+
+    /// Record the connection state of `name` and announce it
+    async fn set_status(&self, name: &str, status: AgentStatus) {
+        self.statuses
+            .write()
+            .await
+            .insert(name.to_string(), status.clone());
+        self.event_hub
+            .publish_agent_config_update(AgentConfigEvent::AgentStatusChanged {
+                name: name.to_string(),
+                status,
+            });
+    }
+
+    /// Mark `name` as failed once the process behind `handle` exits on its
+    /// own. Nothing is reported for a shutdown, or once `handle` was replaced.
+    fn watch_exit(
+        &self,
+        name: String,
+        handle: Arc<AgentHandle>,
+        exited: oneshot::Receiver<String>,
+    ) {
+        let manager = self.clone();
+        smol::spawn(async move {
+            let Ok(reason) = exited.await else {
+                return;
+            };
+            let current = manager
+                .agents
+                .read()
+                .await
+                .get(&name)
+                .is_some_and(|h| Arc::ptr_eq(h, &handle));
+            if current {
+                manager.set_status(&name, AgentStatus::Error(reason)).await;
+            }
+        })
+        .detach();
+    }
+
     /// Get the initialize response for a specific agent
     pub async fn get_agent_init_response(&self, name: &str) -> Option<acp::InitializeResponse> {
         let agents = self.agents.read().await;
@@ -133,24 +193,39 @@ impl AgentManager {
         }
 
         // Spawn new agent
-        let handle = AgentHandle::spawn(
+        self.set_status(&name, AgentStatus::Connecting).await;
+        let (handle, exited) = match AgentHandle::spawn(
             name.clone(),
             config,
             self.permission_store.clone(),
             self.event_hub.clone(),
             self.proxy_config.read().await.clone(),
         )
-        .await?;
+        .await
+        {
+            Ok(handle) => handle,
+            Err(e) => {
+                self.set_status(&name, AgentStatus::Error(e.to_string()))
+                    .await;
+                return Err(e);
+            }
+        };
 
         // Add to agents map
-        let mut agents = self.agents.write().await;
-        agents.insert(name.clone(), Arc::new(handle));
+        let handle = Arc::new(handle);
+        self.agents
+            .write()
+            .await
+            .insert(name.clone(), handle.clone());
+        self.set_status(&name, AgentStatus::Connected).await;
+        self.watch_exit(name.clone(), handle, exited);
         log::info!("Successfully added agent '{}'", name);
         Ok(())
     }
 
     /// Remove an agent from the manager
     pub async fn remove_agent(&self, name: &str) -> Result<()> {
+        self.statuses.write().await.remove(name);
         let handle = {
             let mut agents = self.agents.write().await;
             agents
@@ -171,6 +246,8 @@ impl AgentManager {
             agents.remove(name)
         };
 
+        // An agent that failed to start has a status but no handle
+        self.statuses.write().await.remove(name);
         let Some(handle) = handle else {
             return Ok(false);
         };
@@ -197,18 +274,32 @@ impl AgentManager {
         }
 
         // Spawn new agent
-        let new_handle = AgentHandle::spawn(
+        self.set_status(name, AgentStatus::Connecting).await;
+        let (new_handle, exited) = match AgentHandle::spawn(
             name.to_string(),
             config,
             self.permission_store.clone(),
             self.event_hub.clone(),
             self.proxy_config.read().await.clone(),
         )
-        .await?;
+        .await
+        {
+            Ok(handle) => handle,
+            Err(e) => {
+                self.set_status(name, AgentStatus::Error(e.to_string()))
+                    .await;
+                return Err(e);
+            }
+        };
 
         // Add new agent to map
-        let mut agents = self.agents.write().await;
-        agents.insert(name.to_string(), Arc::new(new_handle));
+        let new_handle = Arc::new(new_handle);
+        self.agents
+            .write()
+            .await
+            .insert(name.to_string(), new_handle.clone());
+        self.set_status(name, AgentStatus::Connected).await;
+        self.watch_exit(name.to_string(), new_handle, exited);
         log::info!("Successfully restarted agent '{}'", name);
         Ok(())
     }
@@ -251,15 +342,18 @@ pub struct AgentHandle {
 }
 
 impl AgentHandle {
+    /// Start the agent process; the receiver yields the reason if the process
+    /// later exits without being shut down
     async fn spawn(
         name: String,
         config: AgentProcessConfig,
         permission_store: Arc<PermissionStore>,
         event_hub: EventHub,
         proxy_config: ProxyConfig,
-    ) -> Result<Self> {
+    ) -> Result<(Self, oneshot::Receiver<String>)> {
         let (sender, receiver) = mpsc::channel(32);
         let (ready_tx, ready_rx) = oneshot::channel();
+        let (exit_tx, exit_rx) = oneshot::channel();
         let init_response = Arc::new(std::sync::RwLock::new(None));
         let init_response_clone = init_response.clone();
         let thread_name = format!("agent-worker-{name}");
@@ -275,6 +369,7 @@ impl AgentHandle {
                     event_hub,
                     receiver,
                     ready_tx,
+                    exit_tx,
                     init_response_clone,
                     proxy_config,
                 ) {
@@ -287,11 +382,14 @@ impl AgentHandle {
             .await
             .map_err(|_| anyhow!("agent {start_name} failed to start"))??;
 
-        Ok(Self {
-            name,
-            sender,
-            init_response,
-        })
+        Ok((
+            Self {
+                name,
+                sender,
+                init_response,
+            },
+            exit_rx,
+        ))
     }
 
     pub async fn new_session(
@@ -502,6 +600,7 @@ fn run_agent_worker(
     event_hub: EventHub,
     command_rx: mpsc::Receiver<AgentCommand>,
     ready_tx: oneshot::Sender<Result<agent_client_protocol::InitializeResponse>>,
+    exit_tx: oneshot::Sender<String>,
     init_response: Arc<std::sync::RwLock<Option<acp::InitializeResponse>>>,
     proxy_config: ProxyConfig,
 ) -> Result<()> {
@@ -520,6 +619,7 @@ fn run_agent_worker(
                 event_hub,
                 command_rx,
                 ready_tx,
+                exit_tx,
                 init_response,
                 proxy_config,
             ))
@@ -534,6 +634,7 @@ async fn agent_event_loop(
     event_hub: EventHub,
     mut command_rx: mpsc::Receiver<AgentCommand>,
     ready_tx: oneshot::Sender<Result<agent_client_protocol::InitializeResponse>>,
+    exit_tx: oneshot::Sender<String>,
     init_response: Arc<std::sync::RwLock<Option<acp::InitializeResponse>>>,
     proxy_config: ProxyConfig,
 ) -> Result<()> {
@@ -675,7 +776,24 @@ async fn agent_event_loop(
         }
     }
 
-    while let Some(command) = command_rx.recv().await {
+    loop {
+        let command = tokio::select! {
+            command = command_rx.recv() => command,
+            status = child.wait() => {
+                let reason = match status {
+                    Ok(status) => {
+                        format!("Agent {} process exited with status: {}", agent_name, status)
+                    }
+                    Err(e) => format!("Failed to wait for agent {} process: {}", agent_name, e),
+                };
+                log::error!("{}", reason);
+                let _ = exit_tx.send(reason);
+                break;
+            }
+        };
+        let Some(command) = command else {
+            break;
+        };
         match command {
             AgentCommand::Initialize { request, respond } => {
                 let result = conn.initialize(*request).await.map_err(|err| anyhow!(err));
@@ -1,7 +1,7 @@
 use crate::core::{EventBusContainer, EventBusStats, SubscriptionId};
 use agentx_types::{
-    AgentConfigEvent, CodeSelectionEvent, Config, PermissionRequestEvent, SessionStatus,
-    SessionUpdateEvent, WorkspaceUpdateEvent,
+    AgentConfigEvent, CodeSelectionEvent, Config, PermissionRequestEvent, SessionErrorEvent,
+    SessionRetryEvent, SessionStatus, SessionUpdateEvent, WorkspaceUpdateEvent,
 };
 
 #[derive(Clone, Debug)]
@@ -10,6 +10,8 @@ pub enum AppEvent {
     CodeSelection(CodeSelectionEvent),
     PermissionRequest(Box<PermissionRequestEvent>),
     SessionUpdate(SessionUpdateEvent),
+    SessionError(SessionErrorEvent),
+    SessionRetry(SessionRetryEvent),
     WorkspaceUpdate(WorkspaceUpdateEvent),
 }
 
@@ -106,6 +108,36 @@ impl EventHub {
         )
     }
 
+    pub fn subscribe_session_errors<F>(&self, callback: F) -> SubscriptionId
+    where
+        F: Fn(&SessionErrorEvent) + Send + Sync + 'static,
+    {
+        self.subscribe_with_filter(
+            move |event| {
+                if let AppEvent::SessionError(event) = event {
+                    callback(event);
+                }
+                true
+            },
+            |event| matches!(event, AppEvent::SessionError(_)),
+        )
+    }
+
+    pub fn subscribe_session_retries<F>(&self, callback: F) -> SubscriptionId
+    where
+        F: Fn(&SessionRetryEvent) + Send + Sync + 'static,
+    {
+        self.subscribe_with_filter(
+            move |event| {
+                if let AppEvent::SessionRetry(event) = event {
+                    callback(event);
+                }
+                true
+            },
+            |event| matches!(event, AppEvent::SessionRetry(_)),
+        )
+    }
+
     pub fn subscribe_session_updates_for_agent<F>(
         &self,
         agent_name: String,
@@ -324,6 +356,7 @@ impl EventHub {
                         AgentConfigEvent::AgentAdded { .. }
                             | AgentConfigEvent::AgentUpdated { .. }
                             | AgentConfigEvent::AgentRemoved { .. }
+                            | AgentConfigEvent::AgentStatusChanged { .. }
                     )
                 )
             },
@@ -444,6 +477,7 @@ impl EventHub {
                         AgentConfigEvent::AgentAdded { name, .. }
                             | AgentConfigEvent::AgentUpdated { name, .. }
                             | AgentConfigEvent::AgentRemoved { name }
+                            | AgentConfigEvent::AgentStatusChanged { name, .. }
                     ) if name == &agent_name
                 )
             },
@@ -469,6 +503,14 @@ impl EventHub {
         self.publish(AppEvent::SessionUpdate(event));
     }
 
+    pub fn publish_session_error(&self, event: SessionErrorEvent) {
+        self.publish(AppEvent::SessionError(event));
+    }
+
+    pub fn publish_session_retry(&self, event: SessionRetryEvent) {
+        self.publish(AppEvent::SessionRetry(event));
+    }
+
     pub fn publish_permission_request(&self, event: PermissionRequestEvent) {
         self.publish(AppEvent::PermissionRequest(Box::new(event)));
     }
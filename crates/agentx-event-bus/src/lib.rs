@@ -7,6 +7,6 @@ pub use hub::{AppEvent, EventHub};
 
 // Re-export types for convenience
 pub use agentx_types::{
-    AgentConfigEvent, CodeSelectionEvent, PermissionRequestEvent, SessionUpdateEvent,
-    WorkspaceUpdateEvent,
+    AgentConfigEvent, CodeSelectionEvent, PermissionRequestEvent, SessionErrorEvent,
+    SessionErrorKind, SessionRetryEvent, SessionUpdateEvent, WorkspaceUpdateEvent,
 };
@@ -71,6 +71,12 @@ impl AgentConfigService {
         config.agent_servers.get(name).cloned()
     }
 
+    /// Get the custom instructions configured for an agent, if any
+    pub async fn instructions_for(&self, agent_name: &str) -> Option<String> {
+        let config = self.config.read().await;
+        config.instructions_for(agent_name).map(str::to_string)
+    }
+
     /// Get the upload directory
     pub async fn get_upload_dir(&self) -> PathBuf {
         let config = self.config.read().await;
@@ -337,6 +343,163 @@ impl AgentConfigService {
         Ok(())
     }
 
+    /// Update the UI theme preference
+    pub async fn update_theme(&self, theme: agentx_types::config::ThemePreference) -> Result<()> {
+        {
+            let mut config = self.config.write().await;
+            config.theme = theme;
+        }
+
+        self.save_to_file().await?;
+
+        log::info!("Successfully updated theme to: {:?}", theme);
+        Ok(())
+    }
+
+    /// Update the UI scale factor
+    pub async fn update_ui_scale(&self, ui_scale: f32) -> Result<()> {
+        {
+            let mut config = self.config.write().await;
+            config.ui_scale = ui_scale;
+        }
+
+        self.save_to_file().await?;
+
+        log::info!("Successfully updated ui_scale to: {}", ui_scale);
+        Ok(())
+    }
+
+    /// Update the diff layout preference
+    pub async fn update_diff_view(
+        &self,
+        diff_view: agentx_types::config::DiffViewPreference,
+    ) -> Result<()> {
+        {
+            let mut config = self.config.write().await;
+            config.diff_view = diff_view;
+        }
+
+        self.save_to_file().await?;
+
+        log::info!("Successfully updated diff_view to: {:?}", diff_view);
+        Ok(())
+    }
+
+    /// Update whether code and diff blocks wrap by default
+    pub async fn update_default_code_wrap(&self, default_code_wrap: bool) -> Result<()> {
+        {
+            let mut config = self.config.write().await;
+            config.default_code_wrap = default_code_wrap;
+        }
+
+        self.save_to_file().await?;
+
+        log::info!(
+            "Successfully updated default_code_wrap to: {}",
+            default_code_wrap
+        );
+        Ok(())
+    }
+
+    /// Update whether consecutive same-kind tool calls are collapsed into a summary
+    pub async fn update_group_tool_calls(&self, group_tool_calls: bool) -> Result<()> {
+        {
+            let mut config = self.config.write().await;
+            config.group_tool_calls = group_tool_calls;
+        }
+
+        self.save_to_file().await?;
+
+        log::info!(
+            "Successfully updated group_tool_calls to: {}",
+            group_tool_calls
+        );
+        Ok(())
+    }
+
+    /// Update whether the code editor draws indent guides
+    pub async fn update_show_indent_guides(&self, show_indent_guides: bool) -> Result<()> {
+        {
+            let mut config = self.config.write().await;
+            config.show_indent_guides = show_indent_guides;
+        }
+
+        self.save_to_file().await?;
+
+        log::info!(
+            "Successfully updated show_indent_guides to: {}",
+            show_indent_guides
+        );
+        Ok(())
+    }
+
+    /// Update whether finished tool calls open the terminal or code editor
+    pub async fn update_auto_open_panels(&self, auto_open_panels: bool) -> Result<()> {
+        {
+            let mut config = self.config.write().await;
+            config.auto_open_panels = auto_open_panels;
+        }
+
+        self.save_to_file().await?;
+
+        log::info!(
+            "Successfully updated auto_open_panels to: {}",
+            auto_open_panels
+        );
+        Ok(())
+    }
+
+    /// Update which whitespace the code editor marks
+    pub async fn update_render_whitespace(
+        &self,
+        render_whitespace: agentx_types::config::WhitespaceRendering,
+    ) -> Result<()> {
+        {
+            let mut config = self.config.write().await;
+            config.render_whitespace = render_whitespace;
+        }
+
+        self.save_to_file().await?;
+
+        log::info!(
+            "Successfully updated render_whitespace to: {:?}",
+            render_whitespace
+        );
+        Ok(())
+    }
+
+    /// Update the agent preselected in the welcome panel
+    pub async fn update_default_agent(&self, default_agent: Option<String>) -> Result<()> {
+        {
+            let mut config = self.config.write().await;
+            if config.default_agent == default_agent {
+                return Ok(());
+            }
+            config.default_agent = default_agent.clone();
+        }
+
+        self.save_to_file().await?;
+
+        log::info!("Successfully updated default_agent to: {:?}", default_agent);
+        Ok(())
+    }
+
+    /// Update the mode preselected for new sessions
+    pub async fn update_default_mode(&self, default_mode: Option<String>) -> Result<()> {
+        {
+            let mut config = self.config.write().await;
+            if config.default_mode == default_mode {
+                return Ok(());
+            }
+            config.default_mode = default_mode.clone();
+        }
+
+        self.save_to_file().await?;
+
+        log::info!("Successfully updated default_mode to: {:?}", default_mode);
+        Ok(())
+    }
+
     // ========== Model Configuration Operations ==========
 
     /// Add a new model configuration
@@ -821,7 +984,26 @@ mod tests {
             commands: HashMap::new(),
             system_prompts: HashMap::new(),
             tool_call_preview_max_lines: 10,
+            max_inline_content_bytes: 1024 * 1024,
             proxy: ProxyConfig::default(),
+            theme: Default::default(),
+            ui_scale: 1.0,
+            diff_view: Default::default(),
+            default_code_wrap: true,
+            group_tool_calls: false,
+            conversation_scroll: Default::default(),
+            default_agent: None,
+            default_mode: None,
+            agent_instructions: HashMap::new(),
+            show_indent_guides: true,
+            render_whitespace: Default::default(),
+            auto_open_panels: false,
+            auto_collapse_completed_tools: false,
+            keybindings: HashMap::new(),
+            request_timeout_secs: 0,
+            max_retries: 0,
+            prompt_token_warning: None,
+            prompt_token_limit: None,
         };
 
         let event_hub = EventHub::new();
@@ -17,7 +17,7 @@ use serde::{Deserialize, Serialize};
 
 use agentx_agent::{AgentHandle, AgentManager};
 use agentx_event_bus::{EventHub, WorkspaceUpdateEvent};
-use agentx_types::SessionStatus;
+use agentx_types::{AgentStatusInfo, SessionStatus};
 
 /// Agent service - manages agents and their sessions
 pub struct AgentService {
@@ -67,6 +67,11 @@ impl AgentService {
         self.agent_manager.list_agents().await
     }
 
+    /// List all configured agents with their connection state
+    pub async fn list_agents_with_status(&self) -> Vec<AgentStatusInfo> {
+        self.agent_manager.list_agents_with_status().await
+    }
+
     /// Get the initialize response for a specific agent
     pub async fn get_agent_init_response(
         &self,
@@ -601,7 +606,7 @@ impl AgentService {
         let result = agent_handle
             .prompt(request)
             .await
-            .map_err(|e| anyhow!("Failed to send prompt: {}", e))?;
+            .map_err(|e| e.context("Failed to send prompt"))?;
 
         self.update_session_status(agent_name, session_id, SessionStatus::Completed);
         // Update activity time
@@ -10,9 +10,9 @@ pub use agent_config_service::AgentConfigService;
 pub use agent_service::{AgentService, AgentSessionInfo};
 pub use ai_service::{AiService, AiServiceConfig, CommentStyle};
 pub use config_watcher::ConfigWatcher;
-pub use message_service::MessageService;
+pub use message_service::{MessageService, RequestPolicy};
 pub use persistence_service::PersistenceService;
 pub use workspace_service::WorkspaceService;
 
-// Re-export SessionStatus from types for convenience
-pub use agentx_types::SessionStatus;
+// Re-export SessionStatus and AgentStatus from types for convenience
+pub use agentx_types::{AgentStatus, AgentStatusInfo, SessionStatus};
@@ -5,26 +5,122 @@
 
 use std::{
     collections::HashMap,
-    sync::{Arc, Mutex},
+    sync::{
+        Arc, Mutex, RwLock,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::{Duration, Instant},
 };
 
 use agent_client_protocol::{
-    AvailableCommand, ContentBlock, ContentChunk, ImageContent, PromptResponse, SessionUpdate,
-    TextContent,
+    self as acp, AvailableCommand, ContentBlock, ContentChunk, ImageContent, PromptResponse,
+    SessionUpdate, TextContent,
 };
 use anyhow::{Result, anyhow};
 
-use agentx_event_bus::{EventHub, SessionUpdateEvent, WorkspaceUpdateEvent};
-use agentx_types::SessionStatus;
+use agentx_event_bus::{
+    EventHub, SessionErrorEvent, SessionErrorKind, SessionRetryEvent, SessionUpdateEvent,
+    WorkspaceUpdateEvent,
+};
+use agentx_types::{Config, SessionStatus};
 
 use super::agent_service::AgentService;
 use super::persistence_service::{PersistedMessage, PersistenceService};
 
+/// How long the agent may stay silent on a prompt and how often it is sent
+/// again when it doesn't answer at all
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RequestPolicy {
+    /// Longest gap between updates from the agent; `None` waits forever
+    pub timeout: Option<Duration>,
+    pub max_retries: u32,
+}
+
+impl From<&Config> for RequestPolicy {
+    fn from(config: &Config) -> Self {
+        Self {
+            timeout: config.request_timeout(),
+            max_retries: config.max_retries,
+        }
+    }
+}
+
+impl RequestPolicy {
+    /// Whether a prompt that failed with `error` on its `attempt`th retry
+    /// (0 for the first send) is sent again. Only timeouts before the agent
+    /// sent anything are: other errors would fail the same way, and once the
+    /// agent has started it may already have run tools.
+    fn should_retry(&self, error: &anyhow::Error, attempt: u32) -> bool {
+        error
+            .downcast_ref::<PromptTimedOut>()
+            .is_some_and(|timed_out| !timed_out.agent_started)
+            && attempt < self.max_retries
+    }
+}
+
+/// The agent went quiet on a prompt for longer than the request timeout
+#[derive(Debug)]
+struct PromptTimedOut {
+    idle: Duration,
+    /// Whether the agent sent any update for the prompt before going quiet
+    agent_started: bool,
+}
+
+impl std::fmt::Display for PromptTimedOut {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Request timed out after {}s without a response",
+            self.idle.as_secs()
+        )
+    }
+}
+
+impl std::error::Error for PromptTimedOut {}
+
+/// How long the agent has been quiet on a prompt. Paused while the agent waits
+/// on the user to answer a permission request.
+#[derive(Debug, Clone, Copy)]
+struct IdleClock {
+    last_activity: Instant,
+    paused: bool,
+}
+
+impl IdleClock {
+    fn new(now: Instant) -> Self {
+        Self {
+            last_activity: now,
+            paused: false,
+        }
+    }
+
+    /// The agent sent an update; restart the clock
+    fn touch(&mut self, now: Instant) {
+        self.last_activity = now;
+        self.paused = false;
+    }
+
+    /// The agent is waiting on the user; stop counting until its next update
+    fn pause(&mut self, now: Instant) {
+        self.last_activity = now;
+        self.paused = true;
+    }
+
+    fn idle(&self, now: Instant) -> Duration {
+        if self.paused {
+            Duration::ZERO
+        } else {
+            now.saturating_duration_since(self.last_activity)
+        }
+    }
+}
+
 /// Message service - handles message sending and event bus interaction
 pub struct MessageService {
     event_hub: EventHub,
     agent_service: Arc<AgentService>,
     persistence_service: Arc<PersistenceService>,
+    request_policy: RwLock<RequestPolicy>,
 }
 
 impl MessageService {
@@ -37,9 +133,25 @@ impl MessageService {
             event_hub,
             agent_service,
             persistence_service,
+            request_policy: RwLock::new(RequestPolicy::default()),
         }
     }
 
+    /// Time out and retry prompts sent from now on according to `policy`
+    pub fn set_request_policy(&self, policy: RequestPolicy) {
+        *self
+            .request_policy
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = policy;
+    }
+
+    fn request_policy(&self) -> RequestPolicy {
+        *self
+            .request_policy
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
     /// Initialize persistence subscription
     ///
     /// This should be called after the MessageService is created.
@@ -158,15 +270,23 @@ impl MessageService {
     /// This method performs the following steps:
     /// 1. Verify the session exists
     /// 2. Publish the user message to the event bus (immediate UI feedback)
-    /// 3. Send the prompt to the agent
+    /// 3. Send the prompt to the agent, led by the agent's custom `instructions`
     ///
     /// Use this when you already have a session ID and want to ensure
-    /// the UI panel has subscribed before the message is sent.
+    /// the UI panel has subscribed before the message is sent. The instructions
+    /// only go to the agent; they are not shown as part of the user message.
+    ///
+    /// A prompt the agent never answered is cancelled and sent again as the
+    /// [`RequestPolicy`] allows, publishing a [`SessionRetryEvent`] each time.
+    /// A failure is also published as a [`SessionErrorEvent`] carrying the
+    /// prompt, so the conversation can show it and offer to send it again,
+    /// and the session goes back to idle.
     pub async fn send_message_to_session(
         &self,
         agent_name: &str,
         session_id: &str,
         content_blocks: Vec<ContentBlock>,
+        instructions: Option<String>,
     ) -> Result<PromptResponse> {
         // 1. Verify session exists
         if self
@@ -174,7 +294,9 @@ impl MessageService {
             .get_session_info(agent_name, session_id)
             .is_none()
         {
-            return Err(anyhow!("Session not found: {}", session_id));
+            let error = anyhow!("Session not found: {}", session_id);
+            self.publish_session_error(session_id, &error, content_blocks);
+            return Err(error);
         }
 
         // 2. Publish user message blocks to event bus (immediate UI feedback)
@@ -183,13 +305,156 @@ impl MessageService {
         }
 
         // 3. Send prompt to agent
-        let result = self
+        let mut prompt_blocks = Vec::with_capacity(content_blocks.len() + 1);
+        if let Some(instructions) = instructions {
+            prompt_blocks.push(ContentBlock::from(instructions));
+        }
+        prompt_blocks.extend(content_blocks.iter().cloned());
+
+        let policy = self.request_policy();
+        let mut attempt = 0;
+        loop {
+            match self
+                .send_prompt_with_timeout(agent_name, session_id, prompt_blocks.clone(), policy)
+                .await
+            {
+                Ok(result) => return Ok(result),
+                Err(e) if policy.should_retry(&e, attempt) => {
+                    attempt += 1;
+                    log::warn!(
+                        "{:#} (session {}), retrying ({}/{})",
+                        e,
+                        session_id,
+                        attempt,
+                        policy.max_retries
+                    );
+                    // The agent may still be working on the abandoned prompt
+                    if let Err(e) = self
+                        .agent_service
+                        .cancel_session(agent_name, session_id)
+                        .await
+                    {
+                        log::warn!("Failed to cancel timed-out prompt: {:#}", e);
+                    }
+                    self.event_hub.publish_session_retry(SessionRetryEvent {
+                        session_id: session_id.to_string(),
+                        attempt,
+                        max_retries: policy.max_retries,
+                        message: e.to_string(),
+                    });
+                }
+                Err(e) => {
+                    if e.downcast_ref::<PromptTimedOut>().is_some() {
+                        // Don't leave the agent working on a prompt the user
+                        // was told has failed
+                        if let Err(e) = self
+                            .agent_service
+                            .cancel_session(agent_name, session_id)
+                            .await
+                        {
+                            log::warn!("Failed to cancel timed-out prompt: {:#}", e);
+                        }
+                    }
+                    // `send_prompt` leaves the session in progress on failure
+                    self.agent_service.update_session_status(
+                        agent_name,
+                        session_id,
+                        SessionStatus::Idle,
+                    );
+                    self.publish_session_error(session_id, &e, content_blocks);
+                    return Err(anyhow!("Failed to send message: {:#}", e));
+                }
+            }
+        }
+    }
+
+    /// Send `prompt` once, failing with [`PromptTimedOut`] when the agent
+    /// sends no update for longer than the policy's timeout. Every update
+    /// restarts the clock, so long turns that keep streaming never time out,
+    /// and a pending permission request stops it until the agent resumes.
+    async fn send_prompt_with_timeout(
+        &self,
+        agent_name: &str,
+        session_id: &str,
+        prompt: Vec<ContentBlock>,
+        policy: RequestPolicy,
+    ) -> Result<PromptResponse> {
+        let send = self
             .agent_service
-            .send_prompt(agent_name, session_id, content_blocks)
-            .await
-            .map_err(|e| anyhow!("Failed to send message: {}", e))?;
+            .send_prompt(agent_name, session_id, prompt);
+        let Some(timeout) = policy.timeout else {
+            return send.await;
+        };
+
+        let clock = Arc::new(Mutex::new(IdleClock::new(Instant::now())));
+        let agent_started = Arc::new(AtomicBool::new(false));
+        let subscription = {
+            let clock = clock.clone();
+            let agent_started = agent_started.clone();
+            self.event_hub.subscribe_session_updates_for_session(
+                session_id.to_string(),
+                move |event| {
+                    // The user's own message is echoed on the same channel
+                    if matches!(*event.update, SessionUpdate::UserMessageChunk(_)) {
+                        return;
+                    }
+                    agent_started.store(true, Ordering::Relaxed);
+                    clock
+                        .lock()
+                        .unwrap_or_else(|poisoned| poisoned.into_inner())
+                        .touch(Instant::now());
+                },
+            )
+        };
+        let permission_subscription = {
+            let clock = clock.clone();
+            let agent_started = agent_started.clone();
+            self.event_hub.subscribe_permission_requests_for_session(
+                session_id.to_string(),
+                move |_| {
+                    agent_started.store(true, Ordering::Relaxed);
+                    clock
+                        .lock()
+                        .unwrap_or_else(|poisoned| poisoned.into_inner())
+                        .pause(Instant::now());
+                },
+            )
+        };
+        let watchdog = async {
+            loop {
+                let idle = clock
+                    .lock()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner())
+                    .idle(Instant::now());
+                if idle >= timeout {
+                    break;
+                }
+                smol::Timer::after(timeout - idle).await;
+            }
+            Err(anyhow!(PromptTimedOut {
+                idle: timeout,
+                agent_started: agent_started.load(Ordering::Relaxed),
+            }))
+        };
+        let result = smol::future::or(send, watchdog).await;
+        self.event_hub.unsubscribe(subscription);
+        self.event_hub.unsubscribe(permission_subscription);
+        result
+    }
 
-        Ok(result)
+    /// Publish a failed prompt to the event bus
+    pub fn publish_session_error(
+        &self,
+        session_id: &str,
+        error: &anyhow::Error,
+        prompt: Vec<ContentBlock>,
+    ) {
+        self.event_hub.publish_session_error(SessionErrorEvent {
+            session_id: session_id.to_string(),
+            kind: classify_prompt_error(error),
+            message: format!("{:#}", error),
+            prompt,
+        });
     }
 
     /// Publish a user message to the event bus (immediate UI feedback)
@@ -266,6 +531,47 @@ impl MessageService {
         rx
     }
 
+    /// Subscribe to timed-out prompts being sent again, optionally only those
+    /// of `session_id`
+    pub fn subscribe_session_retries(
+        &self,
+        session_id: Option<String>,
+    ) -> tokio::sync::mpsc::UnboundedReceiver<SessionRetryEvent> {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+
+        self.event_hub.subscribe_session_retries(move |event| {
+            if let Some(ref filter_id) = session_id {
+                if &event.session_id != filter_id {
+                    return;
+                }
+            }
+
+            let _ = tx.send(event.clone());
+        });
+
+        rx
+    }
+
+    /// Subscribe to failed prompts, optionally only those of `session_id`
+    pub fn subscribe_session_errors(
+        &self,
+        session_id: Option<String>,
+    ) -> tokio::sync::mpsc::UnboundedReceiver<SessionErrorEvent> {
+        let (tx, rx) = tokio::sync::mpsc::unbounded_channel();
+
+        self.event_hub.subscribe_session_errors(move |event| {
+            if let Some(ref filter_id) = session_id {
+                if &event.session_id != filter_id {
+                    return;
+                }
+            }
+
+            let _ = tx.send(event.clone());
+        });
+
+        rx
+    }
+
     /// Load historical messages for a session
     ///
     /// Returns all persisted messages in chronological order
@@ -306,3 +612,80 @@ impl MessageService {
             .get_session_commands(&agent_name, session_id)
     }
 }
+
+/// An error the agent answered with is [`SessionErrorKind::Agent`]; anything
+/// else means the request never got an answer
+fn classify_prompt_error(error: &anyhow::Error) -> SessionErrorKind {
+    if error.downcast_ref::<acp::Error>().is_some() {
+        SessionErrorKind::Agent
+    } else {
+        SessionErrorKind::Transport
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn agent_errors_are_told_apart_from_transport_errors() {
+        let agent_error = anyhow!(acp::Error::internal_error()).context("Failed to send prompt");
+        assert_eq!(classify_prompt_error(&agent_error), SessionErrorKind::Agent);
+
+        let transport_error = anyhow!("agent claude is not running");
+        assert_eq!(
+            classify_prompt_error(&transport_error),
+            SessionErrorKind::Transport
+        );
+    }
+
+    #[test]
+    fn only_unanswered_timeouts_are_retried_and_only_up_to_the_limit() {
+        let policy = RequestPolicy {
+            timeout: Some(Duration::from_secs(30)),
+            max_retries: 2,
+        };
+        let timed_out = anyhow!(PromptTimedOut {
+            idle: Duration::from_secs(30),
+            agent_started: false,
+        });
+        assert!(policy.should_retry(&timed_out, 0));
+        assert!(policy.should_retry(&timed_out, 1));
+        assert!(!policy.should_retry(&timed_out, 2));
+        assert_eq!(
+            classify_prompt_error(&timed_out),
+            SessionErrorKind::Transport
+        );
+
+        let agent_error = anyhow!(acp::Error::internal_error());
+        assert!(!policy.should_retry(&agent_error, 0));
+
+        // The agent may have run tools before going quiet
+        let stalled = anyhow!(PromptTimedOut {
+            idle: Duration::from_secs(30),
+            agent_started: true,
+        });
+        assert!(!policy.should_retry(&stalled, 0));
+    }
+
+    #[test]
+    fn pending_permission_requests_stop_the_idle_clock() {
+        let start = Instant::now();
+        let mut clock = IdleClock::new(start);
+        assert_eq!(
+            clock.idle(start + Duration::from_secs(10)),
+            Duration::from_secs(10)
+        );
+
+        // The user takes longer to answer than the request timeout
+        clock.pause(start + Duration::from_secs(10));
+        assert_eq!(clock.idle(start + Duration::from_secs(300)), Duration::ZERO);
+
+        // The agent's next update after the answer restarts the clock
+        clock.touch(start + Duration::from_secs(300));
+        assert_eq!(
+            clock.idle(start + Duration::from_secs(305)),
+            Duration::from_secs(5)
+        );
+    }
+}
@@ -102,11 +102,9 @@ impl ChunkAccumulator {
         }
     }
 
-    /// Try to append an AgentThoughtChunk
+    /// Try to append the text of an AgentThoughtChunk
     /// Returns Some(FlushData) if type change requires flush, None if accumulated
-    fn try_append_agent_thought_chunk(&mut self, chunk: ContentChunk) -> Option<FlushData> {
-        let text = extract_text_from_content_chunk(&chunk);
-
+    fn try_append_agent_thought_chunk(&mut self, text: String) -> Option<FlushData> {
         match self.chunk_type {
             AccumulatedChunkType::Empty => {
                 self.chunk_type = AccumulatedChunkType::AgentThought;
@@ -329,9 +327,13 @@ fn merge_text_chunks(chunks: &[ContentChunk]) -> ContentChunk {
     chunks[0].clone()
 }
 
-/// Extract text from ContentChunk (for AgentThoughtChunk)
-fn extract_text_from_content_chunk(chunk: &ContentChunk) -> String {
-    match &chunk.content {
+/// Plain-text form of a content block, used for accumulated thoughts
+pub type ContentTextFn = Arc<dyn Fn(&ContentBlock) -> String + Send + Sync>;
+
+/// Text of a thought chunk when no renderer is set with
+/// [`PersistenceService::with_content_text`]
+fn fallback_content_text(block: &ContentBlock) -> String {
+    match block {
         ContentBlock::Text(text) => text.text.clone(),
         ContentBlock::Image(img) => format!("[Image: {}]", img.mime_type),
         _ => "[Non-text content]".to_string(),
@@ -344,6 +346,8 @@ pub struct PersistenceService {
     base_dir: PathBuf,
     /// Thread-safe storage for chunk accumulators per session
     accumulators: Arc<Mutex<HashMap<String, ChunkAccumulator>>>,
+    /// Turns thought chunks into the text that is saved
+    content_text: ContentTextFn,
 }
 
 impl PersistenceService {
@@ -355,9 +359,19 @@ impl PersistenceService {
         Self {
             base_dir,
             accumulators: Arc::new(Mutex::new(HashMap::new())),
+            content_text: Arc::new(fallback_content_text),
         }
     }
 
+    /// Save thought chunks as the text `content_text` gives for their content
+    pub fn with_content_text(
+        mut self,
+        content_text: impl Fn(&ContentBlock) -> String + Send + Sync + 'static,
+    ) -> Self {
+        self.content_text = Arc::new(content_text);
+        self
+    }
+
     /// Get the file path for a session
     fn session_file_path(&self, session_id: &str) -> PathBuf {
         self.base_dir.join(format!("{}.jsonl", session_id))
@@ -394,7 +408,7 @@ impl PersistenceService {
                 }
                 SessionUpdate::AgentThoughtChunk(chunk) => {
                     log::debug!("Accumulating AgentThoughtChunk for session: {}", session_id);
-                    accumulator.try_append_agent_thought_chunk(chunk)
+                    accumulator.try_append_agent_thought_chunk((self.content_text)(&chunk.content))
                 }
                 SessionUpdate::UserMessageChunk(chunk) => {
                     // Don't accumulate user message chunks - each chunk is a distinct content
@@ -0,0 +1,29 @@
+use serde::{Deserialize, Serialize};
+
+/// Connection state of a configured agent process
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AgentStatus {
+    /// Spawning the process and waiting for its initialize response
+    #[default]
+    Connecting,
+    Connected,
+    /// The agent could not be started or its process exited; holds the reason
+    Error(String),
+}
+
+impl AgentStatus {
+    pub fn is_connected(&self) -> bool {
+        matches!(self, Self::Connected)
+    }
+
+    pub fn is_error(&self) -> bool {
+        matches!(self, Self::Error(_))
+    }
+}
+
+/// A configured agent and its connection state
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AgentStatusInfo {
+    pub name: String,
+    pub status: AgentStatus,
+}
@@ -1,6 +1,6 @@
 use agent_client_protocol as acp;
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, path::PathBuf};
+use std::{collections::HashMap, path::PathBuf, time::Duration};
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct Config {
@@ -20,9 +20,109 @@ pub struct Config {
     /// Max lines to show in tool call previews (0 disables truncation)
     #[serde(default = "default_tool_call_preview_max_lines")]
     pub tool_call_preview_max_lines: usize,
+    /// Tool output text larger than this (in bytes) is kept in a temporary
+    /// file and shown as a preview until loaded (0 keeps everything in memory)
+    #[serde(default = "default_max_inline_content_bytes")]
+    pub max_inline_content_bytes: usize,
     /// Network proxy configuration
     #[serde(default)]
     pub proxy: ProxyConfig,
+    /// UI theme: "light", "dark" or "system" (follow the OS appearance)
+    #[serde(default)]
+    pub theme: ThemePreference,
+    /// Scale factor for text and icon sizes (clamped to 0.75-2.0 when applied)
+    #[serde(default = "default_ui_scale")]
+    pub ui_scale: f32,
+    /// Diff layout: "unified" (inline) or "split" (side by side)
+    #[serde(default)]
+    pub diff_view: DiffViewPreference,
+    /// Whether diffs and tool output wrap long lines unless toggled per block;
+    /// unwrapped blocks scroll horizontally
+    #[serde(default = "default_code_wrap")]
+    pub default_code_wrap: bool,
+    /// Collapse runs of consecutive same-kind tool calls (e.g. many file reads)
+    /// into one expandable summary entry
+    #[serde(default)]
+    pub group_tool_calls: bool,
+    /// Scroll axes of conversation views: "vertical", or "both" to also scroll
+    /// wide content (long code lines, tables) horizontally
+    #[serde(default)]
+    pub conversation_scroll: ScrollAxisPreference,
+    /// Agent preselected in the welcome panel; the first agent when unset or unknown
+    #[serde(default)]
+    pub default_agent: Option<String>,
+    /// Mode (id or name) preselected for new sessions when the agent offers it
+    #[serde(default)]
+    pub default_mode: Option<String>,
+    /// Custom instructions per agent name, prepended to every prompt sent to that agent
+    #[serde(default)]
+    pub agent_instructions: HashMap<String, String>,
+    /// Draw a guide per indent level in the code editor
+    #[serde(default = "default_show_indent_guides")]
+    pub show_indent_guides: bool,
+    /// Whitespace marked in the code editor: "none", "all" or "trailing"
+    #[serde(default)]
+    pub render_whitespace: WhitespaceRendering,
+    /// Reveal the terminal or open the file in the code editor when a tool call
+    /// that ran a command or read/edited a file completes (off by default)
+    #[serde(default)]
+    pub auto_open_panels: bool,
+    /// Collapse tool calls once they complete, unless expanded by hand.
+    /// Failed and running ones stay as they are.
+    #[serde(default)]
+    pub auto_collapse_completed_tools: bool,
+    /// Key chords per action name, e.g. `"agent_studio::Open": "secondary-o"`.
+    /// Bound after the built-in keymap, so they override it.
+    #[serde(default)]
+    pub keybindings: HashMap<String, KeybindingConfig>,
+    /// Seconds the agent may go without sending anything for a prompt before
+    /// it is cancelled (0, the default, waits forever)
+    #[serde(default = "default_request_timeout_secs")]
+    pub request_timeout_secs: u64,
+    /// Times a prompt the agent never answered is sent again before it is
+    /// reported as failed (0 by default)
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32,
+    /// Estimated prompt size (in tokens) at which the chat input's size
+    /// counter turns amber; unset keeps it neutral
+    #[serde(default)]
+    pub prompt_token_warning: Option<usize>,
+    /// Estimated prompt size (in tokens) at which the counter turns red
+    #[serde(default)]
+    pub prompt_token_limit: Option<usize>,
+}
+
+impl Config {
+    /// Custom instructions for `agent`; blank entries count as none
+    pub fn instructions_for(&self, agent: &str) -> Option<&str> {
+        self.agent_instructions
+            .get(agent)
+            .map(|instructions| instructions.trim())
+            .filter(|instructions| !instructions.is_empty())
+    }
+
+    /// The configured keybindings that parse, sorted by action name. Invalid
+    /// entries are logged and skipped.
+    pub fn keybindings(&self) -> Vec<Keybinding> {
+        let mut keybindings: Vec<Keybinding> = self
+            .keybindings
+            .iter()
+            .filter_map(|(action, config)| match Keybinding::parse(action, config) {
+                Ok(keybinding) => Some(keybinding),
+                Err(e) => {
+                    log::warn!("Skipping keybinding for '{}': {}", action, e);
+                    None
+                }
+            })
+            .collect();
+        keybindings.sort_by(|a, b| a.action.cmp(&b.action));
+        keybindings
+    }
+
+    /// How long a prompt may take before it times out; `None` when disabled
+    pub fn request_timeout(&self) -> Option<Duration> {
+        (self.request_timeout_secs > 0).then(|| Duration::from_secs(self.request_timeout_secs))
+    }
 }
 
 fn default_upload_dir() -> PathBuf {
@@ -35,6 +135,163 @@ fn default_tool_call_preview_max_lines() -> usize {
     DEFAULT_TOOL_CALL_PREVIEW_MAX_LINES
 }
 
+pub const DEFAULT_MAX_INLINE_CONTENT_BYTES: usize = 1024 * 1024;
+
+fn default_max_inline_content_bytes() -> usize {
+    DEFAULT_MAX_INLINE_CONTENT_BYTES
+}
+
+pub const DEFAULT_REQUEST_TIMEOUT_SECS: u64 = 0;
+
+fn default_request_timeout_secs() -> u64 {
+    DEFAULT_REQUEST_TIMEOUT_SECS
+}
+
+pub const DEFAULT_MAX_RETRIES: u32 = 0;
+
+fn default_max_retries() -> u32 {
+    DEFAULT_MAX_RETRIES
+}
+
+fn default_ui_scale() -> f32 {
+    1.0
+}
+
+fn default_code_wrap() -> bool {
+    true
+}
+
+fn default_show_indent_guides() -> bool {
+    true
+}
+
+/// Theme preference stored in `config.json`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThemePreference {
+    Light,
+    Dark,
+    #[default]
+    System,
+}
+
+/// Diff layout preference stored in `config.json`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffViewPreference {
+    #[default]
+    Unified,
+    Split,
+}
+
+/// Code editor whitespace markers stored in `config.json`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WhitespaceRendering {
+    #[default]
+    None,
+    All,
+    /// Only whitespace at the end of lines
+    Trailing,
+}
+
+/// Scroll axis preference stored in `config.json`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScrollAxisPreference {
+    #[default]
+    Vertical,
+    Both,
+}
+
+/// A keybinding as written in `config.json`: just the chord, or the chord and
+/// the key context it applies in (e.g. `"Terminal"`)
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum KeybindingConfig {
+    Keys(String),
+    WithContext {
+        keys: String,
+        #[serde(default)]
+        context: Option<String>,
+    },
+}
+
+/// A validated keybinding, ready to register with the keymap
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Keybinding {
+    /// Namespaced action name, e.g. `agent_studio::Open`
+    pub action: String,
+    /// Space-separated keystrokes, e.g. `ctrl-k ctrl-c`
+    pub keystrokes: String,
+    pub context: Option<String>,
+}
+
+const KEYBINDING_MODIFIERS: &[&str] = &[
+    "ctrl",
+    "alt",
+    "shift",
+    "cmd",
+    "fn",
+    "super",
+    "win",
+    "secondary",
+    "platform",
+];
+
+impl Keybinding {
+    fn parse(action: &str, config: &KeybindingConfig) -> anyhow::Result<Self> {
+        let (keys, context) = match config {
+            KeybindingConfig::Keys(keys) => (keys, None),
+            KeybindingConfig::WithContext { keys, context } => (keys, context.as_deref()),
+        };
+        match action.split_once("::") {
+            Some((namespace, name)) if !namespace.is_empty() && !name.is_empty() => {}
+            _ => anyhow::bail!("action name must be namespaced, e.g. agent_studio::Open"),
+        }
+
+        let keystrokes: Vec<&str> = keys.split_whitespace().collect();
+        if keystrokes.is_empty() {
+            anyhow::bail!("no keys given");
+        }
+        for keystroke in &keystrokes {
+            validate_keystroke(keystroke)?;
+        }
+
+        Ok(Self {
+            action: action.to_string(),
+            keystrokes: keystrokes.join(" "),
+            context: context
+                .map(str::trim)
+                .filter(|context| !context.is_empty())
+                .map(str::to_string),
+        })
+    }
+}
+
+/// Check one keystroke such as `ctrl-shift-k`: known modifiers, then a key
+/// (`-` itself is written as a trailing `--`)
+fn validate_keystroke(keystroke: &str) -> anyhow::Result<()> {
+    let (modifiers, key) = match keystroke.strip_suffix("--") {
+        Some(modifiers) => (modifiers, "-"),
+        None if keystroke == "-" => ("", keystroke),
+        None => keystroke.rsplit_once('-').unwrap_or(("", keystroke)),
+    };
+    if key.is_empty() {
+        anyhow::bail!("keystroke '{}' has no key", keystroke);
+    }
+    for modifier in modifiers.split('-').filter(|m| !m.is_empty()) {
+        if !KEYBINDING_MODIFIERS.contains(&modifier.to_lowercase().as_str()) {
+            anyhow::bail!(
+                "unknown modifier '{}' in keystroke '{}'",
+                modifier,
+                keystroke
+            );
+        }
+    }
+    Ok(())
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct AgentProcessConfig {
     pub command: String,
@@ -224,3 +481,110 @@ impl ProxyConfig {
 fn is_zero(value: &u16) -> bool {
     *value == 0
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config_with_instructions(instructions: serde_json::Value) -> Config {
+        serde_json::from_value(serde_json::json!({
+            "agent_servers": {},
+            "agent_instructions": instructions,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn instructions_for_configured_agent() {
+        let config = config_with_instructions(serde_json::json!({
+            "claude": "  Answer in British English.\n",
+            "codex": "   ",
+        }));
+
+        assert_eq!(
+            config.instructions_for("claude"),
+            Some("Answer in British English.")
+        );
+        assert_eq!(config.instructions_for("codex"), None);
+        assert_eq!(config.instructions_for("gemini"), None);
+    }
+
+    #[test]
+    fn instructions_default_to_empty() {
+        let config: Config = serde_json::from_str(r#"{"agent_servers": {}}"#).unwrap();
+        assert!(config.agent_instructions.is_empty());
+        assert_eq!(config.instructions_for("claude"), None);
+    }
+
+    #[test]
+    fn request_policy_is_read_from_config() {
+        let config: Config = serde_json::from_value(serde_json::json!({
+            "agent_servers": {},
+            "request_timeout_secs": 90,
+            "max_retries": 5,
+        }))
+        .unwrap();
+        assert_eq!(config.request_timeout(), Some(Duration::from_secs(90)));
+        assert_eq!(config.max_retries, 5);
+
+        let config: Config = serde_json::from_value(serde_json::json!({
+            "agent_servers": {},
+            "request_timeout_secs": 0,
+        }))
+        .unwrap();
+        assert_eq!(config.request_timeout(), None);
+    }
+
+    #[test]
+    fn request_policy_defaults_when_absent() {
+        let config: Config = serde_json::from_str(r#"{"agent_servers": {}}"#).unwrap();
+        assert_eq!(config.request_timeout_secs, DEFAULT_REQUEST_TIMEOUT_SECS);
+        assert_eq!(config.request_timeout(), None);
+        assert_eq!(config.max_retries, DEFAULT_MAX_RETRIES);
+    }
+
+    #[test]
+    fn keybindings_skip_invalid_entries() {
+        let config: Config = serde_json::from_value(serde_json::json!({
+            "agent_servers": {},
+            "keybindings": {
+                "agent_studio::Open": "secondary-o",
+                "agent_studio::ToggleSearch": "ctrl-k  ctrl--",
+                "gpui_term::Clear": { "keys": "ctrl-shift-k", "context": "Terminal" },
+                "agent_studio::Quit": "hyper-q",
+            },
+        }))
+        .unwrap();
+
+        assert_eq!(
+            config.keybindings(),
+            vec![
+                Keybinding {
+                    action: "agent_studio::Open".to_string(),
+                    keystrokes: "secondary-o".to_string(),
+                    context: None,
+                },
+                Keybinding {
+                    action: "agent_studio::ToggleSearch".to_string(),
+                    keystrokes: "ctrl-k ctrl--".to_string(),
+                    context: None,
+                },
+                Keybinding {
+                    action: "gpui_term::Clear".to_string(),
+                    keystrokes: "ctrl-shift-k".to_string(),
+                    context: Some("Terminal".to_string()),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn keystrokes_need_a_key_and_known_modifiers() {
+        assert!(validate_keystroke("f12").is_ok());
+        assert!(validate_keystroke("-").is_ok());
+        assert!(validate_keystroke("cmd-shift--").is_ok());
+        assert!(validate_keystroke("ctrl-").is_err());
+        assert!(validate_keystroke("meta-x").is_err());
+        assert!(Keybinding::parse("Open", &KeybindingConfig::Keys("ctrl-o".into())).is_err());
+    }
+}
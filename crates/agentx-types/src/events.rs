@@ -4,6 +4,7 @@ use agent_client_protocol as acp;
 use agent_client_protocol::SessionUpdate;
 use chrono::{DateTime, Utc};
 
+use crate::agent::AgentStatus;
 use crate::config::{AgentProcessConfig, CommandConfig, Config, McpServerConfig, ModelConfig};
 use crate::session::SessionStatus;
 
@@ -23,6 +24,8 @@ pub enum AgentConfigEvent {
     },
     /// An agent was removed
     AgentRemoved { name: String },
+    /// An agent process connected, started connecting or failed to start
+    AgentStatusChanged { name: String, status: AgentStatus },
 
     // ========== Model Events ==========
     /// A new model was added
@@ -67,6 +70,36 @@ pub struct SessionUpdateEvent {
     pub update: Arc<SessionUpdate>,
 }
 
+/// Where a failed request to an agent broke down
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SessionErrorKind {
+    /// The agent could not be reached (process down, channel closed, unknown session)
+    Transport,
+    /// The agent answered with an error
+    Agent,
+}
+
+/// A prompt that failed, published so the conversation can show it
+#[derive(Clone, Debug)]
+pub struct SessionErrorEvent {
+    pub session_id: String,
+    pub kind: SessionErrorKind,
+    pub message: String,
+    /// The prompt blocks that failed, so they can be sent again
+    pub prompt: Vec<acp::ContentBlock>,
+}
+
+/// A prompt that timed out and is being sent again
+#[derive(Clone, Debug)]
+pub struct SessionRetryEvent {
+    pub session_id: String,
+    /// 1 for the first retry
+    pub attempt: u32,
+    pub max_retries: u32,
+    /// Why the previous attempt failed
+    pub message: String,
+}
+
 /// Permission request event that can be broadcast to subscribers
 #[derive(Clone, Debug)]
 pub struct PermissionRequestEvent {
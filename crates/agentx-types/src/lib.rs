@@ -1,15 +1,17 @@
+pub mod agent;
 pub mod config;
 pub mod config_manager;
 pub mod events;
 pub mod schemas;
 pub mod session;
 
+pub use agent::{AgentStatus, AgentStatusInfo};
 pub use config::{
-    AgentProcessConfig, CommandConfig, Config, DEFAULT_TOOL_CALL_PREVIEW_MAX_LINES,
-    McpServerConfig, ModelConfig, ProxyConfig,
+    AgentProcessConfig, CommandConfig, Config, DEFAULT_TOOL_CALL_PREVIEW_MAX_LINES, Keybinding,
+    KeybindingConfig, McpServerConfig, ModelConfig, ProxyConfig, ThemePreference,
 };
 pub use events::{
-    AgentConfigEvent, CodeSelectionEvent, PermissionRequestEvent, SessionUpdateEvent,
-    WorkspaceUpdateEvent,
+    AgentConfigEvent, CodeSelectionEvent, PermissionRequestEvent, SessionErrorEvent,
+    SessionErrorKind, SessionRetryEvent, SessionUpdateEvent, WorkspaceUpdateEvent,
 };
 pub use session::SessionStatus;
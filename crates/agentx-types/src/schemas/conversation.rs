@@ -1,6 +1,6 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(tag = "type")]
 pub enum ConversationItem {
     UserMessage {
@@ -13,13 +13,18 @@ pub enum ConversationItem {
     },
     /// Plan item following ACP's SessionUpdate::Plan format
     Plan(PlanSchema),
+    /// Titled todo list; rendered like a plan with the title shown as its header
+    AgentTodoList {
+        title: String,
+        entries: Vec<PlanEntrySchema>,
+    },
     ToolCallGroup {
         items: Vec<ToolCallItemSchema>,
     },
 }
 
 /// User message data schema aligned with ACP's PromptRequest format
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct UserMessageDataSchema {
     pub session_id: String,
@@ -28,17 +33,18 @@ pub struct UserMessageDataSchema {
 }
 
 /// Content block schema aligned with ACP's ContentBlock enum
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum ContentBlockSchema {
     Text(TextContentSchema),
     Image(ImageContentSchema),
+    Audio(AudioContentSchema),
     ResourceLink(ResourceLinkSchema),
     Resource(EmbeddedResourceSchema),
 }
 
 /// Text content schema
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct TextContentSchema {
     pub text: String,
     #[serde(rename = "_meta")]
@@ -46,7 +52,7 @@ pub struct TextContentSchema {
 }
 
 /// Image content schema
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct ImageContentSchema {
     pub data: String,
@@ -55,8 +61,18 @@ pub struct ImageContentSchema {
     pub meta: Option<serde_json::Value>,
 }
 
+/// Audio content schema
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AudioContentSchema {
+    pub data: String,
+    pub mime_type: String,
+    #[serde(rename = "_meta")]
+    pub meta: Option<serde_json::Value>,
+}
+
 /// Resource link schema (reference to a resource without embedding content)
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct ResourceLinkSchema {
     pub name: String,
@@ -68,7 +84,7 @@ pub struct ResourceLinkSchema {
 }
 
 /// Embedded resource schema (contains the actual content)
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct EmbeddedResourceSchema {
     pub resource: ResourceContentsSchema,
@@ -77,7 +93,7 @@ pub struct EmbeddedResourceSchema {
 }
 
 /// Resource contents schema (text or blob)
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(tag = "type", rename_all = "snake_case")]
 pub enum ResourceContentsSchema {
     TextResourceContents(TextResourceContentsSchema),
@@ -85,7 +101,7 @@ pub enum ResourceContentsSchema {
 }
 
 /// Text resource contents schema
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct TextResourceContentsSchema {
     pub uri: String,
@@ -97,7 +113,7 @@ pub struct TextResourceContentsSchema {
 }
 
 /// Blob resource contents schema
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct BlobResourceContentsSchema {
     pub uri: String,
@@ -109,7 +125,7 @@ pub struct BlobResourceContentsSchema {
 }
 
 /// Agent message data schema aligned with ACP's ContentChunk format
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct AgentMessageDataSchema {
     pub session_id: String,
@@ -121,7 +137,7 @@ pub struct AgentMessageDataSchema {
 }
 
 /// Content chunk schema aligned with ACP's ContentChunk
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct ContentChunkSchema {
     /// Content block following ACP's ContentBlock structure
@@ -132,7 +148,7 @@ pub struct ContentChunkSchema {
 }
 
 /// Extended metadata for agent messages
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct AgentMessageMetaSchema {
     #[serde(default)]
@@ -142,7 +158,7 @@ pub struct AgentMessageMetaSchema {
 }
 
 /// Plan schema aligned with ACP's Plan structure from SessionUpdate::Plan
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct PlanSchema {
     /// The list of tasks to be accomplished
@@ -153,7 +169,7 @@ pub struct PlanSchema {
 }
 
 /// Plan entry schema aligned with ACP's PlanEntry
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct PlanEntrySchema {
     /// Human-readable description of what this task aims to accomplish
@@ -168,7 +184,7 @@ pub struct PlanEntrySchema {
 }
 
 /// Tool call item schema following ACP's ToolCall format
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct ToolCallItemSchema {
     pub id: String,
     pub data: ToolCallSchema,
@@ -176,7 +192,7 @@ pub struct ToolCallItemSchema {
 }
 
 /// Tool call schema aligned with ACP's ToolCall structure
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct ToolCallSchema {
     /// Unique identifier for this tool call
@@ -197,8 +213,173 @@ pub struct ToolCallSchema {
     pub meta: Option<serde_json::Value>,
 }
 
-/// Tool call content item schema (simplified for mock data)
-#[derive(Debug, Deserialize, Clone)]
+/// Tool call content item schema (simplified for mock data): text output,
+/// or a file edit when `diff` is set
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct ToolCallContentItemSchema {
+    #[serde(default)]
     pub text: String,
+    #[serde(default)]
+    pub diff: Option<ToolCallDiffSchema>,
+}
+
+/// File edit made by a tool call, aligned with ACP's Diff
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolCallDiffSchema {
+    pub path: String,
+    /// Absent for a newly created file
+    #[serde(default)]
+    pub old_text: Option<String>,
+    pub new_text: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// One item of every variant, with every content block and resource kind
+    const EVERY_VARIANT: &str = r#"[
+        {
+            "type": "UserMessage",
+            "id": "user-1",
+            "data": {
+                "sessionId": "session-1",
+                "prompt": [
+                    { "type": "text", "text": "Fix the retry loop" },
+                    { "type": "image", "data": "iVBORw0KGgo=", "mimeType": "image/png" },
+                    { "type": "resource_link", "name": "lib.rs", "uri": "file:///src/lib.rs" },
+                    {
+                        "type": "resource",
+                        "resource": {
+                            "type": "text_resource_contents",
+                            "uri": "file:///Cargo.toml",
+                            "text": "[package]",
+                            "mimeType": "text/x-toml"
+                        }
+                    },
+                    {
+                        "type": "resource",
+                        "resource": {
+                            "type": "blob_resource_contents",
+                            "uri": "file:///trace.bin",
+                            "blob": "AAEC"
+                        }
+                    },
+                    { "type": "audio", "data": "UklGRg==", "mimeType": "audio/wav" }
+                ]
+            }
+        },
+        {
+            "type": "AgentMessage",
+            "id": "agent-1",
+            "data": {
+                "sessionId": "session-1",
+                "chunks": [{ "content": { "type": "text", "text": "On it." } }],
+                "_meta": { "agentName": "Codex", "isComplete": true }
+            }
+        },
+        {
+            "type": "Plan",
+            "entries": [{ "content": "Read code", "priority": "high", "status": "completed" }],
+            "_meta": { "title": "Retry fix" }
+        },
+        {
+            "type": "AgentTodoList",
+            "title": "Follow-ups",
+            "entries": [{ "content": "Add test", "priority": "low", "status": "pending" }]
+        },
+        {
+            "type": "ToolCallGroup",
+            "items": [
+                {
+                    "id": "tool-1",
+                    "open": true,
+                    "data": {
+                        "toolCallId": "call-1",
+                        "title": "Read lib.rs",
+                        "kind": "read",
+                        "status": "completed",
+                        "content": [
+                            { "text": "fn retry() {}" },
+                            {
+                                "diff": {
+                                    "path": "src/lib.rs",
+                                    "oldText": "fn retry() {}",
+                                    "newText": "fn retry() { backoff(); }"
+                                }
+                            }
+                        ]
+                    }
+                },
+                {
+                    "id": "tool-2",
+                    "open": false,
+                    "data": { "toolCallId": "call-2", "title": "Pending edit" }
+                }
+            ]
+        }
+    ]"#;
+
+    #[test]
+    fn every_variant_deserializes() {
+        let items: Vec<ConversationItem> = serde_json::from_str(EVERY_VARIANT).unwrap();
+        assert_eq!(items.len(), 5);
+
+        let ConversationItem::UserMessage { data, .. } = &items[0] else {
+            panic!("expected a user message");
+        };
+        assert!(matches!(data.prompt[0], ContentBlockSchema::Text(_)));
+        assert!(matches!(data.prompt[1], ContentBlockSchema::Image(_)));
+        assert!(matches!(
+            data.prompt[2],
+            ContentBlockSchema::ResourceLink(_)
+        ));
+        assert!(matches!(
+            &data.prompt[3],
+            ContentBlockSchema::Resource(EmbeddedResourceSchema {
+                resource: ResourceContentsSchema::TextResourceContents(_),
+                ..
+            })
+        ));
+        assert!(matches!(
+            &data.prompt[4],
+            ContentBlockSchema::Resource(EmbeddedResourceSchema {
+                resource: ResourceContentsSchema::BlobResourceContents(_),
+                ..
+            })
+        ));
+        assert!(matches!(
+            &data.prompt[5],
+            ContentBlockSchema::Audio(AudioContentSchema { mime_type, .. }) if mime_type == "audio/wav"
+        ));
+
+        let ConversationItem::AgentMessage { data, .. } = &items[1] else {
+            panic!("expected an agent message");
+        };
+        let meta = data.meta.as_ref().unwrap();
+        assert_eq!(meta.agent_name.as_deref(), Some("Codex"));
+        assert!(meta.is_complete);
+
+        let ConversationItem::Plan(plan) = &items[2] else {
+            panic!("expected a plan");
+        };
+        assert_eq!(plan.entries[0].status, "completed");
+        assert_eq!(plan.meta.as_ref().unwrap()["title"], "Retry fix");
+
+        assert!(matches!(
+            &items[3],
+            ConversationItem::AgentTodoList { title, entries } if title == "Follow-ups" && entries.len() == 1
+        ));
+
+        let ConversationItem::ToolCallGroup { items: tools } = &items[4] else {
+            panic!("expected a tool call group");
+        };
+        assert_eq!(tools[0].data.kind.as_deref(), Some("read"));
+        assert_eq!(tools[0].data.content[0].text, "fn retry() {}");
+        let diff = tools[0].data.content[1].diff.as_ref().unwrap();
+        assert_eq!(diff.path, "src/lib.rs");
+        assert_eq!(diff.old_text.as_deref(), Some("fn retry() {}"));
+        assert!(tools[1].data.status.is_none() && tools[1].data.content.is_empty());
+    }
 }
@@ -59,6 +59,9 @@ pub struct WorkspaceTask {
     pub agent_name: String,
     /// Task mode (Auto, Ask, Plan, Code, Explain)
     pub mode: String,
+    /// Optional longer description, matched by the task search
+    #[serde(default)]
+    pub description: Option<String>,
     /// Session ID if a session has been created
     pub session_id: Option<String>,
     /// Task status
@@ -80,6 +83,7 @@ impl WorkspaceTask {
             name,
             agent_name,
             mode,
+            description: None,
             session_id: None,
             status: SessionStatus::Pending,
             created_at: chrono::Utc::now(),
@@ -3,7 +3,7 @@
 //! 本模块集中管理所有应用中使用的 GPUI Actions，便于维护和查找。
 //! Actions 是 GPUI 中用于触发用户操作的类型安全机制。
 
-use agent_client_protocol::{ImageContent, ToolCall};
+use agent_client_protocol::{ContentBlock, ImageContent, ToolCall};
 use gpui::{Action, SharedString, actions};
 use gpui_component::{ThemeMode, dock::DockPlacement, scroll::ScrollbarShow};
 use serde::Deserialize;
@@ -18,15 +18,23 @@ use std::path::PathBuf;
 pub enum PanelKind {
     /// 对话面板，可选 session_id
     Conversation { session_id: Option<String> },
+    /// 从文件打开的已保存对话
+    SavedConversation { path: PathBuf },
+    /// 内置的演示对话
+    DemoConversation,
     /// 终端面板，可选工作目录
     Terminal {
         #[serde(skip)]
         working_directory: Option<PathBuf>,
     },
-    /// 代码编辑器面板，可选工作目录
+    /// 终端输出面板，可选要切换到的终端
+    TerminalOutput { terminal_id: Option<String> },
+    /// 代码编辑器面板，可选工作目录和要打开的文件位置
     CodeEditor {
         #[serde(skip)]
         working_directory: Option<PathBuf>,
+        #[serde(skip)]
+        open_at: Option<(PathBuf, lsp_types::Position)>,
     },
     /// 欢迎面板，可选 workspace_id
     Welcome { workspace_id: Option<String> },
@@ -46,6 +54,9 @@ pub enum PanelCommand {
         placement: DockPlacement,
     },
     Show(PanelKind),
+    /// Like `Show`, but the keyboard focus stays where it is; for panels
+    /// revealed on their own, e.g. when a tool call completes
+    Reveal(PanelKind),
 }
 
 /// 统一的面板操作 Action
@@ -70,6 +81,22 @@ impl PanelAction {
         })
     }
 
+    /// 在中间区域打开一个已保存的对话
+    pub fn open_saved_conversation(path: PathBuf) -> Self {
+        Self(PanelCommand::Add {
+            panel: PanelKind::SavedConversation { path },
+            placement: DockPlacement::Center,
+        })
+    }
+
+    /// 在中间区域打开内置的演示对话
+    pub fn open_demo_conversation() -> Self {
+        Self(PanelCommand::Add {
+            panel: PanelKind::DemoConversation,
+            placement: DockPlacement::Center,
+        })
+    }
+
     pub fn add_terminal(placement: DockPlacement, working_directory: Option<PathBuf>) -> Self {
         Self(PanelCommand::Add {
             panel: PanelKind::Terminal { working_directory },
@@ -79,11 +106,55 @@ impl PanelAction {
 
     pub fn add_code_editor(placement: DockPlacement, working_directory: Option<PathBuf>) -> Self {
         Self(PanelCommand::Add {
-            panel: PanelKind::CodeEditor { working_directory },
+            panel: PanelKind::CodeEditor {
+                working_directory,
+                open_at: None,
+            },
             placement,
         })
     }
 
+    /// 在新的编辑器标签页中打开文件，并将光标移动到 `position`
+    pub fn open_code_editor_at(
+        working_directory: PathBuf,
+        path: PathBuf,
+        position: lsp_types::Position,
+    ) -> Self {
+        Self(PanelCommand::Add {
+            panel: PanelKind::CodeEditor {
+                working_directory: Some(working_directory),
+                open_at: Some((path, position)),
+            },
+            placement: DockPlacement::Center,
+        })
+    }
+
+    /// 显示终端：已有终端时展开底部面板，否则新建一个
+    pub fn show_terminal(working_directory: Option<PathBuf>) -> Self {
+        Self(PanelCommand::Show(PanelKind::Terminal {
+            working_directory,
+        }))
+    }
+
+    /// 显示终端输出面板，并切换到 `terminal_id` 对应的标签页
+    pub fn show_terminal_output(terminal_id: Option<String>) -> Self {
+        Self(PanelCommand::Show(PanelKind::TerminalOutput {
+            terminal_id,
+        }))
+    }
+
+    /// 在右侧编辑器中显示文件，并将光标移动到 `position`
+    pub fn show_code_editor_at(
+        working_directory: PathBuf,
+        path: PathBuf,
+        position: lsp_types::Position,
+    ) -> Self {
+        Self(PanelCommand::Show(PanelKind::CodeEditor {
+            working_directory: Some(working_directory),
+            open_at: Some((path, position)),
+        }))
+    }
+
     pub fn add_welcome(workspace_id: Option<String>, placement: DockPlacement) -> Self {
         Self(PanelCommand::Add {
             panel: PanelKind::Welcome { workspace_id },
@@ -91,6 +162,14 @@ impl PanelAction {
         })
     }
 
+    /// 将 `Show` 改为 `Reveal`：显示面板但不抢占焦点
+    pub fn in_background(self) -> Self {
+        match self.0 {
+            PanelCommand::Show(panel) => Self(PanelCommand::Reveal(panel)),
+            command => Self(command),
+        }
+    }
+
     pub fn show_welcome(workspace_id: Option<String>) -> Self {
         Self(PanelCommand::Show(PanelKind::Welcome { workspace_id }))
     }
@@ -226,6 +305,18 @@ pub struct SendMessageToSession {
     pub code_selections: Vec<AddCodeSelection>,
 }
 
+/// 重新发送失败的提示
+///
+/// 由会话面板中错误项的“重试”按钮触发，原样重发上一次失败的提示内容
+#[derive(Action, Clone, Debug, PartialEq, Deserialize)]
+#[action(namespace = agentx, no_json)]
+pub struct RetryPrompt {
+    /// 会话唯一标识符
+    pub session_id: String,
+    /// 失败的提示内容块
+    pub prompt: Vec<ContentBlock>,
+}
+
 /// 取消会话
 ///
 /// 用于取消正在进行中的会话，由 ConversationPanel 的暂停按钮触发
@@ -282,6 +373,9 @@ impl From<agentx_types::events::CodeSelectionData> for AddCodeSelection {
     }
 }
 
+// 代码编辑器操作 - 跳转到光标处符号的定义（F12）
+actions!(code_editor, [GoToDefinition]);
+
 impl From<AddCodeSelection> for agentx_types::events::CodeSelectionData {
     fn from(action: AddCodeSelection) -> Self {
         Self {
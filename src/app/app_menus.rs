@@ -3,7 +3,7 @@ use gpui_component::{ThemeMode, ThemeRegistry};
 use rust_i18n::t;
 
 use crate::{
-    About, CloseWindow, Open, Quit, SelectLocale, ToggleSearch,
+    About, CloseWindow, Open, PanelAction, Quit, SelectLocale, ToggleSearch,
     app::actions::{SwitchTheme, SwitchThemeMode},
 };
 
@@ -91,10 +91,13 @@ pub fn init(title: impl Into<SharedString>, cx: &mut App) {
         },
         Menu {
             name: t!("menu.help.title").to_string().into(),
-            items: vec![MenuItem::action(
-                t!("menu.help.open_website").to_string(),
-                Open,
-            )],
+            items: vec![
+                MenuItem::action(t!("menu.help.open_website").to_string(), Open),
+                MenuItem::action(
+                    t!("menu.help.demo_conversation").to_string(),
+                    PanelAction::open_demo_conversation(),
+                ),
+            ],
         },
     ]);
 }
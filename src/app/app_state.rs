@@ -3,13 +3,18 @@ use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use crate::{
+    components::ContentRenderers,
     core::agent::{AgentManager, PermissionStore},
-    core::config::DEFAULT_TOOL_CALL_PREVIEW_MAX_LINES,
+    core::config::{
+        DEFAULT_MAX_INLINE_CONTENT_BYTES, DEFAULT_TOOL_CALL_PREVIEW_MAX_LINES,
+        ScrollAxisPreference, ThemePreference, WhitespaceRendering,
+    },
     core::event_bus::EventHub,
     core::services::{
         AgentConfigService, AgentService, AiService, MessageService, PersistenceService,
-        WorkspaceService,
+        RequestPolicy, WorkspaceService,
     },
+    panels::TerminalOutputs,
 };
 
 use super::service_registry::ServiceRegistry;
@@ -25,6 +30,8 @@ pub struct AppState {
     // UI state (GPUI entities)
     pub invisible_panels: Entity<Vec<SharedString>>,
     pub selected_tool_call: Entity<Option<agent_client_protocol::ToolCall>>,
+    /// Command output from tool calls, shown by the terminal output panel
+    pub terminal_outputs: Entity<TerminalOutputs>,
 
     // Infrastructure
     agent_manager: Option<Arc<AgentManager>>,
@@ -37,6 +44,17 @@ pub struct AppState {
     config_path: Option<PathBuf>,
     current_working_dir: PathBuf,
     tool_call_preview_max_lines: usize,
+    max_inline_content_bytes: usize,
+    theme_preference: ThemePreference,
+    conversation_scroll: ScrollAxisPreference,
+    default_agent: Option<String>,
+    default_mode: Option<String>,
+    show_indent_guides: bool,
+    render_whitespace: WhitespaceRendering,
+    auto_open_panels: bool,
+    auto_collapse_completed_tools: bool,
+    prompt_token_warning: Option<usize>,
+    prompt_token_limit: Option<usize>,
 
     // Temporary UI state
     welcome_session: Option<WelcomeSession>,
@@ -60,7 +78,7 @@ impl AppState {
         services.set_workspace_service(Arc::new(workspace_service));
 
         let sessions_dir = crate::core::config_manager::get_sessions_dir();
-        services.set_persistence_service(Arc::new(PersistenceService::new(sessions_dir)));
+        services.set_persistence_service(Arc::new(new_persistence_service(sessions_dir)));
 
         let state = Self {
             invisible_panels: cx.new(|_| Vec::new()),
@@ -71,7 +89,19 @@ impl AppState {
             config_path: None,
             current_working_dir: Self::resolve_initial_working_dir(),
             tool_call_preview_max_lines: DEFAULT_TOOL_CALL_PREVIEW_MAX_LINES,
+            max_inline_content_bytes: DEFAULT_MAX_INLINE_CONTENT_BYTES,
+            theme_preference: ThemePreference::default(),
+            conversation_scroll: ScrollAxisPreference::default(),
+            default_agent: None,
+            default_mode: None,
+            show_indent_guides: true,
+            render_whitespace: WhitespaceRendering::default(),
+            auto_open_panels: false,
+            auto_collapse_completed_tools: false,
+            prompt_token_warning: None,
+            prompt_token_limit: None,
             selected_tool_call: cx.new(|_| None),
+            terminal_outputs: cx.new(|_| TerminalOutputs::default()),
             app_title: SharedString::from(""),
         };
         cx.set_global::<AppState>(state);
@@ -139,7 +169,7 @@ impl AppState {
         let persistence_service = match self.services.persistence_service() {
             Ok(ps) => ps.clone(),
             Err(_) => {
-                let ps = Arc::new(PersistenceService::new(sessions_dir));
+                let ps = Arc::new(new_persistence_service(sessions_dir));
                 self.services.set_persistence_service(ps.clone());
                 ps
             }
@@ -157,6 +187,13 @@ impl AppState {
             agent_service.clone(),
             persistence_service,
         ));
+        message_service.set_request_policy(RequestPolicy::from(&initial_config));
+        {
+            let message_service = message_service.clone();
+            event_hub.subscribe_agent_config_reloads(move |config| {
+                message_service.set_request_policy(RequestPolicy::from(config));
+            });
+        }
 
         // Initialize AgentConfigService if config_path is set
         if let Some(config_path) = &self.config_path {
@@ -189,13 +226,48 @@ impl AppState {
         self.agent_manager = Some(manager);
         self.services.set_agent_service(agent_service);
         self.services.set_message_service(message_service);
-        self.tool_call_preview_max_lines = initial_config.tool_call_preview_max_lines;
+        self.apply_config(&initial_config);
+        self.theme_preference = initial_config.theme;
+        self.conversation_scroll = initial_config.conversation_scroll;
+        self.default_agent = initial_config.default_agent.clone();
+        self.default_mode = initial_config.default_mode.clone();
+        self.show_indent_guides = initial_config.show_indent_guides;
 
         log::info!(
             "Initialized service layer (AgentService, MessageService, PersistenceService, AgentConfigService, AiService)"
         );
     }
 
+    /// Keep the config-derived settings current when `config.json` is reloaded
+    pub fn subscribe_to_config_reloads(cx: &mut App) {
+        let event_hub = Self::global(cx).event_hub().clone();
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        event_hub.subscribe_agent_config_reloads(move |config| {
+            let _ = tx.send(config.clone());
+        });
+
+        cx.spawn(async move |cx| {
+            while let Some(config) = rx.recv().await {
+                let _ = cx.update(|cx| {
+                    Self::global_mut(cx).apply_config(&config);
+                    cx.refresh_windows();
+                });
+            }
+        })
+        .detach();
+    }
+
+    /// Settings read from `config.json` that take effect without a restart
+    fn apply_config(&mut self, config: &crate::core::config::Config) {
+        self.tool_call_preview_max_lines = config.tool_call_preview_max_lines;
+        self.max_inline_content_bytes = config.max_inline_content_bytes;
+        self.render_whitespace = config.render_whitespace;
+        self.auto_open_panels = config.auto_open_panels;
+        self.auto_collapse_completed_tools = config.auto_collapse_completed_tools;
+        self.prompt_token_warning = config.prompt_token_warning;
+        self.prompt_token_limit = config.prompt_token_limit;
+    }
+
     /// Set the config path for AgentConfigService
     pub fn set_config_path(&mut self, path: PathBuf) {
         self.config_path = Some(path);
@@ -292,5 +364,93 @@ impl AppState {
     pub fn tool_call_preview_max_lines(&self) -> usize {
         self.tool_call_preview_max_lines
     }
+
+    /// Get the size above which tool output is offloaded to a temporary file
+    pub fn max_inline_content_bytes(&self) -> usize {
+        self.max_inline_content_bytes
+    }
+
+    /// Get the configured scroll axes for conversation views
+    pub fn conversation_scroll(&self) -> ScrollAxisPreference {
+        self.conversation_scroll
+    }
+
+    /// Get the agent preselected in the welcome panel (`default_agent` in config.json)
+    pub fn default_agent(&self) -> Option<&str> {
+        self.default_agent.as_deref()
+    }
+
+    /// Set the preselected agent; persisting it is up to the caller
+    pub fn set_default_agent(&mut self, agent: Option<String>) {
+        self.default_agent = agent;
+    }
+
+    /// Get the mode preselected for new sessions (`default_mode` in config.json)
+    pub fn default_mode(&self) -> Option<&str> {
+        self.default_mode.as_deref()
+    }
+
+    /// Set the preselected mode; persisting it is up to the caller
+    pub fn set_default_mode(&mut self, mode: Option<String>) {
+        self.default_mode = mode;
+    }
+
+    /// Whether the code editor draws indent guides (`show_indent_guides` in config.json)
+    pub fn show_indent_guides(&self) -> bool {
+        self.show_indent_guides
+    }
+
+    /// Set whether the code editor draws indent guides; persisting it is up to the caller
+    pub fn set_show_indent_guides(&mut self, show: bool) {
+        self.show_indent_guides = show;
+    }
+
+    /// Whitespace the code editor marks (`render_whitespace` in config.json)
+    pub fn render_whitespace(&self) -> WhitespaceRendering {
+        self.render_whitespace
+    }
+
+    /// Set the whitespace the code editor marks; persisting it is up to the caller
+    pub fn set_render_whitespace(&mut self, rendering: WhitespaceRendering) {
+        self.render_whitespace = rendering;
+    }
+
+    /// Whether finished tool calls open their panel (`auto_open_panels` in config.json)
+    pub fn auto_open_panels(&self) -> bool {
+        self.auto_open_panels
+    }
+
+    /// Set whether finished tool calls open their panel; persisting it is up to the caller
+    pub fn set_auto_open_panels(&mut self, auto_open: bool) {
+        self.auto_open_panels = auto_open;
+    }
+
+    /// Whether completed tool calls collapse in new conversation panels
+    /// (`auto_collapse_completed_tools` in config.json)
+    pub fn auto_collapse_completed_tools(&self) -> bool {
+        self.auto_collapse_completed_tools
+    }
+
+    /// Token estimates at which the chat input's size counter turns amber and
+    /// red (`prompt_token_warning` / `prompt_token_limit` in config.json)
+    pub fn prompt_token_thresholds(&self) -> (Option<usize>, Option<usize>) {
+        (self.prompt_token_warning, self.prompt_token_limit)
+    }
+
+    /// Get the configured theme preference
+    pub fn theme_preference(&self) -> ThemePreference {
+        self.theme_preference
+    }
+
+    /// Set the theme preference (see `themes::set_theme_preference` to apply and persist it)
+    pub fn set_theme_preference(&mut self, theme: ThemePreference) {
+        self.theme_preference = theme;
+    }
 }
 impl Global for AppState {}
+
+/// Persistence service that saves thoughts in the text form the UI shows
+fn new_persistence_service(sessions_dir: PathBuf) -> PersistenceService {
+    let renderers = ContentRenderers::default();
+    PersistenceService::new(sessions_dir).with_content_text(move |block| renderers.text(block))
+}
@@ -1,6 +1,9 @@
-use gpui::{App, KeyBinding};
+use std::rc::Rc;
 
-use crate::app::actions::{Open, Paste, Quit, ToggleSearch};
+use gpui::{App, KeyBinding, KeyBindingContextPredicate};
+
+use crate::app::actions::{GoToDefinition, Open, Paste, Quit, ToggleSearch};
+use crate::core::config::{Config, Keybinding};
 use gpui_term::{Clear, Copy, SelectAll};
 
 // 导出KeyBinding设置函数,供主应用使用
@@ -36,5 +39,49 @@ pub fn init(cx: &mut App) {
         KeyBinding::new("cmd-a", SelectAll, Some("Terminal")),
         #[cfg(not(target_os = "macos"))]
         KeyBinding::new("ctrl-shift-a", SelectAll, Some("Terminal")),
+        // Code editor keybindings
+        KeyBinding::new("f12", GoToDefinition, Some("CodeEditor")),
     ]);
 }
+
+/// Register the keybindings from `config.json`. They are bound after the
+/// built-in ones from [`init`], which stay the only defaults, so a chord
+/// given here wins.
+pub fn bind_config_keys(config: &Config, cx: &mut App) {
+    let bindings: Vec<KeyBinding> = config
+        .keybindings()
+        .into_iter()
+        .filter_map(|keybinding| match load_keybinding(&keybinding, cx) {
+            Ok(binding) => Some(binding),
+            Err(e) => {
+                log::warn!(
+                    "Skipping keybinding '{}' for {}: {}",
+                    keybinding.keystrokes,
+                    keybinding.action,
+                    e
+                );
+                None
+            }
+        })
+        .collect();
+    log::info!("Registered {} keybindings from config", bindings.len());
+    cx.bind_keys(bindings);
+}
+
+fn load_keybinding(keybinding: &Keybinding, cx: &App) -> anyhow::Result<KeyBinding> {
+    let action = cx.build_action(&keybinding.action, None)?;
+    let context = keybinding
+        .context
+        .as_deref()
+        .map(KeyBindingContextPredicate::parse)
+        .transpose()?
+        .map(Rc::new);
+    Ok(KeyBinding::load(
+        &keybinding.keystrokes,
+        action,
+        context,
+        false,
+        None,
+        cx.keyboard_mapper().as_ref(),
+    )?)
+}
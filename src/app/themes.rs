@@ -1,11 +1,13 @@
 use std::path::PathBuf;
 
-use gpui::{App, SharedString, px};
-use gpui_component::{ActiveTheme, Theme, ThemeRegistry, scroll::ScrollbarShow};
+use gpui::{App, SharedString, Window, WindowAppearance, px};
+use gpui_component::{ActiveTheme, Theme, ThemeMode, ThemeRegistry, scroll::ScrollbarShow};
 use serde::{Deserialize, Serialize};
 
 use crate::app::actions::{SwitchTheme, SwitchThemeMode};
+use crate::core::config::{Config, DiffViewPreference, ThemePreference};
 use crate::panels::AppSettings;
+use crate::{AppState, CodeWrap, DiffViewMode, GroupToolCalls, UiScale};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct State {
@@ -173,12 +175,164 @@ pub fn init(cx: &mut App) {
         cx.refresh_windows();
     });
     cx.on_action(|switch: &SwitchThemeMode, cx| {
-        let mode = switch.0;
-        Theme::change(mode, None, cx);
-        cx.refresh_windows();
+        let preference = if switch.0.is_dark() {
+            ThemePreference::Dark
+        } else {
+            ThemePreference::Light
+        };
+        set_theme_preference(preference, cx);
     });
 }
 
+/// Resolve a theme preference to a light/dark mode, following `appearance` for "system"
+pub fn resolve_theme_mode(preference: ThemePreference, appearance: WindowAppearance) -> ThemeMode {
+    match preference {
+        ThemePreference::Light => ThemeMode::Light,
+        ThemePreference::Dark => ThemeMode::Dark,
+        ThemePreference::System => match appearance {
+            WindowAppearance::Dark | WindowAppearance::VibrantDark => ThemeMode::Dark,
+            WindowAppearance::Light | WindowAppearance::VibrantLight => ThemeMode::Light,
+        },
+    }
+}
+
+/// Apply the theme configured in `config.json`
+pub fn apply_theme(config: &Config, cx: &mut App) {
+    AppState::global_mut(cx).set_theme_preference(config.theme);
+    let mode = resolve_theme_mode(config.theme, cx.window_appearance());
+    Theme::change(mode, None, cx);
+    cx.refresh_windows();
+}
+
+/// Change the theme preference, applying it live and writing it back to `config.json`
+pub fn set_theme_preference(preference: ThemePreference, cx: &mut App) {
+    AppState::global_mut(cx).set_theme_preference(preference);
+    let mode = resolve_theme_mode(preference, cx.window_appearance());
+    Theme::change(mode, None, cx);
+    cx.refresh_windows();
+
+    let Some(service) = AppState::global(cx).agent_config_service().cloned() else {
+        log::warn!("AgentConfigService not initialized, theme preference not persisted");
+        return;
+    };
+    cx.spawn(async move |_cx| {
+        if let Err(e) = service.update_theme(preference).await {
+            log::error!("Failed to save theme preference: {}", e);
+        }
+    })
+    .detach();
+}
+
+/// Apply the UI scale factor configured in `config.json`
+pub fn apply_ui_scale(config: &Config, cx: &mut App) {
+    cx.set_global(UiScale::new(config.ui_scale));
+    cx.refresh_windows();
+}
+
+/// Change the UI scale factor, applying it live and writing it back to `config.json`
+pub fn set_ui_scale(factor: f32, cx: &mut App) {
+    let scale = UiScale::new(factor);
+    cx.set_global(scale);
+    cx.refresh_windows();
+
+    let Some(service) = AppState::global(cx).agent_config_service().cloned() else {
+        log::warn!("AgentConfigService not initialized, UI scale not persisted");
+        return;
+    };
+    cx.spawn(async move |_cx| {
+        if let Err(e) = service.update_ui_scale(scale.factor()).await {
+            log::error!("Failed to save UI scale: {}", e);
+        }
+    })
+    .detach();
+}
+
+/// Apply the diff layout configured in `config.json`, and write it back
+/// whenever the toggle in a diff view changes it
+pub fn apply_diff_view_mode(config: &Config, cx: &mut App) {
+    cx.set_global(match config.diff_view {
+        DiffViewPreference::Unified => DiffViewMode::Unified,
+        DiffViewPreference::Split => DiffViewMode::Split,
+    });
+    cx.refresh_windows();
+
+    cx.observe_global::<DiffViewMode>(|cx| {
+        let preference = match DiffViewMode::global(cx) {
+            DiffViewMode::Unified => DiffViewPreference::Unified,
+            DiffViewMode::Split => DiffViewPreference::Split,
+        };
+        let Some(service) = AppState::global(cx).agent_config_service().cloned() else {
+            log::warn!("AgentConfigService not initialized, diff layout not persisted");
+            return;
+        };
+        cx.spawn(async move |_cx| {
+            if let Err(e) = service.update_diff_view(preference).await {
+                log::error!("Failed to save diff layout: {}", e);
+            }
+        })
+        .detach();
+    })
+    .detach();
+}
+
+/// Apply the default wrapping of code and diff blocks configured in
+/// `config.json`, and write it back whenever the setting changes it
+pub fn apply_code_wrap(config: &Config, cx: &mut App) {
+    cx.set_global(CodeWrap(config.default_code_wrap));
+    cx.refresh_windows();
+
+    cx.observe_global::<CodeWrap>(|cx| {
+        let wrap = CodeWrap::global(cx);
+        let Some(service) = AppState::global(cx).agent_config_service().cloned() else {
+            log::warn!("AgentConfigService not initialized, code wrapping not persisted");
+            return;
+        };
+        cx.spawn(async move |_cx| {
+            if let Err(e) = service.update_default_code_wrap(wrap).await {
+                log::error!("Failed to save code wrapping: {}", e);
+            }
+        })
+        .detach();
+    })
+    .detach();
+}
+
+/// Apply the tool call grouping configured in `config.json`, and write it
+/// back whenever the setting changes it
+pub fn apply_tool_call_grouping(config: &Config, cx: &mut App) {
+    cx.set_global(GroupToolCalls(config.group_tool_calls));
+    cx.refresh_windows();
+
+    cx.observe_global::<GroupToolCalls>(|cx| {
+        let group = GroupToolCalls::global(cx);
+        let Some(service) = AppState::global(cx).agent_config_service().cloned() else {
+            log::warn!("AgentConfigService not initialized, tool call grouping not persisted");
+            return;
+        };
+        cx.spawn(async move |_cx| {
+            if let Err(e) = service.update_group_tool_calls(group).await {
+                log::error!("Failed to save tool call grouping: {}", e);
+            }
+        })
+        .detach();
+    })
+    .detach();
+}
+
+/// Re-apply the theme after an OS appearance change when following the system theme
+pub fn sync_system_appearance(window: &mut Window, cx: &mut App) {
+    let preference = AppState::global(cx).theme_preference();
+    if preference != ThemePreference::System {
+        return;
+    }
+
+    let mode = resolve_theme_mode(preference, window.appearance());
+    if cx.theme().mode != mode {
+        Theme::change(mode, Some(window), cx);
+        cx.refresh_windows();
+    }
+}
+
 /// Helper function to save current state to file
 pub(crate) fn save_state(cx: &mut App) {
     let existing_state = load_state_file();
@@ -191,3 +345,33 @@ pub(crate) fn save_state(cx: &mut App) {
 
     write_state_file(&state);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dark_config_resolves_to_dark_theme() {
+        let config: Config =
+            serde_json::from_str(r#"{"agent_servers": {}, "theme": "dark"}"#).unwrap();
+        assert_eq!(config.theme, ThemePreference::Dark);
+        assert_eq!(
+            resolve_theme_mode(config.theme, WindowAppearance::Light),
+            ThemeMode::Dark
+        );
+    }
+
+    #[test]
+    fn system_theme_follows_appearance() {
+        let config: Config = serde_json::from_str(r#"{"agent_servers": {}}"#).unwrap();
+        assert_eq!(config.theme, ThemePreference::System);
+        assert_eq!(
+            resolve_theme_mode(config.theme, WindowAppearance::VibrantDark),
+            ThemeMode::Dark
+        );
+        assert_eq!(
+            resolve_theme_mode(config.theme, WindowAppearance::Light),
+            ThemeMode::Light
+        );
+    }
+}
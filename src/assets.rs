@@ -106,11 +106,11 @@ impl IconNamed for Icon {
     }
 }
 
-/// Get icon based on agent name
-pub fn get_agent_icon(name: &str) -> Icon {
+/// Icon of a known agent, matched by name
+pub fn find_agent_icon(name: &str) -> Option<Icon> {
     let name_lower = name.to_lowercase();
     // TODO Check for specific agent names
-    if name_lower.contains("augment") {
+    let icon = if name_lower.contains("augment") {
         crate::assets::Icon::AugmentCode
     } else if name_lower.contains("claude") {
         crate::assets::Icon::Claude
@@ -141,9 +141,15 @@ pub fn get_agent_icon(name: &str) -> Icon {
     } else if name_lower.contains("opencode") {
         crate::assets::Icon::OpenCode
     } else {
-        // Default to Claude icon if no match
-        crate::assets::Icon::Claude
-    }
+        return None;
+    };
+    Some(icon)
+}
+
+/// Get icon based on agent name
+pub fn get_agent_icon(name: &str) -> Icon {
+    // Default to Claude icon if no match
+    find_agent_icon(name).unwrap_or(crate::assets::Icon::Claude)
 }
 
 /// Get default config.json content embedded in the binary
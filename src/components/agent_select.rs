@@ -1,15 +1,63 @@
-use gpui::{AnyElement, App, IntoElement, ParentElement, SharedString, Styled, Window};
-use gpui_component::{Icon, Sizable, h_flex, select::SelectItem};
+use gpui::{
+    AnyElement, App, IntoElement, ParentElement, RenderOnce, SharedString, Styled, Window, div,
+    prelude::FluentBuilder as _,
+};
+use gpui_component::{ActiveTheme, Icon, Sizable, h_flex, select::SelectItem};
+
+use crate::core::services::AgentStatus;
 
 /// An agent item with icon for the select dropdown
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct AgentItem {
     pub name: String,
+    /// Connection state; `None` for placeholder items ("Loading agents...")
+    pub status: Option<AgentStatus>,
 }
 
 impl AgentItem {
     pub fn new(name: impl Into<String>) -> Self {
-        Self { name: name.into() }
+        Self {
+            name: name.into(),
+            status: None,
+        }
+    }
+
+    pub fn status(mut self, status: AgentStatus) -> Self {
+        self.status = Some(status);
+        self
+    }
+
+    /// Whether the agent failed to start and can't take a session
+    pub fn is_unavailable(&self) -> bool {
+        self.status.as_ref().is_some_and(AgentStatus::is_error)
+    }
+
+    fn render_row(&self) -> gpui::Div {
+        let icon = crate::assets::get_agent_icon(&self.name);
+        h_flex()
+            .gap_2()
+            .items_center()
+            .when_some(self.status.clone(), |this, status| {
+                this.child(StatusDot(status))
+            })
+            .child(Icon::new(icon).xsmall())
+            .child(self.name.clone())
+            .when(self.is_unavailable(), |this| this.opacity(0.5))
+    }
+}
+
+/// Dot colored by an agent's connection state
+#[derive(IntoElement)]
+struct StatusDot(AgentStatus);
+
+impl RenderOnce for StatusDot {
+    fn render(self, _window: &mut Window, cx: &mut App) -> impl IntoElement {
+        let color = match self.0 {
+            AgentStatus::Connected => cx.theme().success,
+            AgentStatus::Connecting => cx.theme().warning,
+            AgentStatus::Error(_) => cx.theme().danger,
+        };
+        div().size_1p5().flex_none().rounded_full().bg(color)
     }
 }
 
@@ -21,24 +69,11 @@ impl SelectItem for AgentItem {
     }
 
     fn display_title(&self) -> Option<AnyElement> {
-        let icon = crate::assets::get_agent_icon(&self.name);
-        Some(
-            h_flex()
-                .gap_2()
-                .items_center()
-                .child(Icon::new(icon).xsmall())
-                .child(self.name.clone())
-                .into_any_element(),
-        )
+        Some(self.render_row().into_any_element())
     }
 
     fn render(&self, _window: &mut Window, _cx: &mut App) -> impl gpui::IntoElement {
-        let icon = crate::assets::get_agent_icon(&self.name);
-        h_flex()
-            .gap_2()
-            .items_center()
-            .child(Icon::new(icon).xsmall())
-            .child(self.name.clone())
+        self.render_row()
     }
 
     fn value(&self) -> &Self::Value {
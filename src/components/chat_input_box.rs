@@ -15,10 +15,11 @@ use gpui_component::{
 };
 
 use agent_client_protocol::{AvailableCommand, ImageContent};
+use rust_i18n::t;
 
 use crate::app::actions::AddCodeSelection;
 use crate::components::{
-    AgentItem, FileItem, InputSuggestion, InputSuggestionItem, InputSuggestionState,
+    AgentItem, FileItem, InputHistory, InputSuggestion, InputSuggestionItem, InputSuggestionState,
     ModeSelectItem, ModelSelectItem,
 };
 use crate::core::config::McpServerConfig;
@@ -34,6 +35,148 @@ impl InputSuggestionItem for AvailableCommand {
     }
 }
 
+/// The command prefix typed after a leading `/`, or `None` if the input is not
+/// (or is no longer) a slash command name
+pub fn slash_command_query(input: &str) -> Option<&str> {
+    let query = input.trim_start().strip_prefix('/')?;
+    (!query.chars().any(char::is_whitespace)).then_some(query)
+}
+
+/// The path prefix typed after the last `@`, or `None` if the input doesn't
+/// end in a mention. The `@` has to start a word, so `me@host` isn't one.
+pub fn mention_query(input: &str) -> Option<&str> {
+    let at = input.rfind('@')?;
+    let at_boundary = input[..at]
+        .chars()
+        .next_back()
+        .is_none_or(char::is_whitespace);
+    let query = &input[at + 1..];
+    (at_boundary && !query.chars().any(char::is_whitespace)).then_some(query)
+}
+
+/// `input` with the mention being typed replaced by `@target `, or `None` if
+/// it doesn't end in a mention
+pub fn complete_mention(input: &str, target: &str) -> Option<String> {
+    mention_query(input)?;
+    let prefix = &input[..input.rfind('@')?];
+    Some(format!("{prefix}@{target} "))
+}
+
+/// Commands whose name starts with `prefix`, in the order the agent advertised them
+pub fn matching_commands(commands: Vec<AvailableCommand>, prefix: &str) -> Vec<AvailableCommand> {
+    commands
+        .into_iter()
+        .filter(|command| command.name.starts_with(prefix))
+        .collect()
+}
+
+/// A message submitted from a [`ChatInputBox`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChatSubmit {
+    pub text: String,
+    /// Id of the selected mode, when the box has a mode select
+    pub mode: Option<String>,
+    /// Name of the selected agent, when the box has an agent select
+    pub agent: Option<String>,
+}
+
+impl ChatSubmit {
+    /// The submit for `text`, or `None` when it is blank
+    pub fn new(text: String, mode: Option<String>, agent: Option<String>) -> Option<Self> {
+        (!text.trim().is_empty()).then_some(Self { text, mode, agent })
+    }
+}
+
+/// Rough token count of `text` for the size counter: a token per four characters
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(4)
+}
+
+/// Size of the text in a [`ChatInputBox`], shown under it while typing
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct InputSize {
+    pub chars: usize,
+    pub tokens: usize,
+}
+
+impl InputSize {
+    pub fn of(text: &str) -> Self {
+        Self {
+            chars: text.chars().count(),
+            tokens: estimate_tokens(text),
+        }
+    }
+
+    /// How close the estimate is to the `warning` and `limit` token thresholds
+    fn level(&self, warning: Option<usize>, limit: Option<usize>) -> SizeLevel {
+        if limit.is_some_and(|limit| self.tokens >= limit) {
+            SizeLevel::OverLimit
+        } else if warning.is_some_and(|warning| self.tokens >= warning) {
+            SizeLevel::Warning
+        } else {
+            SizeLevel::Normal
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SizeLevel {
+    Normal,
+    Warning,
+    OverLimit,
+}
+
+/// What a session mode is for, as far as the input's wording goes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModeInputKind {
+    Default,
+    Plan,
+    Code,
+    Ask,
+}
+
+impl ModeInputKind {
+    /// Recognize a mode by its id ("plan", "acceptEdits", "read-only", ...);
+    /// modes without their own wording are `Default`
+    pub fn of(mode_id: Option<&str>) -> Self {
+        let Some(mode_id) = mode_id else {
+            return Self::Default;
+        };
+        let mode_id = mode_id.to_lowercase();
+        if mode_id.contains("plan") || mode_id.contains("architect") {
+            Self::Plan
+        } else if mode_id.contains("ask") || mode_id.contains("read") {
+            Self::Ask
+        } else if mode_id.contains("code") || mode_id.contains("edit") {
+            Self::Code
+        } else {
+            Self::Default
+        }
+    }
+
+    /// Placeholder of the input while this mode is selected
+    pub fn placeholder(self) -> String {
+        match self {
+            Self::Default => t!("welcome.input.placeholder"),
+            Self::Plan => t!("chat_input.mode.plan.placeholder"),
+            Self::Code => t!("chat_input.mode.code.placeholder"),
+            Self::Ask => t!("chat_input.mode.ask.placeholder"),
+        }
+        .to_string()
+    }
+
+    /// Title above the input while this mode is selected
+    pub fn title(self) -> String {
+        match self {
+            Self::Default => t!("chat_input.mode.default.title"),
+            Self::Plan => t!("chat_input.mode.plan.title"),
+            Self::Code => t!("chat_input.mode.code.title"),
+            Self::Ask => t!("chat_input.mode.ask.title"),
+        }
+        .to_string()
+    }
+}
+
 #[derive(Clone)]
 enum ChatSuggestion {
     Command(AvailableCommand),
@@ -78,6 +221,7 @@ pub struct ChatInputBox {
     title: Option<String>,
     on_send: Option<Box<dyn Fn(&gpui::ClickEvent, &mut Window, &mut App) + 'static>>,
     on_cancel: Option<Box<dyn Fn(&gpui::ClickEvent, &mut Window, &mut App) + 'static>>,
+    on_submit: Option<Rc<dyn Fn(&ChatSubmit, &mut Window, &mut App) + 'static>>,
     mode_select: Option<Entity<SelectState<Vec<ModeSelectItem>>>>,
     model_select: Option<Entity<SelectState<Vec<ModelSelectItem>>>>,
     agent_select: Option<Entity<SelectState<Vec<AgentItem>>>>,
@@ -106,6 +250,13 @@ pub struct ChatInputBox {
     on_mcp_toggle: Option<Rc<dyn Fn(&(String, bool), &mut Window, &mut App) + 'static>>,
     /// Whether the input is disabled (e.g., for closed/failed sessions)
     disabled: bool,
+    /// Size of the current text; the counter is only shown when set
+    input_size: Option<InputSize>,
+    /// Token estimates at which the counter turns amber and red
+    token_warning: Option<usize>,
+    token_limit: Option<usize>,
+    /// Sent messages recalled with Up/Down
+    input_history: Option<Entity<InputHistory>>,
 }
 
 impl ChatInputBox {
@@ -117,6 +268,7 @@ impl ChatInputBox {
             title: None,
             on_send: None,
             on_cancel: None,
+            on_submit: None,
             mode_select: None,
             model_select: None,
             agent_select: None,
@@ -138,6 +290,10 @@ impl ChatInputBox {
             selected_mcps: Vec::new(),
             on_mcp_toggle: None,
             disabled: false,
+            input_size: None,
+            token_warning: None,
+            token_limit: None,
+            input_history: None,
         }
     }
 
@@ -156,6 +312,17 @@ impl ChatInputBox {
         self
     }
 
+    /// Set a callback for Enter (Shift+Enter inserts a newline) and, without
+    /// `on_send`, the send button. It gets the text with the selected mode and
+    /// agent; the input is cleared first and blank input is never submitted.
+    pub fn on_submit<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&ChatSubmit, &mut Window, &mut App) + 'static,
+    {
+        self.on_submit = Some(Rc::new(callback));
+        self
+    }
+
     /// Set a callback for when the cancel button is clicked (when session is in progress)
     pub fn on_cancel<F>(mut self, callback: F) -> Self
     where
@@ -311,12 +478,92 @@ impl ChatInputBox {
         self.disabled = disabled;
         self
     }
+
+    /// Show the character count and token estimate of the current text under
+    /// the box. The owner keeps it current from the input's change events.
+    pub fn input_size(mut self, size: InputSize) -> Self {
+        self.input_size = Some(size);
+        self
+    }
+
+    /// Token estimates at which the size counter turns amber (`warning`) and
+    /// red (`limit`); either may be left unset
+    pub fn token_thresholds(mut self, warning: Option<usize>, limit: Option<usize>) -> Self {
+        self.token_warning = warning;
+        self.token_limit = limit;
+        self
+    }
+
+    /// Record sent messages in `history` and recall them with Up/Down. The
+    /// owner keeps the entity so the history outlives a render.
+    pub fn input_history(mut self, history: Entity<InputHistory>) -> Self {
+        self.input_history = Some(history);
+        self
+    }
 }
 
 impl RenderOnce for ChatInputBox {
     fn render(self, window: &mut Window, cx: &mut App) -> impl IntoElement {
         let on_send = self.on_send;
         let on_cancel = self.on_cancel;
+        let input_size = self.input_size;
+        let size_level = input_size.map(|size| size.level(self.token_warning, self.token_limit));
+        let in_progress = matches!(self.session_status, Some(SessionStatus::InProgress));
+        let submit = self
+            .on_submit
+            .clone()
+            .filter(|_| !self.disabled && !in_progress)
+            .map(|on_submit| {
+                let input_state = self.input_state.clone();
+                let mode_select = self.mode_select.clone();
+                let agent_select = self.agent_select.clone();
+                let history = self.input_history.clone();
+                Rc::new(move |window: &mut Window, cx: &mut App| {
+                    let text = input_state.read(cx).value().to_string();
+                    let mode = mode_select
+                        .as_ref()
+                        .and_then(|select| select.read(cx).selected_value().cloned());
+                    let agent = agent_select
+                        .as_ref()
+                        .and_then(|select| select.read(cx).selected_value().cloned());
+                    let Some(submit) = ChatSubmit::new(text, mode, agent) else {
+                        return;
+                    };
+                    if let Some(history) = &history {
+                        history.update(cx, |history, _| history.push(&submit.text));
+                    }
+                    input_state.update(cx, |state, cx| state.set_value("", window, cx));
+                    on_submit(&submit, window, cx);
+                }) as Rc<dyn Fn(&mut Window, &mut App)>
+            });
+        let on_history = self
+            .input_history
+            .clone()
+            .filter(|_| !self.disabled)
+            .map(|history| {
+                let input_state = self.input_state.clone();
+                move |delta: isize, window: &mut Window, cx: &mut App| {
+                    let (text, cursor) = {
+                        let state = input_state.read(cx);
+                        (state.value().to_string(), state.cursor())
+                    };
+                    let Some(value) =
+                        history.update(cx, |history, _| history.recall(delta, &text, cursor))
+                    else {
+                        return false;
+                    };
+                    // The key is being dispatched to the input
+                    let input_state = input_state.clone();
+                    window.defer(cx, move |window, cx| {
+                        input_state.update(cx, |state, cx| state.set_value(value, window, cx));
+                    });
+                    true
+                }
+            });
+        let sent_to_history = self
+            .input_history
+            .clone()
+            .map(|history| (history, self.input_state.clone()));
         let on_paste_callback = self.on_paste.clone();
         let input_state_for_paste = self.input_state.clone();
         let input_state = self.input_state.clone();
@@ -552,6 +799,12 @@ impl RenderOnce for ChatInputBox {
                                 .when_some(suggestion_header, |input, header| input.header(header))
                                 .max_height(px(200.))
                                 .apply_on_confirm(apply_on_confirm)
+                                .when_some(submit.clone(), |input, submit| {
+                                    input.on_submit(move |window, cx| submit(window, cx))
+                                })
+                                .when_some(on_history, |input, on_history| {
+                                    input.on_history(on_history)
+                                })
                                 .input(move |state| {
                                     Input::new(state).appearance(false).disabled(disabled)
                                 })
@@ -823,13 +1076,115 @@ impl RenderOnce for ChatInputBox {
                                     }
                                 } else if let Some(handler) = on_send {
                                     btn = btn.on_click(move |ev, window, cx| {
+                                        if let Some((history, input_state)) = &sent_to_history {
+                                            let text = input_state.read(cx).value();
+                                            history.update(cx, |history, _| history.push(&text));
+                                        }
                                         handler(ev, window, cx);
                                     });
+                                } else if let Some(submit) = submit {
+                                    btn = btn.on_click(move |_, window, cx| submit(window, cx));
                                 }
 
                                 btn
                             }),
                     ),
             )
+            .when_some(input_size, |this, size| {
+                let color = match size_level {
+                    Some(SizeLevel::OverLimit) => theme.danger,
+                    Some(SizeLevel::Warning) => theme.warning,
+                    _ => theme.muted_foreground,
+                };
+                this.child(h_flex().w_full().justify_end().child(
+                    div().text_xs().text_color(color).child(
+                        t!("chat_input.size", chars = size.chars, tokens = size.tokens).to_string(),
+                    ),
+                ))
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn input_size_turns_amber_then_red() {
+        assert_eq!(estimate_tokens(""), 0);
+        assert_eq!(estimate_tokens("abcde"), 2);
+        assert_eq!(InputSize::of("héllo").chars, 5);
+
+        let size = InputSize::of(&"x".repeat(400));
+        assert_eq!(size.tokens, 100);
+        assert_eq!(size.level(None, None), SizeLevel::Normal);
+        assert_eq!(size.level(Some(101), Some(200)), SizeLevel::Normal);
+        assert_eq!(size.level(Some(100), Some(200)), SizeLevel::Warning);
+        assert_eq!(size.level(Some(50), Some(100)), SizeLevel::OverLimit);
+        assert_eq!(size.level(None, Some(100)), SizeLevel::OverLimit);
+    }
+
+    #[test]
+    fn mentions_complete_at_the_last_at_sign() {
+        assert_eq!(mention_query("see @src/ma"), Some("src/ma"));
+        assert_eq!(mention_query("@"), Some(""));
+        assert_eq!(mention_query("line\n@src"), Some("src"));
+        assert_eq!(mention_query("mail me@"), None);
+        assert_eq!(mention_query("mail me@host"), None);
+        assert_eq!(mention_query("@src/main.rs done"), None);
+        assert_eq!(mention_query("no mention"), None);
+
+        assert_eq!(
+            complete_mention("compare @a.rs and @sr", "src/").as_deref(),
+            Some("compare @a.rs and @src/ ")
+        );
+        assert_eq!(complete_mention("@a.rs done", "src/"), None);
+    }
+
+    #[test]
+    fn blank_input_is_not_submitted() {
+        assert_eq!(ChatSubmit::new(" \n\t".to_string(), None, None), None);
+        let submit = ChatSubmit::new(
+            "fix the build\n".to_string(),
+            Some("plan".to_string()),
+            Some("claude".to_string()),
+        )
+        .unwrap();
+        assert_eq!(submit.text, "fix the build\n");
+        assert_eq!(submit.mode.as_deref(), Some("plan"));
+    }
+
+    #[test]
+    fn slash_query_stops_at_whitespace() {
+        assert_eq!(slash_command_query("/"), Some(""));
+        assert_eq!(slash_command_query("  /rev"), Some("rev"));
+        assert_eq!(slash_command_query("/review now"), None);
+        assert_eq!(slash_command_query("review"), None);
+    }
+
+    #[test]
+    fn commands_match_by_prefix() {
+        let commands = vec![
+            AvailableCommand::new("review", "Review the diff"),
+            AvailableCommand::new("init", "Create an AGENTS.md"),
+            AvailableCommand::new("research", "Search the codebase"),
+        ];
+        let names: Vec<String> = matching_commands(commands.clone(), "re")
+            .into_iter()
+            .map(|command| command.name)
+            .collect();
+        assert_eq!(names, ["review", "research"]);
+        assert_eq!(matching_commands(commands, "").len(), 3);
+    }
+
+    #[test]
+    fn modes_are_recognized_by_id() {
+        assert_eq!(ModeInputKind::of(Some("plan")), ModeInputKind::Plan);
+        assert_eq!(ModeInputKind::of(Some("architect")), ModeInputKind::Plan);
+        assert_eq!(ModeInputKind::of(Some("acceptEdits")), ModeInputKind::Code);
+        assert_eq!(ModeInputKind::of(Some("code")), ModeInputKind::Code);
+        assert_eq!(ModeInputKind::of(Some("read-only")), ModeInputKind::Ask);
+        assert_eq!(ModeInputKind::of(Some("default")), ModeInputKind::Default);
+        assert_eq!(ModeInputKind::of(None), ModeInputKind::Default);
     }
 }
@@ -4,8 +4,11 @@ use gpui_component::{
     list::{ListDelegate, ListItem, ListState},
 };
 use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
 
+use crate::utils::fuzzy::fuzzy_match;
+
 /// File item in the file picker
 #[derive(Clone, Debug)]
 pub struct FileItem {
@@ -50,6 +53,8 @@ pub struct FilePickerDelegate {
     on_select: Option<Box<dyn Fn(FileItem) + 'static>>,
     selection_tx: Option<mpsc::UnboundedSender<FileItem>>,
     scan_state: ScanState,
+    /// When the items were last scanned
+    scanned_at: Option<Instant>,
 }
 
 impl FilePickerDelegate {
@@ -63,6 +68,7 @@ impl FilePickerDelegate {
             on_select: None,
             selection_tx: None,
             scan_state: ScanState::NotStarted,
+            scanned_at: None,
         }
     }
 
@@ -99,9 +105,24 @@ impl FilePickerDelegate {
         self.scan_state = ScanState::Scanning;
     }
 
+    /// Scan again on the next request, keeping the current items until then
+    pub fn mark_stale(&mut self) {
+        if self.scan_state == ScanState::Ready {
+            self.scan_state = ScanState::NotStarted;
+        }
+    }
+
+    /// Whether the items are missing, outdated or older than `max_age`
+    pub fn needs_rescan(&self, max_age: Duration) -> bool {
+        self.needs_scan()
+            || (self.scan_state == ScanState::Ready
+                && self.scanned_at.is_some_and(|at| at.elapsed() > max_age))
+    }
+
     pub fn set_items(&mut self, items: Vec<FileItem>) {
         self.all_items = items;
         self.scan_state = ScanState::Ready;
+        self.scanned_at = Some(Instant::now());
         let query = self.search_query.clone();
         self.set_search_query(query);
     }
@@ -113,6 +134,7 @@ impl FilePickerDelegate {
         self.search_query.clear();
         self.selected_index = None;
         self.scan_state = ScanState::NotStarted;
+        self.scanned_at = None;
     }
 
     /// Scan directory recursively and return all files and folders
@@ -188,18 +210,21 @@ impl FilePickerDelegate {
         if self.search_query.is_empty() {
             self.filtered_items = self.all_items.clone();
         } else {
-            self.filtered_items = self
+            // Best fuzzy match on the name or the relative path, highest score first
+            let mut scored: Vec<(i64, &FileItem)> = self
                 .all_items
                 .iter()
-                .filter(|item| {
-                    item.name.to_lowercase().contains(&self.search_query)
-                        || item
-                            .relative_path
-                            .to_lowercase()
-                            .contains(&self.search_query)
+                .filter_map(|item| {
+                    let score = [&item.name, &item.relative_path]
+                        .into_iter()
+                        .filter_map(|text| fuzzy_match(&self.search_query, text))
+                        .map(|m| m.score)
+                        .max()?;
+                    Some((score, item))
                 })
-                .cloned()
                 .collect();
+            scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+            self.filtered_items = scored.into_iter().map(|(_, item)| item.clone()).collect();
         }
     }
 }
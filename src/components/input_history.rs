@@ -0,0 +1,153 @@
+use std::collections::VecDeque;
+
+/// Messages kept by a new [`InputHistory`]
+pub const DEFAULT_INPUT_HISTORY_SIZE: usize = 100;
+
+/// Submitted messages recalled with Up/Down in a chat input, shell-style.
+/// The oldest ones are dropped past `capacity`.
+#[derive(Debug, Clone)]
+pub struct InputHistory {
+    entries: VecDeque<String>,
+    capacity: usize,
+    /// Entry shown in the input while navigating
+    position: Option<usize>,
+    /// What the input held before the first Up, restored by Down past the newest entry
+    draft: String,
+}
+
+impl Default for InputHistory {
+    fn default() -> Self {
+        Self::new(DEFAULT_INPUT_HISTORY_SIZE)
+    }
+}
+
+impl InputHistory {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::new(),
+            capacity,
+            position: None,
+            draft: String::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Remember a submitted message and stop navigating. Blank messages and
+    /// repeats of the newest entry aren't kept.
+    pub fn push(&mut self, text: &str) {
+        self.position = None;
+        self.draft.clear();
+        if self.capacity == 0
+            || text.trim().is_empty()
+            || self.entries.back().is_some_and(|last| last == text)
+        {
+            return;
+        }
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(text.to_string());
+    }
+
+    /// The text to show for Up (`delta < 0`) or Down in an input holding
+    /// `text` with the cursor at byte offset `cursor`, or `None` to leave the
+    /// key to the input.
+    ///
+    /// Up starts recalling only with the cursor at the very start, and keeps
+    /// going back while the recalled entry is unedited and the cursor is on
+    /// its first line. Down only moves forward from an unedited entry with
+    /// the cursor on its last line, so moving around multi-line text works as
+    /// usual.
+    pub fn recall(&mut self, delta: isize, text: &str, cursor: usize) -> Option<String> {
+        let navigating = self
+            .position
+            .is_some_and(|ix| self.entries.get(ix).is_some_and(|entry| entry == text));
+        if !navigating {
+            self.position = None;
+        }
+        let (before, after) = text.split_at_checked(cursor).unwrap_or((text, ""));
+
+        if delta < 0 {
+            if !(cursor == 0 || (navigating && !before.contains('\n'))) {
+                return None;
+            }
+            let ix = match self.position {
+                None if self.entries.is_empty() => return None,
+                None => {
+                    self.draft = text.to_string();
+                    self.entries.len() - 1
+                }
+                Some(0) => return None,
+                Some(ix) => ix - 1,
+            };
+            self.position = Some(ix);
+            self.entries.get(ix).cloned()
+        } else {
+            if !navigating || after.contains('\n') {
+                return None;
+            }
+            let ix = self.position? + 1;
+            if ix < self.entries.len() {
+                self.position = Some(ix);
+                self.entries.get(ix).cloned()
+            } else {
+                self.position = None;
+                Some(std::mem::take(&mut self.draft))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn up_and_down_walk_the_history_and_restore_the_draft() {
+        let mut history = InputHistory::new(3);
+        assert_eq!(history.recall(-1, "", 0), None);
+        for text in ["one", "two", "two", "  ", "three", "four"] {
+            history.push(text);
+        }
+        assert_eq!(history.len(), 3);
+
+        assert_eq!(history.recall(-1, "dr", 0).as_deref(), Some("four"));
+        assert_eq!(history.recall(-1, "four", 4).as_deref(), Some("three"));
+        assert_eq!(history.recall(-1, "three", 5).as_deref(), Some("two"));
+        assert_eq!(history.recall(-1, "two", 3), None);
+        assert_eq!(history.recall(1, "two", 3).as_deref(), Some("three"));
+        assert_eq!(history.recall(1, "three", 5).as_deref(), Some("four"));
+        assert_eq!(history.recall(1, "four", 4).as_deref(), Some("dr"));
+        assert_eq!(history.recall(1, "dr", 2), None);
+    }
+
+    #[test]
+    fn arrows_keep_moving_the_cursor_inside_text() {
+        let mut history = InputHistory::new(10);
+        history.push("first\nsecond");
+        history.push("latest");
+
+        // A draft only gives way to the history with the cursor at its start
+        assert_eq!(history.recall(-1, "draft", 3), None);
+        assert_eq!(history.recall(1, "draft", 5), None);
+
+        assert_eq!(history.recall(-1, "", 0).as_deref(), Some("latest"));
+        let multi_line = history.recall(-1, "latest", 6).unwrap();
+        // Between the lines of an entry the arrows just move the cursor
+        assert_eq!(history.recall(-1, &multi_line, 8), None);
+        assert_eq!(history.recall(1, &multi_line, 2), None);
+        assert_eq!(history.recall(1, &multi_line, 8).as_deref(), Some("latest"));
+
+        // Once edited, the entry is a draft like any other
+        assert_eq!(history.recall(-1, "latest", 0), Some(multi_line));
+        assert_eq!(history.recall(1, "first\nsecond!", 13), None);
+        assert_eq!(history.recall(-1, "first\nsecond!", 13), None);
+    }
+}
@@ -70,6 +70,11 @@ pub struct InputSuggestionState<T: InputSuggestionItem + 'static> {
     on_confirm: Option<Rc<dyn Fn(&T, &mut Window, &mut App)>>,
     on_open_change: Option<Rc<dyn Fn(&bool, &mut Window, &mut App)>>,
     on_select_change: Option<Rc<dyn Fn(Option<usize>, &mut Window, &mut App)>>,
+    /// Called on Enter while the list is closed
+    on_submit: Option<Rc<dyn Fn(&mut Window, &mut App)>>,
+    /// Called on Up (-1) and Down (1) while the list is closed; true if it
+    /// handled the key
+    on_history: Option<Rc<dyn Fn(isize, &mut Window, &mut App) -> bool>>,
     _subscriptions: Vec<Subscription>,
     _keystroke_subscription: Option<Subscription>,
 }
@@ -119,6 +124,8 @@ impl<T: InputSuggestionItem + 'static> InputSuggestionState<T> {
             on_confirm: None,
             on_open_change: None,
             on_select_change: None,
+            on_submit: None,
+            on_history: None,
             _subscriptions,
             _keystroke_subscription,
         }
@@ -185,6 +192,21 @@ impl<T: InputSuggestionItem + 'static> InputSuggestionState<T> {
         self.on_select_change = on_select_change;
     }
 
+    /// Submit on Enter (without Shift) while the list is closed; Shift+Enter
+    /// is left to the input
+    pub fn set_on_submit(&mut self, on_submit: Option<Rc<dyn Fn(&mut Window, &mut App)>>) {
+        self.on_submit = on_submit;
+    }
+
+    /// Offer Up and Down to `on_history` while the list is closed; keys it
+    /// doesn't handle move the cursor as usual
+    pub fn set_on_history(
+        &mut self,
+        on_history: Option<Rc<dyn Fn(isize, &mut Window, &mut App) -> bool>>,
+    ) {
+        self.on_history = on_history;
+    }
+
     pub fn set_selected_index(
         &mut self,
         index: Option<usize>,
@@ -231,7 +253,8 @@ impl<T: InputSuggestionItem + 'static> InputSuggestionState<T> {
         cx: &mut Context<Self>,
     ) -> bool {
         if !self.open || !self.enabled {
-            return false;
+            return self.submit_on_enter(event, window, cx)
+                || self.recall_history(event, window, cx);
         }
         if event.keystroke.modifiers.modified() {
             return false;
@@ -250,6 +273,32 @@ impl<T: InputSuggestionItem + 'static> InputSuggestionState<T> {
         }
     }
 
+    fn submit_on_enter(&self, event: &KeystrokeEvent, window: &mut Window, cx: &mut App) -> bool {
+        let Some(on_submit) = self.on_submit.clone() else {
+            return false;
+        };
+        if event.keystroke.key != "enter" || event.keystroke.modifiers.modified() {
+            return false;
+        }
+        // The callback may read this state, which is being updated
+        window.defer(cx, move |window, cx| on_submit(window, cx));
+        true
+    }
+
+    fn recall_history(&self, event: &KeystrokeEvent, window: &mut Window, cx: &mut App) -> bool {
+        let Some(on_history) = self.on_history.as_ref() else {
+            return false;
+        };
+        if event.keystroke.modifiers.modified() {
+            return false;
+        }
+        match event.keystroke.key.as_str() {
+            "up" => on_history(-1, window, cx),
+            "down" => on_history(1, window, cx),
+            _ => false,
+        }
+    }
+
     fn on_input_event(
         &mut self,
         _: &Entity<InputState>,
@@ -390,6 +439,8 @@ pub struct InputSuggestion<T: InputSuggestionItem + 'static> {
     on_confirm: Option<Rc<dyn Fn(&T, &mut Window, &mut App)>>,
     on_open_change: Option<Rc<dyn Fn(&bool, &mut Window, &mut App)>>,
     on_select_change: Option<Rc<dyn Fn(Option<usize>, &mut Window, &mut App)>>,
+    on_submit: Option<Rc<dyn Fn(&mut Window, &mut App)>>,
+    on_history: Option<Rc<dyn Fn(isize, &mut Window, &mut App) -> bool>>,
     input_builder: Option<Rc<dyn Fn(&Entity<InputState>) -> Input>>,
     render_item: Option<Rc<dyn Fn(&T, bool, &mut Window, &mut App) -> gpui::AnyElement>>,
     style: StyleRefinement,
@@ -413,6 +464,8 @@ impl<T: InputSuggestionItem + 'static> InputSuggestion<T> {
             on_confirm: None,
             on_open_change: None,
             on_select_change: None,
+            on_submit: None,
+            on_history: None,
             input_builder: None,
             render_item: None,
             style: StyleRefinement::default(),
@@ -501,6 +554,25 @@ impl<T: InputSuggestionItem + 'static> InputSuggestion<T> {
         self
     }
 
+    /// Called on Enter (without Shift) while the list is closed
+    pub fn on_submit<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(&mut Window, &mut App) + 'static,
+    {
+        self.on_submit = Some(Rc::new(callback));
+        self
+    }
+
+    /// Called on Up (-1) and Down (1) while the list is closed; returning
+    /// false leaves the key to the input
+    pub fn on_history<F>(mut self, callback: F) -> Self
+    where
+        F: Fn(isize, &mut Window, &mut App) -> bool + 'static,
+    {
+        self.on_history = Some(Rc::new(callback));
+        self
+    }
+
     pub fn input<F>(mut self, builder: F) -> Self
     where
         F: Fn(&Entity<InputState>) -> Input + 'static,
@@ -550,6 +622,8 @@ impl<T: InputSuggestionItem + 'static> RenderOnce for InputSuggestion<T> {
         let on_confirm = self.on_confirm.clone();
         let on_open_change = self.on_open_change.clone();
         let on_select_change = self.on_select_change.clone();
+        let on_submit = self.on_submit.clone();
+        let on_history = self.on_history.clone();
         let clear_on_confirm = self.clear_on_confirm;
         let apply_on_confirm = self.apply_on_confirm;
 
@@ -566,6 +640,8 @@ impl<T: InputSuggestionItem + 'static> RenderOnce for InputSuggestion<T> {
                 on_open_change,
                 on_select_change,
             );
+            state.set_on_submit(on_submit);
+            state.set_on_history(on_history);
             state.set_clear_on_confirm(clear_on_confirm);
             state.set_apply_on_confirm(apply_on_confirm);
         });
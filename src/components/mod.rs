@@ -2,6 +2,7 @@ mod agent_select;
 mod chat_input_box;
 mod command_suggestions_popover;
 mod file_picker;
+mod input_history;
 mod input_suggestion;
 mod select_items;
 mod status_indicator;
@@ -10,16 +11,24 @@ mod status_indicator;
 pub use agentx_acp_ui::{
     AcpMessageStream, AcpMessageStreamOptions, AgentMessage, AgentMessageData, AgentMessageMeta,
     AgentMessageOptions, AgentMessageView, AgentThoughtItem, AgentTodoList, AgentTodoListView,
-    DiffSummary, DiffSummaryData, DiffSummaryOptions, DiffSummaryToolCallHandler, DiffView,
-    FileChangeStats, PermissionRequest, PermissionRequestOptions, PermissionRequestView,
-    PermissionResponseHandler, PlanMeta, ToolCallItem, ToolCallItemOptions, ToolCallItemView,
-    UserMessage, UserMessageData, UserMessageView,
+    CodeWrap, ContentRenderers, DiffApplyOutcome, DiffApplyProgress, DiffApplyRequested,
+    DiffBatchApplyRequested, DiffRevertRequested, DiffSummary, DiffSummaryData, DiffSummaryOptions,
+    DiffSummaryToolCallHandler, DiffView, DiffViewMode, ErrorKind, FileChangeStats, GroupToolCalls,
+    MessageStats, PermissionRequest, PermissionRequestOptions, PermissionRequestView,
+    PermissionResponseHandler, PlanMeta, TIMESTAMP_META_KEY, ToolCallItem, ToolCallItemOptions,
+    ToolCallItemView, UiScale, UserMessage, UserMessageData, UserMessageView,
+    decode_base64_content, extract_terminal_output, find_matches, message_stats, mode_display_name,
+    render_unsupported, scaled, truncate_chars, truncate_large_tool_output,
 };
 
 pub use agent_select::AgentItem;
 
-pub use chat_input_box::ChatInputBox;
+pub use chat_input_box::{
+    ChatInputBox, ChatSubmit, InputSize, ModeInputKind, complete_mention, estimate_tokens,
+    matching_commands, mention_query, slash_command_query,
+};
 
+pub use input_history::{DEFAULT_INPUT_HISTORY_SIZE, InputHistory};
 pub use input_suggestion::{InputSuggestion, InputSuggestionItem, InputSuggestionState};
 
 pub use file_picker::{FileItem, FilePickerDelegate};
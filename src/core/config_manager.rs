@@ -178,8 +178,20 @@ pub fn get_docks_layout_path() -> PathBuf {
     user_data_dir_or_temp().join("docks-layout.json")
 }
 
+/// Get task pins file path (favorite and recently run tasks)
+/// Always uses user data directory: <user_data_dir>/task-pins.json
+pub fn get_task_pins_path() -> PathBuf {
+    user_data_dir_or_temp().join("task-pins.json")
+}
+
 /// Get sessions directory path
 /// Always uses user data directory: <user_data_dir>/sessions
 pub fn get_sessions_dir() -> PathBuf {
     user_data_dir_or_temp().join("sessions")
 }
+
+/// Get chat input drafts directory path
+/// Always uses user data directory: <user_data_dir>/drafts
+pub fn get_drafts_dir() -> PathBuf {
+    user_data_dir_or_temp().join("drafts")
+}
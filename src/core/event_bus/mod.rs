@@ -9,8 +9,8 @@ pub use agentx_event_bus::batching::{
 pub use agentx_event_bus::core::{EventBus, EventBusContainer, EventBusStats, SubscriptionId};
 pub use agentx_event_bus::hub::{AppEvent, EventHub};
 pub use agentx_event_bus::{
-    AgentConfigEvent, CodeSelectionEvent, PermissionRequestEvent, SessionUpdateEvent,
-    WorkspaceUpdateEvent,
+    AgentConfigEvent, CodeSelectionEvent, PermissionRequestEvent, SessionErrorEvent,
+    SessionErrorKind, SessionRetryEvent, SessionUpdateEvent, WorkspaceUpdateEvent,
 };
 
 // GPUI-specific helpers that depend on gpui types
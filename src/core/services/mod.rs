@@ -5,10 +5,12 @@
 pub use agentx_services::AgentConfigService;
 pub use agentx_services::AgentService;
 pub use agentx_services::AgentSessionInfo;
+pub use agentx_services::AgentStatus;
+pub use agentx_services::AgentStatusInfo;
 pub use agentx_services::AiService;
 pub use agentx_services::CommentStyle;
 pub use agentx_services::ConfigWatcher;
-pub use agentx_services::MessageService;
 pub use agentx_services::PersistenceService;
 pub use agentx_services::SessionStatus;
 pub use agentx_services::WorkspaceService;
+pub use agentx_services::{MessageService, RequestPolicy};
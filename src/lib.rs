@@ -18,8 +18,9 @@ pub use assets::get_default_config;
 // Re-export from panels module
 use crate::panels::{DockPanelContainer, DockPanelState};
 pub use panels::{
-    AppSettings, CodeEditorPanel, ConversationPanel, SessionManagerPanel, SettingsPanel, TaskPanel,
-    TerminalPanel, ToolCallDetailPanel, WelcomePanel,
+    AppSettings, CodeEditorPanel, ConversationPanel, SavedSessionsPanel, SessionManagerPanel,
+    SettingsPanel, TaskPanel, TerminalOutputPanel, TerminalPanel, ToolCallDetailPanel,
+    WelcomePanel,
 };
 
 // Re-export from core module
@@ -38,10 +39,10 @@ pub use app::{
     actions::{
         About, AddAgent, AddSessionToList, CancelSession, CloseWindow, CreateTaskFromWelcome, Info,
         NewSessionConversationPanel, Open, OpenSessionManager, PanelAction, Quit,
-        ReloadAgentConfig, RemoveAgent, RestartAgent, SelectFont, SelectLocale, SelectRadius,
-        SelectScrollbarShow, SelectedAgentTask, SendMessageToSession, SetUploadDir, ShowPanelInfo,
-        Tab, TabPrev, TestAction, ToggleDockToggleButton, TogglePanelVisible, ToggleSearch,
-        UpdateAgent,
+        ReloadAgentConfig, RemoveAgent, RestartAgent, RetryPrompt, SelectFont, SelectLocale,
+        SelectRadius, SelectScrollbarShow, SelectedAgentTask, SendMessageToSession, SetUploadDir,
+        ShowPanelInfo, Tab, TabPrev, TestAction, ToggleDockToggleButton, TogglePanelVisible,
+        ToggleSearch, UpdateAgent,
     },
     app_menus, menu, system_tray, themes, title_bar,
 };
@@ -58,10 +59,12 @@ pub use title_bar::AppTitleBar;
 pub use components::{
     AcpMessageStream, AcpMessageStreamOptions, AgentMessage, AgentMessageData, AgentMessageMeta,
     AgentMessageOptions, AgentMessageView, AgentThoughtItem, AgentTodoList, AgentTodoListView,
-    ChatInputBox, DiffSummary, DiffSummaryData, DiffSummaryOptions, DiffSummaryToolCallHandler,
-    FileChangeStats, PermissionRequest, PermissionRequestOptions, PermissionRequestView,
-    PermissionResponseHandler, PlanMeta, StatusIndicator, ToolCallItem, ToolCallItemOptions,
-    ToolCallItemView, UserMessage, UserMessageData, UserMessageView,
+    ChatInputBox, CodeWrap, DiffApplyOutcome, DiffApplyProgress, DiffApplyRequested,
+    DiffBatchApplyRequested, DiffRevertRequested, DiffSummary, DiffSummaryData, DiffSummaryOptions,
+    DiffSummaryToolCallHandler, DiffViewMode, FileChangeStats, GroupToolCalls, PermissionRequest,
+    PermissionRequestOptions, PermissionRequestView, PermissionResponseHandler, PlanMeta,
+    StatusIndicator, ToolCallItem, ToolCallItemOptions, ToolCallItemView, UiScale, UserMessage,
+    UserMessageData, UserMessageView, mode_display_name, scaled,
 };
 
 // Re-export ACP types for convenience
@@ -74,6 +74,17 @@ fn main() {
 
             println!("Config loaded from {}", config_path.display());
 
+            // Apply the configured theme (light/dark/system), UI scale, diff layout, code
+            // wrapping, tool call grouping and keybindings before agents start
+            let _ = cx.update(|cx| {
+                agentx::themes::apply_theme(&config, cx);
+                agentx::themes::apply_ui_scale(&config, cx);
+                agentx::themes::apply_diff_view_mode(&config, cx);
+                agentx::themes::apply_code_wrap(&config, cx);
+                agentx::themes::apply_tool_call_grouping(&config, cx);
+                agentx::key_binding::bind_config_keys(&config, cx);
+            });
+
             // Inject nodejs_path from AppSettings into agent configs
             let nodejs_path = cx.update(|cx| {
                 agentx::AppSettings::global(cx).nodejs_path.clone()
@@ -112,6 +123,7 @@ fn main() {
                         agentx::AppState::global_mut(cx).set_config_path(config_path.clone());
                         // Then set agent manager with config
                         agentx::AppState::global_mut(cx).set_agent_manager(manager, config);
+                        agentx::AppState::subscribe_to_config_reloads(cx);
                         agentx::AppState::global_mut(cx).set_permission_store(permission_store);
 
                         // Get message service for persistence initialization
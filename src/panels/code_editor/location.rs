@@ -0,0 +1,152 @@
+//! File references such as `src/main.rs:42:7` or `src/main.rs#L42`, as they
+//! show up in agent output, and clamping a position to a buffer.
+
+use std::path::PathBuf;
+
+use gpui_component::input::Position;
+
+/// A file and an optional 1-based line and column, as written by people and tools
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileLocation {
+    pub path: PathBuf,
+    pub line: Option<u32>,
+    pub column: Option<u32>,
+}
+
+impl FileLocation {
+    /// Parse `path`, `path:line`, `path:line:column`, `path#Lline` or
+    /// `path#LlineCcolumn` (a `-Lend` range suffix is ignored). Quotes,
+    /// backticks, brackets and trailing punctuation around the reference
+    /// are dropped. Returns `None` when no path is left.
+    pub fn parse(reference: &str) -> Option<Self> {
+        let is_wrapper =
+            |c: char| matches!(c, '`' | '"' | '\'' | '(' | ')' | '[' | ']' | '<' | '>');
+        let reference = reference
+            .trim()
+            .trim_start_matches(is_wrapper)
+            .trim_end_matches(|c: char| is_wrapper(c) || matches!(c, '.' | ',' | ';' | ':'));
+
+        if let Some((path, anchor)) = reference.rsplit_once("#L") {
+            let anchor = anchor.split('-').next().unwrap_or_default();
+            let (line, column) = match anchor.split_once('C') {
+                Some((line, column)) => (line, Some(column)),
+                None => (anchor, None),
+            };
+            if let Ok(line) = line.parse() {
+                return Self::new(path, Some(line), column.and_then(|c| c.parse().ok()));
+            }
+        }
+
+        let mut path = reference;
+        let mut numbers = Vec::new();
+        while numbers.len() < 2 {
+            let Some((rest, last)) = path.rsplit_once(':') else {
+                break;
+            };
+            let Ok(number) = last.parse::<u32>() else {
+                break;
+            };
+            numbers.insert(0, number);
+            path = rest;
+        }
+        Self::new(path, numbers.first().copied(), numbers.get(1).copied())
+    }
+
+    fn new(path: &str, line: Option<u32>, column: Option<u32>) -> Option<Self> {
+        (!path.is_empty()).then(|| Self {
+            path: PathBuf::from(path),
+            line,
+            column,
+        })
+    }
+
+    /// Zero-based editor position; a missing line or column means the first one
+    pub fn position(&self) -> Position {
+        Position::new(
+            self.line.unwrap_or(1).saturating_sub(1),
+            self.column.unwrap_or(1).saturating_sub(1),
+        )
+    }
+}
+
+/// `position` moved inside `text`: past the last line goes to the last line,
+/// past the end of a line goes to the end of that line
+pub fn clamp_position(text: &str, position: Position) -> Position {
+    let line_count = text.split('\n').count() as u32;
+    let line = position.line.min(line_count.saturating_sub(1));
+    let line_len = text
+        .split('\n')
+        .nth(line as usize)
+        .map(|line| line.trim_end_matches('\r').chars().count() as u32)
+        .unwrap_or(0);
+    Position::new(line, position.character.min(line_len))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn location(path: &str, line: Option<u32>, column: Option<u32>) -> Option<FileLocation> {
+        Some(FileLocation {
+            path: PathBuf::from(path),
+            line,
+            column,
+        })
+    }
+
+    #[test]
+    fn parses_common_reference_styles() {
+        assert_eq!(
+            FileLocation::parse("src/main.rs:42"),
+            location("src/main.rs", Some(42), None)
+        );
+        assert_eq!(
+            FileLocation::parse("`src/main.rs:42:7`,"),
+            location("src/main.rs", Some(42), Some(7))
+        );
+        assert_eq!(
+            FileLocation::parse("src/main.rs#L42"),
+            location("src/main.rs", Some(42), None)
+        );
+        assert_eq!(
+            FileLocation::parse("src/main.rs#L42C3-L50"),
+            location("src/main.rs", Some(42), Some(3))
+        );
+        assert_eq!(
+            FileLocation::parse("(C:\\work\\lib.rs:3)"),
+            location("C:\\work\\lib.rs", Some(3), None)
+        );
+        assert_eq!(
+            FileLocation::parse("Cargo.toml"),
+            location("Cargo.toml", None, None)
+        );
+        assert_eq!(FileLocation::parse(":42"), None);
+        assert_eq!(FileLocation::parse("  "), None);
+    }
+
+    #[test]
+    fn position_is_zero_based() {
+        let parsed = FileLocation::parse("src/main.rs:42:7").unwrap();
+        assert_eq!(parsed.position(), Position::new(41, 6));
+        let parsed = FileLocation::parse("src/main.rs").unwrap();
+        assert_eq!(parsed.position(), Position::new(0, 0));
+    }
+
+    #[test]
+    fn positions_are_clamped_to_the_buffer() {
+        let text = "fn main() {\r\n    println!(\"é\");\n}";
+        assert_eq!(
+            clamp_position(text, Position::new(1, 4)),
+            Position::new(1, 4)
+        );
+        assert_eq!(
+            clamp_position(text, Position::new(0, 99)),
+            Position::new(0, 11)
+        );
+        assert_eq!(
+            clamp_position(text, Position::new(99, 99)),
+            Position::new(2, 1)
+        );
+        assert_eq!(clamp_position("", Position::new(5, 5)), Position::new(0, 0));
+    }
+}
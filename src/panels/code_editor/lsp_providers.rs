@@ -16,8 +16,14 @@ use lsp_types::{
 
 use crate::AppState;
 
-use super::lsp_store::CodeEditorPanelLspStore;
-use super::types::{RUST_DOC_URLS, completion_item};
+use super::lsp_store::{BUFFER_URI, CodeEditorPanelLspStore};
+use super::symbols::outline_symbols;
+use super::types::{completion_item, hover_markdown, rust_doc_url};
+
+/// Delay before a completion request is served, so rapid typing only queries once
+const COMPLETION_DEBOUNCE: Duration = Duration::from_millis(150);
+/// Delay before a hover request is served, so sweeping the mouse over text only queries once
+const HOVER_DEBOUNCE: Duration = Duration::from_millis(300);
 
 // ============================================================================
 // CompletionProvider Implementation
@@ -37,12 +43,17 @@ impl CompletionProvider for CodeEditorPanelLspStore {
             return Task::ready(Ok(CompletionResponse::Array(vec![])));
         }
 
-        // Simulate to delay for fetching completions
+        // Each request supersedes the previous one; stale results are dropped
+        let generation = self.begin_completion_request();
+        let store = self.clone();
         let rope = rope.clone();
         let items = self.completions.clone();
         AppContext::background_spawn(cx, async move {
-            // Simulate a slow completion source, to test Editor async handling.
-            smol::Timer::after(Duration::from_millis(20)).await;
+            smol::Timer::after(COMPLETION_DEBOUNCE).await;
+            if !store.is_current_completion(generation) {
+                log::debug!("Discarding superseded completion request #{}", generation);
+                return Ok(CompletionResponse::Array(vec![]));
+            }
 
             if trigger_character.starts_with("/") {
                 let start = offset.saturating_sub(trigger_character.len());
@@ -161,32 +172,47 @@ impl HoverProvider for CodeEditorPanelLspStore {
         text: &Rope,
         offset: usize,
         _window: &mut Window,
-        _cx: &mut App,
+        cx: &mut App,
     ) -> Task<Result<Option<lsp_types::Hover>>> {
-        let word = text.word_at(offset);
+        let word = text.word_at(offset).to_string();
         if word.is_empty() {
             return Task::ready(Ok(None));
         }
 
-        let Some(item) = self.completions.iter().find(|item| item.label == word) else {
-            return Task::ready(Ok(None));
-        };
-
-        let contents = if let Some(doc) = &item.documentation {
-            match doc {
-                lsp_types::Documentation::String(s) => s.clone(),
-                lsp_types::Documentation::MarkupContent(mc) => mc.value.clone(),
+        // Each request supersedes the previous one; stale results are dropped
+        let generation = self.begin_hover_request();
+        let store = self.clone();
+        cx.background_spawn(async move {
+            smol::Timer::after(HOVER_DEBOUNCE).await;
+            if !store.is_current_hover(generation) {
+                return Ok(None);
             }
-        } else {
-            "No documentation available.".to_string()
-        };
 
-        let hover = lsp_types::Hover {
-            contents: lsp_types::HoverContents::Scalar(lsp_types::MarkedString::String(contents)),
-            range: None,
-        };
+            let documentation = store
+                .completions
+                .iter()
+                .find(|item| item.label == word)
+                .and_then(|item| item.documentation.as_ref())
+                .map(|doc| match doc {
+                    lsp_types::Documentation::String(s) => s.clone(),
+                    lsp_types::Documentation::MarkupContent(mc) => mc.value.clone(),
+                });
+            let doc_url = rust_doc_url(&word);
+
+            // Servers may answer with empty contents; show no popover then
+            let Some(markdown) = hover_markdown(documentation.as_deref(), doc_url.as_deref())
+            else {
+                return Ok(None);
+            };
 
-        Task::ready(Ok(Some(hover)))
+            Ok(Some(lsp_types::Hover {
+                contents: lsp_types::HoverContents::Markup(lsp_types::MarkupContent {
+                    kind: lsp_types::MarkupKind::Markdown,
+                    value: markdown,
+                }),
+                range: None,
+            }))
+        })
     }
 }
 
@@ -202,12 +228,19 @@ impl DefinitionProvider for CodeEditorPanelLspStore {
         _window: &mut Window,
         _cx: &mut App,
     ) -> Task<Result<Vec<lsp_types::LocationLink>>> {
+        Task::ready(Ok(self.definition_links(text, offset)))
+    }
+}
+
+impl CodeEditorPanelLspStore {
+    /// Answer a `textDocument/definition` request for the symbol at `offset`
+    pub fn definition_links(&self, text: &Rope, offset: usize) -> Vec<lsp_types::LocationLink> {
         let Some(word_range) = text.word_range(offset) else {
-            return Task::ready(Ok(vec![]));
+            return vec![];
         };
         let word = text.slice(word_range.clone()).to_string();
 
-        let document_uri = lsp_types::Uri::from_str("file://CodeEditorPanel").unwrap();
+        let document_uri = lsp_types::Uri::from_str(BUFFER_URI).unwrap();
         let start = text.offset_to_position(word_range.start);
         let end = text.offset_to_position(word_range.end);
         let symbol_range = lsp_types::Range { start, end };
@@ -223,37 +256,40 @@ impl DefinitionProvider for CodeEditorPanelLspStore {
                     character: 23,
                 },
             };
-            return Task::ready(Ok(vec![lsp_types::LocationLink {
+            return vec![lsp_types::LocationLink {
                 target_uri: document_uri,
                 target_range: target_range,
                 target_selection_range: target_range,
                 origin_selection_range: Some(symbol_range),
-            }]));
+            }];
         }
 
-        let names = RUST_DOC_URLS
-            .iter()
-            .map(|(name, _)| *name)
-            .collect::<Vec<_>>();
-        for (ix, t) in names.iter().enumerate() {
-            if *t == word {
-                let url = RUST_DOC_URLS[ix].1;
-                let location = lsp_types::LocationLink {
-                    target_uri: lsp_types::Uri::from_str(&format!(
-                        "https://doc.rust-lang.org/std/{}.html",
-                        url
-                    ))
-                    .unwrap(),
-                    target_selection_range: lsp_types::Range::default(),
-                    target_range: lsp_types::Range::default(),
-                    origin_selection_range: Some(symbol_range),
-                };
-
-                return Task::ready(Ok(vec![location]));
-            }
+        if let Some(url) = rust_doc_url(&word) {
+            return vec![lsp_types::LocationLink {
+                target_uri: lsp_types::Uri::from_str(&url).unwrap(),
+                target_selection_range: lsp_types::Range::default(),
+                target_range: lsp_types::Range::default(),
+                origin_selection_range: Some(symbol_range),
+            }];
         }
 
-        Task::ready(Ok(vec![]))
+        vec![]
+    }
+}
+
+// ============================================================================
+// DocumentSymbol Implementation
+// ============================================================================
+
+impl CodeEditorPanelLspStore {
+    /// Answer a `textDocument/documentSymbol` request, or `None` when the
+    /// language has no symbol provider
+    pub fn document_symbols(
+        &self,
+        text: &str,
+        language: &str,
+    ) -> Option<Vec<lsp_types::DocumentSymbol>> {
+        matches!(language, "rust" | "navi").then(|| outline_symbols(text))
     }
 }
 
@@ -1,15 +1,99 @@
-use std::sync::{Arc, RwLock};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{
+        Arc, RwLock,
+        atomic::{AtomicBool, AtomicU64, Ordering},
+    },
+};
 
 use gpui_component::highlighter::Diagnostic;
-use lsp_types::{CodeAction, CompletionItem};
+use lsp_types::{CodeAction, CompletionItem, GotoDefinitionResponse};
 use std::ops::Range;
 
+/// Placeholder uri the built-in providers use for the buffer being edited
+pub(super) const BUFFER_URI: &str = "file://CodeEditorPanel";
+
+/// Where a go-to-definition result points
+#[derive(Clone, Debug, PartialEq)]
+pub enum DefinitionTarget {
+    /// A range in a local file
+    File {
+        path: PathBuf,
+        range: lsp_types::Range,
+    },
+    /// An external page, e.g. std documentation
+    Url(String),
+}
+
+impl DefinitionTarget {
+    /// `path:line:column` relative to `root`, or the url
+    pub fn label(&self, root: &Path) -> String {
+        match self {
+            Self::File { path, range } => format!(
+                "{}:{}:{}",
+                path.strip_prefix(root).unwrap_or(path).display(),
+                range.start.line + 1,
+                range.start.character + 1
+            ),
+            Self::Url(url) => url.clone(),
+        }
+    }
+}
+
+/// Flatten a `textDocument/definition` response into navigation targets.
+///
+/// Links to [`BUFFER_URI`] resolve to `current_path`; uris that are neither
+/// local files nor web pages are dropped.
+pub fn resolve_definition(
+    response: GotoDefinitionResponse,
+    current_path: &Path,
+) -> Vec<DefinitionTarget> {
+    let locations: Vec<(lsp_types::Uri, lsp_types::Range)> = match response {
+        GotoDefinitionResponse::Scalar(location) => vec![(location.uri, location.range)],
+        GotoDefinitionResponse::Array(locations) => locations
+            .into_iter()
+            .map(|location| (location.uri, location.range))
+            .collect(),
+        GotoDefinitionResponse::Link(links) => links
+            .into_iter()
+            .map(|link| (link.target_uri, link.target_selection_range))
+            .collect(),
+    };
+
+    locations
+        .into_iter()
+        .filter_map(|(uri, range)| {
+            let uri = uri.as_str();
+            if uri == BUFFER_URI {
+                Some(DefinitionTarget::File {
+                    path: current_path.to_path_buf(),
+                    range,
+                })
+            } else if let Some(path) = file_uri_to_path(uri) {
+                Some(DefinitionTarget::File { path, range })
+            } else if uri.starts_with("http://") || uri.starts_with("https://") {
+                Some(DefinitionTarget::Url(uri.to_string()))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
 #[derive(Clone)]
 pub struct CodeEditorPanelLspStore {
     pub(super) completions: Arc<Vec<CompletionItem>>,
     pub(super) code_actions: Arc<RwLock<Vec<(Range<usize>, CodeAction)>>>,
-    pub(super) diagnostics: Arc<RwLock<Vec<Diagnostic>>>,
+    /// Latest diagnostics batch per file; a new batch replaces the old one
+    pub(super) diagnostics: Arc<RwLock<HashMap<PathBuf, Vec<Diagnostic>>>>,
     pub(super) dirty: Arc<RwLock<bool>>,
+    /// Set when diagnostics changed and the editor has not picked them up yet
+    pub(super) diagnostics_dirty: Arc<AtomicBool>,
+    /// Generation of the most recent completion request; older ones are discarded
+    pub(super) completion_generation: Arc<AtomicU64>,
+    /// Generation of the most recent hover request; older ones are discarded
+    pub(super) hover_generation: Arc<AtomicU64>,
 }
 
 impl CodeEditorPanelLspStore {
@@ -22,20 +106,55 @@ impl CodeEditorPanelLspStore {
         Self {
             completions: Arc::new(vec![]),
             code_actions: Arc::new(RwLock::new(vec![])),
-            diagnostics: Arc::new(RwLock::new(vec![])),
+            diagnostics: Arc::new(RwLock::new(HashMap::new())),
             dirty: Arc::new(RwLock::new(false)),
+            diagnostics_dirty: Arc::new(AtomicBool::new(false)),
+            completion_generation: Arc::new(AtomicU64::new(0)),
+            hover_generation: Arc::new(AtomicU64::new(0)),
         }
     }
 
-    pub fn diagnostics(&self) -> Vec<Diagnostic> {
+    /// Start a new completion request, superseding any request still in flight
+    pub fn begin_completion_request(&self) -> u64 {
+        self.completion_generation.fetch_add(1, Ordering::SeqCst) + 1
+    }
+
+    /// Whether `generation` is still the latest completion request
+    pub fn is_current_completion(&self, generation: u64) -> bool {
+        self.completion_generation.load(Ordering::SeqCst) == generation
+    }
+
+    /// Start a new hover request, superseding any request still in flight
+    pub fn begin_hover_request(&self) -> u64 {
+        self.hover_generation.fetch_add(1, Ordering::SeqCst) + 1
+    }
+
+    /// Whether `generation` is still the latest hover request
+    pub fn is_current_hover(&self, generation: u64) -> bool {
+        self.hover_generation.load(Ordering::SeqCst) == generation
+    }
+
+    /// Diagnostics currently reported for `path`
+    pub fn diagnostics(&self, path: &Path) -> Vec<Diagnostic> {
         let guard = self.diagnostics.read().unwrap();
-        guard.clone()
+        guard.get(path).cloned().unwrap_or_default()
     }
 
-    pub fn update_diagnostics(&self, diagnostics: Vec<Diagnostic>) {
+    /// Replace all diagnostics of `path` with a new batch
+    pub fn update_diagnostics(&self, path: PathBuf, diagnostics: Vec<Diagnostic>) {
         let mut guard = self.diagnostics.write().unwrap();
-        *guard = diagnostics;
+        if diagnostics.is_empty() {
+            guard.remove(&path);
+        } else {
+            guard.insert(path, diagnostics);
+        }
         *self.dirty.write().unwrap() = true;
+        self.diagnostics_dirty.store(true, Ordering::SeqCst);
+    }
+
+    /// Whether diagnostics changed since the last call, resetting the flag
+    pub fn take_diagnostics_dirty(&self) -> bool {
+        self.diagnostics_dirty.swap(false, Ordering::SeqCst)
     }
 
     pub fn code_actions(&self) -> Vec<(Range<usize>, CodeAction)> {
@@ -54,3 +173,136 @@ impl CodeEditorPanelLspStore {
         *guard
     }
 }
+
+/// Local path of a `file://` uri, decoding percent escapes
+fn file_uri_to_path(uri: &str) -> Option<PathBuf> {
+    let encoded = uri.strip_prefix("file://")?;
+    let bytes = encoded.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%')
+            .then(|| encoded.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+
+    let path = String::from_utf8(decoded).ok()?;
+    // `file:///C:/x` on Windows carries a leading slash before the drive letter
+    let path = match path.strip_prefix('/') {
+        Some(rest) if rest.get(1..2) == Some(":") => rest.to_string(),
+        _ => path,
+    };
+    Some(PathBuf::from(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_latest_completion_request_is_current() {
+        let store = CodeEditorPanelLspStore::new();
+
+        let requests = [
+            store.begin_completion_request(),
+            store.begin_completion_request(),
+            store.begin_completion_request(),
+        ];
+
+        let applied: Vec<u64> = requests
+            .into_iter()
+            .filter(|generation| store.is_current_completion(*generation))
+            .collect();
+        assert_eq!(applied, vec![requests[2]]);
+    }
+
+    #[test]
+    fn new_batch_replaces_file_diagnostics() {
+        let store = CodeEditorPanelLspStore::new();
+        let path = Path::new("/work/my app/main.navi");
+        let publish = |messages: &[&str]| {
+            let start = gpui_component::input::Position::default();
+            let diagnostics = messages
+                .iter()
+                .map(|message| Diagnostic::new(start..start, message.to_string()))
+                .collect();
+            store.update_diagnostics(path.to_path_buf(), diagnostics);
+        };
+
+        publish(&["unknown field", "missing semicolon"]);
+        assert!(store.take_diagnostics_dirty());
+        assert!(!store.take_diagnostics_dirty());
+        assert_eq!(store.diagnostics(path).len(), 2);
+
+        publish(&["unused import"]);
+        let messages: Vec<String> = store
+            .diagnostics(path)
+            .iter()
+            .map(|diagnostic| diagnostic.message.to_string())
+            .collect();
+        assert_eq!(messages, ["unused import"]);
+
+        publish(&[]);
+        assert!(store.diagnostics(path).is_empty());
+        assert!(store.diagnostics(Path::new("/work/other.navi")).is_empty());
+    }
+
+    #[test]
+    fn definition_response_resolves_to_targets() {
+        use lsp_types::{Location, LocationLink, Position, Range, Uri};
+        use std::str::FromStr;
+
+        let range = Range::new(Position::new(4, 2), Position::new(4, 9));
+        let current = Path::new("/work/app/main.navi");
+
+        let scalar = GotoDefinitionResponse::Scalar(Location::new(
+            Uri::from_str("file:///work/app/lib.navi").unwrap(),
+            range,
+        ));
+        let targets = resolve_definition(scalar, current);
+        assert_eq!(
+            targets,
+            [DefinitionTarget::File {
+                path: PathBuf::from("/work/app/lib.navi"),
+                range
+            }]
+        );
+        assert_eq!(targets[0].label(Path::new("/work/app")), "lib.navi:5:3");
+
+        let links = GotoDefinitionResponse::Link(vec![
+            LocationLink {
+                origin_selection_range: None,
+                target_uri: Uri::from_str(BUFFER_URI).unwrap(),
+                target_range: range,
+                target_selection_range: range,
+            },
+            LocationLink {
+                origin_selection_range: None,
+                target_uri: Uri::from_str("https://doc.rust-lang.org/std/vec/struct.Vec.html")
+                    .unwrap(),
+                target_range: Range::default(),
+                target_selection_range: Range::default(),
+            },
+        ]);
+        let targets = resolve_definition(links, current);
+        assert_eq!(
+            targets[0],
+            DefinitionTarget::File {
+                path: current.to_path_buf(),
+                range
+            }
+        );
+        assert!(matches!(&targets[1], DefinitionTarget::Url(url) if url.ends_with("Vec.html")));
+    }
+}
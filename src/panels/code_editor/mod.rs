@@ -1,22 +1,106 @@
+mod location;
 mod lsp_providers;
 mod lsp_store;
 mod panel;
+mod symbols;
 mod types;
+mod whitespace;
 
+pub use location::FileLocation;
 pub use panel::CodeEditorPanel;
 
-use gpui_component::highlighter::{LanguageConfig, LanguageRegistry};
+use std::path::Path;
+
+use anyhow::{Context as _, Result};
+use gpui_component::highlighter::{self, LanguageConfig, LanguageRegistry};
+use tree_sitter::{Language, Query};
+
+/// Languages registered by [`init`] on top of the ones built into the highlighter
+const CUSTOM_LANGUAGES: &[&str] = &["navi"];
 
 pub fn init() {
+    if let Err(e) = register_navi(tree_sitter_navi::HIGHLIGHTS_QUERY) {
+        log::warn!("navi files will open as plain text: {:#}", e);
+    }
+}
+
+/// Names of the languages the editor can highlight, sorted
+pub fn available_languages() -> Vec<&'static str> {
+    let mut languages: Vec<&'static str> = highlighter::Language::all()
+        .map(|language| language.name())
+        .chain(CUSTOM_LANGUAGES.iter().copied())
+        .collect();
+    languages.sort_unstable();
+    languages.dedup();
+    languages
+}
+
+/// Highlighting language for `path`, from its extension
+pub fn detect_language(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or_default();
+    CUSTOM_LANGUAGES
+        .iter()
+        .copied()
+        .find(|language| *language == extension)
+        .unwrap_or_else(|| highlighter::Language::from_str(extension).name())
+}
+
+/// Register the navi language with `highlights`. If the grammar rejects the
+/// query, navi is still registered, without highlighting, and the error is returned.
+fn register_navi(highlights: &str) -> Result<()> {
+    let language: Language = tree_sitter_navi::LANGUAGE.into();
+    let checked = validate_query(&language, highlights).context("Invalid navi highlights query");
+    let highlights = if checked.is_ok() { highlights } else { "" };
+
     LanguageRegistry::singleton().register(
         "navi",
-        &LanguageConfig::new(
-            "navi",
-            tree_sitter_navi::LANGUAGE.into(),
-            vec![],
-            tree_sitter_navi::HIGHLIGHTS_QUERY,
-            "",
-            "",
-        ),
+        &LanguageConfig::new("navi", language, vec![], highlights, "", ""),
     );
+    checked
+}
+
+/// Compile `query` against `language` so a bad query fails here rather than
+/// when the first file is highlighted
+fn validate_query(language: &Language, query: &str) -> Result<()> {
+    Query::new(language, query)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INVALID_QUERY: &str = "(no_such_node) @keyword";
+
+    #[test]
+    fn bundled_navi_query_is_valid() {
+        let language: Language = tree_sitter_navi::LANGUAGE.into();
+        assert!(validate_query(&language, tree_sitter_navi::HIGHLIGHTS_QUERY).is_ok());
+        assert!(validate_query(&language, INVALID_QUERY).is_err());
+    }
+
+    #[test]
+    fn invalid_query_still_registers_navi() {
+        let err = register_navi(INVALID_QUERY).unwrap_err();
+        assert!(format!("{:#}", err).contains("Invalid navi highlights query"));
+        register_navi(tree_sitter_navi::HIGHLIGHTS_QUERY).unwrap();
+    }
+
+    #[test]
+    fn language_list_includes_custom_languages() {
+        let languages = available_languages();
+        assert!(languages.contains(&"navi"));
+        assert!(languages.contains(&"rust"));
+        assert!(languages.windows(2).all(|pair| pair[0] < pair[1]));
+    }
+
+    #[test]
+    fn language_is_detected_from_extension() {
+        assert_eq!(detect_language(Path::new("src/main.navi")), "navi");
+        assert_eq!(detect_language(Path::new("src/main.rs")), "rust");
+        assert!(available_languages().contains(&detect_language(Path::new("Makefile"))));
+    }
 }
@@ -6,28 +6,39 @@ use gpui_component::{
     ActiveTheme, Icon, IconName, Sizable, StyledExt, WindowExt,
     button::{Button, ButtonVariants as _},
     h_flex,
-    highlighter::{Diagnostic, DiagnosticSeverity, Language},
+    highlighter::{Diagnostic, DiagnosticSeverity},
     input::{Input, InputEvent, InputState, Position, RopeExt, TabSize},
     list::ListItem,
+    menu::{DropdownMenu, PopupMenuItem},
+    notification::Notification,
     resizable::{h_resizable, resizable_panel},
+    tooltip::Tooltip,
     tree::{TreeState, tree},
     v_flex,
 };
-use lsp_types::{CodeActionKind, TextEdit, WorkspaceEdit};
+use lsp_types::{CodeActionKind, DocumentSymbol, GotoDefinitionResponse, TextEdit, WorkspaceEdit};
 use rust_i18n::t;
 
+use super::location::clamp_position;
 use super::lsp_providers::TextConvertor;
-use super::lsp_store::CodeEditorPanelLspStore;
-use super::types::build_file_items;
-use crate::AppState;
+use super::lsp_store::{BUFFER_URI, CodeEditorPanelLspStore, DefinitionTarget, resolve_definition};
+use super::symbols::{chain_levels, enclosing_chain};
+use super::types::{build_file_items, resolve_workspace_root};
+use super::whitespace::{line_glyphs, visible_lines};
+use super::{available_languages, detect_language};
+use crate::{
+    AppState, PanelAction, app::actions::GoToDefinition, core::config::WhitespaceRendering, utils,
+};
 
 pub struct CodeEditorPanel {
     editor: Entity<InputState>,
     tree_state: Entity<TreeState>,
     go_to_line_state: Entity<InputState>,
-    language: Language,
+    /// Highlighting language: detected from the file, or picked in the status bar
+    language: &'static str,
     line_number: bool,
     indent_guides: bool,
+    render_whitespace: WhitespaceRendering,
     soft_wrap: bool,
     show_file_tree: bool,
     files_loaded: bool,
@@ -37,10 +48,23 @@ pub struct CodeEditorPanel {
     workspace_id: Option<String>,
     workspace_name: Option<String>,
     working_directory: PathBuf,
+    /// Outline of the open file; `None` when its language has no symbol provider
+    document_symbols: Option<Vec<DocumentSymbol>>,
     _subscriptions: Vec<Subscription>,
     _lint_task: Task<()>,
+    _symbols_task: Task<()>,
 }
 
+/// Delay before the outline is rebuilt after an edit
+const SYMBOLS_DEBOUNCE: std::time::Duration = std::time::Duration::from_millis(200);
+
+/// Editor line height as a multiple of the font size, set explicitly so the
+/// whitespace glyphs line up with the text
+const LINE_HEIGHT_SCALE: f32 = 1.618;
+
+/// Width of the line-number gutter beyond its digits, in character widths
+const GUTTER_EXTRA_COLUMNS: f32 = 2.;
+
 impl crate::panels::dock_panel::DockPanel for CodeEditorPanel {
     fn title() -> &'static str {
         "CodeEditor"
@@ -84,15 +108,33 @@ impl CodeEditorPanel {
         cx.new(|cx| Self::new(window, working_dir, cx))
     }
 
+    /// Editor rooted at `working_dir` with `path` opened and the cursor at `position`
+    pub fn view_for_file(
+        window: &mut Window,
+        working_dir: PathBuf,
+        path: PathBuf,
+        position: Position,
+        cx: &mut App,
+    ) -> Entity<Self> {
+        let view = Self::view_with_working_dir(window, Some(working_dir), cx);
+        if let Err(e) = Self::open_file(view.clone(), path.clone(), Some(position), window, cx) {
+            log::warn!("[CodeEditorPanel] Failed to open {}: {}", path.display(), e);
+            window.push_notification(Notification::error(e.to_string()), cx);
+        }
+        view
+    }
+
     pub fn new(window: &mut Window, working_dir: Option<PathBuf>, cx: &mut Context<Self>) -> Self {
-        let default_language = Language::from_str("rust");
+        let default_language = "rust";
         let lsp_store = CodeEditorPanelLspStore::new();
+        let indent_guides = AppState::global(cx).show_indent_guides();
+        let render_whitespace = AppState::global(cx).render_whitespace();
 
         let editor = cx.new(|cx| {
             let mut editor = InputState::new(window, cx)
-                .code_editor(default_language.name())
+                .code_editor(default_language)
                 .line_number(true)
-                .indent_guides(true)
+                .indent_guides(indent_guides)
                 .tab_size(TabSize {
                     tab_size: 4,
                     hard_tabs: false,
@@ -103,7 +145,7 @@ impl CodeEditorPanel {
             let lsp_store = Rc::new(lsp_store.clone());
             // editor.lsp.completion_provider = Some(lsp_store.clone());
             editor.lsp.code_action_providers = vec![lsp_store.clone(), Rc::new(TextConvertor)];
-            // editor.lsp.hover_provider = Some(lsp_store.clone());
+            editor.lsp.hover_provider = Some(lsp_store.clone());
             // editor.lsp.definition_provider = Some(lsp_store.clone());
             // editor.lsp.document_color_provider = Some(lsp_store.clone());
 
@@ -115,9 +157,16 @@ impl CodeEditorPanel {
         let working_dir =
             working_dir.unwrap_or_else(|| AppState::global(cx).current_working_dir().clone());
 
-        let _subscriptions = vec![cx.subscribe(&editor, |this, _, _: &InputEvent, cx| {
-            this.lint_document(cx);
-        })];
+        let _subscriptions = vec![
+            cx.subscribe(&editor, |this, _, event: &InputEvent, cx| {
+                this.lint_document(cx);
+                if let InputEvent::Change = event {
+                    this.refresh_symbols(cx);
+                }
+            }),
+            // Cursor moves only notify the editor; re-render for the breadcrumbs
+            cx.observe(&editor, |_, _, cx| cx.notify()),
+        ];
 
         Self {
             editor,
@@ -125,7 +174,8 @@ impl CodeEditorPanel {
             go_to_line_state,
             language: default_language,
             line_number: true,
-            indent_guides: true,
+            indent_guides,
+            render_whitespace,
             soft_wrap: false,
             show_file_tree: true,
             files_loaded: false,
@@ -135,8 +185,10 @@ impl CodeEditorPanel {
             workspace_id: None,
             workspace_name: None,
             working_directory: working_dir,
+            document_symbols: None,
             _subscriptions,
             _lint_task: Task::ready(()),
+            _symbols_task: Task::ready(()),
         }
     }
 
@@ -169,6 +221,80 @@ impl CodeEditorPanel {
         Self::load_files(self.tree_state.clone(), self.working_directory.clone(), cx);
     }
 
+    /// Point the editor at another project directory.
+    ///
+    /// Rebuilds the file tree and its `.gitignore` based ignorer for `root` and
+    /// uses the folder name as the workspace name. If `root` does not exist or is
+    /// not a directory, an error is shown and the current tree is kept.
+    pub fn open_workspace(&mut self, root: PathBuf, window: &mut Window, cx: &mut Context<Self>) {
+        let root = match resolve_workspace_root(&root) {
+            Ok(root) => root,
+            Err(e) => {
+                log::warn!("[CodeEditorPanel] Cannot open workspace: {}", e);
+                struct OpenWorkspaceError;
+                let note = Notification::error(e.to_string()).id::<OpenWorkspaceError>();
+                window.push_notification(note, cx);
+                return;
+            }
+        };
+
+        log::info!("[CodeEditorPanel] Opening workspace: {}", root.display());
+        self.workspace_name = root
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string());
+        self.working_directory = root.clone();
+        self.files_loaded = true;
+        Self::load_files(self.tree_state.clone(), root, cx);
+        cx.notify();
+    }
+
+    /// Pick a folder with the system dialog and open it as the workspace
+    fn pick_workspace(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let dialog_title = t!("code_editor.dialog.select_folder").to_string();
+
+        cx.spawn_in(window, async move |this, cx| {
+            let Some(folder) = utils::pick_folder(&dialog_title).await else {
+                return;
+            };
+
+            _ = cx.update(move |window, cx| {
+                _ = this.update(cx, |this, cx| {
+                    this.open_workspace(folder, window, cx);
+                });
+            });
+        })
+        .detach();
+    }
+
+    /// File shown in the editor, if any
+    pub fn current_file_path(&self) -> Option<&PathBuf> {
+        self.current_file_path.as_ref()
+    }
+
+    /// Show `path` with the cursor at the zero-based `line` and `column`,
+    /// loading it unless it is already open. Positions past the end of the
+    /// file or line are clamped.
+    pub fn open_at(
+        &mut self,
+        path: PathBuf,
+        line: u32,
+        column: u32,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let position = Position::new(line, column);
+        if self.current_file_path.as_ref() == Some(&path) {
+            let text = self.editor.read(cx).text().to_string();
+            self.jump_to(clamp_position(&text, position), window, cx);
+            return;
+        }
+
+        if let Err(e) = Self::open_file(cx.entity(), path.clone(), Some(position), window, cx) {
+            log::warn!("[CodeEditorPanel] Failed to open {}: {}", path.display(), e);
+            window.push_notification(Notification::error(e.to_string()), cx);
+        }
+    }
+
     /// Get the workspace_id (if available)
     pub fn workspace_id(&self) -> Option<String> {
         self.workspace_id.clone()
@@ -233,11 +359,12 @@ impl CodeEditorPanel {
     }
 
     fn lint_document(&mut self, cx: &mut Context<Self>) {
-        let language = self.language.name().to_string();
+        let language = self.language.to_string();
         let lsp_store = self.lsp_store.clone();
         let text = self.editor.read(cx).text().clone();
+        let path = self.current_file_path.clone().unwrap_or_default();
 
-        self._lint_task = cx.background_spawn(async move {
+        let lint = cx.background_spawn(async move {
             let value = text.to_string();
             let result = autocorrect::lint_for(value.as_str(), &language);
 
@@ -270,7 +397,7 @@ impl CodeEditorPanel {
                 let edit = WorkspaceEdit {
                     changes: Some(
                         std::iter::once((
-                            lsp_types::Uri::from_str("file://CodeEditorPanel").unwrap(),
+                            lsp_types::Uri::from_str(BUFFER_URI).unwrap(),
                             vec![text_edit],
                         ))
                         .collect(),
@@ -290,21 +417,171 @@ impl CodeEditorPanel {
             }
 
             lsp_store.update_code_actions(code_actions.clone());
-            lsp_store.update_diagnostics(diagnostics.clone());
+            lsp_store.update_diagnostics(path, diagnostics.clone());
+        });
+
+        self._lint_task = cx.spawn(async move |this, cx| {
+            lint.await;
+            _ = this.update(cx, |this, cx| {
+                this.sync_diagnostics(cx);
+                cx.notify();
+            });
+        });
+    }
+
+    /// Rebuild the outline shortly after the last edit
+    fn refresh_symbols(&mut self, cx: &mut Context<Self>) {
+        let language = self.language.to_string();
+        let lsp_store = self.lsp_store.clone();
+        let text = self.editor.read(cx).text().clone();
+
+        self._symbols_task = cx.spawn(async move |this, cx| {
+            smol::Timer::after(SYMBOLS_DEBOUNCE).await;
+            let symbols = cx
+                .background_spawn(async move {
+                    lsp_store.document_symbols(&text.to_string(), &language)
+                })
+                .await;
+            _ = this.update(cx, |this, cx| {
+                this.document_symbols = symbols;
+                cx.notify();
+            });
+        });
+    }
+
+    /// Push the current file's diagnostics into the editor, which draws the
+    /// squiggly underlines and shows the message on hover
+    fn sync_diagnostics(&mut self, cx: &mut Context<Self>) {
+        let diagnostics = self.current_diagnostics();
+        self.editor.update(cx, |state, cx| {
+            if let Some(set) = state.diagnostics_mut() {
+                set.clear();
+                set.extend(diagnostics);
+            }
+            cx.notify();
+        });
+    }
+
+    fn current_diagnostics(&self) -> Vec<Diagnostic> {
+        let path = self.current_file_path.clone().unwrap_or_default();
+        let mut diagnostics = self.lsp_store.diagnostics(&path);
+        diagnostics.sort_by_key(|diagnostic| {
+            (
+                diagnostic.range.start.line,
+                diagnostic.range.start.character,
+            )
+        });
+        diagnostics
+    }
+
+    /// Move the cursor to `position` in the open file
+    fn jump_to(&mut self, position: Position, window: &mut Window, cx: &mut Context<Self>) {
+        self.editor.update(cx, |state, cx| {
+            state.set_cursor_position(position, window, cx);
+            state.focus(window, cx);
+        });
+    }
+
+    fn on_go_to_definition(
+        &mut self,
+        _: &GoToDefinition,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if !self.go_to_definition(window, cx) {
+            struct DefinitionNotFound;
+            let note = Notification::info(t!("code_editor.definition.not_found").to_string())
+                .id::<DefinitionNotFound>();
+            window.push_notification(note, cx);
+        }
+    }
+
+    /// Go to the definition of the symbol under the cursor; several results
+    /// open a picker. Returns `false` when nothing was found.
+    fn go_to_definition(&mut self, window: &mut Window, cx: &mut Context<Self>) -> bool {
+        let (text, offset) = {
+            let state = self.editor.read(cx);
+            (state.text().clone(), state.cursor())
+        };
+        let links = self.lsp_store.definition_links(&text, offset);
+        let current_path = self.current_file_path.clone().unwrap_or_default();
+        let mut targets = resolve_definition(GotoDefinitionResponse::Link(links), &current_path);
+
+        match targets.len() {
+            0 => return false,
+            1 => self.navigate_to_definition(targets.remove(0), window, cx),
+            _ => self.pick_definition(targets, window, cx),
+        }
+        true
+    }
+
+    /// Jump within the open file, open another file in a new editor tab, or
+    /// open a documentation link in the browser
+    fn navigate_to_definition(
+        &mut self,
+        target: DefinitionTarget,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        match target {
+            DefinitionTarget::Url(url) => cx.open_url(&url),
+            DefinitionTarget::File { path, range }
+                if path == self.current_file_path.clone().unwrap_or_default() =>
+            {
+                self.jump_to(range.start, window, cx);
+            }
+            DefinitionTarget::File { path, range } => {
+                let action = PanelAction::open_code_editor_at(
+                    self.working_directory.clone(),
+                    path,
+                    range.start,
+                );
+                window.dispatch_action(Box::new(action), cx);
+            }
+        }
+    }
+
+    fn pick_definition(
+        &mut self,
+        targets: Vec<DefinitionTarget>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let view = cx.entity();
+        let root = self.working_directory.clone();
+
+        window.open_dialog(cx, move |dialog, _, _| {
+            dialog
+                .title(t!("code_editor.definition.pick_title").to_string())
+                .child(
+                    v_flex()
+                        .gap_1()
+                        .children(targets.iter().enumerate().map(|(ix, target)| {
+                            let view = view.clone();
+                            let target = target.clone();
+                            Button::new(("definition-target", ix))
+                                .ghost()
+                                .small()
+                                .label(target.label(&root))
+                                .on_click(move |_, window, cx| {
+                                    window.close_dialog(cx);
+                                    view.update(cx, |this, cx| {
+                                        this.navigate_to_definition(target.clone(), window, cx);
+                                    });
+                                })
+                        })),
+                )
         });
     }
 
     fn open_file(
         view: Entity<Self>,
         path: PathBuf,
+        position: Option<Position>,
         window: &mut Window,
         cx: &mut App,
     ) -> Result<()> {
-        let language = path
-            .extension()
-            .and_then(|ext| ext.to_str())
-            .unwrap_or_default();
-        let language = Language::from_str(&language);
+        let language = detect_language(&path);
         let content = std::fs::read_to_string(&path)?;
         let path_clone = path.clone();
 
@@ -312,13 +589,19 @@ impl CodeEditorPanel {
             .spawn(cx, async move |window| {
                 _ = view.update_in(window, |this, window, cx| {
                     _ = this.editor.update(cx, |this, cx| {
-                        this.set_highlighter(language.name(), cx);
+                        this.set_highlighter(language, cx);
+                        let position = position.map(|position| clamp_position(&content, position));
                         this.set_value(content, window, cx);
+                        if let Some(position) = position {
+                            this.set_cursor_position(position, window, cx);
+                        }
                     });
 
                     this.language = language;
                     this.current_file_path = Some(path_clone);
                     this.has_opened_file = true;
+                    this.sync_diagnostics(cx);
+                    this.refresh_symbols(cx);
                     cx.notify();
                 });
             })
@@ -359,6 +642,7 @@ impl CodeEditorPanel {
                                 Self::open_file(
                                     cx.entity(),
                                     PathBuf::from(item.id.as_str()),
+                                    None,
                                     _window,
                                     cx,
                                 )
@@ -396,6 +680,21 @@ impl CodeEditorPanel {
             }))
     }
 
+    fn render_open_folder_button(
+        &self,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        Button::new("open-folder")
+            .icon(IconName::FolderOpen)
+            .ghost()
+            .xsmall()
+            .tooltip(t!("code_editor.tooltip.open_folder").to_string())
+            .on_click(cx.listener(|this, _, window, cx| {
+                this.pick_workspace(window, cx);
+            }))
+    }
+
     fn render_line_number_button(
         &self,
         _: &mut Window,
@@ -469,10 +768,176 @@ impl CodeEditorPanel {
                 this.editor.update(cx, |state, cx| {
                     state.set_indent_guides(this.indent_guides, window, cx);
                 });
+                save_show_indent_guides(this.indent_guides, cx);
                 cx.notify();
             }))
     }
 
+    /// Mark no whitespace, all of it, or only trailing whitespace
+    fn set_render_whitespace(&mut self, rendering: WhitespaceRendering, cx: &mut Context<Self>) {
+        if rendering == self.render_whitespace {
+            return;
+        }
+        self.render_whitespace = rendering;
+        save_render_whitespace(rendering, cx);
+        cx.notify();
+    }
+
+    /// Glyphs over the spaces and tabs picked by `render_whitespace`, laid out
+    /// for the lines on screen only. They follow the editor's scroll offset
+    /// and are skipped with soft wrap, where lines no longer map to rows.
+    fn render_whitespace_overlay(&self, window: &mut Window, cx: &App) -> Option<AnyElement> {
+        use gpui_component::input::RopeExt;
+
+        if self.render_whitespace == WhitespaceRendering::None || self.soft_wrap {
+            return None;
+        }
+        let font_size = cx.theme().mono_font_size;
+        let line_height = font_size * LINE_HEIGHT_SCALE;
+        let text_system = window.text_system();
+        let font_id = text_system.resolve_font(&font(cx.theme().mono_font_family.clone()));
+        let char_width = text_system.em_advance(font_id, font_size).ok()?;
+
+        let state = self.editor.read(cx);
+        let text = state.text();
+        let line_count = text.lines_len();
+        let scroll = state.scroll_handle().offset();
+        let text_left = if self.line_number {
+            let digits = line_count.max(1).to_string().len() as f32;
+            char_width * (digits + GUTTER_EXTRA_COLUMNS)
+        } else {
+            px(0.)
+        };
+
+        let rows = visible_lines(
+            -scroll.y,
+            window.viewport_size().height,
+            line_height,
+            line_count,
+        );
+        let color = cx.theme().muted_foreground.opacity(0.6);
+        let trailing_bg = cx.theme().danger.opacity(0.15);
+        let mut glyphs = Vec::new();
+        for row in rows {
+            let start = text.position_to_offset(&Position::new(row as u32, 0));
+            let end = text.position_to_offset(&Position::new(row as u32 + 1, 0));
+            let line = text.slice(start..end).to_string();
+            for glyph in line_glyphs(row as u32, &line, 4, self.render_whitespace) {
+                glyphs.push(
+                    div()
+                        .absolute()
+                        .top(line_height * row as f32 + scroll.y)
+                        .left(text_left + char_width * glyph.display_column as f32 + scroll.x)
+                        .w(char_width)
+                        .h(line_height)
+                        .flex()
+                        .items_center()
+                        .justify_center()
+                        .text_color(color)
+                        .when(
+                            self.render_whitespace == WhitespaceRendering::Trailing,
+                            |this| this.bg(trailing_bg),
+                        )
+                        .child(glyph.symbol()),
+                );
+            }
+        }
+
+        Some(
+            div()
+                .absolute()
+                .inset_0()
+                .overflow_hidden()
+                .font_family(cx.theme().mono_font_family.clone())
+                .text_size(font_size)
+                .line_height(line_height)
+                .children(glyphs)
+                .into_any_element(),
+        )
+    }
+
+    fn render_whitespace_button(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let current = self.render_whitespace;
+        let view = cx.entity();
+
+        Button::new("render-whitespace")
+            .ghost()
+            .xsmall()
+            .label("¶")
+            .tooltip(t!("code_editor.tooltip.render_whitespace").to_string())
+            .dropdown_menu(move |mut menu, _, _| {
+                for (rendering, label) in [
+                    (WhitespaceRendering::None, t!("code_editor.whitespace.none")),
+                    (WhitespaceRendering::All, t!("code_editor.whitespace.all")),
+                    (
+                        WhitespaceRendering::Trailing,
+                        t!("code_editor.whitespace.trailing_only"),
+                    ),
+                ] {
+                    let view = view.clone();
+                    menu = menu.item(
+                        PopupMenuItem::new(label.to_string())
+                            .checked(rendering == current)
+                            .on_click(move |_, _, cx| {
+                                view.update(cx, |this, cx| {
+                                    this.set_render_whitespace(rendering, cx)
+                                });
+                            }),
+                    );
+                }
+                menu
+            })
+    }
+
+    /// Highlight the buffer as `name`, one of [`available_languages`], instead
+    /// of the language detected from the file extension
+    pub fn set_language(&mut self, name: &str, cx: &mut Context<Self>) {
+        let Some(language) = available_languages()
+            .into_iter()
+            .find(|language| *language == name)
+        else {
+            log::warn!("[CodeEditorPanel] Unknown language: {}", name);
+            return;
+        };
+        if language == self.language {
+            return;
+        }
+
+        self.language = language;
+        self.editor.update(cx, |state, cx| {
+            state.set_highlighter(language, cx);
+        });
+        self.lint_document(cx);
+        self.refresh_symbols(cx);
+        cx.notify();
+    }
+
+    /// Status bar picker overriding the highlighting language of the buffer
+    fn render_language_button(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let current = self.language;
+        let view = cx.entity();
+
+        Button::new("language-mode")
+            .ghost()
+            .xsmall()
+            .label(current)
+            .tooltip(t!("code_editor.tooltip.language_mode").to_string())
+            .dropdown_menu(move |mut menu, _, _| {
+                menu = menu.scrollable(true).max_h(px(360.));
+                for language in available_languages() {
+                    let view = view.clone();
+                    menu = menu.item(
+                        PopupMenuItem::new(language)
+                            .checked(language == current)
+                            .on_click(move |_, _, cx| {
+                                view.update(cx, |this, cx| this.set_language(language, cx));
+                            }),
+                    );
+                }
+                menu
+            })
+    }
+
     fn render_go_to_line_button(
         &self,
         _window: &mut Window,
@@ -599,6 +1064,173 @@ impl CodeEditorPanel {
         log::info!("[CodeEditorPanel] Code selection event published");
     }
 
+    /// Marker strip next to the editor with one mark per diagnostic, placed
+    /// proportionally to its line; clicking a mark jumps to the problem
+    fn render_diagnostic_gutter(
+        &self,
+        diagnostics: &[Diagnostic],
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let line_count = self.editor.read(cx).text().lines_len().max(1);
+
+        div()
+            .relative()
+            .h_full()
+            .w(px(10.))
+            .flex_shrink_0()
+            .border_l_1()
+            .border_color(cx.theme().border)
+            .children(diagnostics.iter().enumerate().map(|(ix, diagnostic)| {
+                let position = diagnostic.range.start;
+                let top = position.line as f32 / line_count as f32;
+                let message = SharedString::from(format!(
+                    "{}:{} {}: {}",
+                    position.line + 1,
+                    position.character + 1,
+                    severity_label(&diagnostic.severity),
+                    diagnostic.message
+                ));
+
+                div()
+                    .id(("diagnostic-marker", ix))
+                    .absolute()
+                    .top(relative(top))
+                    .left(px(2.))
+                    .w(px(6.))
+                    .h(px(4.))
+                    .rounded(px(1.))
+                    .cursor_pointer()
+                    .bg(severity_color(&diagnostic.severity, cx))
+                    .tooltip(move |window, cx| Tooltip::new(message.clone()).build(window, cx))
+                    .on_click(cx.listener(move |this, _, window, cx| {
+                        this.jump_to(position, window, cx);
+                    }))
+            }))
+    }
+
+    /// Error / warning counts in the status bar; clicking jumps to the first one
+    fn render_diagnostic_summary(
+        &self,
+        diagnostics: &[Diagnostic],
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let errors = diagnostics
+            .iter()
+            .filter(|d| matches!(d.severity, DiagnosticSeverity::Error))
+            .count();
+        let warnings = diagnostics
+            .iter()
+            .filter(|d| matches!(d.severity, DiagnosticSeverity::Warning))
+            .count();
+        let first = diagnostics.first().map(|diagnostic| diagnostic.range.start);
+
+        Button::new("diagnostic-summary")
+            .ghost()
+            .xsmall()
+            .tooltip(t!("code_editor.tooltip.diagnostics").to_string())
+            .child(
+                h_flex()
+                    .gap_1p5()
+                    .items_center()
+                    .child(
+                        Icon::new(IconName::CircleX)
+                            .size(px(14.))
+                            .text_color(cx.theme().red),
+                    )
+                    .child(errors.to_string())
+                    .child(
+                        Icon::new(IconName::TriangleAlert)
+                            .size(px(14.))
+                            .text_color(cx.theme().warning),
+                    )
+                    .child(warnings.to_string()),
+            )
+            .when_some(first, |this, position| {
+                this.on_click(cx.listener(move |this, _, window, cx| {
+                    this.jump_to(position, window, cx);
+                }))
+            })
+    }
+
+    /// File path segments followed by the symbols enclosing the cursor; each
+    /// symbol opens a menu of its siblings to jump to
+    fn render_breadcrumbs(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let path = self.current_file_path.clone().unwrap_or_default();
+        let relative = path
+            .strip_prefix(&self.working_directory)
+            .unwrap_or(&path)
+            .to_path_buf();
+        let path_segments: Vec<String> = relative
+            .iter()
+            .map(|segment| segment.to_string_lossy().to_string())
+            .collect();
+
+        let cursor = self.editor.read(cx).cursor_position();
+        let symbols = self.document_symbols.as_deref().unwrap_or_default();
+        let chain = enclosing_chain(symbols, cursor);
+        let levels = chain_levels(symbols, &chain);
+        let view = cx.entity();
+
+        let separator = || {
+            Icon::new(IconName::ChevronRight)
+                .size(px(12.))
+                .text_color(cx.theme().muted_foreground)
+        };
+
+        h_flex()
+            .id("breadcrumbs")
+            .w_full()
+            .h(px(26.))
+            .px_3()
+            .gap_1()
+            .items_center()
+            .overflow_x_hidden()
+            .text_xs()
+            .text_color(cx.theme().muted_foreground)
+            .border_b_1()
+            .border_color(cx.theme().border)
+            .children(path_segments.into_iter().enumerate().map(|(ix, segment)| {
+                h_flex()
+                    .gap_1()
+                    .items_center()
+                    .when(ix > 0, |this| this.child(separator()))
+                    .child(segment)
+            }))
+            .children(
+                chain
+                    .iter()
+                    .zip(levels)
+                    .enumerate()
+                    .map(|(depth, (&ix, level))| {
+                        let siblings: Vec<(String, Position)> = level
+                            .iter()
+                            .map(|symbol| (symbol.name.clone(), symbol.selection_range.start))
+                            .collect();
+                        let view = view.clone();
+
+                        h_flex().gap_1().items_center().child(separator()).child(
+                            Button::new(("breadcrumb-symbol", depth))
+                                .ghost()
+                                .xsmall()
+                                .label(level[ix].name.clone())
+                                .dropdown_menu(move |mut menu, _, _| {
+                                    for (name, position) in siblings.iter().cloned() {
+                                        let view = view.clone();
+                                        menu = menu.item(PopupMenuItem::new(name).on_click(
+                                            move |_, window, cx| {
+                                                view.update(cx, |this, cx| {
+                                                    this.jump_to(position, window, cx);
+                                                });
+                                            },
+                                        ));
+                                    }
+                                    menu
+                                }),
+                        )
+                    }),
+            )
+    }
+
     fn render_empty_state(&self, _: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         v_flex()
             .size_full()
@@ -636,17 +1268,10 @@ impl Render for CodeEditorPanel {
     fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         use gpui_component::input::RopeExt;
 
-        // Update diagnostics
-        // if self.lsp_store.is_dirty() {
-        //     let diagnostics = self.lsp_store.diagnostics();
-        //     self.editor.update(cx, |state, cx| {
-        //         state.diagnostics_mut().map(|set| {
-        //             set.clear();
-        //             set.extend(diagnostics);
-        //         });
-        //         cx.notify();
-        //     });
-        // }
+        if self.lsp_store.take_diagnostics_dirty() {
+            self.sync_diagnostics(cx);
+        }
+        let diagnostics = self.current_diagnostics();
 
         // 提取选择范围信息
         let selection_info = self.editor.update(cx, |state, cx| {
@@ -676,13 +1301,39 @@ impl Render for CodeEditorPanel {
             }
         });
 
-        let editor_input = Input::new(&self.editor)
-            .bordered(false)
-            .p_0()
-            .h_full()
-            .font_family(cx.theme().mono_font_family.clone())
-            .text_size(cx.theme().mono_font_size)
-            .focus_bordered(false)
+        let editor_body = h_flex()
+            .w_full()
+            .flex_1()
+            .on_mouse_up(
+                MouseButton::Left,
+                cx.listener(|this, event: &MouseUpEvent, window, cx| {
+                    if event.modifiers.secondary() {
+                        this.go_to_definition(window, cx);
+                    }
+                }),
+            )
+            .child(
+                div()
+                    .relative()
+                    .flex_1()
+                    .h_full()
+                    .child(
+                        Input::new(&self.editor)
+                            .bordered(false)
+                            .p_0()
+                            .h_full()
+                            .font_family(cx.theme().mono_font_family.clone())
+                            .text_size(cx.theme().mono_font_size)
+                            .line_height(cx.theme().mono_font_size * LINE_HEIGHT_SCALE)
+                            .focus_bordered(false),
+                    )
+                    .children(self.render_whitespace_overlay(window, cx)),
+            )
+            .child(self.render_diagnostic_gutter(&diagnostics, cx));
+        let editor_input = v_flex()
+            .size_full()
+            .child(self.render_breadcrumbs(cx))
+            .child(editor_body)
             .into_any_element();
 
         // 根据是否打开文件决定显示内容
@@ -719,38 +1370,102 @@ impl Render for CodeEditorPanel {
             }
         };
 
-        v_flex().id("app").size_full().child(
-            v_flex()
-                .id("source")
-                .w_full()
-                .flex_1()
-                .child(main_content)
-                .child(
-                    h_flex()
-                        .justify_between()
-                        .text_sm()
-                        .bg(cx.theme().background)
-                        // .py_1p5()
-                        .h(px(30.))
-                        .px_4()
-                        .border_t_1()
-                        .border_color(cx.theme().border)
-                        .text_color(cx.theme().muted_foreground)
-                        .child(
-                            h_flex()
-                                .gap_3()
-                                .child(self.render_toggle_file_tree_button(window, cx))
-                                .child(self.render_line_number_button(window, cx))
-                                .child(self.render_soft_wrap_button(window, cx))
-                                .child(self.render_indent_guides_button(window, cx)),
-                        )
-                        .child(
-                            h_flex()
-                                .gap_3()
-                                .child(self.render_selection_range_info(window, cx, selection_info))
-                                .child(self.render_go_to_line_button(window, cx)),
-                        ),
-                ),
-        )
+        v_flex()
+            .id("app")
+            .key_context("CodeEditor")
+            .on_action(cx.listener(Self::on_go_to_definition))
+            .size_full()
+            .child(
+                v_flex()
+                    .id("source")
+                    .w_full()
+                    .flex_1()
+                    .child(main_content)
+                    .child(
+                        h_flex()
+                            .justify_between()
+                            .text_sm()
+                            .bg(cx.theme().background)
+                            // .py_1p5()
+                            .h(px(30.))
+                            .px_4()
+                            .border_t_1()
+                            .border_color(cx.theme().border)
+                            .text_color(cx.theme().muted_foreground)
+                            .child(
+                                h_flex()
+                                    .gap_3()
+                                    .child(self.render_toggle_file_tree_button(window, cx))
+                                    .child(self.render_open_folder_button(window, cx))
+                                    .child(self.render_line_number_button(window, cx))
+                                    .child(self.render_soft_wrap_button(window, cx))
+                                    .child(self.render_indent_guides_button(window, cx))
+                                    .child(self.render_whitespace_button(cx)),
+                            )
+                            .child(
+                                h_flex()
+                                    .gap_3()
+                                    .when(self.has_opened_file, |this| {
+                                        this.child(self.render_diagnostic_summary(&diagnostics, cx))
+                                    })
+                                    .child(self.render_selection_range_info(
+                                        window,
+                                        cx,
+                                        selection_info,
+                                    ))
+                                    .child(self.render_go_to_line_button(window, cx))
+                                    .when(self.has_opened_file, |this| {
+                                        this.child(self.render_language_button(cx))
+                                    }),
+                            ),
+                    ),
+            )
+    }
+}
+
+/// Write the indent guides toggle back to `config.json`
+fn save_show_indent_guides(show: bool, cx: &mut App) {
+    AppState::global_mut(cx).set_show_indent_guides(show);
+    let Some(service) = AppState::global(cx).agent_config_service().cloned() else {
+        return;
+    };
+    cx.spawn(async move |_cx| {
+        if let Err(e) = service.update_show_indent_guides(show).await {
+            log::error!("Failed to save indent guides setting: {}", e);
+        }
+    })
+    .detach();
+}
+
+/// Write the whitespace rendering choice back to `config.json`
+fn save_render_whitespace(rendering: WhitespaceRendering, cx: &mut App) {
+    AppState::global_mut(cx).set_render_whitespace(rendering);
+    let Some(service) = AppState::global(cx).agent_config_service().cloned() else {
+        return;
+    };
+    cx.spawn(async move |_cx| {
+        if let Err(e) = service.update_render_whitespace(rendering).await {
+            log::error!("Failed to save whitespace rendering: {}", e);
+        }
+    })
+    .detach();
+}
+
+fn severity_label(severity: &DiagnosticSeverity) -> String {
+    match severity {
+        DiagnosticSeverity::Error => t!("code_editor.diagnostic.error"),
+        DiagnosticSeverity::Warning => t!("code_editor.diagnostic.warning"),
+        DiagnosticSeverity::Info => t!("code_editor.diagnostic.info"),
+        DiagnosticSeverity::Hint => t!("code_editor.diagnostic.hint"),
+    }
+    .to_string()
+}
+
+fn severity_color(severity: &DiagnosticSeverity, cx: &App) -> Hsla {
+    match severity {
+        DiagnosticSeverity::Error => cx.theme().red,
+        DiagnosticSeverity::Warning => cx.theme().warning,
+        DiagnosticSeverity::Info => cx.theme().info,
+        DiagnosticSeverity::Hint => cx.theme().muted_foreground,
     }
 }
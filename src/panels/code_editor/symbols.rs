@@ -0,0 +1,281 @@
+//! Document outline used for `textDocument/documentSymbol` when no language
+//! server answers, plus helpers to find the symbols enclosing the cursor.
+
+use lsp_types::{DocumentSymbol, Position, Range, SymbolKind};
+
+/// Declaration keywords recognized by [`outline_symbols`]
+const KEYWORDS: &[(&str, SymbolKind)] = &[
+    ("mod", SymbolKind::MODULE),
+    ("struct", SymbolKind::STRUCT),
+    ("enum", SymbolKind::ENUM),
+    ("trait", SymbolKind::INTERFACE),
+    ("interface", SymbolKind::INTERFACE),
+    ("impl", SymbolKind::OBJECT),
+    ("fn", SymbolKind::FUNCTION),
+];
+
+/// Modifiers that may precede a declaration keyword
+const MODIFIERS: &[&str] = &["pub", "async", "unsafe", "default", "extern"];
+
+/// Build a symbol tree for Rust-like sources (Rust, navi).
+///
+/// Declarations are found line by line and their bodies by brace matching,
+/// skipping comments, string and char literals. Declarations without a body
+/// (`mod foo;`, `struct Unit;`) cover their own line only.
+pub fn outline_symbols(text: &str) -> Vec<DocumentSymbol> {
+    let chars: Vec<char> = text.chars().collect();
+    let positions = char_positions(&chars);
+
+    let mut flat = Vec::new();
+    let mut line_start = 0;
+    for line in text.split('\n') {
+        let line_len = line.chars().count();
+        if let Some((column, kind, name)) = parse_declaration(line) {
+            let start = line_start + column;
+            let end = body_end(&chars, start).unwrap_or(line_start + line_len);
+            let range = Range::new(positions[start], positions[end.min(chars.len())]);
+            flat.push(symbol(name, kind, range));
+        }
+        line_start += line_len + 1;
+    }
+
+    nest(flat)
+}
+
+/// Indices of the symbols enclosing `position`, outermost first, one per level
+pub fn enclosing_chain(symbols: &[DocumentSymbol], position: Position) -> Vec<usize> {
+    let mut chain = Vec::new();
+    let mut level = symbols;
+    while let Some(ix) = level
+        .iter()
+        .position(|symbol| contains(&symbol.range, position))
+    {
+        chain.push(ix);
+        level = level[ix].children.as_deref().unwrap_or_default();
+    }
+    chain
+}
+
+/// The sibling lists along `chain`: roots first, then each parent's children
+pub fn chain_levels<'a>(
+    symbols: &'a [DocumentSymbol],
+    chain: &[usize],
+) -> Vec<&'a [DocumentSymbol]> {
+    let mut levels = Vec::with_capacity(chain.len());
+    let mut level = symbols;
+    for &ix in chain {
+        levels.push(level);
+        level = level[ix].children.as_deref().unwrap_or_default();
+    }
+    levels
+}
+
+fn contains(range: &Range, position: Position) -> bool {
+    let key = |p: &Position| (p.line, p.character);
+    key(&range.start) <= key(&position) && key(&position) <= key(&range.end)
+}
+
+/// Column, kind and name of a declaration starting on `line`
+fn parse_declaration(line: &str) -> Option<(usize, SymbolKind, String)> {
+    let trimmed = line.trim_start();
+    if trimmed.starts_with("//") {
+        return None;
+    }
+    let column = line.chars().count() - trimmed.chars().count();
+
+    let mut rest = trimmed;
+    loop {
+        let word_end = rest
+            .find(|c: char| c.is_whitespace() || c == '(' || c == '<')
+            .unwrap_or(rest.len());
+        let word = &rest[..word_end];
+        if MODIFIERS.contains(&word) {
+            // `pub(crate)`, `extern "C"` and similar carry an argument
+            rest = rest[word_end..].trim_start();
+            if let Some(after) = rest.strip_prefix('(') {
+                rest = after.split_once(')')?.1.trim_start();
+            } else if rest.starts_with('"') {
+                rest = rest[1..].split_once('"')?.1.trim_start();
+            }
+            continue;
+        }
+
+        let (_, kind) = KEYWORDS.iter().find(|(keyword, _)| *keyword == word)?;
+        let signature = rest[word_end..].trim_start();
+        let name = if *kind == SymbolKind::OBJECT {
+            // `impl<T> Display for Foo<T> where ..` reads as `impl Display for Foo<T>`
+            let signature = match signature.strip_prefix('<') {
+                Some(after) => after.split_once('>').map_or("", |(_, s)| s).trim_start(),
+                None => signature,
+            };
+            let end = [signature.find('{'), signature.find(" where")]
+                .into_iter()
+                .flatten()
+                .min()
+                .unwrap_or(signature.len());
+            format!("impl {}", signature[..end].trim())
+        } else {
+            signature
+                .chars()
+                .take_while(|c| c.is_alphanumeric() || *c == '_')
+                .collect()
+        };
+        if name.is_empty() || name == "impl " {
+            return None;
+        }
+        return Some((column, *kind, name));
+    }
+}
+
+/// Char index just past the `}` closing the body of the declaration at
+/// `start`, or `None` when the declaration ends with `;` first
+fn body_end(chars: &[char], start: usize) -> Option<usize> {
+    let mut depth = 0usize;
+    let mut nesting = 0usize;
+    let mut i = start;
+    while i < chars.len() {
+        match chars[i] {
+            '/' if chars.get(i + 1) == Some(&'/') => {
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+            }
+            '"' => {
+                i += 1;
+                while i < chars.len() && chars[i] != '"' {
+                    if chars[i] == '\\' {
+                        i += 1;
+                    }
+                    i += 1;
+                }
+            }
+            // Char literals like '{'; lifetimes have no closing quote
+            '\'' if chars.get(i + 2) == Some(&'\'') => i += 2,
+            '(' | '[' => nesting += 1,
+            ')' | ']' => nesting = nesting.saturating_sub(1),
+            ';' if depth == 0 && nesting == 0 => return None,
+            '{' => depth += 1,
+            '}' if depth > 0 => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i + 1);
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Line/column of every char index, plus one past the end
+fn char_positions(chars: &[char]) -> Vec<Position> {
+    let mut positions = Vec::with_capacity(chars.len() + 1);
+    let (mut line, mut character) = (0, 0);
+    for c in chars {
+        positions.push(Position::new(line, character));
+        if *c == '\n' {
+            line += 1;
+            character = 0;
+        } else {
+            character += 1;
+        }
+    }
+    positions.push(Position::new(line, character));
+    positions
+}
+
+#[allow(deprecated)]
+fn symbol(name: String, kind: SymbolKind, range: Range) -> DocumentSymbol {
+    DocumentSymbol {
+        name,
+        detail: None,
+        kind,
+        tags: None,
+        deprecated: None,
+        range,
+        selection_range: Range::new(range.start, range.start),
+        children: None,
+    }
+}
+
+/// Turn a flat, start-ordered list into a tree by range containment
+fn nest(flat: Vec<DocumentSymbol>) -> Vec<DocumentSymbol> {
+    let mut roots: Vec<DocumentSymbol> = Vec::new();
+    let mut stack: Vec<DocumentSymbol> = Vec::new();
+
+    for symbol in flat {
+        while let Some(top) = stack.pop() {
+            if contains(&top.range, symbol.range.start) {
+                stack.push(top);
+                break;
+            }
+            attach(top, &mut stack, &mut roots);
+        }
+        stack.push(symbol);
+    }
+    while let Some(top) = stack.pop() {
+        attach(top, &mut stack, &mut roots);
+    }
+    roots
+}
+
+fn attach(symbol: DocumentSymbol, stack: &mut [DocumentSymbol], roots: &mut Vec<DocumentSymbol>) {
+    match stack.last_mut() {
+        Some(parent) => parent.children.get_or_insert_with(Vec::new).push(symbol),
+        None => roots.push(symbol),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = r#"mod net {
+    pub(crate) struct Client {
+        url: String,
+    }
+
+    impl<T> Retry for Client where T: Clone {
+        fn retry(&self) {
+            let s = "}";
+        }
+
+        pub async fn backoff(&self, delays: [u64; 3]) {}
+    }
+}
+
+struct Unit;
+fn main() {}
+"#;
+
+    #[test]
+    fn outline_nests_declarations() {
+        let symbols = outline_symbols(SOURCE);
+        let names: Vec<&str> = symbols.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["net", "Unit", "main"]);
+
+        let net = symbols[0].children.as_ref().unwrap();
+        let names: Vec<&str> = net.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["Client", "impl Retry for Client"]);
+
+        let methods = net[1].children.as_ref().unwrap();
+        let names: Vec<&str> = methods.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["retry", "backoff"]);
+        assert_eq!(symbols[1].range.start.line, symbols[1].range.end.line);
+    }
+
+    #[test]
+    fn chain_follows_cursor() {
+        let symbols = outline_symbols(SOURCE);
+
+        // Inside `retry`, on the string containing a brace
+        let chain = enclosing_chain(&symbols, Position::new(7, 20));
+        assert_eq!(chain, [0, 1, 0]);
+        let levels = chain_levels(&symbols, &chain);
+        assert_eq!(levels[2][1].name, "backoff");
+
+        assert_eq!(enclosing_chain(&symbols, Position::new(15, 5)), [2]);
+        assert!(enclosing_chain(&symbols, Position::new(13, 0)).is_empty());
+    }
+}
@@ -1,8 +1,9 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use autocorrect::ignorer::Ignorer;
 use gpui_component::tree::TreeItem;
 use lsp_types::{CompletionItem, CompletionTextEdit, InsertReplaceEdit};
+use rust_i18n::t;
 
 // ============================================================================
 // Constants
@@ -26,6 +27,26 @@ pub const RUST_DOC_URLS: &[(&str, &str)] = &[
 // Helper Functions
 // ============================================================================
 
+/// std documentation page for a known type or trait name
+pub fn rust_doc_url(symbol: &str) -> Option<String> {
+    RUST_DOC_URLS
+        .iter()
+        .find(|(name, _)| *name == symbol)
+        .map(|(_, path)| format!("https://doc.rust-lang.org/std/{}.html", path))
+}
+
+/// Markdown shown in the hover popover, or `None` when there is nothing to show
+pub fn hover_markdown(documentation: Option<&str>, doc_url: Option<&str>) -> Option<String> {
+    let documentation = documentation.map(str::trim).filter(|doc| !doc.is_empty());
+    let link = doc_url.map(|url| format!("[{}]({})", t!("code_editor.hover.open_docs"), url));
+
+    match (documentation, link) {
+        (Some(doc), Some(link)) => Some(format!("{}\n\n{}", doc, link)),
+        (Some(doc), None) => Some(doc.to_string()),
+        (None, link) => link,
+    }
+}
+
 pub fn completion_item(
     replace_range: &lsp_types::Range,
     label: &str,
@@ -46,6 +67,17 @@ pub fn completion_item(
     }
 }
 
+/// Validate a directory chosen as the editor's workspace root.
+pub fn resolve_workspace_root(root: &Path) -> anyhow::Result<PathBuf> {
+    if !root.exists() {
+        anyhow::bail!(t!("code_editor.error.folder_not_found", path = root.display()).to_string());
+    }
+    if !root.is_dir() {
+        anyhow::bail!(t!("code_editor.error.not_a_folder", path = root.display()).to_string());
+    }
+    Ok(root.to_path_buf())
+}
+
 pub fn build_file_items(ignorer: &Ignorer, root: &PathBuf, path: &PathBuf) -> Vec<TreeItem> {
     let mut items = Vec::new();
 
@@ -79,3 +111,22 @@ pub fn build_file_items(ignorer: &Ignorer, root: &PathBuf, path: &PathBuf) -> Ve
     });
     items
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hover_combines_docs_with_std_link() {
+        let url = rust_doc_url("Vec").unwrap();
+        assert_eq!(url, "https://doc.rust-lang.org/std/vec/struct.Vec.html");
+        assert!(rust_doc_url("Frobnicator").is_none());
+
+        let markdown = hover_markdown(Some("A growable array."), Some(&url)).unwrap();
+        assert!(markdown.starts_with("A growable array.\n\n["));
+        assert!(markdown.ends_with("(https://doc.rust-lang.org/std/vec/struct.Vec.html)"));
+
+        assert_eq!(hover_markdown(Some("  "), None), None);
+        assert_eq!(hover_markdown(None, None), None);
+    }
+}
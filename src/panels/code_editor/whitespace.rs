@@ -0,0 +1,119 @@
+//! Whitespace glyphs for the code editor: a middot for each space and an
+//! arrow for each tab, drawn over the buffer for the lines on screen only.
+
+use std::ops::Range;
+
+use gpui::Pixels;
+use gpui_component::input::Position;
+
+use crate::core::config::WhitespaceRendering;
+
+/// A space or tab to draw a glyph over
+#[derive(Debug, Clone, PartialEq)]
+pub(super) struct WhitespaceGlyph {
+    /// Where it is in the buffer, in the editor's UTF-16 line/column units
+    pub position: Position,
+    /// Column it's displayed at, with tabs reaching the next tab stop
+    pub display_column: u32,
+    pub tab: bool,
+}
+
+impl WhitespaceGlyph {
+    pub fn symbol(&self) -> &'static str {
+        if self.tab { "→" } else { "·" }
+    }
+}
+
+/// Glyphs that `rendering` asks for on line `line_ix`, whose text is `line`
+pub(super) fn line_glyphs(
+    line_ix: u32,
+    line: &str,
+    tab_size: u32,
+    rendering: WhitespaceRendering,
+) -> Vec<WhitespaceGlyph> {
+    if rendering == WhitespaceRendering::None {
+        return Vec::new();
+    }
+    let line = line.trim_end_matches(['\n', '\r']);
+    let content_end = match rendering {
+        WhitespaceRendering::Trailing => line.trim_end_matches([' ', '\t']).len(),
+        _ => 0,
+    };
+
+    let mut glyphs = Vec::new();
+    let mut column = 0;
+    let mut display_column = 0;
+    for (ix, c) in line.char_indices() {
+        let tab = c == '\t';
+        if (tab || c == ' ') && ix >= content_end {
+            glyphs.push(WhitespaceGlyph {
+                position: Position::new(line_ix, column),
+                display_column,
+                tab,
+            });
+        }
+        column += c.len_utf16() as u32;
+        display_column = if tab {
+            (display_column / tab_size.max(1) + 1) * tab_size.max(1)
+        } else {
+            display_column + 1
+        };
+    }
+    glyphs
+}
+
+/// Lines of a `line_count` line buffer that show in a viewport `height` tall,
+/// scrolled `scroll_top` down
+pub(super) fn visible_lines(
+    scroll_top: Pixels,
+    height: Pixels,
+    line_height: Pixels,
+    line_count: usize,
+) -> Range<usize> {
+    if line_height <= Pixels::ZERO {
+        return 0..0;
+    }
+    let first = (scroll_top / line_height).max(0.).floor() as usize;
+    let count = (height / line_height).ceil() as usize + 1;
+    first.min(line_count)..(first + count).min(line_count)
+}
+
+#[cfg(test)]
+mod tests {
+    use gpui::px;
+
+    use super::*;
+
+    fn columns(glyphs: &[WhitespaceGlyph]) -> Vec<(u32, u32, bool)> {
+        glyphs
+            .iter()
+            .map(|glyph| (glyph.position.character, glyph.display_column, glyph.tab))
+            .collect()
+    }
+
+    #[test]
+    fn trailing_mode_marks_only_line_ends() {
+        let glyphs = line_glyphs(3, "fn main() {  \r\n", 4, WhitespaceRendering::Trailing);
+        assert_eq!(columns(&glyphs), vec![(11, 11, false), (12, 12, false)]);
+        assert_eq!(glyphs[0].position.line, 3);
+        assert!(line_glyphs(0, "\tlet x = 1;", 4, WhitespaceRendering::Trailing).is_empty());
+    }
+
+    #[test]
+    fn all_mode_marks_every_space_and_tab_in_utf16_columns() {
+        let glyphs = line_glyphs(0, "\t😀 x\t", 4, WhitespaceRendering::All);
+        // The emoji is two UTF-16 units but one display column
+        assert_eq!(
+            columns(&glyphs),
+            vec![(0, 0, true), (3, 5, false), (5, 7, true)]
+        );
+        assert!(line_glyphs(0, "a  b ", 4, WhitespaceRendering::None).is_empty());
+    }
+
+    #[test]
+    fn only_lines_on_screen_are_visible() {
+        assert_eq!(visible_lines(px(0.), px(100.), px(20.), 1000), 0..6);
+        assert_eq!(visible_lines(px(410.), px(100.), px(20.), 1000), 20..26);
+        assert_eq!(visible_lines(px(410.), px(100.), px(20.), 22), 20..22);
+    }
+}
@@ -0,0 +1,463 @@
+//! Conversion from `conversation_schema` items (fixture / mock data) into ACP
+//! types, so they render through the same `AcpMessageStream` as live sessions.
+
+use std::hash::{DefaultHasher, Hasher};
+use std::sync::LazyLock;
+
+use agent_client_protocol::{
+    self as acp, AudioContent, BlobResourceContents, ContentBlock, ContentChunk, Diff,
+    EmbeddedResource, EmbeddedResourceResource, ImageContent, Plan, PlanEntry, PlanEntryPriority,
+    PlanEntryStatus, ResourceLink, SessionUpdate, TextResourceContents, ToolCall, ToolCallContent,
+};
+
+use anyhow::{Context as _, Result};
+
+use crate::AgentTodoList;
+use crate::components::{ContentRenderers, TIMESTAMP_META_KEY};
+use crate::conversation_schema::{
+    ContentBlockSchema, ConversationItem, PlanEntrySchema, PlanSchema, ResourceContentsSchema,
+    ToolCallSchema,
+};
+
+/// Parse a JSON array of conversation items (e.g. `mock_conversation.json`)
+pub fn parse_conversation_items(json: &str) -> Result<Vec<ConversationItem>> {
+    serde_json::from_str(json).context("Failed to parse conversation")
+}
+
+/// Items to show for conversation `json`, with the error to show instead
+/// when it is malformed (logged here)
+pub fn load_conversation(json: &str) -> (Vec<ConversationItem>, Option<String>) {
+    match parse_conversation_items(json) {
+        Ok(items) => (items, None),
+        Err(e) => {
+            log::error!("{:#}", e);
+            (Vec::new(), Some(format!("{:#}", e)))
+        }
+    }
+}
+
+/// Fingerprint of a schema item, kept for the items on screen instead of a
+/// copy of them
+pub fn conversation_item_fingerprint(item: &ConversationItem) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    hasher.write(&serde_json::to_vec(item).unwrap_or_default());
+    hasher.finish()
+}
+
+/// The part of `items` not on screen yet when the items fingerprinted in
+/// `loaded` are, or `None` when `items` doesn't start with them and the
+/// conversation must be rebuilt
+pub fn new_conversation_items<'a>(
+    loaded: &[u64],
+    items: &'a [ConversationItem],
+) -> Option<&'a [ConversationItem]> {
+    let shown = items.get(..loaded.len())?;
+    shown
+        .iter()
+        .map(conversation_item_fingerprint)
+        .eq(loaded.iter().copied())
+        .then(|| &items[loaded.len()..])
+}
+
+/// Agent name recorded in an agent message's `_meta`, if any
+pub fn conversation_item_agent_name(item: &ConversationItem) -> Option<&str> {
+    match item {
+        ConversationItem::AgentMessage { data, .. } => data
+            .meta
+            .as_ref()
+            .and_then(|meta| meta.agent_name.as_deref()),
+        _ => None,
+    }
+}
+
+/// Renderers that check mapped blocks, shared by every conversion
+static CONTENT_RENDERERS: LazyLock<ContentRenderers> = LazyLock::new(ContentRenderers::default);
+
+/// Convert a schema content block into an ACP content block. Blocks whose
+/// data can't be used become a text placeholder, as the renderers decide.
+pub fn map_content_block(block: &ContentBlockSchema) -> ContentBlock {
+    let block = match block {
+        ContentBlockSchema::Text(text) => ContentBlock::from(text.text.clone()),
+        ContentBlockSchema::Image(image) => ContentBlock::Image(ImageContent::new(
+            image.data.clone(),
+            image.mime_type.clone(),
+        )),
+        ContentBlockSchema::Audio(audio) => ContentBlock::Audio(AudioContent::new(
+            audio.data.clone(),
+            audio.mime_type.clone(),
+        )),
+        ContentBlockSchema::ResourceLink(link) => {
+            let mut resource_link = ResourceLink::new(link.name.clone(), link.uri.clone());
+            resource_link.mime_type = link.mime_type.clone();
+            ContentBlock::ResourceLink(resource_link)
+        }
+        ContentBlockSchema::Resource(embedded) => {
+            let resource = match &embedded.resource {
+                ResourceContentsSchema::TextResourceContents(text) => {
+                    let mut contents =
+                        TextResourceContents::new(text.text.clone(), text.uri.clone());
+                    contents.mime_type = text.mime_type.clone();
+                    EmbeddedResourceResource::TextResourceContents(contents)
+                }
+                ResourceContentsSchema::BlobResourceContents(blob) => {
+                    let mut contents =
+                        BlobResourceContents::new(blob.blob.clone(), blob.uri.clone());
+                    contents.mime_type = blob.mime_type.clone();
+                    EmbeddedResourceResource::BlobResourceContents(contents)
+                }
+            };
+            ContentBlock::Resource(EmbeddedResource::new(resource))
+        }
+    };
+    CONTENT_RENDERERS.validated(block)
+}
+
+/// Convert a schema plan entry into an ACP plan entry. An unknown priority
+/// or status is logged and shown as medium or pending.
+pub fn map_plan_entry(entry: &PlanEntrySchema) -> PlanEntry {
+    let priority = parse_plan_priority(&entry.priority).unwrap_or_else(|e| {
+        log::warn!("{:#}", e);
+        PlanEntryPriority::Medium
+    });
+    let status = parse_plan_status(&entry.status).unwrap_or_else(|e| {
+        log::warn!("{:#}", e);
+        PlanEntryStatus::Pending
+    });
+
+    PlanEntry::new(entry.content.clone(), priority, status)
+}
+
+/// Parse a plan entry priority, ignoring case
+pub fn parse_plan_priority(value: &str) -> Result<PlanEntryPriority> {
+    match normalize_variant(value).as_str() {
+        "high" => Ok(PlanEntryPriority::High),
+        "medium" => Ok(PlanEntryPriority::Medium),
+        "low" => Ok(PlanEntryPriority::Low),
+        _ => anyhow::bail!("Unknown plan entry priority {:?}", value),
+    }
+}
+
+/// Parse a plan entry status, ignoring case and accepting `in-progress`
+/// and `inprogress` for `in_progress`
+pub fn parse_plan_status(value: &str) -> Result<PlanEntryStatus> {
+    match normalize_variant(value).as_str() {
+        "pending" => Ok(PlanEntryStatus::Pending),
+        "in_progress" | "inprogress" => Ok(PlanEntryStatus::InProgress),
+        "completed" => Ok(PlanEntryStatus::Completed),
+        _ => anyhow::bail!("Unknown plan entry status {:?}", value),
+    }
+}
+
+/// `value` trimmed and lowercased, with `-` and spaces as `_`
+fn normalize_variant(value: &str) -> String {
+    value.trim().to_lowercase().replace(['-', ' '], "_")
+}
+
+/// Convert a schema plan into an ACP plan, keeping its `_meta` (title, etc.)
+pub fn map_plan(plan: &PlanSchema) -> Plan {
+    let mut result = Plan::new(plan.entries.iter().map(map_plan_entry).collect());
+    result.meta = plan
+        .meta
+        .as_ref()
+        .and_then(|meta| meta.as_object().cloned());
+    result
+}
+
+/// Build a titled todo list from an `AgentTodoList` schema item
+pub fn map_agent_todo_list(title: &str, entries: &[PlanEntrySchema]) -> AgentTodoList {
+    AgentTodoList::new()
+        .title(title)
+        .entries(entries.iter().map(map_plan_entry).collect())
+}
+
+/// Convert a schema tool call into an ACP tool call
+pub fn map_tool_call(tool_call: &ToolCallSchema) -> ToolCall {
+    let mut result = ToolCall::new(tool_call.tool_call_id.clone(), tool_call.title.clone());
+    if let Some(kind) = tool_call.kind.as_deref().and_then(parse_snake_case) {
+        result.kind = kind;
+    }
+    if let Some(status) = tool_call.status.as_deref().and_then(parse_snake_case) {
+        result.status = status;
+    }
+    result.content = tool_call
+        .content
+        .iter()
+        .map(|item| match &item.diff {
+            Some(diff) => ToolCallContent::Diff(
+                Diff::new(diff.path.clone(), diff.new_text.clone()).old_text(diff.old_text.clone()),
+            ),
+            None => {
+                ToolCallContent::Content(acp::Content::new(ContentBlock::from(item.text.clone())))
+            }
+        })
+        .collect();
+    result
+}
+
+/// Convert a conversation item into the session updates that render it
+pub fn conversation_item_to_updates(item: &ConversationItem) -> Vec<SessionUpdate> {
+    match item {
+        ConversationItem::UserMessage { data, .. } => data
+            .prompt
+            .iter()
+            .map(|block| {
+                SessionUpdate::UserMessageChunk(ContentChunk::new(map_content_block(block)))
+            })
+            .collect(),
+        ConversationItem::AgentMessage { data, .. } => data
+            .chunks
+            .iter()
+            .map(|chunk| {
+                SessionUpdate::AgentMessageChunk(ContentChunk::new(map_content_block(
+                    &chunk.content,
+                )))
+            })
+            .collect(),
+        ConversationItem::Plan(plan) => vec![SessionUpdate::Plan(map_plan(plan))],
+        ConversationItem::AgentTodoList { title, entries } => vec![SessionUpdate::Plan(
+            map_agent_todo_list(title, entries).into_plan(),
+        )],
+        ConversationItem::ToolCallGroup { items } => items
+            .iter()
+            .map(|item| SessionUpdate::ToolCall(map_tool_call(&item.data)))
+            .collect(),
+    }
+}
+
+/// `update` with its persisted `timestamp` (RFC 3339) recorded in the
+/// message chunk's `_meta`, so history shows when each message was sent.
+/// Other updates and chunks that already carry a time are left as they are.
+pub fn stamp_message_time(mut update: SessionUpdate, timestamp: &str) -> SessionUpdate {
+    match &mut update {
+        SessionUpdate::UserMessageChunk(chunk) | SessionUpdate::AgentMessageChunk(chunk) => {
+            if let Ok(time) = chrono::DateTime::parse_from_rfc3339(timestamp) {
+                chunk
+                    .meta
+                    .get_or_insert_with(Default::default)
+                    .entry(TIMESTAMP_META_KEY)
+                    .or_insert_with(|| serde_json::json!(time.timestamp_millis()));
+            }
+        }
+        _ => {}
+    }
+    update
+}
+
+/// Parse a snake_case ACP enum value (e.g. `ToolKind`, `ToolCallStatus`)
+fn parse_snake_case<T: serde::de::DeserializeOwned>(value: &str) -> Option<T> {
+    serde_json::from_value(serde_json::Value::String(value.to_string())).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PlanMeta;
+
+    const MOCK_CONVERSATION: &str = include_str!("../../fixtures/mock_conversation.json");
+
+    #[test]
+    fn titled_todo_list_keeps_its_title() {
+        let items: Vec<ConversationItem> = serde_json::from_str(MOCK_CONVERSATION).unwrap();
+        let (title, entries) = items
+            .iter()
+            .find_map(|item| match item {
+                ConversationItem::AgentTodoList { title, entries } => Some((title, entries)),
+                _ => None,
+            })
+            .expect("fixture contains a titled todo list");
+
+        let plan = map_agent_todo_list(title, entries).into_plan();
+        assert_eq!(plan.entries.len(), 3);
+        assert_eq!(plan.entries[1].status, PlanEntryStatus::InProgress);
+
+        let meta: PlanMeta =
+            serde_json::from_value(serde_json::Value::Object(plan.meta.unwrap())).unwrap();
+        assert_eq!(meta.title.as_deref(), Some("Retry policy"));
+
+        // The panel renders the item from its plan update, title included
+        let item = items
+            .iter()
+            .find(|item| matches!(item, ConversationItem::AgentTodoList { .. }))
+            .unwrap();
+        let updates = conversation_item_to_updates(item);
+        let [SessionUpdate::Plan(plan)] = updates.as_slice() else {
+            panic!("a todo list becomes one plan update");
+        };
+        let rendered = AgentTodoList::from_plan(plan.clone()).into_plan();
+        let meta: PlanMeta =
+            serde_json::from_value(serde_json::Value::Object(rendered.meta.unwrap())).unwrap();
+        assert_eq!(meta.title.as_deref(), Some("Retry policy"));
+    }
+
+    #[test]
+    fn plan_entry_spellings_are_lenient_but_checked() {
+        for spelling in ["in_progress", "In-Progress", "INPROGRESS", " in progress "] {
+            assert_eq!(
+                parse_plan_status(spelling).unwrap(),
+                PlanEntryStatus::InProgress
+            );
+        }
+        assert_eq!(
+            parse_plan_priority("HIGH").unwrap(),
+            PlanEntryPriority::High
+        );
+        assert!(parse_plan_status("complete").is_err());
+        assert!(parse_plan_priority("urgent").is_err());
+
+        // Typos still render, with the fallback values
+        let entry: PlanEntrySchema = serde_json::from_value(serde_json::json!({
+            "content": "Ship it",
+            "priority": "urgent",
+            "status": "doen"
+        }))
+        .unwrap();
+        let entry = map_plan_entry(&entry);
+        assert_eq!(entry.priority, PlanEntryPriority::Medium);
+        assert_eq!(entry.status, PlanEntryStatus::Pending);
+    }
+
+    #[test]
+    fn loading_malformed_conversation_reports_an_error() {
+        let (items, error) = load_conversation("[{\"type\": \"UserMessage\", \"id\": ");
+        assert!(items.is_empty());
+        assert!(error.unwrap().starts_with("Failed to parse conversation"));
+
+        let (items, error) = load_conversation(MOCK_CONVERSATION);
+        assert!(!items.is_empty());
+        assert_eq!(error, None);
+    }
+
+    #[test]
+    fn only_appended_items_are_new() {
+        let items: Vec<ConversationItem> = serde_json::from_str(MOCK_CONVERSATION).unwrap();
+        assert!(items.len() > 2);
+
+        let loaded: Vec<u64> = items.iter().map(conversation_item_fingerprint).collect();
+        let added = new_conversation_items(&loaded[..2], &items).unwrap();
+        assert_eq!(added, &items[2..]);
+        assert!(new_conversation_items(&loaded, &items).unwrap().is_empty());
+        assert_eq!(new_conversation_items(&[], &items).unwrap(), &items[..]);
+        assert!(new_conversation_items(&loaded[1..], &items).is_none());
+        assert!(new_conversation_items(&loaded, &items[..2]).is_none());
+    }
+
+    #[test]
+    fn every_fixture_item_produces_updates() {
+        let items: Vec<ConversationItem> = serde_json::from_str(MOCK_CONVERSATION).unwrap();
+        for item in &items {
+            assert!(!conversation_item_to_updates(item).is_empty());
+        }
+    }
+
+    #[test]
+    fn fixture_resources_keep_text_and_blob_apart() {
+        use agentx_acp_ui::{ResourceKind, get_resource_info};
+
+        let items: Vec<ConversationItem> = serde_json::from_str(MOCK_CONVERSATION).unwrap();
+        let kinds: Vec<ResourceKind> = items
+            .iter()
+            .filter_map(|item| match item {
+                ConversationItem::UserMessage { data, .. } => Some(data),
+                _ => None,
+            })
+            .flat_map(|data| data.prompt.iter().map(map_content_block))
+            .filter_map(|block| get_resource_info(&block))
+            .map(|info| {
+                if info.kind == ResourceKind::Blob {
+                    assert!(info.text.is_none(), "blob rendered as text");
+                }
+                info.kind
+            })
+            .collect();
+
+        assert!(kinds.contains(&ResourceKind::Text));
+        assert!(kinds.contains(&ResourceKind::Blob));
+    }
+
+    #[test]
+    fn tool_call_diffs_become_diff_content() {
+        let tool_call: ToolCallSchema = serde_json::from_value(serde_json::json!({
+            "toolCallId": "call-1",
+            "title": "Edit lib.rs",
+            "status": "completed",
+            "content": [
+                { "text": "Edited" },
+                { "diff": { "path": "src/lib.rs", "newText": "fn main() {}" } }
+            ]
+        }))
+        .unwrap();
+        let mapped = map_tool_call(&tool_call);
+        assert!(matches!(&mapped.content[0], ToolCallContent::Content(_)));
+        assert!(matches!(
+            &mapped.content[1],
+            ToolCallContent::Diff(diff) if diff.old_text.is_none() && diff.new_text == "fn main() {}"
+        ));
+    }
+
+    #[test]
+    fn audio_blocks_become_audio_content() {
+        let audio: ContentBlockSchema = serde_json::from_value(serde_json::json!({
+            "type": "audio",
+            "data": "UklGRg==",
+            "mimeType": "audio/wav"
+        }))
+        .unwrap();
+        assert!(matches!(
+            map_content_block(&audio),
+            ContentBlock::Audio(audio) if audio.data == "UklGRg==" && audio.mime_type == "audio/wav"
+        ));
+    }
+
+    #[test]
+    fn invalid_base64_falls_back_to_text() {
+        let image: ContentBlockSchema = serde_json::from_value(serde_json::json!({
+            "type": "image",
+            "data": "not*base64",
+            "mimeType": "image/png"
+        }))
+        .unwrap();
+        assert!(matches!(
+            map_content_block(&image),
+            ContentBlock::Text(text) if text.text == "[invalid image data]"
+        ));
+
+        let blob: ContentBlockSchema = serde_json::from_value(serde_json::json!({
+            "type": "resource",
+            "resource": {
+                "type": "blob_resource_contents",
+                "uri": "file:///logo.png",
+                "blob": "iVBORw0KGgo",
+                "mimeType": "image/png"
+            }
+        }))
+        .unwrap();
+        assert!(matches!(map_content_block(&blob), ContentBlock::Text(_)));
+    }
+
+    #[test]
+    fn persisted_times_are_stamped_on_message_chunks() {
+        let chunk = SessionUpdate::AgentMessageChunk(ContentChunk::new(ContentBlock::from(
+            "hi".to_string(),
+        )));
+        let SessionUpdate::AgentMessageChunk(stamped) =
+            stamp_message_time(chunk, "2024-05-01T12:00:00+00:00")
+        else {
+            panic!("expected an agent message chunk");
+        };
+        let meta = stamped.meta.as_ref().unwrap();
+        assert_eq!(
+            meta[TIMESTAMP_META_KEY],
+            serde_json::json!(1_714_564_800_000i64)
+        );
+
+        // An unparseable time leaves the chunk alone
+        let chunk = SessionUpdate::UserMessageChunk(ContentChunk::new(ContentBlock::from(
+            "hi".to_string(),
+        )));
+        let SessionUpdate::UserMessageChunk(unstamped) = stamp_message_time(chunk, "yesterday")
+        else {
+            panic!("expected a user message chunk");
+        };
+        assert!(unstamped.meta.is_none());
+    }
+}
@@ -0,0 +1,57 @@
+//! Links to one item of a conversation: `agentx://session/<session id>#<anchor>`,
+//! where the anchor is the item's (see `AcpMessageStream::anchors`).
+
+const DEEP_LINK_PREFIX: &str = "agentx://session/";
+
+/// A session and one of its items
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DeepLink {
+    pub session_id: String,
+    pub anchor: String,
+}
+
+impl DeepLink {
+    pub fn new(session_id: impl Into<String>, anchor: impl Into<String>) -> Self {
+        Self {
+            session_id: session_id.into(),
+            anchor: anchor.into(),
+        }
+    }
+
+    /// Parse a link made by `to_string`; `None` for anything else
+    pub fn parse(link: &str) -> Option<Self> {
+        let (session_id, anchor) = link
+            .trim()
+            .strip_prefix(DEEP_LINK_PREFIX)?
+            .split_once('#')?;
+        if session_id.is_empty() || anchor.is_empty() {
+            return None;
+        }
+        Some(Self::new(session_id, anchor))
+    }
+}
+
+impl std::fmt::Display for DeepLink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}{}#{}", DEEP_LINK_PREFIX, self.session_id, self.anchor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn links_round_trip() {
+        let link = DeepLink::new("sess-42", "tool-call-call_7");
+        assert_eq!(
+            link.to_string(),
+            "agentx://session/sess-42#tool-call-call_7"
+        );
+        assert_eq!(DeepLink::parse(&link.to_string()), Some(link));
+
+        assert_eq!(DeepLink::parse("agentx://session/sess-42"), None);
+        assert_eq!(DeepLink::parse("agentx://session/#agent-1"), None);
+        assert_eq!(DeepLink::parse("https://example.com/#agent-1"), None);
+    }
+}
@@ -0,0 +1,169 @@
+//! Writing agent-proposed edits to disk when the user applies or reverts them
+//! from the conversation.
+
+use std::io;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Result, anyhow};
+use gpui::{App, Task};
+
+use crate::{DiffApplyOutcome, DiffApplyRequested};
+
+/// How a file on disk relates to an edit that is about to be applied
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum DiskState {
+    /// Still holds the text the edit started from
+    Unchanged,
+    /// Already holds the edited text (e.g. the agent wrote it itself), or is
+    /// already gone when the edit deletes it
+    AlreadyApplied,
+    /// Changed by something else since the agent produced the edit
+    Conflict,
+}
+
+/// Compare the current file contents (`None` if missing) with an edit
+pub(super) fn disk_state(current: Option<&str>, change: &DiffApplyRequested) -> DiskState {
+    if current == change.new_text.as_deref() {
+        DiskState::AlreadyApplied
+    } else if current == change.old_text.as_deref() {
+        DiskState::Unchanged
+    } else {
+        DiskState::Conflict
+    }
+}
+
+/// Apply one file of an "Apply all" batch, refusing to overwrite files that
+/// changed on disk since the agent's edit
+pub(super) fn apply_change(path: &Path, change: &DiffApplyRequested) -> DiffApplyOutcome {
+    let current = match std::fs::read_to_string(path) {
+        Ok(text) => Some(text),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+        Err(e) => return DiffApplyOutcome::Failed(e.to_string()),
+    };
+
+    match disk_state(current.as_deref(), change) {
+        DiskState::AlreadyApplied => DiffApplyOutcome::Applied,
+        DiskState::Conflict => {
+            log::warn!(
+                "Not applying diff to {}: file changed on disk",
+                path.display()
+            );
+            DiffApplyOutcome::Conflict
+        }
+        DiskState::Unchanged => match write_text(path, change.new_text.as_deref()) {
+            Ok(()) => DiffApplyOutcome::Applied,
+            Err(e) => DiffApplyOutcome::Failed(e.to_string()),
+        },
+    }
+}
+
+/// Apply `change` to `path` off the main thread, with the same on-disk check as
+/// "Apply all". Resolves once the file is written, or with an error if it changed
+/// since the edit. A revert passes the edit with its old and new text swapped.
+pub(super) fn write_diff_text(
+    path: PathBuf,
+    change: DiffApplyRequested,
+    cx: &mut App,
+) -> Task<Result<()>> {
+    cx.background_spawn(async move { write_change(&path, &change) })
+}
+
+fn write_change(path: &Path, change: &DiffApplyRequested) -> Result<()> {
+    match apply_change(path, change) {
+        DiffApplyOutcome::Applied => {
+            log::info!("Wrote diff to {}", path.display());
+            Ok(())
+        }
+        DiffApplyOutcome::Conflict => {
+            Err(anyhow!("{} changed on disk since the edit", path.display()))
+        }
+        DiffApplyOutcome::Failed(e) => Err(anyhow!("Failed to write {}: {}", path.display(), e)),
+    }
+}
+
+/// Resolve a diff path against the session's working directory
+pub(super) fn resolve_diff_path(working_directory: Option<&str>, path: &Path) -> PathBuf {
+    match working_directory {
+        Some(working_directory) if path.is_relative() => Path::new(working_directory).join(path),
+        _ => path.to_path_buf(),
+    }
+}
+
+fn write_text(path: &Path, text: Option<&str>) -> io::Result<()> {
+    match text {
+        Some(text) => {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(path, text)
+        }
+        None => std::fs::remove_file(path),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn change(old_text: Option<&str>, new_text: Option<&str>) -> DiffApplyRequested {
+        DiffApplyRequested {
+            path: PathBuf::from("src/lib.rs"),
+            old_text: old_text.map(str::to_string),
+            new_text: new_text.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn edited_files_are_conflicts() {
+        let edit = change(Some("a\n"), Some("a\nb\n"));
+        assert_eq!(disk_state(Some("a\n"), &edit), DiskState::Unchanged);
+        assert_eq!(disk_state(Some("a\nb\n"), &edit), DiskState::AlreadyApplied);
+        assert_eq!(disk_state(Some("a\nc\n"), &edit), DiskState::Conflict);
+        assert_eq!(disk_state(None, &edit), DiskState::Conflict);
+
+        let created = change(None, Some("fn main() {}\n"));
+        assert_eq!(disk_state(None, &created), DiskState::Unchanged);
+        assert_eq!(disk_state(Some(""), &created), DiskState::Conflict);
+
+        let deleted = change(Some("a\n"), None);
+        assert_eq!(disk_state(Some("a\n"), &deleted), DiskState::Unchanged);
+        assert_eq!(disk_state(None, &deleted), DiskState::AlreadyApplied);
+        assert_eq!(disk_state(Some(""), &deleted), DiskState::Conflict);
+    }
+
+    #[test]
+    fn deletions_remove_the_file() {
+        let dir = std::env::temp_dir().join(format!("agentx-diff-writes-{}", std::process::id()));
+        let path = dir.join("old.rs");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(&path, "a\n").unwrap();
+
+        let deleted = change(Some("a\n"), None);
+        let first = apply_change(&path, &deleted);
+        let existed = path.exists();
+        let again = apply_change(&path, &deleted);
+        std::fs::remove_dir_all(&dir).ok();
+
+        assert_eq!(first, DiffApplyOutcome::Applied);
+        assert!(!existed);
+        assert_eq!(again, DiffApplyOutcome::Applied);
+    }
+
+    #[test]
+    fn fragment_diffs_on_longer_files_are_refused() {
+        let dir = std::env::temp_dir().join(format!("agentx-diff-fragment-{}", std::process::id()));
+        let path = dir.join("lib.rs");
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(&path, "fn a() {}\nfn b() {}\n").unwrap();
+
+        let fragment = change(Some("fn a() {}\n"), Some("fn a() { 1 }\n"));
+        let applied = write_change(&path, &fragment);
+        let reverted = write_change(&path, &change(Some("fn a() { 1 }\n"), Some("fn a() {}\n")));
+        let contents = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_dir_all(&dir).ok();
+
+        assert!(applied.is_err());
+        assert!(reverted.is_err());
+        assert_eq!(contents, "fn a() {}\nfn b() {}\n");
+    }
+}
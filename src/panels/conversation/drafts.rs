@@ -0,0 +1,137 @@
+//! Unsent chat input text, kept so it survives closing and reopening the
+//! panel. One file per session in `<user_data_dir>/drafts/<id>.txt`.
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, PoisonError};
+
+use anyhow::{Context as _, Result};
+
+use super::saved_sessions::file_name_for_id;
+use crate::components::truncate_chars;
+
+/// Characters of a draft kept on disk; the rest is dropped
+pub const MAX_DRAFT_CHARS: usize = 20_000;
+
+/// File holding the draft for `key` (a session id) in `dir`
+pub fn draft_path(dir: &Path, key: &str) -> PathBuf {
+    dir.join(format!("{}.txt", file_name_for_id(key)))
+}
+
+/// The stored draft at `path`, if there is a non-blank one
+pub fn load_draft(path: &Path) -> Option<String> {
+    let text = std::fs::read_to_string(path).ok()?;
+    (!text.trim().is_empty()).then_some(text)
+}
+
+/// Store `text` at `path`, capped at [`MAX_DRAFT_CHARS`]; blank text removes
+/// the draft instead
+pub fn save_draft(path: &Path, text: &str) -> Result<()> {
+    if text.trim().is_empty() {
+        return clear_draft(path);
+    }
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {:?}", parent))?;
+    }
+    let (text, _) = truncate_chars(text, MAX_DRAFT_CHARS);
+    std::fs::write(path, text).with_context(|| format!("Failed to write draft: {:?}", path))
+}
+
+/// Remove the draft at `path`; a missing one is already cleared
+pub fn clear_draft(path: &Path) -> Result<()> {
+    match std::fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+            Err(e).with_context(|| format!("Failed to delete draft: {:?}", path))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Orders the draft writes of one panel. Each save or clear starts a new
+/// generation; a save still waiting to write when a newer one started is
+/// skipped, and one already writing finishes before the newer one runs.
+#[derive(Clone, Debug, Default)]
+pub struct DraftGenerations(Arc<Mutex<u64>>);
+
+impl DraftGenerations {
+    /// Start a generation for a save that writes later
+    pub fn next(&self) -> u64 {
+        let mut latest = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        *latest += 1;
+        *latest
+    }
+
+    /// Run `write` for `generation` unless a newer one has started
+    pub fn write_if_latest(
+        &self,
+        generation: u64,
+        write: impl FnOnce() -> Result<()>,
+    ) -> Result<()> {
+        let latest = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        if *latest != generation {
+            return Ok(());
+        }
+        write()
+    }
+
+    /// Run `write` as a new generation, after any write in flight
+    pub fn write_now(&self, write: impl FnOnce() -> Result<()>) -> Result<()> {
+        let mut latest = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+        *latest += 1;
+        write()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn drafts_round_trip_capped_and_cleared() {
+        let dir = std::env::temp_dir().join(format!("agentx-drafts-{}", std::process::id()));
+        let path = draft_path(&dir, "sess/1");
+        assert_eq!(path.file_name().unwrap(), "sess_1.txt");
+        assert_eq!(load_draft(&path), None);
+
+        save_draft(&path, "half a thought").unwrap();
+        let saved = load_draft(&path);
+        save_draft(&path, &"é".repeat(MAX_DRAFT_CHARS + 10)).unwrap();
+        let capped = load_draft(&path).unwrap();
+        save_draft(&path, "  \n").unwrap();
+        let blanked = path.exists();
+        save_draft(&path, "again").unwrap();
+        clear_draft(&path).unwrap();
+        clear_draft(&path).unwrap();
+        let cleared = load_draft(&path);
+        std::fs::remove_dir_all(&dir).ok();
+
+        assert_eq!(saved.as_deref(), Some("half a thought"));
+        assert_eq!(capped.chars().count(), MAX_DRAFT_CHARS);
+        assert!(!blanked);
+        assert_eq!(cleared, None);
+    }
+
+    #[test]
+    fn a_save_started_before_a_clear_is_skipped() {
+        let dir = std::env::temp_dir().join(format!("agentx-drafts-gen-{}", std::process::id()));
+        let path = draft_path(&dir, "sess");
+        let generations = DraftGenerations::default();
+
+        let stale = generations.next();
+        generations.write_now(|| clear_draft(&path)).unwrap();
+        generations
+            .write_if_latest(stale, || save_draft(&path, "sent already"))
+            .unwrap();
+        let after_stale_save = load_draft(&path);
+
+        let latest = generations.next();
+        generations
+            .write_if_latest(latest, || save_draft(&path, "still typing"))
+            .unwrap();
+        let after_latest_save = load_draft(&path);
+        std::fs::remove_dir_all(&dir).ok();
+
+        assert_eq!(after_stale_save, None);
+        assert_eq!(after_latest_save.as_deref(), Some("still typing"));
+    }
+}
@@ -1,3 +1,12 @@
+mod conversion;
+mod deep_link;
+mod diff_writes;
+mod drafts;
 mod panel;
+mod panel_intents;
+pub(crate) mod saved_sessions;
+pub(crate) mod session_search;
 
-pub use panel::ConversationPanel;
+pub use deep_link::DeepLink;
+pub use panel::{CancelRequested, ConversationPanel};
+pub use panel_intents::PanelIntent;
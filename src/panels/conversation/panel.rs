@@ -1,25 +1,70 @@
 use gpui::{
-    App, ClipboardEntry, Context, Entity, FocusHandle, Focusable, IntoElement, ParentElement,
-    Render, ScrollHandle, SharedString, Styled, Window, div, prelude::*, px,
+    App, ClipboardEntry, ClipboardItem, Context, Entity, EventEmitter, FocusHandle, Focusable,
+    IntoElement, KeyDownEvent, MouseButton, ParentElement, Render, ScrollHandle, SharedString,
+    Styled, Subscription, Task, Window, div, prelude::*, px,
 };
 
 use gpui_component::{
-    ActiveTheme, Icon, IconName, Sizable, StyledExt, h_flex, input::InputState, skeleton::Skeleton,
-    spinner::Spinner, v_flex,
+    ActiveTheme, Icon, IconName, Sizable, StyledExt,
+    button::{Button, ButtonVariants},
+    h_flex,
+    input::{Input, InputEvent, InputState},
+    menu::{DropdownMenu as _, PopupMenuItem},
+    scroll::{ScrollableElement as _, ScrollbarAxis},
+    skeleton::Skeleton,
+    spinner::Spinner,
+    v_flex,
 };
 
 // Use the published ACP schema crate
-use agent_client_protocol::{ImageContent, PlanEntryStatus, RequestPermissionResponse, ToolCall};
+use agent_client_protocol::{
+    AvailableCommand, ContentBlock, ImageContent, PlanEntryStatus, RequestPermissionResponse,
+    SessionUpdate, ToolCall, ToolCallContent, ToolKind,
+};
+use anyhow::{Result, anyhow};
 use chrono::{DateTime, Utc};
 use rust_i18n::t;
 use smol::Timer;
-use std::{sync::Arc, time::Duration};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
 
-use crate::assets::get_agent_icon;
+use super::conversion::{
+    conversation_item_agent_name, conversation_item_fingerprint, conversation_item_to_updates,
+    load_conversation, new_conversation_items, stamp_message_time,
+};
+use super::deep_link::DeepLink;
+use super::diff_writes::{apply_change, resolve_diff_path, write_diff_text};
+use super::drafts::{DraftGenerations, clear_draft, draft_path, load_draft, save_draft};
+use super::panel_intents::{PanelIntent, ToolCallTracker};
+use super::saved_sessions::{
+    SavedSession, SavedSessionMeta, list_saved_sessions, push_transcript_update,
+    saved_session_path, session_title,
+};
+use crate::assets::find_agent_icon;
+use crate::conversation_schema::ConversationItem;
+use crate::core::config::ScrollAxisPreference;
+use crate::core::event_bus::{SessionErrorEvent, SessionErrorKind, SessionRetryEvent};
+use crate::utils::time::format_time_friendly;
 use crate::{
-    AcpMessageStream, AcpMessageStreamOptions, AppState, ChatInputBox, DiffSummaryOptions,
-    PanelAction, PermissionRequestOptions, SendMessageToSession, ToolCallItemOptions,
-    app::actions::AddCodeSelection, core::services::SessionStatus, panels::dock_panel::DockPanel,
+    AcpMessageStream, AcpMessageStreamOptions, AppState, ChatInputBox, DiffApplyProgress,
+    DiffApplyRequested, DiffBatchApplyRequested, DiffSummary, DiffSummaryData, DiffSummaryOptions,
+    PanelAction, PermissionRequestOptions, RetryPrompt, SendMessageToSession, ToolCallItemOptions,
+    app::actions::AddCodeSelection,
+    components::{
+        ChatSubmit, ContentRenderers, DEFAULT_INPUT_HISTORY_SIZE, ErrorKind, FileItem,
+        FilePickerDelegate, InputHistory, InputSize, complete_mention, matching_commands,
+        mention_query, slash_command_query, truncate_large_tool_output,
+    },
+    core::{
+        config_manager::{get_drafts_dir, get_sessions_dir},
+        services::SessionStatus,
+    },
+    mode_display_name,
+    panels::dock_panel::DockPanel,
+    scaled,
 };
 
 /// Session status information for display
@@ -31,17 +76,67 @@ pub struct SessionStatusInfo {
     pub message_count: usize,
 }
 
+/// Emitted when the user asks to stop the agent's current generation
+#[derive(Clone, Debug)]
+pub struct CancelRequested {
+    pub session_id: Option<String>,
+}
+
 /// Conversation panel that displays SessionUpdate messages from ACP
 pub struct ConversationPanel {
     focus_handle: FocusHandle,
     /// ACP message stream UI
     message_stream: Entity<AcpMessageStream>,
+    /// Session-wide file change summary pinned above the transcript
+    diff_summary: Entity<DiffSummary>,
+    /// Tool call updates not yet folded into `diff_summary`
+    pending_diff_updates: Vec<SessionUpdate>,
+    /// Live tool calls until they complete, to emit their `PanelIntent`
+    tool_call_tracker: ToolCallTracker,
+    /// Every update shown so far, in order, written out by `save_session`
+    transcript: Vec<SessionUpdate>,
+    /// Fingerprints of the schema items shown so far, so `set_items` only
+    /// adds the new ones
+    item_fingerprints: Vec<u64>,
+    /// Sessions in the sessions directory, offered in the "Saved" menu
+    saved_sessions: Vec<(PathBuf, SavedSessionMeta)>,
+    /// Commands from the latest `AvailableCommandsUpdate`, offered in the `/` palette
+    available_commands: Vec<AvailableCommand>,
+    /// Commands matching the `/` prefix currently typed
+    command_suggestions: Vec<AvailableCommand>,
+    show_command_suggestions: bool,
+    /// Workspace files offered for an `@` mention; scanned on the first one
+    /// and again once a tool call writes files or the scan gets old
+    mention_files: FilePickerDelegate,
+    /// Files matching the `@` prefix currently typed
+    file_suggestions: Vec<FileItem>,
+    _file_suggestions_task: Option<Task<()>>,
+    _mention_scan_task: Option<Task<()>>,
     /// Optional session ID to filter updates (None = all sessions)
     session_id: Option<String>,
     /// Scroll handle for auto-scrolling to bottom
     scroll_handle: ScrollHandle,
+    /// Axes the transcript scrolls on (`conversation_scroll` in config.json)
+    scroll_axis: ScrollbarAxis,
+    /// Whether the scrollbar is shown; it hides after `SCROLLBAR_HIDE_DELAY` idle
+    scrollbar_visible: bool,
+    _scrollbar_hide_task: Option<Task<()>>,
+    /// Whether the transcript follows new content: it was near the bottom
+    /// when last scrolled. Scrolled up, "Jump to latest" shows instead.
+    at_bottom: bool,
     /// Input state for the chat input box
     input_state: Entity<InputState>,
+    /// Size of the input's text, kept current by its change events
+    input_size: InputSize,
+    /// Messages sent from this panel, recalled with Up/Down in the input
+    input_history: Entity<InputHistory>,
+    /// Where the unsent input of the session shown is kept, live or loaded by
+    /// `load_session`; panels without a session keep none
+    draft_path: Option<PathBuf>,
+    /// Writes the unsent input text once typing pauses
+    _draft_save_task: Option<Task<()>>,
+    /// Keeps a save already writing from undoing a later save or clear
+    draft_generations: DraftGenerations,
     /// List of pasted images: (ImageContent, filename)
     pasted_images: Vec<(ImageContent, String)>,
     /// List of code selections from editor
@@ -52,24 +147,98 @@ pub struct ConversationPanel {
     workspace_id: Option<String>,
     workspace_name: Option<String>,
     working_directory: Option<String>,
+    /// Error from the last failed conversation load, shown instead of the empty state
+    load_error: Option<String>,
+    /// Shows a saved conversation; there is no live session to send to
+    read_only: bool,
+    /// Blocks of the last prompt that failed, sent again by the error item's retry
+    last_failed_prompt: Option<Vec<ContentBlock>>,
+    /// Collapse tool calls as they complete; starts from config.json, toggled per panel
+    auto_collapse_completed_tools: bool,
+    /// Render agent messages and thoughts as markdown; on unless toggled off
+    markdown: bool,
+    /// Whether the transcript search bar is shown
+    show_search: bool,
+    search_input: Entity<InputState>,
+    /// Anchor of the search match last scrolled to
+    search_match: Option<String>,
+    _subscriptions: Vec<Subscription>,
 }
 
+/// Bundled demo conversation used by [`ConversationPanel::demo`]
+const MOCK_CONVERSATION: &str = include_str!("../../fixtures/mock_conversation.json");
+
 const MESSAGE_SERVICE_RETRY_DELAY_MS: u64 = 500;
 const MESSAGE_SERVICE_MAX_RETRIES: usize = 60;
 const AUTO_SCROLL_THRESHOLD_PX: f32 = 120.0;
+/// How long the scrollbar stays visible after the last scroll or hover
+const SCROLLBAR_HIDE_DELAY: Duration = Duration::from_millis(1500);
+/// Pause in typing before the unsent input text is written to disk
+const DRAFT_SAVE_DELAY: Duration = Duration::from_millis(500);
+/// Pause in typing after an `@` before the file suggestions are refreshed
+const FILE_SUGGESTIONS_DEBOUNCE: Duration = Duration::from_millis(150);
+const MAX_FILE_SUGGESTIONS: usize = 8;
+/// Age past which the workspace files offered for `@` mentions are scanned again
+const MENTION_FILES_MAX_AGE: Duration = Duration::from_secs(30);
 
 impl ConversationPanel {
-    /// Create a new panel with mock data (for demo purposes)
+    /// Create an empty panel following updates from every session
     pub fn view(window: &mut Window, cx: &mut App) -> Entity<Self> {
         log::info!("🚀 Creating ConversationPanel view");
         let entity = cx.new(|cx| Self::new(window, cx));
         Self::subscribe_to_updates(&entity, None, cx);
         Self::subscribe_to_permissions(&entity, None, cx);
         Self::subscribe_to_code_selections(&entity, cx);
+        Self::subscribe_to_diff_writes(&entity, cx);
+        Self::subscribe_to_panel_intents(&entity, window, cx);
         log::info!("✅ ConversationPanel view created and subscribed");
         entity
     }
 
+    /// Create a panel pre-filled with the bundled demo conversation
+    pub fn demo(window: &mut Window, cx: &mut App) -> Entity<Self> {
+        cx.new(|cx| {
+            let mut panel = Self::new(window, cx);
+            panel.load_conversation_json(MOCK_CONVERSATION, cx);
+            panel
+        })
+    }
+
+    /// Create a panel showing `items`, with no session behind it. Nothing is
+    /// subscribed; pass later versions of the conversation to `set_items`.
+    pub fn view_with_items(
+        items: Vec<ConversationItem>,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Entity<Self> {
+        cx.new(|cx| Self::with_items(items, window, cx))
+    }
+
+    /// A panel showing `items`, for hosts that create the entity themselves
+    pub fn with_items(
+        items: Vec<ConversationItem>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let mut panel = Self::new(window, cx);
+        panel.set_items(items, cx);
+        panel
+    }
+
+    /// Create a panel showing `updates`, with no session behind it. Nothing is
+    /// subscribed; feed later updates (e.g. from a stream) to `append_updates`.
+    pub fn from_updates(
+        updates: impl IntoIterator<Item = SessionUpdate>,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Entity<Self> {
+        cx.new(|cx| {
+            let mut panel = Self::new(window, cx);
+            panel.append_updates(updates, cx);
+            panel
+        })
+    }
+
     /// Create a new panel for a specific session (no mock data)
     pub fn view_for_session(session_id: String, window: &mut Window, cx: &mut App) -> Entity<Self> {
         log::info!("🚀 Creating ConversationPanel for session: {}", session_id);
@@ -81,11 +250,29 @@ impl ConversationPanel {
         Self::subscribe_to_updates(&entity, Some(session_id.clone()), cx);
         Self::subscribe_to_permissions(&entity, Some(session_id.clone()), cx);
         Self::subscribe_to_code_selections(&entity, cx);
+        Self::subscribe_to_diff_writes(&entity, cx);
+        Self::subscribe_to_panel_intents(&entity, window, cx);
         Self::subscribe_to_status_updates(&entity, Some(session_id.clone()), cx);
         log::info!("✅ ConversationPanel created for session: {}", session_id);
         entity
     }
 
+    /// Create a panel showing a conversation saved to `path` (see `save_session`)
+    pub fn view_for_saved_session(
+        path: PathBuf,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Entity<Self> {
+        let entity = cx.new(|cx| {
+            let mut panel = Self::new(window, cx);
+            panel.open_saved_session(path, cx);
+            panel
+        });
+        Self::subscribe_to_code_selections(&entity, cx);
+        Self::subscribe_to_diff_writes(&entity, cx);
+        entity
+    }
+
     pub fn session_id(&self) -> Option<String> {
         self.session_id.clone()
     }
@@ -105,12 +292,12 @@ impl ConversationPanel {
         self.working_directory.clone()
     }
 
-    fn new(window: &mut Window, cx: &mut App) -> Self {
+    fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
         log::info!("🔧 Initializing ConversationPanel (new)");
         Self::new_internal(None, window, cx)
     }
 
-    fn new_for_session(session_id: String, window: &mut Window, cx: &mut App) -> Self {
+    fn new_for_session(session_id: String, window: &mut Window, cx: &mut Context<Self>) -> Self {
         log::info!(
             "🔧 Initializing ConversationPanel for session: {}",
             session_id
@@ -118,25 +305,535 @@ impl ConversationPanel {
         Self::new_internal(Some(session_id), window, cx)
     }
 
-    fn new_internal(session_id: Option<String>, window: &mut Window, cx: &mut App) -> Self {
+    fn new_internal(
+        session_id: Option<String>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> Self {
         let focus_handle = cx.focus_handle();
         let scroll_handle = ScrollHandle::new();
         let input_state = Self::create_input_state(window, cx);
-        let message_stream = Self::create_message_stream(cx);
+        let input_subscription = cx.subscribe_in(
+            &input_state,
+            window,
+            |this, _input, event: &InputEvent, _window, cx| {
+                if let InputEvent::Change = event {
+                    this.on_input_change(cx);
+                }
+            },
+        );
+        let search_input = cx.new(|cx| {
+            InputState::new(window, cx)
+                .placeholder(t!("conversation.search_placeholder").to_string())
+        });
+        let search_subscription = cx.subscribe_in(
+            &search_input,
+            window,
+            |this, input, event: &InputEvent, window, cx| match event {
+                InputEvent::Change => {
+                    let query = input.read(cx).value().to_string();
+                    // A pasted link to an item of this conversation jumps to it
+                    let opened = DeepLink::parse(&query)
+                        .is_some_and(|link| this.open_deep_link(link, window, cx));
+                    if !opened {
+                        this.set_search_query(&query, window, cx);
+                    }
+                }
+                InputEvent::PressEnter { secondary } => {
+                    this.select_search_match(if *secondary { -1 } else { 1 }, cx);
+                }
+                _ => {}
+            },
+        );
+        let auto_collapse_completed_tools = AppState::global(cx).auto_collapse_completed_tools();
+        let draft = session_id
+            .as_deref()
+            .map(|session_id| draft_path(&get_drafts_dir(), session_id));
+        let message_stream = Self::create_message_stream(auto_collapse_completed_tools, true, cx);
+        let panel = cx.entity().downgrade();
+        let diff_summary = cx.new(|_| {
+            DiffSummary::new(DiffSummaryData::default())
+                .with_options(Self::diff_summary_options())
+                .on_apply_all(Arc::new(move |request, _window, cx| {
+                    let _ = panel.update(cx, |_, cx| cx.emit(request));
+                }))
+                .collapsed(true)
+        });
 
-        Self {
+        let mut panel = Self {
             focus_handle,
             message_stream,
+            diff_summary,
+            pending_diff_updates: Vec::new(),
+            tool_call_tracker: ToolCallTracker::default(),
+            transcript: Vec::new(),
+            item_fingerprints: Vec::new(),
+            saved_sessions: Vec::new(),
+            available_commands: Vec::new(),
+            command_suggestions: Vec::new(),
+            show_command_suggestions: false,
+            mention_files: FilePickerDelegate::new(&panel_working_directory(None, cx)),
+            file_suggestions: Vec::new(),
+            _file_suggestions_task: None,
+            _mention_scan_task: None,
             session_id,
             scroll_handle,
+            at_bottom: true,
+            scroll_axis: match AppState::global(cx).conversation_scroll() {
+                ScrollAxisPreference::Vertical => ScrollbarAxis::Vertical,
+                ScrollAxisPreference::Both => ScrollbarAxis::Both,
+            },
+            scrollbar_visible: false,
+            _scrollbar_hide_task: None,
             input_state,
+            input_size: InputSize::default(),
+            input_history: cx.new(|_| InputHistory::new(DEFAULT_INPUT_HISTORY_SIZE)),
+            draft_path: draft,
+            _draft_save_task: None,
+            draft_generations: DraftGenerations::default(),
             pasted_images: Vec::new(),
             code_selections: Vec::new(),
             session_status: None,
             workspace_id: None,
             workspace_name: None,
             working_directory: None,
+            load_error: None,
+            read_only: false,
+            last_failed_prompt: None,
+            auto_collapse_completed_tools,
+            markdown: true,
+            show_search: false,
+            search_input,
+            search_match: None,
+            _subscriptions: vec![input_subscription, search_subscription],
+        };
+        panel.refresh_saved_sessions(cx);
+        panel.restore_draft(window, cx);
+        cx.on_release(|this, cx| this.flush_draft(cx)).detach();
+        panel
+    }
+
+    /// Load a conversation from JSON (see `conversation_schema::ConversationItem`).
+    /// Malformed input is logged and leaves the panel empty with a visible error.
+    pub fn load_conversation_json(&mut self, json: &str, cx: &mut Context<Self>) {
+        let (items, load_error) = load_conversation(json);
+        self.load_error = load_error;
+        self.set_items(items, cx);
+        cx.notify();
+    }
+
+    /// Show `items`. When they extend the items already shown only the new ones
+    /// are added, so existing views (e.g. expanded tool calls) keep their state;
+    /// otherwise the conversation is rebuilt.
+    pub fn set_items(&mut self, items: Vec<ConversationItem>, cx: &mut Context<Self>) {
+        match new_conversation_items(&self.item_fingerprints, &items) {
+            Some([]) => return,
+            Some(added) => self.load_conversation_items(added, cx),
+            None => {
+                self.reset_conversation(cx);
+                self.load_conversation_items(&items, cx);
+            }
+        }
+        self.item_fingerprints = items.iter().map(conversation_item_fingerprint).collect();
+    }
+
+    /// Show `updates` after what is already shown
+    pub fn append_updates(
+        &mut self,
+        updates: impl IntoIterator<Item = SessionUpdate>,
+        cx: &mut Context<Self>,
+    ) {
+        let session_id = self.session_id.clone();
+        for update in updates {
+            self.process_update(update, session_id.as_deref(), None, cx);
+        }
+        self.refresh_diff_summary(cx);
+        cx.notify();
+    }
+
+    fn load_conversation_items(&mut self, items: &[ConversationItem], cx: &mut Context<Self>) {
+        let session_id = self.session_id.clone();
+        for item in items {
+            let agent_name = conversation_item_agent_name(item);
+            for update in conversation_item_to_updates(item) {
+                self.process_update(update, session_id.as_deref(), agent_name, cx);
+            }
+        }
+        self.refresh_diff_summary(cx);
+        cx.notify();
+    }
+
+    /// Forward an update to the message stream, keeping tool calls for the diff
+    /// summary and commands for the `/` palette
+    fn process_update(
+        &mut self,
+        update: SessionUpdate,
+        session_id: Option<&str>,
+        agent_name: Option<&str>,
+        cx: &mut Context<Self>,
+    ) {
+        // The stream moves oversized tool output to disk; the copies kept here
+        // only hold its preview
+        let mut kept = update.clone();
+        truncate_large_tool_output(&mut kept, AppState::global(cx).max_inline_content_bytes());
+        match &update {
+            SessionUpdate::ToolCall(_) | SessionUpdate::ToolCallUpdate(_) => {
+                self.pending_diff_updates.push(kept.clone());
+                if writes_files(&update) {
+                    self.mention_files.mark_stale();
+                }
+            }
+            SessionUpdate::AvailableCommandsUpdate(commands_update) => {
+                self.available_commands = commands_update.available_commands.clone();
+            }
+            _ => {}
+        }
+        push_transcript_update(&mut self.transcript, kept);
+        self.message_stream.update(cx, |stream, cx| {
+            stream.process_update(update, session_id, agent_name, cx);
+        });
+    }
+
+    /// Follow a live update's tool call and, if it just completed, emit the
+    /// panel it points at (unless `auto_open_panels` is off)
+    fn emit_panel_intent(&mut self, update: &SessionUpdate, cx: &mut Context<Self>) {
+        if let Some(intent) = self.tool_call_tracker.track(update)
+            && AppState::global(cx).auto_open_panels()
+        {
+            cx.emit(intent);
+        }
+    }
+
+    /// Hand command output carried by a live update to the terminal output panel
+    fn record_terminal_output(&self, update: &SessionUpdate, cx: &mut Context<Self>) {
+        let outputs = AppState::global(cx).terminal_outputs.clone();
+        outputs.update(cx, |outputs, cx| {
+            if outputs.record(update) {
+                cx.notify();
+            }
+        });
+    }
+
+    /// Save everything shown in the panel to `path`, with its title and agent
+    pub fn save_session(&mut self, path: PathBuf, cx: &mut Context<Self>) -> Task<Result<()>> {
+        // Panels without a session (e.g. demo data) are named after the file
+        let id = self.session_id.clone().unwrap_or_else(|| {
+            path.file_stem()
+                .map(|stem| stem.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        });
+        let agent_name = self
+            .session_status
+            .as_ref()
+            .map(|status| status.agent_name.clone())
+            .or_else(|| {
+                let agent_service = AppState::global(cx).agent_service()?;
+                agent_service.get_agent_for_session(&id)
+            });
+        let session = SavedSession::new(
+            id,
+            session_title(&self.transcript),
+            agent_name,
+            self.transcript.clone(),
+        );
+
+        cx.spawn(async move |this, cx| {
+            cx.background_spawn(async move { session.save_to(&path) })
+                .await?;
+            this.update(cx, |this, cx| this.refresh_saved_sessions(cx))?;
+            Ok(())
+        })
+    }
+
+    /// Show a saved session, read-only. Only for panels without a live
+    /// session, whose updates would otherwise mix into it. Updates this build
+    /// doesn't recognize are skipped.
+    pub fn load_session(&mut self, path: PathBuf, cx: &mut Context<Self>) -> Task<Result<()>> {
+        if let Some(session_id) = &self.session_id {
+            return Task::ready(Err(anyhow!(
+                "Panel is showing live session {}; open saved sessions in a panel of their own",
+                session_id
+            )));
         }
+        cx.spawn(async move |this, cx| {
+            let session = cx
+                .background_spawn(async move { SavedSession::load_from(&path) })
+                .await?;
+            this.update(cx, |this, cx| {
+                log::info!(
+                    "Opening saved session {} ({} updates)",
+                    session.meta.id,
+                    session.updates.len()
+                );
+                this.reset_conversation(cx);
+                this.read_only = true;
+                this.draft_path = Some(draft_path(&get_drafts_dir(), &session.meta.id));
+                for update in session.updates {
+                    this.process_update(
+                        update,
+                        Some(session.meta.id.as_str()),
+                        session.meta.agent_name.as_deref(),
+                        cx,
+                    );
+                }
+                this.refresh_diff_summary(cx);
+                this.scroll_to_bottom(cx);
+            })
+        })
+    }
+
+    /// Save to `<sessions_dir>/<id>.json`, logging failures
+    fn save_to_sessions_dir(&mut self, cx: &mut Context<Self>) {
+        let id = self
+            .session_id
+            .clone()
+            .unwrap_or_else(|| format!("conversation-{}", Utc::now().format("%Y%m%d-%H%M%S")));
+        let path = saved_session_path(&get_sessions_dir(), &id);
+        let task = self.save_session(path, cx);
+        cx.spawn(async move |_, _| {
+            if let Err(e) = task.await {
+                log::error!("Failed to save session: {:#}", e);
+            }
+        })
+        .detach();
+    }
+
+    fn open_saved_session(&mut self, path: PathBuf, cx: &mut Context<Self>) {
+        let task = self.load_session(path, cx);
+        cx.spawn(async move |this, cx| {
+            if let Err(e) = task.await {
+                log::error!("Failed to open saved session: {:#}", e);
+                let _ = this.update(cx, |this, cx| {
+                    this.load_error = Some(format!("{:#}", e));
+                    cx.notify();
+                });
+            }
+        })
+        .detach();
+    }
+
+    /// Re-read the saved sessions list off the main thread
+    fn refresh_saved_sessions(&mut self, cx: &mut Context<Self>) {
+        cx.spawn(async move |this, cx| {
+            let sessions = cx
+                .background_spawn(async move { list_saved_sessions(&get_sessions_dir()) })
+                .await;
+            let _ = this.update(cx, |this, cx| {
+                this.saved_sessions = sessions;
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    /// Drop everything shown so far, keeping the session id and input
+    fn reset_conversation(&mut self, cx: &mut Context<Self>) {
+        self.message_stream =
+            Self::create_message_stream(self.auto_collapse_completed_tools, self.markdown, cx);
+        self.pending_diff_updates.clear();
+        self.tool_call_tracker = ToolCallTracker::default();
+        self.transcript.clear();
+        self.item_fingerprints.clear();
+        self.available_commands.clear();
+        self.load_error = None;
+        self.last_failed_prompt = None;
+        self.diff_summary.update(cx, |summary, cx| {
+            summary.update_data(DiffSummaryData::default(), cx);
+        });
+    }
+
+    /// Show the agent's commands matching a `/` prefix in the input, the
+    /// workspace files matching an `@` mention, and the size of the text
+    fn on_input_change(&mut self, cx: &mut Context<Self>) {
+        let value = self.input_state.read(cx).value();
+        let size = InputSize::of(&value);
+        if size != self.input_size {
+            self.input_size = size;
+            cx.notify();
+        }
+        let suggestions = slash_command_query(&value)
+            .map(|prefix| matching_commands(self.available_commands(cx), prefix))
+            .unwrap_or_default();
+        let show = !suggestions.is_empty();
+        if show || self.show_command_suggestions {
+            self.command_suggestions = suggestions;
+            self.show_command_suggestions = show;
+            cx.notify();
+        }
+
+        if mention_query(&value).is_some() {
+            self.schedule_file_suggestions(cx);
+        } else {
+            self.clear_file_suggestions(cx);
+        }
+        self.schedule_draft_save(cx);
+    }
+
+    /// Put the text left unsent when the session's panel was last closed back
+    /// in the input
+    fn restore_draft(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let Some(draft) = self.draft_path.as_deref().and_then(load_draft) else {
+            return;
+        };
+        self.input_state.update(cx, |state, cx| {
+            state.set_value(draft, window, cx);
+        });
+    }
+
+    fn schedule_draft_save(&mut self, cx: &mut Context<Self>) {
+        let Some(path) = self.draft_path.clone().filter(|_| !self.read_only) else {
+            return;
+        };
+        let text = self.input_state.read(cx).value().to_string();
+        let generations = self.draft_generations.clone();
+        let generation = generations.next();
+        self._draft_save_task = Some(cx.spawn(async move |_this, _cx| {
+            Timer::after(DRAFT_SAVE_DELAY).await;
+            let save = move || generations.write_if_latest(generation, || save_draft(&path, &text));
+            if let Err(e) = smol::unblock(save).await {
+                log::warn!("Failed to save input draft: {:#}", e);
+            }
+        }));
+    }
+
+    /// Write the input text now instead of waiting for the pending save. The
+    /// disabled input of a saved conversation leaves its draft as it is.
+    fn flush_draft(&mut self, cx: &mut App) {
+        let Some(path) = self.draft_path.as_deref().filter(|_| !self.read_only) else {
+            return;
+        };
+        self._draft_save_task = None;
+        let text = self.input_state.read(cx).value();
+        if let Err(e) = self.draft_generations.write_now(|| save_draft(path, &text)) {
+            log::warn!("Failed to save input draft: {:#}", e);
+        }
+    }
+
+    /// Refresh the file suggestions once typing pauses. A newer keystroke
+    /// drops the pending refresh, but not a scan of the working directory.
+    fn schedule_file_suggestions(&mut self, cx: &mut Context<Self>) {
+        let root = panel_working_directory(self.working_directory.as_deref(), cx);
+        if self.mention_files.root_path() != root {
+            self.mention_files.reset_root(root.clone());
+        }
+        if self.mention_files.needs_rescan(MENTION_FILES_MAX_AGE) {
+            self.scan_mention_files(root, cx);
+        }
+
+        self._file_suggestions_task = Some(cx.spawn(async move |this, cx| {
+            Timer::after(FILE_SUGGESTIONS_DEBOUNCE).await;
+            let _ = this.update(cx, |this, cx| this.refresh_file_suggestions(cx));
+        }));
+    }
+
+    /// Scan `root` for `@` mentions off the main thread, then refresh the
+    /// suggestions if a mention is still being typed
+    fn scan_mention_files(&mut self, root: PathBuf, cx: &mut Context<Self>) {
+        self.mention_files.mark_scanning();
+        self._mention_scan_task = Some(cx.spawn(async move |this, cx| {
+            let scan_root = root.clone();
+            let items =
+                smol::unblock(move || FilePickerDelegate::scan_directory(&scan_root, &scan_root))
+                    .await;
+            let _ = this.update(cx, |this, cx| {
+                if this.mention_files.root_path() != root {
+                    return;
+                }
+                this.mention_files.set_items(items);
+                this.refresh_file_suggestions(cx);
+            });
+        }));
+    }
+
+    fn refresh_file_suggestions(&mut self, cx: &mut Context<Self>) {
+        let value = self.input_state.read(cx).value();
+        let Some(query) = mention_query(&value) else {
+            self.clear_file_suggestions(cx);
+            return;
+        };
+        self.mention_files.set_search_query(query.to_string());
+        self.file_suggestions = self
+            .mention_files
+            .filtered_items()
+            .iter()
+            .take(MAX_FILE_SUGGESTIONS)
+            .cloned()
+            .collect();
+        cx.notify();
+    }
+
+    fn clear_file_suggestions(&mut self, cx: &mut Context<Self>) {
+        self._file_suggestions_task = None;
+        if !self.file_suggestions.is_empty() {
+            self.file_suggestions.clear();
+            cx.notify();
+        }
+    }
+
+    /// Replace the `@` mention being typed with the file's relative path
+    fn apply_file_selection(
+        &mut self,
+        file: &FileItem,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let mut path = file.relative_path.clone();
+        if file.is_folder && !path.ends_with('/') {
+            path.push('/');
+        }
+        let input_state = self.input_state.clone();
+        if let Some(value) = complete_mention(&input_state.read(cx).value(), &path) {
+            window.defer(cx, move |window, cx| {
+                input_state.update(cx, |state, cx| {
+                    state.set_value(value, window, cx);
+                });
+            });
+        }
+        self.clear_file_suggestions(cx);
+    }
+
+    /// Commands advertised for this session; falls back to the ones MessageService
+    /// stored if the update arrived before this panel subscribed
+    fn available_commands(&self, cx: &App) -> Vec<AvailableCommand> {
+        if !self.available_commands.is_empty() {
+            return self.available_commands.clone();
+        }
+        let (Some(session_id), Some(message_service)) = (
+            self.session_id.as_deref(),
+            AppState::global(cx).message_service(),
+        ) else {
+            return Vec::new();
+        };
+        message_service
+            .get_commands_by_session_id(session_id)
+            .unwrap_or_default()
+    }
+
+    fn apply_command_selection(
+        &mut self,
+        command: &AvailableCommand,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let value = format!("/{} ", command.name);
+        self.input_state.update(cx, |state, cx| {
+            state.set_value(SharedString::from(value), window, cx);
+        });
+        self.show_command_suggestions = false;
+        self.command_suggestions.clear();
+        cx.notify();
+    }
+
+    /// Fold the tool call updates since the last refresh into the pinned diff
+    /// summary
+    fn refresh_diff_summary(&mut self, cx: &mut Context<Self>) {
+        if self.pending_diff_updates.is_empty() {
+            return;
+        }
+        let updates = std::mem::take(&mut self.pending_diff_updates);
+        self.diff_summary.update(cx, |summary, cx| {
+            summary.apply_updates(&updates, cx);
+        });
     }
 
     fn create_input_state(window: &mut Window, cx: &mut App) -> Entity<InputState> {
@@ -148,18 +845,79 @@ impl ConversationPanel {
         })
     }
 
-    fn create_message_stream(cx: &mut App) -> Entity<AcpMessageStream> {
-        let icon_provider = Arc::new(|name: &str| Icon::new(get_agent_icon(name)));
+    fn create_message_stream(
+        auto_collapse_completed_tools: bool,
+        markdown: bool,
+        cx: &mut Context<Self>,
+    ) -> Entity<AcpMessageStream> {
+        let icon_provider = Arc::new(|name: &str| find_agent_icon(name).map(Icon::new));
+        let apply_panel = cx.entity().downgrade();
+        let revert_panel = apply_panel.clone();
+        let retry_panel = apply_panel.clone();
+        let resource_panel = apply_panel.clone();
+        let content_renderers = Arc::new(ContentRenderers::default().on_open_resource(Arc::new(
+            move |path, window, cx| {
+                let Some(panel) = resource_panel.upgrade() else {
+                    return;
+                };
+                let panel_directory = panel.read(cx).working_directory.clone();
+                let action = code_editor_action(panel_directory.as_deref(), &path, None, cx);
+                window.dispatch_action(Box::new(action), cx);
+            },
+        )));
         let tool_call_options = ToolCallItemOptions::default()
             .preview_max_lines(AppState::global(cx).tool_call_preview_max_lines())
+            .max_inline_content_bytes(AppState::global(cx).max_inline_content_bytes())
             .on_open_detail(Arc::new(|tool_call, window, cx| {
                 let action = PanelAction::show_tool_call_detail(
                     tool_call.tool_call_id.to_string(),
                     tool_call,
                 );
                 window.dispatch_action(Box::new(action), cx);
-            }));
-        let diff_summary_options = DiffSummaryOptions {
+            }))
+            .on_open_terminal(Arc::new(|terminal_id, window, cx| {
+                let action = PanelAction::show_terminal_output(Some(terminal_id));
+                window.dispatch_action(Box::new(action), cx);
+            }))
+            .on_apply_diff(Arc::new(move |request, _window, cx| {
+                match apply_panel.upgrade() {
+                    Some(panel) => Self::write_diff(&panel, request, cx),
+                    None => Task::ready(Err(anyhow!("The conversation was closed"))),
+                }
+            }))
+            .on_revert_diff(Arc::new(move |request, _window, cx| {
+                match revert_panel.upgrade() {
+                    Some(panel) => {
+                        // Reverting applies the edit backwards
+                        let change = DiffApplyRequested {
+                            path: request.path,
+                            old_text: Some(request.new_text),
+                            new_text: request.old_text,
+                        };
+                        Self::write_diff(&panel, change, cx)
+                    }
+                    None => Task::ready(Err(anyhow!("The conversation was closed"))),
+                }
+            }))
+            .auto_collapse_completed(auto_collapse_completed_tools)
+            .content_renderers(content_renderers.clone());
+        let options = AcpMessageStreamOptions {
+            agent_icon_provider: icon_provider,
+            tool_call_item_options: tool_call_options,
+            diff_summary_options: Self::diff_summary_options(),
+            on_retry: Some(Arc::new(move |window, cx| {
+                let _ = retry_panel.update(cx, |this, cx| this.retry_last_prompt(window, cx));
+            })),
+            content_renderers,
+            markdown,
+        };
+
+        cx.new(|_| AcpMessageStream::with_options(options))
+    }
+
+    /// Clicking a file in a diff summary opens its (merged) tool call in the detail panel
+    fn diff_summary_options() -> DiffSummaryOptions {
+        DiffSummaryOptions {
             on_open_tool_call: Some(Arc::new(
                 |tool_call: ToolCall, window: &mut Window, cx: &mut App| {
                     let action = PanelAction::show_tool_call_detail(
@@ -169,15 +927,170 @@ impl ConversationPanel {
                     window.dispatch_action(Box::new(action), cx);
                 },
             )),
-        };
+            ..Default::default()
+        }
+    }
 
-        let options = AcpMessageStreamOptions {
-            agent_icon_provider: icon_provider,
-            tool_call_item_options: tool_call_options,
-            diff_summary_options,
+    /// Scroll the item with `anchor` (see `AcpMessageStream::anchors`) to the
+    /// top; false when no item has it or it hasn't been painted yet
+    pub fn scroll_to_item(&mut self, anchor: &str, cx: &mut Context<Self>) -> bool {
+        let Some(item) = self.message_stream.read(cx).item_bounds(anchor) else {
+            return false;
         };
+        let viewport = self.scroll_handle.bounds();
+        let offset = self.scroll_handle.offset();
+        let max_offset = self.scroll_handle.max_offset().height;
+        let y = (offset.y - (item.top() - viewport.top()))
+            .as_f32()
+            .clamp(-max_offset.as_f32(), 0.);
+        self.scroll_handle.set_offset(gpui::point(offset.x, px(y)));
+        self.at_bottom = self.should_auto_scroll();
+        cx.notify();
+        true
+    }
 
-        cx.new(|_| AcpMessageStream::with_options(options))
+    /// Link to the item with `anchor`; `None` for panels without a session
+    pub fn deep_link(&self, anchor: &str) -> Option<DeepLink> {
+        let session_id = self.session_id.as_ref()?;
+        Some(DeepLink::new(session_id.clone(), anchor))
+    }
+
+    /// Put the link to the current search match on the clipboard
+    fn copy_search_match_link(&self, cx: &mut App) {
+        let link = self
+            .search_match
+            .as_deref()
+            .and_then(|anchor| self.deep_link(anchor));
+        if let Some(link) = link {
+            cx.write_to_clipboard(ClipboardItem::new_string(link.to_string()));
+        }
+    }
+
+    /// Scroll to the item `link` points at, showing the whole transcript
+    /// again; false when the link is to another session
+    fn open_deep_link(
+        &mut self,
+        link: DeepLink,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        if self.session_id.as_deref() != Some(link.session_id.as_str()) {
+            return false;
+        }
+        self.search_match = None;
+        self.message_stream.update(cx, |stream, cx| {
+            stream.set_search_query("", cx);
+        });
+        // The item has bounds to scroll to once the unfiltered list is painted
+        cx.on_next_frame(window, move |this, _, cx| {
+            this.scroll_to_item(&link.anchor, cx);
+        });
+        cx.notify();
+        true
+    }
+
+    /// Turn auto-collapse of completed tool calls on or off for this panel
+    pub fn set_auto_collapse_completed_tools(
+        &mut self,
+        auto_collapse: bool,
+        cx: &mut Context<Self>,
+    ) {
+        self.auto_collapse_completed_tools = auto_collapse;
+        self.message_stream.update(cx, |stream, cx| {
+            stream.set_auto_collapse_completed(auto_collapse, cx);
+        });
+        cx.notify();
+    }
+
+    /// Turn markdown rendering of agent messages and thoughts on or off
+    pub fn set_markdown(&mut self, markdown: bool, cx: &mut Context<Self>) {
+        self.markdown = markdown;
+        self.message_stream.update(cx, |stream, cx| {
+            stream.set_markdown(markdown, cx);
+        });
+        cx.notify();
+    }
+
+    /// Show the transcript search bar and focus it, or close it
+    pub fn toggle_search(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.show_search {
+            self.close_search(window, cx);
+            return;
+        }
+        self.show_search = true;
+        self.search_input
+            .update(cx, |state, cx| state.focus(window, cx));
+        cx.notify();
+    }
+
+    /// Hide the search bar and show the whole transcript again
+    pub fn close_search(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.show_search = false;
+        self.search_input
+            .update(cx, |state, cx| state.set_value("", window, cx));
+        self.set_search_query("", window, cx);
+    }
+
+    /// Filter the transcript to items containing `query` and scroll to the
+    /// first one; an empty query shows everything
+    fn set_search_query(&mut self, query: &str, window: &mut Window, cx: &mut Context<Self>) {
+        self.search_match = None;
+        self.message_stream.update(cx, |stream, cx| {
+            stream.set_search_query(query, cx);
+        });
+        // Matches have bounds to scroll to once the filtered list is painted
+        cx.on_next_frame(window, |this, _, cx| this.select_search_match(0, cx));
+        cx.notify();
+    }
+
+    /// Scroll to the search match `delta` steps from the current one, wrapping
+    /// around
+    fn select_search_match(&mut self, delta: isize, cx: &mut Context<Self>) {
+        self.search_match = self
+            .message_stream
+            .read(cx)
+            .step_search_match(self.search_match.as_deref(), delta);
+        if let Some(anchor) = self.search_match.clone() {
+            self.scroll_to_item(&anchor, cx);
+        }
+        cx.notify();
+    }
+
+    /// Override the configured scroll axes for this panel
+    pub fn set_scroll_axis(&mut self, axis: ScrollbarAxis, cx: &mut Context<Self>) {
+        self.scroll_axis = axis;
+        cx.notify();
+    }
+
+    /// Show the scrollbar and (re)start the countdown to hiding it
+    fn reveal_scrollbar(&mut self, cx: &mut Context<Self>) {
+        if !self.scrollbar_visible {
+            self.scrollbar_visible = true;
+            cx.notify();
+        }
+        self._scrollbar_hide_task = Some(cx.spawn(async move |this, cx| {
+            Timer::after(SCROLLBAR_HIDE_DELAY).await;
+            let _ = this.update(cx, |this, cx| {
+                this.scrollbar_visible = false;
+                cx.notify();
+            });
+        }));
+    }
+
+    /// Scroll to the newest item and follow new content again
+    pub fn scroll_to_bottom(&mut self, cx: &mut Context<Self>) {
+        self.scroll_handle.scroll_to_bottom();
+        self.at_bottom = true;
+        cx.notify();
+    }
+
+    /// Re-render when scrolling moved the transcript to or away from the bottom
+    fn update_at_bottom(&mut self, cx: &mut Context<Self>) {
+        let at_bottom = self.should_auto_scroll();
+        if at_bottom != self.at_bottom {
+            self.at_bottom = at_bottom;
+            cx.notify();
+        }
     }
 
     fn should_auto_scroll(&self) -> bool {
@@ -224,15 +1137,17 @@ impl ConversationPanel {
                                         persisted_msg.timestamp
                                     );
 
-                                    this.message_stream.update(cx, |stream, cx| {
-                                        stream.process_update(
+                                    this.process_update(
+                                        stamp_message_time(
                                             persisted_msg.update,
-                                            Some(session_id.as_str()),
-                                            agent_name.as_deref(),
-                                            cx,
-                                        );
-                                    });
+                                            &persisted_msg.timestamp,
+                                        ),
+                                        Some(session_id.as_str()),
+                                        agent_name.as_deref(),
+                                        cx,
+                                    );
                                 }
+                                this.refresh_diff_summary(cx);
 
                                 let total_items = this.message_stream.read(cx).len();
                                 log::info!(
@@ -241,11 +1156,7 @@ impl ConversationPanel {
                                     total_items
                                 );
 
-                                this.message_stream.update(cx, |stream, cx| {
-                                    stream.add_diff_summary_if_needed(cx);
-                                });
-                                this.scroll_handle.scroll_to_bottom();
-                                cx.notify();
+                                this.scroll_to_bottom(cx);
                             });
                         } else {
                             log::warn!("Entity dropped while loading history");
@@ -316,8 +1227,38 @@ impl ConversationPanel {
         let session_filter_log_end = session_filter.clone();
 
         // Use MessageService to subscribe with automatic filtering
+        let mut errors = message_service.subscribe_session_errors(session_filter.clone());
+        let mut retries = message_service.subscribe_session_retries(session_filter.clone());
         let mut rx = message_service.subscribe_session_updates(session_filter);
 
+        // Failed prompts show up inline, in order with the updates
+        let weak_errors = weak_entity.clone();
+        cx.spawn(async move |cx| {
+            while let Some(event) = errors.recv().await {
+                let Some(entity) = weak_errors.upgrade() else {
+                    break;
+                };
+                let _ = cx.update(|cx| {
+                    entity.update(cx, |this, cx| this.on_session_error(event, cx));
+                });
+            }
+        })
+        .detach();
+
+        // Timed-out prompts being sent again show up the same way
+        let weak_retries = weak_entity.clone();
+        cx.spawn(async move |cx| {
+            while let Some(event) = retries.recv().await {
+                let Some(entity) = weak_retries.upgrade() else {
+                    break;
+                };
+                let _ = cx.update(|cx| {
+                    entity.update(cx, |this, cx| this.on_session_retry(event, cx));
+                });
+            }
+        })
+        .detach();
+
         // Spawn background task to receive updates and update entity
         cx.spawn(async move |cx| {
             log::info!(
@@ -342,23 +1283,22 @@ impl ConversationPanel {
                 let _ = cx.update(move |cx| {
                     if let Some(entity) = weak.upgrade() {
                         entity.update(cx, |this, cx| {
-                            let should_auto_scroll = this.should_auto_scroll();
-
                             for event in events {
                                 let session_id = event.session_id.clone();
                                 let agent_name = event.agent_name.clone();
                                 let update = (*event.update).clone();
-                                this.message_stream.update(cx, |stream, cx| {
-                                    stream.process_update(
-                                        update,
-                                        Some(session_id.as_str()),
-                                        agent_name.as_deref(),
-                                        cx,
-                                    );
-                                });
+                                this.record_terminal_output(&update, cx);
+                                this.emit_panel_intent(&update, cx);
+                                this.process_update(
+                                    update,
+                                    Some(session_id.as_str()),
+                                    agent_name.as_deref(),
+                                    cx,
+                                );
                             }
+                            this.refresh_diff_summary(cx);
 
-                            if should_auto_scroll {
+                            if this.at_bottom {
                                 this.scroll_handle.scroll_to_bottom();
                             }
                             cx.notify();
@@ -544,6 +1484,81 @@ impl ConversationPanel {
         );
     }
 
+    /// Write `text` to a diff's `path`, resolved against the panel's working
+    /// directory; `None` removes the file
+    fn write_diff(
+        entity: &Entity<Self>,
+        change: DiffApplyRequested,
+        cx: &mut App,
+    ) -> Task<Result<()>> {
+        let path = entity.update(cx, |this, _| {
+            this.mention_files.mark_stale();
+            resolve_diff_path(this.working_directory.as_deref(), &change.path)
+        });
+        write_diff_text(path, change, cx)
+    }
+
+    /// Apply "Apply all" batches from the pinned summary to disk
+    pub fn subscribe_to_diff_writes(entity: &Entity<Self>, cx: &mut App) {
+        cx.subscribe(entity, |panel, request: &DiffBatchApplyRequested, cx| {
+            Self::apply_all_changes(panel, request.changes.clone(), cx);
+        })
+        .detach();
+    }
+
+    /// Reveal the terminal, or show the file in the code editor, when a tool
+    /// call completes; relative paths resolve against the panel's working directory
+    pub fn subscribe_to_panel_intents(entity: &Entity<Self>, window: &mut Window, cx: &mut App) {
+        window
+            .subscribe(entity, cx, |panel, intent: &PanelIntent, window, cx| {
+                let panel_directory = panel.read(cx).working_directory.clone();
+                let action = match intent {
+                    // Output the agent reported goes to its tab in the output panel,
+                    // which switches to it once the output arrives
+                    PanelIntent::RevealTerminal {
+                        terminal_id: Some(terminal_id),
+                    } => PanelAction::show_terminal_output(Some(terminal_id.clone())),
+                    PanelIntent::RevealTerminal { terminal_id: None } => {
+                        PanelAction::show_terminal(Some(panel_working_directory(
+                            panel_directory.as_deref(),
+                            cx,
+                        )))
+                    }
+                    PanelIntent::OpenFile { path, line } => {
+                        code_editor_action(panel_directory.as_deref(), path, *line, cx)
+                    }
+                };
+                // Revealed on its own, so typing in the chat input isn't interrupted
+                window.dispatch_action(Box::new(action.in_background()), cx);
+            })
+            .detach();
+    }
+
+    /// Apply a batch one file at a time, reporting progress to the pinned summary
+    fn apply_all_changes(entity: Entity<Self>, changes: Vec<DiffApplyRequested>, cx: &mut App) {
+        entity.update(cx, |this, _| this.mention_files.mark_stale());
+        let panel = entity.read(cx);
+        let summary = panel.diff_summary.clone();
+        let working_directory = panel.working_directory.clone();
+        let mut progress = DiffApplyProgress::new(changes.len());
+
+        cx.spawn(async move |cx| {
+            for change in changes {
+                let path = resolve_diff_path(working_directory.as_deref(), &change.path);
+                let display_path = change.path.clone();
+                let outcome = cx
+                    .background_spawn(async move { apply_change(&path, &change) })
+                    .await;
+                progress.record(display_path, outcome);
+                let _ = summary.update(cx, |summary, cx| {
+                    summary.set_apply_progress(progress.clone(), cx);
+                });
+            }
+            log::info!("Apply all: {}", progress.label());
+        })
+        .detach();
+    }
+
     /// Subscribe to WorkspaceUpdateBus to receive session status updates
     pub fn subscribe_to_status_updates(
         entity: &Entity<Self>,
@@ -613,11 +1628,21 @@ impl ConversationPanel {
                                     status
                                 );
 
+                                // Tool calls still open when the turn ends will never complete
+                                if matches!(
+                                    status,
+                                    SessionStatus::Completed
+                                        | SessionStatus::Idle
+                                        | SessionStatus::Failed
+                                        | SessionStatus::Closed
+                                ) {
+                                    this.tool_call_tracker.clear();
+                                }
+
                                 // Mark last message as complete when session completes or becomes idle
                                 if matches!(status, SessionStatus::Completed | SessionStatus::Idle) {
                                     this.message_stream.update(cx, |stream, cx| {
                                         stream.mark_last_complete(cx);
-                                        stream.add_diff_summary_if_needed(cx);
                                     });
 
                                     log::debug!(
@@ -689,6 +1714,67 @@ impl ConversationPanel {
         handled
     }
 
+    /// Show an error inline in the conversation
+    pub fn push_error(
+        &mut self,
+        kind: ErrorKind,
+        message: impl Into<String>,
+        cx: &mut Context<Self>,
+    ) {
+        self.message_stream.update(cx, |stream, cx| {
+            stream.push_error(kind, message, cx);
+        });
+        if self.at_bottom {
+            self.scroll_handle.scroll_to_bottom();
+        }
+        cx.notify();
+    }
+
+    /// A prompt to this session failed: show it and keep the prompt for retry
+    fn on_session_error(&mut self, event: SessionErrorEvent, cx: &mut Context<Self>) {
+        let kind = match event.kind {
+            SessionErrorKind::Transport => ErrorKind::Transport,
+            SessionErrorKind::Agent => ErrorKind::Agent,
+        };
+        self.last_failed_prompt = Some(event.prompt);
+        self.push_error(kind, event.message, cx);
+    }
+
+    /// A prompt to this session timed out and is being sent again
+    fn on_session_retry(&mut self, event: SessionRetryEvent, cx: &mut Context<Self>) {
+        self.message_stream.update(cx, |stream, cx| {
+            stream.push_retry(event.attempt, event.max_retries, event.message, cx);
+        });
+        if self.at_bottom {
+            self.scroll_handle.scroll_to_bottom();
+        }
+        cx.notify();
+    }
+
+    /// Send the last failed prompt again
+    fn retry_last_prompt(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let (Some(session_id), Some(prompt)) =
+            (self.session_id.clone(), self.last_failed_prompt.take())
+        else {
+            return;
+        };
+        window.dispatch_action(Box::new(RetryPrompt { session_id, prompt }), cx);
+    }
+
+    /// Send `text` with the pasted images and code selections
+    fn send_prompt(&mut self, text: String, window: &mut Window, cx: &mut Context<Self>) {
+        if let Some(path) = self.draft_path.as_deref() {
+            self._draft_save_task = None;
+            if let Err(e) = self.draft_generations.write_now(|| clear_draft(path)) {
+                log::warn!("Failed to clear input draft: {:#}", e);
+            }
+        }
+        let images = std::mem::take(&mut self.pasted_images);
+        let code_selections = std::mem::take(&mut self.code_selections);
+        self.send_message(text, images, code_selections, window, cx);
+        cx.notify();
+    }
+
     /// Send a message to the current session
     /// Dispatches SendMessageToSession action to workspace for handling
     fn send_message(
@@ -721,6 +1807,256 @@ impl ConversationPanel {
         window.dispatch_action(Box::new(action), cx);
     }
 
+    /// Stop the current generation: notify observers, then cancel the ACP session
+    fn request_cancel(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        cx.emit(CancelRequested {
+            session_id: self.session_id.clone(),
+        });
+        self.send_cancel_message(window, cx);
+    }
+
+    /// Render the "generating" indicator with a stop button
+    fn render_generating_bar(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        h_flex()
+            .w_full()
+            .px_6()
+            .py_1()
+            .gap_2()
+            .items_center()
+            .child(Spinner::new().small().color(cx.theme().muted_foreground))
+            .child(
+                div()
+                    .flex_1()
+                    .text_xs()
+                    .text_color(cx.theme().muted_foreground)
+                    .child(t!("conversation.generating").to_string()),
+            )
+            .child(
+                Button::new("conversation-stop-generation")
+                    .icon(Icon::new(crate::assets::Icon::SquarePause))
+                    .label(t!("conversation.stop").to_string())
+                    .ghost()
+                    .xsmall()
+                    .on_click(cx.listener(|this, _ev, window, cx| {
+                        this.request_cancel(window, cx);
+                        cx.notify();
+                    })),
+            )
+    }
+
+    /// Search field with the match position, previous/next and close;
+    /// Escape closes it
+    fn render_search_bar(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let (match_count, position) = self
+            .message_stream
+            .read(cx)
+            .search_match_position(self.search_match.as_deref());
+        let has_query = !self.search_input.read(cx).value().is_empty();
+        let label = match (match_count, position) {
+            (0, _) => t!("conversation.search_no_matches").to_string(),
+            (count, Some(ix)) => format!("{}/{}", ix + 1, count),
+            (count, None) => t!("conversation.search_matches", count = count).to_string(),
+        };
+
+        h_flex()
+            .flex_none()
+            .w_full()
+            .px_4()
+            .pt_2()
+            .gap_1()
+            .items_center()
+            .child(
+                div()
+                    .flex_1()
+                    .capture_key_down(cx.listener(|this, event: &KeyDownEvent, window, cx| {
+                        if event.keystroke.key == "escape" {
+                            this.close_search(window, cx);
+                            cx.stop_propagation();
+                        }
+                    }))
+                    .child(Input::new(&self.search_input).small()),
+            )
+            .when(has_query, |this| {
+                this.child(
+                    div()
+                        .flex_shrink_0()
+                        .text_xs()
+                        .text_color(cx.theme().muted_foreground)
+                        .child(label),
+                )
+            })
+            .child(
+                Button::new("conversation-search-previous")
+                    .icon(IconName::ChevronUp)
+                    .ghost()
+                    .xsmall()
+                    .disabled(match_count == 0)
+                    .tooltip(t!("conversation.search_previous").to_string())
+                    .on_click(cx.listener(|this, _, _, cx| this.select_search_match(-1, cx))),
+            )
+            .child(
+                Button::new("conversation-search-next")
+                    .icon(IconName::ChevronDown)
+                    .ghost()
+                    .xsmall()
+                    .disabled(match_count == 0)
+                    .tooltip(t!("conversation.search_next").to_string())
+                    .on_click(cx.listener(|this, _, _, cx| this.select_search_match(1, cx))),
+            )
+            .child(
+                Button::new("conversation-search-copy-link")
+                    .icon(IconName::Copy)
+                    .ghost()
+                    .xsmall()
+                    .disabled(position.is_none())
+                    .tooltip(t!("conversation.search_copy_link").to_string())
+                    .on_click(cx.listener(|this, _, _, cx| this.copy_search_match_link(cx))),
+            )
+            .child(
+                Button::new("conversation-search-close")
+                    .icon(IconName::Close)
+                    .ghost()
+                    .xsmall()
+                    .on_click(cx.listener(|this, _, window, cx| this.close_search(window, cx))),
+            )
+    }
+
+    /// Render the status row with the agent's current mode and command count
+    fn render_status_bar(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let current_mode = self
+            .message_stream
+            .read(cx)
+            .current_mode()
+            .map(str::to_string);
+        let badge = || {
+            h_flex()
+                .gap_1()
+                .px_2()
+                .py_0p5()
+                .rounded(cx.theme().radius)
+                .bg(cx.theme().muted.opacity(0.5))
+                .text_xs()
+                .text_color(cx.theme().muted_foreground)
+        };
+
+        h_flex()
+            .w_full()
+            .px_6()
+            .pt_1()
+            .gap_2()
+            .justify_end()
+            .when(!self.available_commands.is_empty(), |this| {
+                this.child(
+                    badge().child(
+                        t!(
+                            "conversation.commands_available",
+                            count = self.available_commands.len()
+                        )
+                        .to_string(),
+                    ),
+                )
+            })
+            .when_some(current_mode, |this, mode| {
+                this.child(badge().child(format!(
+                    "{}: {}",
+                    t!("conversation.mode"),
+                    mode_display_name(&mode)
+                )))
+            })
+            .child(
+                Button::new("conversation-auto-open-panels")
+                    .ghost()
+                    .xsmall()
+                    .label(t!("conversation.auto_open_panels").to_string())
+                    .selected(AppState::global(cx).auto_open_panels())
+                    .tooltip(t!("conversation.auto_open_panels_tooltip").to_string())
+                    .on_click(|_, _, cx| {
+                        let auto_open = !AppState::global(cx).auto_open_panels();
+                        save_auto_open_panels(auto_open, cx);
+                        cx.refresh_windows();
+                    }),
+            )
+            .child(
+                Button::new("conversation-auto-collapse-tools")
+                    .ghost()
+                    .xsmall()
+                    .label(t!("conversation.auto_collapse_tools").to_string())
+                    .selected(self.auto_collapse_completed_tools)
+                    .tooltip(t!("conversation.auto_collapse_tools_tooltip").to_string())
+                    .on_click(cx.listener(|this, _, _, cx| {
+                        let auto_collapse = !this.auto_collapse_completed_tools;
+                        this.set_auto_collapse_completed_tools(auto_collapse, cx);
+                    })),
+            )
+            .child(
+                Button::new("conversation-search")
+                    .ghost()
+                    .xsmall()
+                    .icon(IconName::Search)
+                    .label(t!("conversation.search").to_string())
+                    .selected(self.show_search)
+                    .tooltip(t!("conversation.search_tooltip").to_string())
+                    .on_click(cx.listener(|this, _, window, cx| {
+                        this.toggle_search(window, cx);
+                    })),
+            )
+            .child(
+                Button::new("conversation-markdown")
+                    .ghost()
+                    .xsmall()
+                    .label(t!("conversation.markdown").to_string())
+                    .selected(self.markdown)
+                    .tooltip(t!("conversation.markdown_tooltip").to_string())
+                    .on_click(cx.listener(|this, _, _, cx| {
+                        let markdown = !this.markdown;
+                        this.set_markdown(markdown, cx);
+                    })),
+            )
+            .when(!self.transcript.is_empty(), |this| {
+                this.child(
+                    Button::new("conversation-save-session")
+                        .ghost()
+                        .xsmall()
+                        .label(t!("conversation.save_session").to_string())
+                        .on_click(cx.listener(|this, _, _, cx| this.save_to_sessions_dir(cx))),
+                )
+            })
+            .when(!self.saved_sessions.is_empty(), |this| {
+                this.child(self.render_saved_sessions_menu())
+            })
+    }
+
+    /// "Saved" dropdown listing saved sessions, newest first
+    fn render_saved_sessions_menu(&self) -> impl IntoElement {
+        let sessions = self.saved_sessions.clone();
+
+        Button::new("conversation-saved-sessions")
+            .ghost()
+            .xsmall()
+            .label(t!("conversation.saved_sessions").to_string())
+            .dropdown_menu(move |mut menu, _, _| {
+                for (path, meta) in &sessions {
+                    let title = meta
+                        .title
+                        .clone()
+                        .unwrap_or_else(|| t!("conversation.untitled_session").to_string());
+                    let saved_at = DateTime::parse_from_rfc3339(&meta.saved_at)
+                        .map(|time| format_time_friendly(&time))
+                        .unwrap_or_default();
+                    let label = match &meta.agent_name {
+                        Some(agent) => format!("{} · {} · {}", title, agent, saved_at),
+                        None => format!("{} · {}", title, saved_at),
+                    };
+                    // In a panel of its own: this one stays on its live session
+                    let action = PanelAction::open_saved_conversation(path.clone());
+                    menu = menu.item(PopupMenuItem::new(label).on_click(move |_, window, cx| {
+                        window.dispatch_action(Box::new(action.clone()), cx)
+                    }));
+                }
+                menu
+            })
+    }
+
     /// Cancel the current session
     /// Dispatches cancel via AgentService to avoid lost actions
     fn send_cancel_message(&self, _window: &mut Window, cx: &mut Context<Self>) {
@@ -767,6 +2103,9 @@ impl ConversationPanel {
     /// Check if the input should be disabled based on session status
     /// Returns true if the session is closed, failed, or not resumable
     fn is_input_disabled(&self) -> bool {
+        if self.read_only {
+            return true;
+        }
         match &self.session_status {
             Some(status_info) => {
                 matches!(
@@ -847,7 +2186,7 @@ impl ConversationPanel {
                                         .bg(cx.theme().muted.opacity(0.5))
                                         .child(
                                             Icon::new(crate::assets::Icon::ListTodo)
-                                                .size(px(12.))
+                                                .size(scaled(12., cx))
                                                 .text_color(cx.theme().muted_foreground),
                                         )
                                         .child(
@@ -869,7 +2208,7 @@ impl ConversationPanel {
                                     .gap_1p5()
                                     .child(
                                         Icon::new(IconName::Info)
-                                            .size(px(12.))
+                                            .size(scaled(12., cx))
                                             .text_color(cx.theme().muted_foreground),
                                     )
                                     .child(
@@ -898,21 +2237,21 @@ impl ConversationPanel {
                                 Skeleton::new()
                                     .w_full()
                                     .max_w(px(480.))
-                                    .h(px(16.))
+                                    .h(scaled(16., cx))
                                     .rounded(cx.theme().radius),
                             )
                             .child(
                                 Skeleton::new()
                                     .w_full()
                                     .max_w(px(420.))
-                                    .h(px(16.))
+                                    .h(scaled(16., cx))
                                     .rounded(cx.theme().radius),
                             )
                             .child(
                                 Skeleton::new()
                                     .w_full()
                                     .max_w(px(360.))
-                                    .h(px(16.))
+                                    .h(scaled(16., cx))
                                     .rounded(cx.theme().radius),
                             ),
                     ),
@@ -955,6 +2294,53 @@ impl DockPanel for ConversationPanel {
     }
 }
 
+/// Whether `update` is a tool call that edits, deletes or moves files, so
+/// the cached `@` mention file list may be out of date
+fn writes_files(update: &SessionUpdate) -> bool {
+    let (kind, content) = match update {
+        SessionUpdate::ToolCall(call) => (Some(call.kind), Some(&call.content)),
+        SessionUpdate::ToolCallUpdate(update) => {
+            (update.fields.kind, update.fields.content.as_ref())
+        }
+        _ => return false,
+    };
+    matches!(
+        kind,
+        Some(ToolKind::Edit | ToolKind::Delete | ToolKind::Move)
+    ) || content.is_some_and(|content| {
+        content
+            .iter()
+            .any(|item| matches!(item, ToolCallContent::Diff(_)))
+    })
+}
+
+/// The panel's working directory, or the app's when it has none
+fn panel_working_directory(panel_directory: Option<&str>, cx: &App) -> PathBuf {
+    panel_directory
+        .map(PathBuf::from)
+        .unwrap_or_else(|| AppState::global(cx).current_working_dir().clone())
+}
+
+/// Show `path` (relative to the panel's working directory) in the code editor
+/// at the 1-based `line`
+fn code_editor_action(
+    panel_directory: Option<&str>,
+    path: &Path,
+    line: Option<u32>,
+    cx: &App,
+) -> PanelAction {
+    let line = line.unwrap_or(1).saturating_sub(1);
+    PanelAction::show_code_editor_at(
+        panel_working_directory(panel_directory, cx),
+        resolve_diff_path(panel_directory, path),
+        lsp_types::Position::new(line, 0),
+    )
+}
+
+impl EventEmitter<CancelRequested> for ConversationPanel {}
+impl EventEmitter<PanelIntent> for ConversationPanel {}
+impl EventEmitter<DiffBatchApplyRequested> for ConversationPanel {}
+
 impl Focusable for ConversationPanel {
     fn focus_handle(&self, _cx: &App) -> FocusHandle {
         self.focus_handle.clone()
@@ -964,6 +2350,9 @@ impl Focusable for ConversationPanel {
 impl Render for ConversationPanel {
     fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
         let is_empty = self.message_stream.read(cx).is_empty();
+        let has_file_changes = self.diff_summary.read(cx).data().has_changes();
+        let is_generating = self.message_stream.read(cx).is_generating(cx);
+        let has_mode = self.message_stream.read(cx).current_mode().is_some();
         let message_list = v_flex()
             .p_4()
             .gap_3()
@@ -975,37 +2364,130 @@ impl Render for ConversationPanel {
         v_flex()
             .id("messages")
             .size_full()
+            .when(has_file_changes, |this| {
+                // Session diff summary pinned above the transcript
+                this.child(
+                    div()
+                        .flex_none()
+                        .w_full()
+                        .px_4()
+                        .pt_2()
+                        .child(self.diff_summary.clone()),
+                )
+            })
+            .when(self.show_search, |this| {
+                this.child(self.render_search_bar(cx))
+            })
             .child(
-                // Scrollable message area - takes remaining space
+                // Scrollable message area - takes remaining space. The scrollbar
+                // sits on this wrapper so it stays put while the content scrolls.
                 div()
-                    .id("conversation-scroll-container")
+                    .relative()
                     .flex_1()
                     .w_full()
-                    .track_scroll(&self.scroll_handle)
-                    .overflow_y_scroll()
-                    .size_full()
-                    .when(is_empty, |this| {
-                        // Show empty state with centered text
+                    .min_h_0()
+                    .child(
+                        div()
+                            .id("conversation-scroll-container")
+                            .size_full()
+                            .track_scroll(&self.scroll_handle)
+                            .overflow_y_scroll()
+                            // With both axes enabled gpui keeps the plain wheel vertical;
+                            // horizontal scrolling needs a trackpad swipe or shift+wheel
+                            .when(self.scroll_axis == ScrollbarAxis::Both, |this| {
+                                this.overflow_x_scroll()
+                            })
+                            .on_scroll_wheel(cx.listener(|this, _, _, cx| {
+                                this.reveal_scrollbar(cx);
+                                this.update_at_bottom(cx);
+                            }))
+                            .on_hover(cx.listener(|this, hovered: &bool, _, cx| {
+                                if *hovered {
+                                    this.reveal_scrollbar(cx);
+                                }
+                            }))
+                            .when(is_empty, |this| {
+                                // Show empty state (or the load failure) with centered text
+                                let load_error = self.load_error.clone();
+                                this.child(
+                                    div()
+                                        .size_full()
+                                        .flex()
+                                        .items_center()
+                                        .justify_center()
+                                        .child(match load_error {
+                                            Some(error) => v_flex()
+                                                .items_center()
+                                                .gap_1()
+                                                .child(
+                                                    div()
+                                                        .text_color(cx.theme().red)
+                                                        .text_sm()
+                                                        .child(
+                                                            t!("conversation.load_failed")
+                                                                .to_string(),
+                                                        ),
+                                                )
+                                                .child(
+                                                    div()
+                                                        .text_color(cx.theme().muted_foreground)
+                                                        .text_xs()
+                                                        .child(error),
+                                                )
+                                                .into_any_element(),
+                                            None => div()
+                                                .text_color(cx.theme().muted_foreground)
+                                                .text_sm()
+                                                .child(t!("conversation.empty").to_string())
+                                                .into_any_element(),
+                                        }),
+                                )
+                            })
+                            .when(!is_empty, |this| {
+                                // Show message list
+                                this.pb_3() // Add padding at bottom so messages don't get hidden behind input box
+                                    .child(message_list)
+                            }),
+                    )
+                    // Dragging the scrollbar ends with a mouse up rather than a wheel event
+                    .on_mouse_up(
+                        MouseButton::Left,
+                        cx.listener(|this, _, _, cx| this.update_at_bottom(cx)),
+                    )
+                    .when(self.scrollbar_visible, |this| {
+                        this.scrollbar(&self.scroll_handle, self.scroll_axis)
+                    })
+                    .when(!is_empty && !self.at_bottom, |this| {
                         this.child(
-                            div()
-                                .size_full()
-                                .flex()
-                                .items_center()
+                            h_flex()
+                                .absolute()
+                                .bottom_2()
+                                .left_0()
+                                .right_0()
                                 .justify_center()
                                 .child(
-                                    div()
-                                        .text_color(cx.theme().muted_foreground)
-                                        .text_sm()
-                                        .child(t!("conversation.empty").to_string()),
+                                    Button::new("conversation-jump-to-latest")
+                                        .icon(IconName::ArrowDown)
+                                        .label(t!("conversation.jump_to_latest").to_string())
+                                        .small()
+                                        .on_click(cx.listener(|this, _, _, cx| {
+                                            this.scroll_to_bottom(cx);
+                                        })),
                                 ),
                         )
-                    })
-                    .when(!is_empty, |this| {
-                        // Show message list
-                        this.pb_3() // Add padding at bottom so messages don't get hidden behind input box
-                            .child(message_list)
                     }),
             )
+            .when(is_generating, |this| {
+                // Stop control while the agent is still streaming
+                this.child(self.render_generating_bar(cx))
+            })
+            .when(
+                has_mode
+                    || !self.available_commands.is_empty()
+                    || !self.transcript.is_empty()
+                    || !self.saved_sessions.is_empty(),
+                |this| this.child(self.render_status_bar(cx)),
+            )
             .child(
                 // Chat input box at bottom (fixed, not scrollable)
                 div()
@@ -1018,13 +2500,27 @@ impl Render for ConversationPanel {
                     .child({
                         let entity = cx.entity().clone();
                         let is_disabled = self.is_input_disabled();
+                        let (token_warning, token_limit) =
+                            AppState::global(cx).prompt_token_thresholds();
                         ChatInputBox::new("chat-input", self.input_state.clone())
+                            .input_size(self.input_size)
+                            .input_history(self.input_history.clone())
+                            .token_thresholds(token_warning, token_limit)
                             .pasted_images(self.pasted_images.clone())
                             .code_selections(self.code_selections.clone())
                             .session_status(
                                 self.session_status.as_ref().map(|info| info.status.clone()),
                             )
                             .disabled(is_disabled)
+                            .command_suggestions(self.command_suggestions.clone())
+                            .show_command_suggestions(self.show_command_suggestions)
+                            .on_command_select(cx.listener(|this, command, window, cx| {
+                                this.apply_command_selection(command, window, cx);
+                            }))
+                            .file_suggestions(self.file_suggestions.clone())
+                            .on_file_select(cx.listener(|this, file: &FileItem, window, cx| {
+                                this.apply_file_selection(file, window, cx);
+                            }))
                             .on_paste(move |window, cx| {
                                 entity.update(cx, |this, cx| {
                                     this.handle_paste(window, cx);
@@ -1054,21 +2550,62 @@ impl Render for ConversationPanel {
                                     this.input_state.update(cx, |state, cx| {
                                         state.set_value(SharedString::from(""), window, cx);
                                     });
-
-                                    // Send the message with images and code selections
-                                    let images = std::mem::take(&mut this.pasted_images);
-                                    let code_selections = std::mem::take(&mut this.code_selections);
-                                    this.send_message(text, images, code_selections, window, cx);
-
-                                    cx.notify();
+                                    this.send_prompt(text, window, cx);
                                 }
                             }))
+                            .on_submit(cx.listener(|this, submit: &ChatSubmit, window, cx| {
+                                this.send_prompt(submit.text.clone(), window, cx);
+                            }))
                             .on_cancel(cx.listener(|this, _ev, window, cx| {
                                 log::info!("[ConversationPanel] on_cancel callback triggered");
-                                this.send_cancel_message(window, cx);
+                                this.request_cancel(window, cx);
                                 cx.notify();
                             }))
                     }),
             )
     }
 }
+
+/// Write the auto-open choice back to `config.json`
+fn save_auto_open_panels(auto_open: bool, cx: &mut App) {
+    AppState::global_mut(cx).set_auto_open_panels(auto_open);
+    let Some(service) = AppState::global(cx).agent_config_service().cloned() else {
+        return;
+    };
+    cx.spawn(async move |_cx| {
+        if let Err(e) = service.update_auto_open_panels(auto_open).await {
+            log::error!("Failed to save auto-open setting: {}", e);
+        }
+    })
+    .detach();
+}
+
+#[cfg(test)]
+mod tests {
+    use agent_client_protocol::{ContentBlock, ContentChunk};
+
+    use super::*;
+
+    #[gpui::test]
+    fn from_updates_shows_and_records_the_updates(cx: &mut gpui::TestAppContext) {
+        cx.update(|cx| {
+            gpui_component::init(cx);
+            AppState::init(cx);
+        });
+        let chunk = |text: &str| ContentChunk::new(ContentBlock::from(text.to_string()));
+        let updates = vec![
+            SessionUpdate::UserMessageChunk(chunk("hi")),
+            SessionUpdate::AgentMessageChunk(chunk("Hello")),
+            SessionUpdate::AgentMessageChunk(chunk(", there")),
+        ];
+
+        let cx = cx.add_empty_window();
+        let panel = cx.update(|window, cx| ConversationPanel::from_updates(updates, window, cx));
+        panel.read_with(cx, |panel, cx| {
+            // Chunks of one message are shown and saved as one
+            assert_eq!(panel.message_stream.read(cx).anchors().len(), 2);
+            assert_eq!(panel.transcript.len(), 2);
+            assert_eq!(panel.session_id, None);
+        });
+    }
+}
@@ -0,0 +1,190 @@
+//! Panels a finished tool call points at: the terminal for commands, the
+//! code editor for files read or edited. The conversation panel emits these
+//! as events and the host decides how to surface them.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use agent_client_protocol::{SessionUpdate, ToolCall, ToolCallContent, ToolCallStatus, ToolKind};
+
+/// Emitted when a tool call completes that another panel can show
+#[derive(Clone, Debug, PartialEq)]
+pub enum PanelIntent {
+    /// A command ran; reveal the terminal
+    RevealTerminal {
+        /// Terminal the agent ran the command in, if it reported one
+        terminal_id: Option<String>,
+    },
+    /// A file was read or edited; show it in the code editor
+    OpenFile {
+        /// Path as the agent reported it (may be relative to the working directory)
+        path: PathBuf,
+        /// 1-based line the tool call pointed at
+        line: Option<u32>,
+    },
+}
+
+impl PanelIntent {
+    /// The intent of a tool call, from its kind; `None` for kinds no panel shows
+    pub fn for_tool_call(tool_call: &ToolCall) -> Option<Self> {
+        match tool_call.kind {
+            ToolKind::Execute => {
+                let terminal_id = tool_call.content.iter().find_map(|content| match content {
+                    ToolCallContent::Terminal(terminal) => Some(terminal.terminal_id.to_string()),
+                    _ => None,
+                });
+                Some(Self::RevealTerminal { terminal_id })
+            }
+            ToolKind::Edit | ToolKind::Read => {
+                if let Some(location) = tool_call.locations.first() {
+                    return Some(Self::OpenFile {
+                        path: location.path.clone(),
+                        line: location.line,
+                    });
+                }
+                tool_call.content.iter().find_map(|content| match content {
+                    ToolCallContent::Diff(diff) => Some(Self::OpenFile {
+                        path: diff.path.clone(),
+                        line: None,
+                    }),
+                    _ => None,
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Follows tool calls across their updates until they complete. Updates only
+/// carry the fields that changed, so the kind and locations come from earlier ones.
+#[derive(Default)]
+pub(super) struct ToolCallTracker {
+    pending: HashMap<String, ToolCall>,
+}
+
+impl ToolCallTracker {
+    /// Record `update`; returns the intent of a tool call it completed
+    pub fn track(&mut self, update: &SessionUpdate) -> Option<PanelIntent> {
+        let tool_call = match update {
+            SessionUpdate::ToolCall(tool_call) => {
+                self.pending
+                    .insert(tool_call.tool_call_id.to_string(), tool_call.clone());
+                tool_call.tool_call_id.to_string()
+            }
+            SessionUpdate::ToolCallUpdate(update) => {
+                let id = update.tool_call_id.to_string();
+                match self.pending.get_mut(&id) {
+                    Some(tool_call) => tool_call.update(update.fields.clone()),
+                    None => {
+                        let tool_call = ToolCall::try_from(update.clone()).ok()?;
+                        self.pending.insert(id.clone(), tool_call);
+                    }
+                }
+                id
+            }
+            _ => return None,
+        };
+
+        match self.pending.get(&tool_call)?.status {
+            ToolCallStatus::Completed => {
+                let tool_call = self.pending.remove(&tool_call)?;
+                PanelIntent::for_tool_call(&tool_call)
+            }
+            ToolCallStatus::Failed => {
+                self.pending.remove(&tool_call);
+                None
+            }
+            _ => None,
+        }
+    }
+
+    /// Forget the calls that haven't completed, e.g. when the turn ends
+    pub fn clear(&mut self) {
+        self.pending.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use agent_client_protocol::{Diff, ToolCallLocation, ToolCallUpdate, ToolCallUpdateFields};
+
+    #[test]
+    fn intents_follow_the_tool_kind() {
+        let mut read = ToolCall::new("tc-1", "Read main.rs");
+        read.kind = ToolKind::Read;
+        read.locations = vec![ToolCallLocation {
+            path: PathBuf::from("src/main.rs"),
+            line: Some(12),
+            meta: None,
+        }];
+        assert_eq!(
+            PanelIntent::for_tool_call(&read),
+            Some(PanelIntent::OpenFile {
+                path: PathBuf::from("src/main.rs"),
+                line: Some(12),
+            })
+        );
+
+        let mut edit = ToolCall::new("tc-2", "Edit lib.rs");
+        edit.kind = ToolKind::Edit;
+        edit.content = vec![ToolCallContent::Diff(Diff::new(
+            PathBuf::from("src/lib.rs"),
+            "fn main() {}".to_string(),
+        ))];
+        assert_eq!(
+            PanelIntent::for_tool_call(&edit),
+            Some(PanelIntent::OpenFile {
+                path: PathBuf::from("src/lib.rs"),
+                line: None,
+            })
+        );
+
+        let mut execute = ToolCall::new("tc-3", "cargo test");
+        execute.kind = ToolKind::Execute;
+        assert_eq!(
+            PanelIntent::for_tool_call(&execute),
+            Some(PanelIntent::RevealTerminal { terminal_id: None })
+        );
+
+        let mut search = ToolCall::new("tc-4", "grep");
+        search.kind = ToolKind::Search;
+        assert_eq!(PanelIntent::for_tool_call(&search), None);
+    }
+
+    #[test]
+    fn tracker_emits_once_the_call_completes() {
+        let mut tracker = ToolCallTracker::default();
+        let mut execute = ToolCall::new("tc-1", "cargo build");
+        execute.kind = ToolKind::Execute;
+        execute.status = ToolCallStatus::InProgress;
+
+        assert_eq!(tracker.track(&SessionUpdate::ToolCall(execute)), None);
+        let done = SessionUpdate::ToolCallUpdate(ToolCallUpdate::new(
+            "tc-1",
+            ToolCallUpdateFields::new().status(ToolCallStatus::Completed),
+        ));
+        assert_eq!(
+            tracker.track(&done),
+            Some(PanelIntent::RevealTerminal { terminal_id: None })
+        );
+        // A repeated completion has nothing left to reveal
+        assert_eq!(tracker.track(&done), None);
+
+        let failed = SessionUpdate::ToolCallUpdate(ToolCallUpdate::new(
+            "tc-2",
+            ToolCallUpdateFields::new()
+                .kind(ToolKind::Execute)
+                .title("cargo test".to_string())
+                .status(ToolCallStatus::Failed),
+        ));
+        assert_eq!(tracker.track(&failed), None);
+        assert!(tracker.pending.is_empty());
+
+        let mut stalled = ToolCall::new("tc-3", "cargo run");
+        stalled.kind = ToolKind::Execute;
+        tracker.track(&SessionUpdate::ToolCall(stalled));
+        tracker.clear();
+        assert!(tracker.pending.is_empty());
+    }
+}
@@ -0,0 +1,346 @@
+//! Whole conversations saved by the user and reopened later, stored as one
+//! JSON file per session in `<user_data_dir>/sessions/<id>.json` (next to the
+//! live `<id>.jsonl` history written by `PersistenceService`).
+
+use std::path::{Path, PathBuf};
+
+use agent_client_protocol::{ContentBlock, SessionUpdate};
+use anyhow::{Context as _, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::components::truncate_chars;
+
+/// Format version written into every saved session
+pub const SAVED_SESSION_VERSION: u32 = 1;
+/// Characters of the first user message kept as a session title
+const MAX_TITLE_CHARS: usize = 60;
+/// Updates kept in a live transcript; past it the oldest are dropped
+pub const MAX_TRANSCRIPT_UPDATES: usize = 5_000;
+
+/// Metadata shown in the saved sessions list
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SavedSessionMeta {
+    pub id: String,
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub agent_name: Option<String>,
+    /// RFC 3339 time of the save
+    pub saved_at: String,
+    /// User and agent messages, counting a run of chunks as one message
+    #[serde(default)]
+    pub message_count: usize,
+    #[serde(default)]
+    pub version: u32,
+}
+
+/// A conversation as the panel received it
+#[derive(Clone, Debug, Serialize)]
+pub struct SavedSession {
+    pub meta: SavedSessionMeta,
+    pub updates: Vec<SessionUpdate>,
+}
+
+/// On-disk shape read leniently: updates stay raw until each is parsed on its own
+#[derive(Deserialize)]
+struct RawSavedSession {
+    meta: SavedSessionMeta,
+    #[serde(default)]
+    updates: Vec<serde_json::Value>,
+}
+
+/// Only the metadata, so listing sessions doesn't parse every update
+#[derive(Deserialize)]
+struct SavedSessionHeader {
+    meta: SavedSessionMeta,
+}
+
+impl SavedSession {
+    pub fn new(
+        id: impl Into<String>,
+        title: Option<String>,
+        agent_name: Option<String>,
+        updates: Vec<SessionUpdate>,
+    ) -> Self {
+        Self {
+            meta: SavedSessionMeta {
+                id: id.into(),
+                title,
+                agent_name,
+                saved_at: chrono::Utc::now().to_rfc3339(),
+                message_count: count_messages(&updates),
+                version: SAVED_SESSION_VERSION,
+            },
+            updates,
+        }
+    }
+
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {:?}", parent))?;
+        }
+        let json = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, json).with_context(|| format!("Failed to save session: {:?}", path))
+    }
+
+    pub fn load_from(path: &Path) -> Result<Self> {
+        let json = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read saved session: {:?}", path))?;
+        Self::from_json(&json).with_context(|| format!("Invalid saved session: {:?}", path))
+    }
+
+    /// Parse a saved session, skipping updates this build doesn't understand
+    /// (e.g. variants added by a newer protocol version) instead of failing
+    pub fn from_json(json: &str) -> Result<Self> {
+        let raw: RawSavedSession = serde_json::from_str(json)?;
+        if raw.meta.version > SAVED_SESSION_VERSION {
+            log::warn!(
+                "Saved session {} has version {}, newer than {}",
+                raw.meta.id,
+                raw.meta.version,
+                SAVED_SESSION_VERSION
+            );
+        }
+
+        let total = raw.updates.len();
+        let updates: Vec<SessionUpdate> = raw
+            .updates
+            .into_iter()
+            .filter_map(|value| serde_json::from_value(value).ok())
+            .collect();
+        if updates.len() < total {
+            log::warn!(
+                "Skipped {} unrecognized updates in saved session {}",
+                total - updates.len(),
+                raw.meta.id
+            );
+        }
+
+        Ok(Self {
+            meta: raw.meta,
+            updates,
+        })
+    }
+}
+
+/// Messages in `updates`: consecutive chunks of the same side are one message
+pub fn count_messages(updates: &[SessionUpdate]) -> usize {
+    let mut count = 0;
+    let mut previous_is_user = None;
+    for update in updates {
+        let is_user = match update {
+            SessionUpdate::UserMessageChunk(_) => true,
+            SessionUpdate::AgentMessageChunk(_) => false,
+            SessionUpdate::AgentThoughtChunk(_) => continue,
+            _ => {
+                previous_is_user = None;
+                continue;
+            }
+        };
+        if previous_is_user != Some(is_user) {
+            count += 1;
+        }
+        previous_is_user = Some(is_user);
+    }
+    count
+}
+
+/// Add `update` to a live `transcript`. Streamed agent message and thought
+/// text is merged into the chunk before it, so the transcript doesn't hold one
+/// update per token, and past [`MAX_TRANSCRIPT_UPDATES`] the oldest tenth is
+/// dropped.
+pub fn push_transcript_update(transcript: &mut Vec<SessionUpdate>, update: SessionUpdate) {
+    let merged = match (transcript.last_mut(), &update) {
+        (Some(SessionUpdate::AgentMessageChunk(last)), SessionUpdate::AgentMessageChunk(chunk))
+        | (Some(SessionUpdate::AgentThoughtChunk(last)), SessionUpdate::AgentThoughtChunk(chunk)) => {
+            match (&mut last.content, &chunk.content) {
+                (ContentBlock::Text(last), ContentBlock::Text(text)) => {
+                    last.text.push_str(&text.text);
+                    true
+                }
+                _ => false,
+            }
+        }
+        _ => false,
+    };
+    if merged {
+        return;
+    }
+    if transcript.len() >= MAX_TRANSCRIPT_UPDATES {
+        let dropped = MAX_TRANSCRIPT_UPDATES / 10;
+        log::info!(
+            "Transcript is full, dropping its {} oldest updates",
+            dropped
+        );
+        transcript.drain(..dropped);
+    }
+    transcript.push(update);
+}
+
+/// Title for a saved session: the first line of the first user message
+pub fn session_title(updates: &[SessionUpdate]) -> Option<String> {
+    updates.iter().find_map(|update| match update {
+        SessionUpdate::UserMessageChunk(chunk) => match &chunk.content {
+            ContentBlock::Text(text) => {
+                let line = text.text.lines().find(|line| !line.trim().is_empty())?;
+                let (title, truncated) = truncate_chars(line.trim(), MAX_TITLE_CHARS);
+                Some(if truncated {
+                    format!("{}…", title)
+                } else {
+                    title.to_string()
+                })
+            }
+            _ => None,
+        },
+        _ => None,
+    })
+}
+
+/// `id` as a file name stem; characters unsafe in file names become `_`
+pub(crate) fn file_name_for_id(id: &str) -> String {
+    id.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Where the session `id` is saved
+pub fn saved_session_path(dir: &Path, id: &str) -> PathBuf {
+    dir.join(format!("{}.json", file_name_for_id(id)))
+}
+
+/// Remove a saved session file
+pub fn delete_saved_session(path: &Path) -> Result<()> {
+    std::fs::remove_file(path)
+        .with_context(|| format!("Failed to delete saved session: {:?}", path))
+}
+
+/// Saved sessions in `dir`, most recently saved first. Unreadable files are skipped.
+pub fn list_saved_sessions(dir: &Path) -> Vec<(PathBuf, SavedSessionMeta)> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    let mut sessions: Vec<(PathBuf, SavedSessionMeta)> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .filter_map(|path| {
+            let json = std::fs::read_to_string(&path).ok()?;
+            match serde_json::from_str::<SavedSessionHeader>(&json) {
+                Ok(header) => Some((path, header.meta)),
+                Err(e) => {
+                    log::warn!("Ignoring saved session {:?}: {}", path, e);
+                    None
+                }
+            }
+        })
+        .collect();
+    sessions.sort_by(|(_, a), (_, b)| b.saved_at.cmp(&a.saved_at));
+    sessions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use agent_client_protocol::ContentChunk;
+
+    fn message(text: &str) -> SessionUpdate {
+        SessionUpdate::AgentMessageChunk(ContentChunk::new(ContentBlock::from(text.to_string())))
+    }
+
+    #[test]
+    fn saved_session_round_trips() {
+        let dir =
+            std::env::temp_dir().join(format!("agentx-saved-sessions-{}", std::process::id()));
+        let session = SavedSession::new(
+            "sess/1",
+            Some("Retry policy".to_string()),
+            Some("claude".to_string()),
+            vec![message("hello"), message("world")],
+        );
+        let path = saved_session_path(&dir, &session.meta.id);
+        assert_eq!(path.file_name().unwrap(), "sess_1.json");
+        session.save_to(&path).unwrap();
+
+        let loaded = SavedSession::load_from(&path).unwrap();
+        let listed = list_saved_sessions(&dir);
+        delete_saved_session(&path).unwrap();
+        let remaining = list_saved_sessions(&dir);
+        std::fs::remove_dir_all(&dir).ok();
+
+        assert_eq!(loaded.meta, session.meta);
+        assert_eq!(loaded.updates.len(), 2);
+        assert_eq!(loaded.meta.message_count, 1);
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].1.title.as_deref(), Some("Retry policy"));
+        assert!(remaining.is_empty());
+    }
+
+    #[test]
+    fn unknown_updates_are_skipped() {
+        let mut json = serde_json::to_value(SavedSession::new(
+            "sess-2",
+            None,
+            None,
+            vec![message("kept")],
+        ))
+        .unwrap();
+        json["updates"]
+            .as_array_mut()
+            .unwrap()
+            .push(serde_json::json!({ "sessionUpdate": "from_the_future", "x": 1 }));
+
+        let loaded = SavedSession::from_json(&json.to_string()).unwrap();
+        assert_eq!(loaded.updates.len(), 1);
+        assert!(SavedSession::from_json("{\"updates\": []}").is_err());
+    }
+
+    #[test]
+    fn transcripts_merge_streamed_text_and_stay_bounded() {
+        let user = |text: &str| {
+            SessionUpdate::UserMessageChunk(ContentChunk::new(ContentBlock::from(text.to_string())))
+        };
+        let text = |update: &SessionUpdate| match update {
+            SessionUpdate::UserMessageChunk(chunk) | SessionUpdate::AgentMessageChunk(chunk) => {
+                match &chunk.content {
+                    ContentBlock::Text(text) => text.text.clone(),
+                    _ => String::new(),
+                }
+            }
+            _ => String::new(),
+        };
+        let mut transcript = Vec::new();
+        for update in [user("hi"), message("Hel"), message("lo"), user("again")] {
+            push_transcript_update(&mut transcript, update);
+        }
+        assert_eq!(transcript.len(), 3);
+        assert_eq!(text(&transcript[1]), "Hello");
+        assert_eq!(count_messages(&transcript), 3);
+
+        for ix in 0..MAX_TRANSCRIPT_UPDATES {
+            push_transcript_update(&mut transcript, user(&ix.to_string()));
+        }
+        assert!(transcript.len() <= MAX_TRANSCRIPT_UPDATES);
+        let last = (MAX_TRANSCRIPT_UPDATES - 1).to_string();
+        assert_eq!(transcript.last().map(text), Some(last));
+    }
+
+    #[test]
+    fn title_comes_from_first_user_message() {
+        let user = SessionUpdate::UserMessageChunk(ContentChunk::new(ContentBlock::from(
+            "\n  Add retries to the client  \nwith backoff".to_string(),
+        )));
+        assert_eq!(
+            session_title(&[message("hi"), user]).as_deref(),
+            Some("Add retries to the client")
+        );
+        assert_eq!(session_title(&[message("hi")]), None);
+    }
+}
@@ -0,0 +1,249 @@
+//! Full-text search over saved sessions. The text of each session is kept in
+//! memory and only re-read when its file changes, so searching as the user
+//! types costs a directory listing rather than parsing every session.
+
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+use std::path::PathBuf;
+use std::sync::{LazyLock, Mutex};
+use std::time::SystemTime;
+
+use agent_client_protocol::SessionUpdate;
+
+use super::saved_sessions::{SavedSession, SavedSessionMeta};
+use crate::components::{ContentRenderers, find_matches};
+use crate::core::config_manager::get_sessions_dir;
+
+/// Characters of context kept on each side of a match in its snippet
+const SNIPPET_CONTEXT_CHARS: usize = 40;
+
+/// Index over the sessions directory, shared by every caller of `search_sessions`
+static SESSION_INDEX: LazyLock<Mutex<SessionSearchIndex>> =
+    LazyLock::new(|| Mutex::new(SessionSearchIndex::new(get_sessions_dir())));
+
+/// A saved session containing the query
+#[derive(Clone, Debug, PartialEq)]
+pub struct SessionSearchHit {
+    pub path: PathBuf,
+    pub meta: SavedSessionMeta,
+    /// Text around the first match, on one line
+    pub snippet: String,
+    /// Byte range of the match in `snippet`
+    pub highlight: Range<usize>,
+    /// Matches in the whole session
+    pub match_count: usize,
+}
+
+struct IndexedSession {
+    modified: Option<SystemTime>,
+    meta: SavedSessionMeta,
+    text: String,
+}
+
+/// Searchable text of the saved sessions in one directory
+pub struct SessionSearchIndex {
+    dir: PathBuf,
+    sessions: HashMap<PathBuf, IndexedSession>,
+}
+
+impl SessionSearchIndex {
+    pub fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            sessions: HashMap::new(),
+        }
+    }
+
+    /// Read sessions added or changed since the last refresh and forget
+    /// deleted ones
+    pub fn refresh(&mut self) {
+        let Ok(entries) = std::fs::read_dir(&self.dir) else {
+            self.sessions.clear();
+            return;
+        };
+
+        let mut seen = HashSet::new();
+        for path in entries.flatten().map(|entry| entry.path()) {
+            if !path.extension().is_some_and(|ext| ext == "json") {
+                continue;
+            }
+            let modified = std::fs::metadata(&path)
+                .and_then(|metadata| metadata.modified())
+                .ok();
+            seen.insert(path.clone());
+            if self
+                .sessions
+                .get(&path)
+                .is_some_and(|session| session.modified.is_some() && session.modified == modified)
+            {
+                continue;
+            }
+            match SavedSession::load_from(&path) {
+                Ok(session) => {
+                    let text = session_text(&session.updates);
+                    self.sessions.insert(
+                        path,
+                        IndexedSession {
+                            modified,
+                            meta: session.meta,
+                            text,
+                        },
+                    );
+                }
+                Err(e) => {
+                    log::warn!("Not indexing saved session: {:#}", e);
+                    self.sessions.remove(&path);
+                }
+            }
+        }
+        self.sessions.retain(|path, _| seen.contains(path));
+    }
+
+    /// Indexed sessions containing `query` (ignoring ASCII case), most
+    /// recently saved first
+    pub fn search(&self, query: &str) -> Vec<SessionSearchHit> {
+        let mut hits: Vec<SessionSearchHit> = self
+            .sessions
+            .iter()
+            .filter_map(|(path, session)| {
+                let matches = find_matches(&session.text, query);
+                let first = matches.first()?.clone();
+                let (snippet, highlight) = snippet(&session.text, first);
+                Some(SessionSearchHit {
+                    path: path.clone(),
+                    meta: session.meta.clone(),
+                    snippet,
+                    highlight,
+                    match_count: matches.len(),
+                })
+            })
+            .collect();
+        hits.sort_by(|a, b| b.meta.saved_at.cmp(&a.meta.saved_at));
+        hits
+    }
+}
+
+/// Saved sessions in the sessions directory whose messages contain `query`.
+/// Only files changed since the previous search are read.
+pub fn search_sessions(query: &str) -> Vec<SessionSearchHit> {
+    let query = query.trim();
+    if query.is_empty() {
+        return Vec::new();
+    }
+    let mut index = SESSION_INDEX
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    index.refresh();
+    index.search(query)
+}
+
+/// User and agent messages and tool call titles, one line per message
+fn session_text(updates: &[SessionUpdate]) -> String {
+    let renderers = ContentRenderers::default();
+    let mut text = String::new();
+    let mut previous_is_user = None;
+    for update in updates {
+        let (is_user, part) = match update {
+            SessionUpdate::UserMessageChunk(chunk) => (Some(true), renderers.text(&chunk.content)),
+            SessionUpdate::AgentMessageChunk(chunk) => {
+                (Some(false), renderers.text(&chunk.content))
+            }
+            SessionUpdate::ToolCall(tool_call) => (None, tool_call.title.clone()),
+            _ => continue,
+        };
+        if !text.is_empty() && (is_user.is_none() || previous_is_user != is_user) {
+            text.push('\n');
+        }
+        text.push_str(&part);
+        previous_is_user = is_user;
+    }
+    text
+}
+
+/// One line of `text` around `found`, with `…` where it was cut, and where
+/// `found` ends up in it
+fn snippet(text: &str, found: Range<usize>) -> (String, Range<usize>) {
+    let start = text[..found.start]
+        .char_indices()
+        .rev()
+        .nth(SNIPPET_CONTEXT_CHARS - 1)
+        .map_or(0, |(ix, _)| ix);
+    let end = text[found.end..]
+        .char_indices()
+        .nth(SNIPPET_CONTEXT_CHARS)
+        .map_or(text.len(), |(ix, _)| found.end + ix);
+
+    let prefix = if start > 0 { "…" } else { "" };
+    let suffix = if end < text.len() { "…" } else { "" };
+    // Newlines and spaces are both one byte, so `found` keeps its offsets
+    let line = text[start..end].replace(['\n', '\r'], " ");
+    let highlight_start = prefix.len() + found.start - start;
+    (
+        format!("{}{}{}", prefix, line, suffix),
+        highlight_start..highlight_start + found.len(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use agent_client_protocol::{ContentBlock, ContentChunk};
+
+    fn user(text: &str) -> SessionUpdate {
+        SessionUpdate::UserMessageChunk(ContentChunk::new(ContentBlock::from(text.to_string())))
+    }
+
+    fn agent(text: &str) -> SessionUpdate {
+        SessionUpdate::AgentMessageChunk(ContentChunk::new(ContentBlock::from(text.to_string())))
+    }
+
+    fn snippet_of(text: &str, query: &str) -> (String, Range<usize>) {
+        snippet(text, find_matches(text, query)[0].clone())
+    }
+
+    #[test]
+    fn snippets_are_cut_around_the_match() {
+        let text = format!("{}\nretry with backoff\n{}", "é".repeat(60), "z".repeat(60));
+        let (snippet, highlight) = snippet_of(&text, "BACKOFF");
+
+        assert_eq!(&snippet[highlight], "backoff");
+        assert!(snippet.starts_with('…') && snippet.ends_with('…'));
+        assert!(!snippet.contains('\n'));
+        assert_eq!(
+            snippet.chars().count(),
+            2 * SNIPPET_CONTEXT_CHARS + "backoff".len() + 2
+        );
+
+        let (whole, highlight) = snippet_of("short text", "text");
+        assert_eq!(whole, "short text");
+        assert_eq!(highlight, 6..10);
+    }
+
+    #[test]
+    fn messages_are_joined_per_speaker() {
+        let text = session_text(&[user("Add "), user("retries"), agent("Done"), agent(".")]);
+        assert_eq!(text, "Add retries\nDone.");
+    }
+
+    #[test]
+    fn index_follows_the_directory() {
+        let dir =
+            std::env::temp_dir().join(format!("agentx-session-search-{}", std::process::id()));
+        let mut index = SessionSearchIndex::new(dir.clone());
+        let first = dir.join("a.json");
+        SavedSession::new("a", None, None, vec![user("flaky test in ci")])
+            .save_to(&first)
+            .unwrap();
+
+        index.refresh();
+        let hits = index.search("FLAKY");
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].meta.id, "a");
+        assert_eq!(hits[0].match_count, 1);
+
+        std::fs::remove_file(&first).unwrap();
+        index.refresh();
+        assert!(index.search("flaky").is_empty());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -15,8 +15,8 @@ use serde::{Deserialize, Serialize};
 
 use crate::AppState;
 use crate::panels::{
-    CodeEditorPanel, ConversationPanel, SessionManagerPanel, SettingsPanel, TaskPanel,
-    TerminalPanel, ToolCallDetailPanel, WelcomePanel,
+    CodeEditorPanel, ConversationPanel, SavedSessionsPanel, SessionManagerPanel, SettingsPanel,
+    TaskPanel, TerminalOutputPanel, TerminalPanel, ToolCallDetailPanel, WelcomePanel,
 };
 use crate::{ShowPanelInfo, ToggleSearch};
 
@@ -266,11 +266,34 @@ impl DockPanelContainer {
         session_id: String,
         window: &mut Window,
         cx: &mut App,
+    ) -> Entity<Self> {
+        let agent_studio = ConversationPanel::view_for_session(session_id, window, cx);
+        Self::conversation_container(agent_studio, cx)
+    }
+
+    /// Create a ConversationPanel showing a conversation saved to `path`
+    pub fn panel_for_saved_session(
+        path: std::path::PathBuf,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Entity<Self> {
+        let agent_studio = ConversationPanel::view_for_saved_session(path, window, cx);
+        Self::conversation_container(agent_studio, cx)
+    }
+
+    /// Create a ConversationPanel showing the bundled demo conversation
+    pub fn panel_for_demo(window: &mut Window, cx: &mut App) -> Entity<Self> {
+        let agent_studio = ConversationPanel::demo(window, cx);
+        Self::conversation_container(agent_studio, cx)
+    }
+
+    fn conversation_container(
+        agent_studio: Entity<ConversationPanel>,
+        cx: &mut App,
     ) -> Entity<Self> {
         let name = ConversationPanel::title();
         let title_key = ConversationPanel::title_key();
         let description = ConversationPanel::description();
-        let agent_studio = ConversationPanel::view_for_session(session_id, window, cx);
         let agent_studio_klass = ConversationPanel::klass();
 
         let view = cx.new(|cx| {
@@ -425,8 +448,10 @@ impl DockPanelContainer {
         match agent_state.agent_studio_klass.as_ref() {
             "TaskPanel" => Self::panel::<TaskPanel>(window, cx),
             "SessionManagerPanel" => Self::panel::<SessionManagerPanel>(window, cx),
+            "SavedSessionsPanel" => Self::panel::<SavedSessionsPanel>(window, cx),
             "SettingsPanel" => Self::panel::<SettingsPanel>(window, cx),
             "ToolCallDetailPanel" => Self::panel::<ToolCallDetailPanel>(window, cx),
+            "TerminalOutputPanel" => Self::panel::<TerminalOutputPanel>(window, cx),
             "ConversationPanel" => {
                 if let Some(session_id) = agent_state
                     .session_id
@@ -503,11 +528,35 @@ impl DockPanelContainer {
     ) -> Entity<Self> {
         use crate::CodeEditorPanel;
 
+        let agent_studio =
+            CodeEditorPanel::view_with_working_dir(window, Some(working_directory), cx);
+        Self::code_editor_container(agent_studio, cx)
+    }
+
+    /// 创建代码编辑器面板并打开文件，光标定位到 `position`
+    pub fn panel_for_code_editor_at(
+        working_directory: std::path::PathBuf,
+        path: std::path::PathBuf,
+        position: lsp_types::Position,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Entity<Self> {
+        use crate::CodeEditorPanel;
+
+        let agent_studio =
+            CodeEditorPanel::view_for_file(window, working_directory, path, position, cx);
+        Self::code_editor_container(agent_studio, cx)
+    }
+
+    fn code_editor_container(
+        agent_studio: Entity<crate::CodeEditorPanel>,
+        cx: &mut App,
+    ) -> Entity<Self> {
+        use crate::CodeEditorPanel;
+
         let name = CodeEditorPanel::title();
         let title_key = CodeEditorPanel::title_key();
         let description = CodeEditorPanel::description();
-        let agent_studio =
-            CodeEditorPanel::view_with_working_dir(window, Some(working_directory), cx);
         let agent_studio_klass = CodeEditorPanel::klass();
 
         let view = cx.new(|cx| {
@@ -596,6 +645,9 @@ pub struct DockPanelState {
     pub workspace_name: Option<String>,
     #[serde(default)]
     pub working_directory: Option<String>,
+    /// File open in a code editor
+    #[serde(default)]
+    pub file_path: Option<String>,
 }
 
 impl DockPanelState {
@@ -606,6 +658,7 @@ impl DockPanelState {
             "workspace_id": self.workspace_id,
             "workspace_name": self.workspace_name,
             "working_directory": self.working_directory,
+            "file_path": self.file_path,
         })
     }
 
@@ -622,13 +675,24 @@ impl Panel for DockPanelContainer {
     fn title(
         &mut self,
         _window: &mut gpui::Window,
-        _cx: &mut gpui::Context<'_, DockPanelContainer>,
+        cx: &mut gpui::Context<'_, DockPanelContainer>,
     ) -> impl gpui::IntoElement {
         let title = if let Some(key) = &self.title_key {
             SharedString::from(t!(key.as_ref()).to_string())
         } else {
             self.name.clone()
         };
+
+        // Code editors opened on a folder show the folder name next to the title
+        let folder_name = self
+            .agent_studio
+            .clone()
+            .and_then(|view| view.downcast::<CodeEditorPanel>().ok())
+            .and_then(|entity| entity.read(cx).workspace_name());
+        let title = match folder_name {
+            Some(folder_name) => SharedString::from(format!("{} - {}", title, folder_name)),
+            None => title,
+        };
         title.into_any_element()
     }
 
@@ -717,6 +781,7 @@ impl Panel for DockPanelContainer {
         let mut workspace_id = None;
         let mut workspace_name = None;
         let mut working_directory = None;
+        let mut file_path = None;
 
         // Helper function to normalize Windows paths (remove \\?\ prefix)
         fn normalize_path(path: std::path::PathBuf) -> String {
@@ -754,6 +819,7 @@ impl Panel for DockPanelContainer {
                         workspace_id = panel.workspace_id();
                         workspace_name = panel.workspace_name();
                         working_directory = Some(normalize_path(panel.working_directory()));
+                        file_path = panel.current_file_path().cloned().map(normalize_path);
                     }
                 }
                 "TerminalPanel" => {
@@ -774,6 +840,7 @@ impl Panel for DockPanelContainer {
             workspace_id,
             workspace_name,
             working_directory,
+            file_path,
         };
         state.info = PanelInfo::panel(agent_state.to_value());
         state
@@ -0,0 +1,610 @@
+//! Saved conversations from `<user_data_dir>/sessions/`, grouped by age,
+//! searchable, and reopened in a conversation panel when clicked.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use chrono::{DateTime, Local};
+use gpui::{
+    AnyView, App, AppContext, Context, Entity, FocusHandle, Focusable, HighlightStyle,
+    InteractiveElement, IntoElement, ParentElement, Render, SharedString,
+    StatefulInteractiveElement, Styled, StyledText, Subscription, Task, Window, div,
+    prelude::FluentBuilder,
+};
+use gpui_component::{
+    ActiveTheme, Icon, IconName, Sizable, StyledExt, WindowExt as _,
+    button::{Button, ButtonVariant, ButtonVariants},
+    dialog::DialogButtonProps,
+    h_flex,
+    input::{Input, InputEvent, InputState},
+    label::Label,
+    scroll::ScrollableElement as _,
+    v_flex,
+};
+use rust_i18n::t;
+
+use crate::PanelAction;
+use crate::core::config_manager::get_sessions_dir;
+use crate::panels::conversation::saved_sessions::{
+    SavedSessionMeta, delete_saved_session, list_saved_sessions,
+};
+use crate::panels::conversation::session_search::{SessionSearchHit, search_sessions};
+use crate::panels::dock_panel::DockPanel;
+use crate::utils::fuzzy::fuzzy_match;
+use crate::utils::time::format_time_friendly;
+
+/// Pause in typing before session contents are searched
+const CONTENT_SEARCH_DEBOUNCE: Duration = Duration::from_millis(250);
+
+/// Section a saved session is listed under
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SavedSessionAge {
+    Today,
+    ThisWeek,
+    Older,
+}
+
+impl SavedSessionAge {
+    fn of(saved_at: &str, now: DateTime<Local>) -> Self {
+        let Ok(saved_at) = DateTime::parse_from_rfc3339(saved_at) else {
+            return Self::Older;
+        };
+        let saved_on = saved_at.with_timezone(&Local).date_naive();
+        let days = (now.date_naive() - saved_on).num_days();
+        match days {
+            ..=0 => Self::Today,
+            1..=6 => Self::ThisWeek,
+            _ => Self::Older,
+        }
+    }
+
+    fn title(self) -> String {
+        match self {
+            Self::Today => t!("saved_sessions.section.today"),
+            Self::ThisWeek => t!("saved_sessions.section.this_week"),
+            Self::Older => t!("saved_sessions.section.older"),
+        }
+        .to_string()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SavedSessionSection {
+    pub age: SavedSessionAge,
+    pub sessions: Vec<(PathBuf, SavedSessionMeta)>,
+}
+
+/// Sessions matching `query` (title, agent or id), grouped by age. Input is
+/// expected newest first and that order is kept within each section.
+pub fn group_saved_sessions(
+    sessions: &[(PathBuf, SavedSessionMeta)],
+    query: &str,
+    now: DateTime<Local>,
+) -> Vec<SavedSessionSection> {
+    let mut sections: Vec<SavedSessionSection> = Vec::new();
+    for (path, meta) in sessions {
+        let matches = query.is_empty()
+            || [
+                meta.title.as_deref(),
+                meta.agent_name.as_deref(),
+                Some(meta.id.as_str()),
+            ]
+            .into_iter()
+            .flatten()
+            .any(|text| fuzzy_match(query, text).is_some());
+        if !matches {
+            continue;
+        }
+
+        let age = SavedSessionAge::of(&meta.saved_at, now);
+        let entry = (path.clone(), meta.clone());
+        match sections.iter_mut().find(|section| section.age == age) {
+            Some(section) => section.sessions.push(entry),
+            None => sections.push(SavedSessionSection {
+                age,
+                sessions: vec![entry],
+            }),
+        }
+    }
+    sections.sort_by_key(|section| section.age as u8);
+    sections
+}
+
+/// Saved Sessions Panel - browse, search, reopen and delete saved conversations
+pub struct SavedSessionsPanel {
+    focus_handle: FocusHandle,
+    search_input: Entity<InputState>,
+    /// Newest first
+    sessions: Vec<(PathBuf, SavedSessionMeta)>,
+    is_loading: bool,
+    /// Sessions whose messages contain the query
+    content_hits: Vec<SessionSearchHit>,
+    _content_search: Task<()>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl DockPanel for SavedSessionsPanel {
+    fn title() -> &'static str {
+        "Saved Sessions"
+    }
+
+    fn title_key() -> Option<&'static str> {
+        Some("saved_sessions.title")
+    }
+
+    fn description() -> &'static str {
+        "Saved conversations"
+    }
+
+    fn new_view(window: &mut Window, cx: &mut App) -> Entity<impl Render> {
+        Self::view(window, cx)
+    }
+
+    fn on_active_any(view: AnyView, active: bool, _window: &mut Window, cx: &mut App) {
+        // Pick up sessions saved from a conversation panel since the last visit
+        if !active {
+            return;
+        }
+        if let Ok(panel) = view.downcast::<Self>() {
+            panel.update(cx, |panel, cx| panel.reload(cx));
+        }
+    }
+}
+
+impl SavedSessionsPanel {
+    pub fn view(window: &mut Window, cx: &mut App) -> Entity<Self> {
+        cx.new(|cx| Self::new(window, cx))
+    }
+
+    fn new(window: &mut Window, cx: &mut Context<Self>) -> Self {
+        let search_input = cx.new(|cx| InputState::new(window, cx));
+        let search_subscription =
+            cx.subscribe(&search_input, |this, _input, event: &InputEvent, cx| {
+                if let InputEvent::Change = event {
+                    this.search_contents(cx);
+                }
+                cx.notify();
+            });
+
+        let mut panel = Self {
+            focus_handle: cx.focus_handle(),
+            search_input,
+            sessions: Vec::new(),
+            is_loading: false,
+            content_hits: Vec::new(),
+            _content_search: Task::ready(()),
+            _subscriptions: vec![search_subscription],
+        };
+        panel.reload(cx);
+        panel
+    }
+
+    /// Re-read the sessions directory off the main thread
+    fn reload(&mut self, cx: &mut Context<Self>) {
+        self.is_loading = true;
+        cx.spawn(async move |this, cx| {
+            let sessions = cx
+                .background_spawn(async move { list_saved_sessions(&get_sessions_dir()) })
+                .await;
+            let _ = this.update(cx, |this, cx| {
+                this.sessions = sessions;
+                this.is_loading = false;
+                cx.notify();
+            });
+        })
+        .detach();
+    }
+
+    /// Search the messages of every saved session shortly after the last
+    /// keystroke; a newer query replaces the pending one
+    fn search_contents(&mut self, cx: &mut Context<Self>) {
+        let query = self.search_input.read(cx).text().to_string();
+        if query.trim().is_empty() {
+            self.content_hits.clear();
+            self._content_search = Task::ready(());
+            return;
+        }
+
+        self._content_search = cx.spawn(async move |this, cx| {
+            smol::Timer::after(CONTENT_SEARCH_DEBOUNCE).await;
+            let hits = cx
+                .background_spawn(async move { search_sessions(&query) })
+                .await;
+            let _ = this.update(cx, |this, cx| {
+                this.content_hits = hits;
+                cx.notify();
+            });
+        });
+    }
+
+    fn open_session(&self, path: PathBuf, window: &mut Window, cx: &mut Context<Self>) {
+        window.dispatch_action(Box::new(PanelAction::open_saved_conversation(path)), cx);
+    }
+
+    fn confirm_delete(
+        &self,
+        path: PathBuf,
+        title: String,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let panel = cx.entity().downgrade();
+        window.open_dialog(cx, move |dialog, _window, _cx| {
+            let path = path.clone();
+            let panel = panel.clone();
+            dialog
+                .title(t!("saved_sessions.dialog.delete.title").to_string())
+                .confirm()
+                .button_props(
+                    DialogButtonProps::default()
+                        .ok_text(t!("saved_sessions.dialog.delete.ok").to_string())
+                        .ok_variant(ButtonVariant::Danger)
+                        .cancel_text(t!("saved_sessions.dialog.cancel").to_string()),
+                )
+                .on_ok(move |_, _window, cx| {
+                    let path = path.clone();
+                    let _ = panel.update(cx, |this, cx| this.delete_session(path, cx));
+                    true
+                })
+                .child(
+                    v_flex().w_full().gap_2().p_4().child(
+                        Label::new(
+                            t!("saved_sessions.dialog.delete.message", title = title).to_string(),
+                        )
+                        .text_sm(),
+                    ),
+                )
+        });
+    }
+
+    fn delete_session(&mut self, path: PathBuf, cx: &mut Context<Self>) {
+        cx.spawn(async move |this, cx| {
+            let result = cx
+                .background_spawn({
+                    let path = path.clone();
+                    async move { delete_saved_session(&path) }
+                })
+                .await;
+            match result {
+                Ok(()) => {
+                    let _ = this.update(cx, |this, cx| {
+                        this.sessions.retain(|(p, _)| *p != path);
+                        this.content_hits.retain(|hit| hit.path != path);
+                        cx.notify();
+                    });
+                }
+                Err(e) => log::error!("{:#}", e),
+            }
+        })
+        .detach();
+    }
+
+    fn render_header(&self, cx: &Context<Self>) -> impl IntoElement {
+        let theme = cx.theme();
+
+        h_flex()
+            .w_full()
+            .gap_2()
+            .items_center()
+            .px_3()
+            .py_3()
+            .border_b_1()
+            .border_color(theme.border)
+            .child(
+                div().flex_1().child(
+                    Input::new(&self.search_input)
+                        .small()
+                        .cleanable(true)
+                        .prefix(
+                            Icon::new(IconName::Search)
+                                .size_4()
+                                .text_color(theme.muted_foreground),
+                        ),
+                ),
+            )
+            .child(
+                Button::new("saved-sessions-reload")
+                    .icon(IconName::Redo)
+                    .ghost()
+                    .xsmall()
+                    .loading(self.is_loading)
+                    .on_click(cx.listener(|this, _, _, cx| this.reload(cx))),
+            )
+    }
+
+    fn render_section(
+        &self,
+        section: &SavedSessionSection,
+        cx: &Context<Self>,
+    ) -> impl IntoElement {
+        let theme = cx.theme();
+
+        v_flex()
+            .w_full()
+            .child(
+                div()
+                    .px_3()
+                    .py_2()
+                    .text_sm()
+                    .font_medium()
+                    .text_color(theme.foreground)
+                    .child(section.age.title()),
+            )
+            .children(
+                section
+                    .sessions
+                    .iter()
+                    .map(|(path, meta)| self.render_session(path, meta, cx)),
+            )
+    }
+
+    fn render_session(
+        &self,
+        path: &Path,
+        meta: &SavedSessionMeta,
+        cx: &Context<Self>,
+    ) -> impl IntoElement {
+        let theme = cx.theme();
+        let title = meta
+            .title
+            .clone()
+            .unwrap_or_else(|| t!("conversation.untitled_session").to_string());
+        let saved_at = DateTime::parse_from_rfc3339(&meta.saved_at)
+            .map(|time| format_time_friendly(&time))
+            .unwrap_or_default();
+        let details = [
+            meta.agent_name.clone(),
+            Some(saved_at),
+            Some(t!("saved_sessions.messages", count = meta.message_count).to_string()),
+        ]
+        .into_iter()
+        .flatten()
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(" · ");
+        let id = path.to_string_lossy().to_string();
+        let open_path = path.to_path_buf();
+        let delete_path = path.to_path_buf();
+        let delete_title = title.clone();
+
+        h_flex()
+            .id(SharedString::from(id.clone()))
+            .w_full()
+            .items_center()
+            .gap_2()
+            .px_3()
+            .py_1p5()
+            .cursor_pointer()
+            .hover(|this| this.bg(theme.accent.opacity(0.3)))
+            .on_click(cx.listener(move |this, _, window, cx| {
+                this.open_session(open_path.clone(), window, cx);
+            }))
+            .child(
+                v_flex()
+                    .flex_1()
+                    .min_w_0()
+                    .child(
+                        div()
+                            .text_sm()
+                            .text_color(theme.foreground)
+                            .truncate()
+                            .child(title),
+                    )
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(theme.muted_foreground)
+                            .truncate()
+                            .child(details),
+                    ),
+            )
+            .child(
+                Button::new(SharedString::from(format!("delete-{}", id)))
+                    .icon(Icon::new(crate::assets::Icon::Trash2))
+                    .ghost()
+                    .xsmall()
+                    .on_click(cx.listener(move |this, _, window, cx| {
+                        cx.stop_propagation();
+                        this.confirm_delete(delete_path.clone(), delete_title.clone(), window, cx);
+                    })),
+            )
+    }
+
+    fn render_content_hits(&self, cx: &Context<Self>) -> impl IntoElement {
+        let theme = cx.theme();
+
+        v_flex()
+            .w_full()
+            .child(
+                div()
+                    .px_3()
+                    .py_2()
+                    .text_sm()
+                    .font_medium()
+                    .text_color(theme.foreground)
+                    .child(t!("saved_sessions.section.contents").to_string()),
+            )
+            .children(self.content_hits.iter().map(|hit| {
+                let title = hit
+                    .meta
+                    .title
+                    .clone()
+                    .unwrap_or_else(|| t!("conversation.untitled_session").to_string());
+                let open_path = hit.path.clone();
+                let highlight = HighlightStyle {
+                    background_color: Some(theme.yellow.opacity(0.4)),
+                    ..Default::default()
+                };
+
+                v_flex()
+                    .id(SharedString::from(format!(
+                        "content-hit-{}",
+                        hit.path.to_string_lossy()
+                    )))
+                    .w_full()
+                    .px_3()
+                    .py_1p5()
+                    .cursor_pointer()
+                    .hover(|this| this.bg(theme.accent.opacity(0.3)))
+                    .on_click(cx.listener(move |this, _, window, cx| {
+                        this.open_session(open_path.clone(), window, cx);
+                    }))
+                    .child(
+                        h_flex()
+                            .w_full()
+                            .gap_2()
+                            .child(
+                                div()
+                                    .flex_1()
+                                    .min_w_0()
+                                    .text_sm()
+                                    .text_color(theme.foreground)
+                                    .truncate()
+                                    .child(title),
+                            )
+                            .child(
+                                div()
+                                    .flex_none()
+                                    .text_xs()
+                                    .text_color(theme.muted_foreground)
+                                    .child(
+                                        t!("saved_sessions.matches", count = hit.match_count)
+                                            .to_string(),
+                                    ),
+                            ),
+                    )
+                    .child(
+                        div()
+                            .text_xs()
+                            .text_color(theme.muted_foreground)
+                            .truncate()
+                            .child(
+                                StyledText::new(hit.snippet.clone())
+                                    .with_highlights([(hit.highlight.clone(), highlight)]),
+                            ),
+                    )
+            }))
+    }
+}
+
+impl Focusable for SavedSessionsPanel {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for SavedSessionsPanel {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let query = self.search_input.read(cx).text().to_string();
+        let sections = group_saved_sessions(&self.sessions, query.trim(), Local::now());
+        let theme = cx.theme();
+
+        v_flex()
+            .id("saved-sessions-panel")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .child(self.render_header(cx))
+            .child(
+                v_flex()
+                    .id("saved-sessions-list")
+                    .flex_1()
+                    .min_h_0()
+                    .overflow_y_scrollbar()
+                    .when(
+                        sections.is_empty() && self.content_hits.is_empty() && !self.is_loading,
+                        |this| {
+                            this.child(
+                                div()
+                                    .p_4()
+                                    .text_sm()
+                                    .text_color(theme.muted_foreground)
+                                    .child(if query.trim().is_empty() {
+                                        t!("saved_sessions.empty").to_string()
+                                    } else {
+                                        t!("saved_sessions.no_matches").to_string()
+                                    }),
+                            )
+                        },
+                    )
+                    .children(
+                        sections
+                            .iter()
+                            .map(|section| self.render_section(section, cx)),
+                    )
+                    .when(!self.content_hits.is_empty(), |this| {
+                        this.child(self.render_content_hits(cx))
+                    }),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    fn saved(
+        id: &str,
+        title: &str,
+        agent: &str,
+        saved_at: DateTime<Local>,
+    ) -> (PathBuf, SavedSessionMeta) {
+        (
+            PathBuf::from(format!("/tmp/{id}.json")),
+            SavedSessionMeta {
+                id: id.to_string(),
+                title: Some(title.to_string()),
+                agent_name: Some(agent.to_string()),
+                saved_at: saved_at.to_rfc3339(),
+                message_count: 2,
+                version: 1,
+            },
+        )
+    }
+
+    #[test]
+    fn sessions_are_grouped_by_age() {
+        let now = Local.with_ymd_and_hms(2026, 3, 12, 15, 0, 0).unwrap();
+        let sessions = vec![
+            saved("a", "Retry policy", "claude", now - Duration::hours(1)),
+            saved("b", "Flaky test", "codex", now - Duration::days(3)),
+            saved("c", "Release notes", "claude", now - Duration::days(30)),
+            saved("d", "Lint fixes", "codex", now - Duration::days(4)),
+        ];
+
+        let sections = group_saved_sessions(&sessions, "", now);
+        let ages: Vec<SavedSessionAge> = sections.iter().map(|s| s.age).collect();
+        assert_eq!(
+            ages,
+            [
+                SavedSessionAge::Today,
+                SavedSessionAge::ThisWeek,
+                SavedSessionAge::Older
+            ]
+        );
+        let this_week: Vec<&str> = sections[1]
+            .sessions
+            .iter()
+            .map(|(_, meta)| meta.id.as_str())
+            .collect();
+        assert_eq!(this_week, ["b", "d"]);
+    }
+
+    #[test]
+    fn search_matches_title_and_agent() {
+        let now = Local.with_ymd_and_hms(2026, 3, 12, 15, 0, 0).unwrap();
+        let sessions = vec![
+            saved("a", "Retry policy", "claude", now),
+            saved("b", "Flaky test", "codex", now),
+        ];
+
+        let by_title = group_saved_sessions(&sessions, "retry", now);
+        assert_eq!(by_title[0].sessions.len(), 1);
+        assert_eq!(by_title[0].sessions[0].1.id, "a");
+
+        let by_agent = group_saved_sessions(&sessions, "codex", now);
+        assert_eq!(by_agent[0].sessions[0].1.id, "b");
+
+        assert!(group_saved_sessions(&sessions, "zzz", now).is_empty());
+    }
+}
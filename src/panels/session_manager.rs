@@ -15,6 +15,7 @@ use gpui_component::{
 
 use crate::{
     AppState,
+    components::truncate_chars,
     core::services::{AgentSessionInfo, SessionStatus},
     panels::dock_panel::DockPanel,
 };
@@ -631,11 +632,7 @@ impl Render for SessionManagerPanel {
                                                         let session_id_for_close = session_id.clone();
                                                         let session_id_for_open = session_id.clone();
                                                         let status_color = self.status_color(&session.status, cx);
-                                                        let short_id = if session_id.len() > 12 {
-                                                            &session_id[..12]
-                                                        } else {
-                                                            &session_id
-                                                        };
+                                                        let short_id = truncate_chars(&session_id, 12).0;
                                                         // Create unique button ID from indices
                                                         let btn_id = agent_idx * 1000 + session_idx;
 
@@ -746,11 +743,7 @@ impl Render for SessionManagerPanel {
                                                         .gap_2()
                                                         .children(state.sessions.iter().enumerate().map(|(session_idx, session)| {
                                                             let session_id = session.session_id.to_string();
-                                                            let short_id = if session_id.len() > 12 {
-                                                                &session_id[..12]
-                                                            } else {
-                                                                &session_id
-                                                            };
+                                                            let short_id = truncate_chars(&session_id, 12).0;
                                                             let title = session.title.clone().unwrap_or_else(|| "Untitled".to_string());
                                                             let updated_at = session
                                                                 .updated_at
@@ -8,6 +8,11 @@ use gpui_component::{
 };
 use rust_i18n::t;
 
+use agentx_acp_ui::{DEFAULT_UI_SCALE, MAX_UI_SCALE, MIN_UI_SCALE};
+
+use crate::themes::set_ui_scale;
+use crate::{CodeWrap, GroupToolCalls, UiScale};
+
 use super::panel::SettingsPanel;
 use super::types::AppSettings;
 
@@ -66,6 +71,51 @@ impl SettingsPanel {
                         .description(
                             t!("settings.general.appearance.resettable.description").to_string(),
                         ),
+                        SettingItem::new(
+                            t!("settings.general.appearance.code_wrap.label").to_string(),
+                            SettingField::switch(
+                                |cx: &App| CodeWrap::global(cx),
+                                |val: bool, cx: &mut App| {
+                                    cx.set_global(CodeWrap(val));
+                                    cx.refresh_windows();
+                                },
+                            )
+                            .default_value(true),
+                        )
+                        .description(
+                            t!("settings.general.appearance.code_wrap.description").to_string(),
+                        ),
+                        SettingItem::new(
+                            t!("settings.general.appearance.ui_scale.label").to_string(),
+                            SettingField::number_input(
+                                NumberFieldOptions {
+                                    min: MIN_UI_SCALE as f64,
+                                    max: MAX_UI_SCALE as f64,
+                                    ..Default::default()
+                                },
+                                |cx: &App| UiScale::global(cx) as f64,
+                                |val: f64, cx: &mut App| set_ui_scale(val as f32, cx),
+                            )
+                            .default_value(DEFAULT_UI_SCALE as f64),
+                        )
+                        .description(
+                            t!("settings.general.appearance.ui_scale.description").to_string(),
+                        ),
+                        SettingItem::new(
+                            t!("settings.general.appearance.group_tool_calls.label").to_string(),
+                            SettingField::switch(
+                                |cx: &App| GroupToolCalls::global(cx),
+                                |val: bool, cx: &mut App| {
+                                    cx.set_global(GroupToolCalls(val));
+                                    cx.refresh_windows();
+                                },
+                            )
+                            .default_value(false),
+                        )
+                        .description(
+                            t!("settings.general.appearance.group_tool_calls.description")
+                                .to_string(),
+                        ),
                         SettingItem::new(
                             t!("settings.general.appearance.group_variant.label").to_string(),
                             SettingField::dropdown(
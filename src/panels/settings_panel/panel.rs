@@ -188,6 +188,8 @@ impl SettingsPanel {
             AgentConfigEvent::AgentRemoved { name } => {
                 self.cached_agents.remove(name);
             }
+            // Connection state isn't part of the cached configuration
+            AgentConfigEvent::AgentStatusChanged { .. } => {}
 
             // Model events
             AgentConfigEvent::ModelAdded { name, config } => {
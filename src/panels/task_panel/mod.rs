@@ -1,3 +1,4 @@
 mod panel;
+mod pins;
 
-pub use panel::TaskPanel;
+pub use panel::{BatchRunRequested, TaskPanel};
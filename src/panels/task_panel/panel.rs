@@ -4,15 +4,18 @@
 //! - Expandable/collapsible workspace groups
 //! - Task items with status indicators
 //! - Tree view (by workspace) and timeline view (by date)
+//! - Multi-select (checkbox, Cmd/Ctrl-click, Shift-click) and batch run
+//! - Favorites and Recent sections pinned above the workspace groups
 
 use gpui::{
-    App, AppContext, ClickEvent, Context, Entity, FocusHandle, Focusable, InteractiveElement,
-    IntoElement, MouseButton, ParentElement, Pixels, Render, SharedString,
+    App, AppContext, ClickEvent, Context, Entity, EventEmitter, FocusHandle, Focusable,
+    InteractiveElement, IntoElement, MouseButton, ParentElement, Pixels, Render, SharedString,
     StatefulInteractiveElement, Styled, Subscription, Window, div, prelude::FluentBuilder, px,
 };
 use gpui_component::{
     ActiveTheme, Icon, IconName, Selectable, Sizable, StyledExt,
     button::{Button, ButtonGroup, ButtonVariants},
+    checkbox::Checkbox,
     dock::DockPlacement,
     h_flex,
     input::{Input, InputState},
@@ -22,14 +25,16 @@ use gpui_component::{
 };
 use rust_i18n::t;
 use smol::Timer;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::rc::Rc;
 use std::time::Duration;
 
+use super::pins::{PinnedKind, PinnedSection, TaskPins, prepare_pinned_sections};
 use crate::core::services::WorkspaceService;
 use crate::core::{event_bus::WorkspaceUpdateEvent, services::SessionStatus};
 use crate::panels::dock_panel::DockPanel;
 use crate::schemas::workspace_schema::WorkspaceTask;
+use crate::utils::fuzzy::fuzzy_match;
 use crate::{AppState, OpenSessionManager, PanelAction, StatusIndicator, utils};
 
 // ============================================================================
@@ -52,6 +57,12 @@ pub struct WorkspaceGroup {
     pub is_expanded: bool,
 }
 
+/// Emitted when the user runs the multi-selected tasks as a batch
+#[derive(Clone, Debug)]
+pub struct BatchRunRequested {
+    pub task_ids: Vec<String>,
+}
+
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
 pub enum ViewMode {
     Tree,
@@ -66,6 +77,12 @@ pub struct TaskPanel {
     focus_handle: FocusHandle,
     workspaces: Vec<WorkspaceGroup>,
     selected_task_id: Option<String>,
+    /// Tasks checked for a batch run (by task id)
+    selected_tasks: HashSet<String>,
+    /// Last task toggled into the selection, the start of a Shift-click range
+    selection_anchor: Option<String>,
+    /// Favorite and recently run tasks
+    pins: TaskPins,
     context_menu_task_id: Option<String>,
     view_mode: ViewMode,
     _subscriptions: Vec<Subscription>,
@@ -131,6 +148,9 @@ impl TaskPanel {
             focus_handle: cx.focus_handle(),
             workspaces: Vec::new(),
             selected_task_id: None,
+            selected_tasks: HashSet::new(),
+            selection_anchor: None,
+            pins: TaskPins::load(),
             context_menu_task_id: None,
             view_mode: ViewMode::Tree,
             _subscriptions: vec![search_subscription],
@@ -525,6 +545,21 @@ impl TaskPanel {
     }
 
     fn ensure_selected_task_valid(&mut self) {
+        let task_ids: HashSet<&String> = self
+            .workspaces
+            .iter()
+            .flat_map(|w| w.tasks.iter())
+            .map(|t| &t.id)
+            .collect();
+        self.selected_tasks.retain(|id| task_ids.contains(id));
+        if self
+            .selection_anchor
+            .as_ref()
+            .is_some_and(|id| !task_ids.contains(id))
+        {
+            self.selection_anchor = None;
+        }
+
         let selected_is_valid = self.selected_task_id.as_ref().is_some_and(|id| {
             self.workspaces
                 .iter()
@@ -753,6 +788,84 @@ impl TaskPanel {
         self.selected_task_id.as_deref()
     }
 
+    /// Tasks checked for a batch run
+    pub fn selected_tasks(&self) -> &HashSet<String> {
+        &self.selected_tasks
+    }
+
+    fn toggle_task_selection(&mut self, task_id: String, cx: &mut Context<Self>) {
+        if !self.selected_tasks.remove(&task_id) {
+            self.selected_tasks.insert(task_id.clone());
+        }
+        self.selection_anchor = Some(task_id);
+        cx.notify();
+    }
+
+    /// Shift-click: select every task between the anchor and `task_id` within
+    /// the same workspace, or just toggle `task_id` if there is no anchor there
+    fn select_task_range(&mut self, task_id: String, cx: &mut Context<Self>) {
+        let range = self.selection_anchor.as_deref().and_then(|anchor| {
+            let section = self.section_task_ids(&task_id, cx);
+            task_range(&section, anchor, &task_id)
+        });
+        match range {
+            Some(range) => {
+                self.selected_tasks.extend(range);
+                cx.notify();
+            }
+            None => self.toggle_task_selection(task_id, cx),
+        }
+    }
+
+    fn select_all_in_workspace(&mut self, workspace_id: &str, cx: &mut Context<Self>) {
+        if let Some(workspace) = self
+            .get_filtered_workspaces(cx)
+            .into_iter()
+            .find(|w| w.id == workspace_id)
+        {
+            self.selected_tasks
+                .extend(workspace.tasks.iter().map(|t| t.id.clone()));
+            cx.notify();
+        }
+    }
+
+    fn clear_task_selection(&mut self, cx: &mut Context<Self>) {
+        self.selected_tasks.clear();
+        self.selection_anchor = None;
+        cx.notify();
+    }
+
+    /// Visible task ids of the workspace containing `task_id`, in display order
+    fn section_task_ids(&self, task_id: &str, cx: &Context<Self>) -> Vec<String> {
+        self.get_filtered_workspaces(cx)
+            .iter()
+            .find(|w| w.tasks.iter().any(|t| t.id == task_id))
+            .map(|w| w.tasks.iter().map(|t| t.id.clone()).collect())
+            .unwrap_or_default()
+    }
+
+    /// Emit the batch-run event and open every selected task in its own panel
+    fn run_selected_tasks(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let task_ids: Vec<String> = self
+            .workspaces
+            .iter()
+            .flat_map(sorted_tasks)
+            .map(|t| t.id.clone())
+            .filter(|id| self.selected_tasks.contains(id))
+            .collect();
+        if task_ids.is_empty() {
+            return;
+        }
+
+        cx.emit(BatchRunRequested {
+            task_ids: task_ids.clone(),
+        });
+        for task_id in &task_ids {
+            self.open_task_in_new_panel(task_id, window, cx);
+        }
+        self.clear_task_selection(cx);
+    }
+
     fn set_view_mode(&mut self, mode: ViewMode, cx: &mut Context<Self>) {
         self.view_mode = mode;
         cx.notify();
@@ -766,17 +879,37 @@ impl TaskPanel {
             .and_then(|t| t.session_id.clone())
     }
 
+    fn toggle_favorite(&mut self, task_id: &str, cx: &mut Context<Self>) {
+        self.pins.toggle_favorite(task_id);
+        self.pins.save();
+        cx.notify();
+    }
+
+    /// Remember a task in the Recent section
+    fn record_task_run(&mut self, task_id: &str, cx: &mut Context<Self>) {
+        self.pins.record_run(task_id);
+        self.pins.save();
+        cx.notify();
+    }
+
     fn open_task_in_current_panel(
-        &self,
+        &mut self,
         task_id: &str,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
+        self.record_task_run(task_id, cx);
         let action = PanelAction::show_conversation(self.session_id_for_task(task_id));
         window.dispatch_action(Box::new(action), cx);
     }
 
-    fn open_task_in_new_panel(&self, task_id: &str, window: &mut Window, cx: &mut Context<Self>) {
+    fn open_task_in_new_panel(
+        &mut self,
+        task_id: &str,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.record_task_run(task_id, cx);
         match self.session_id_for_task(task_id) {
             Some(session_id) => {
                 window.dispatch_action(
@@ -860,36 +993,31 @@ impl TaskPanel {
     // Search & Filter
     // ========================================================================
 
+    /// Workspaces with their tasks in display order: newest first, or by match
+    /// score when searching
     fn get_filtered_workspaces(&self, cx: &Context<Self>) -> Vec<WorkspaceGroup> {
         let search_query = self.search_input.read(cx).text().to_string().to_lowercase();
 
         if search_query.is_empty() {
-            return self.workspaces.clone();
+            return self
+                .workspaces
+                .iter()
+                .map(|workspace| WorkspaceGroup {
+                    tasks: sorted_tasks(workspace),
+                    ..workspace.clone()
+                })
+                .collect();
         }
 
         self.workspaces
             .iter()
             .filter_map(|workspace| {
                 // Filter tasks that match the search query
-                let filtered_tasks: Vec<_> = workspace
-                    .tasks
-                    .iter()
-                    .filter(|task| {
-                        task.name.to_lowercase().contains(&search_query)
-                            || task.agent_name.to_lowercase().contains(&search_query)
-                            || task.mode.to_lowercase().contains(&search_query)
-                            || task
-                                .last_message
-                                .as_ref()
-                                .map(|msg| msg.to_lowercase().contains(&search_query))
-                                .unwrap_or(false)
-                    })
-                    .cloned()
-                    .collect();
+                let filtered_tasks = filter_tasks(&workspace.tasks, &search_query);
 
                 // Include workspace if it has matching tasks or its name matches
                 if !filtered_tasks.is_empty()
-                    || workspace.name.to_lowercase().contains(&search_query)
+                    || fuzzy_match(&search_query, &workspace.name).is_some()
                 {
                     Some(WorkspaceGroup {
                         id: workspace.id.clone(),
@@ -965,6 +1093,10 @@ impl TaskPanel {
     fn render_footer(&self, cx: &Context<Self>) -> impl IntoElement {
         let theme = cx.theme();
 
+        if !self.selected_tasks.is_empty() {
+            return self.render_selection_footer(cx).into_any_element();
+        }
+
         h_flex()
             .w_full()
             .justify_between()
@@ -1011,6 +1143,46 @@ impl TaskPanel {
                             })),
                     ),
             )
+            .into_any_element()
+    }
+
+    /// Footer shown while tasks are multi-selected: run them or clear the selection
+    fn render_selection_footer(&self, cx: &Context<Self>) -> impl IntoElement {
+        let theme = cx.theme();
+
+        h_flex()
+            .w_full()
+            .justify_between()
+            .items_center()
+            .px_3()
+            .h(px(29.))
+            .border_t_1()
+            .border_color(theme.border)
+            .child(
+                Button::new("run-selected-tasks")
+                    .primary()
+                    .small()
+                    .icon(IconName::ArrowRight)
+                    .label(
+                        t!(
+                            "task_panel.selection.run",
+                            count = self.selected_tasks.len()
+                        )
+                        .to_string(),
+                    )
+                    .on_click(cx.listener(|this, _, window, cx| {
+                        this.run_selected_tasks(window, cx);
+                    })),
+            )
+            .child(
+                Button::new("clear-task-selection")
+                    .ghost()
+                    .small()
+                    .label(t!("task_panel.selection.clear").to_string())
+                    .on_click(cx.listener(|this, _, _, cx| {
+                        this.clear_task_selection(cx);
+                    })),
+            )
     }
 
     // ========================================================================
@@ -1048,9 +1220,14 @@ impl TaskPanel {
                         ),
                 )
             })
-            // Show workspaces when not loading
+            // Show pinned sections, then workspaces, when not loading
             .when(!self.is_loading, |this| {
                 this.children(
+                    prepare_pinned_sections(&filtered_workspaces, &self.pins)
+                        .iter()
+                        .map(|section| self.render_pinned_section(section, cx)),
+                )
+                .children(
                     filtered_workspaces.iter().map(|workspace| {
                         self.render_workspace_group(workspace, entity.clone(), cx)
                     }),
@@ -1058,6 +1235,52 @@ impl TaskPanel {
             })
     }
 
+    fn render_pinned_section(
+        &self,
+        section: &PinnedSection,
+        cx: &Context<Self>,
+    ) -> impl IntoElement {
+        let theme = cx.theme();
+        let (id, icon, title) = match section.kind {
+            PinnedKind::Favorites => (
+                "pinned-favorites",
+                Icon::new(IconName::Star),
+                t!("task_panel.section.favorites"),
+            ),
+            PinnedKind::Recent => (
+                "pinned-recent",
+                Icon::new(crate::assets::Icon::ListOrdered),
+                t!("task_panel.section.recent"),
+            ),
+        };
+
+        // Own element id so rows don't share state with the same task in its workspace
+        v_flex()
+            .id(id)
+            .w_full()
+            .child(
+                h_flex()
+                    .gap_1p5()
+                    .items_center()
+                    .px_3()
+                    .py_2()
+                    .child(icon.size_4().text_color(theme.muted_foreground))
+                    .child(
+                        div()
+                            .text_sm()
+                            .font_medium()
+                            .text_color(theme.foreground)
+                            .child(title.to_string()),
+                    ),
+            )
+            .children(
+                section
+                    .tasks
+                    .iter()
+                    .map(|task| self.render_task_item(task, cx)),
+            )
+    }
+
     fn render_workspace_group(
         &self,
         workspace: &WorkspaceGroup,
@@ -1069,10 +1292,6 @@ impl TaskPanel {
         let is_expanded = workspace.is_expanded;
         let workspace_name = workspace.name.clone();
 
-        // Sort tasks by created_at descending (newest first)
-        let mut sorted_tasks = workspace.tasks.clone();
-        sorted_tasks.sort_by_key(|task| std::cmp::Reverse(task.created_at));
-
         v_flex()
             .w_full()
             // Workspace header row
@@ -1129,6 +1348,22 @@ impl TaskPanel {
                             let workspace_path = workspace_path.clone();
                             let entity = entity.clone();
                             menu = menu
+                                .item(
+                                    PopupMenuItem::new(
+                                        t!("task_panel.workspace.select_all").to_string(),
+                                    )
+                                    .icon(IconName::Check)
+                                    .on_click({
+                                        let workspace_id = workspace_id.clone();
+                                        let entity = entity.clone();
+                                        move |_, _, cx| {
+                                            entity.update(cx, |this, cx| {
+                                                this.select_all_in_workspace(&workspace_id, cx);
+                                            });
+                                        }
+                                    }),
+                                )
+                                .separator()
                                 .item(
                                     PopupMenuItem::new(
                                         t!("task_panel.workspace.open_terminal").to_string(),
@@ -1232,7 +1467,8 @@ impl TaskPanel {
             .when(is_expanded, |this| {
                 this.child(self.render_new_task_button(&workspace.id, cx))
                     .children(
-                        sorted_tasks
+                        workspace
+                            .tasks
                             .iter()
                             .map(|task| self.render_task_item(task, cx)),
                     )
@@ -1282,6 +1518,7 @@ impl TaskPanel {
         let theme = cx.theme();
         let task_id = task.id.clone();
         let is_selected = self.selected_task_id.as_ref() == Some(&task_id);
+        let is_checked = self.selected_tasks.contains(&task_id);
 
         v_flex()
             .id(SharedString::from(format!("task-{}", task_id)))
@@ -1306,6 +1543,15 @@ impl TaskPanel {
                     if !event.standard_click() {
                         return;
                     }
+                    let modifiers = event.modifiers();
+                    if modifiers.shift {
+                        this.select_task_range(task_id.clone(), cx);
+                        return;
+                    }
+                    if modifiers.secondary() {
+                        this.toggle_task_selection(task_id.clone(), cx);
+                        return;
+                    }
                     this.handle_task_click(task_id.clone(), event.click_count(), window, cx);
                 }
             }))
@@ -1331,6 +1577,21 @@ impl TaskPanel {
                             .items_center()
                             .min_w_0()
                             .flex_1()
+                            .child(
+                                Checkbox::new(SharedString::from(format!(
+                                    "task-check-{}",
+                                    task_id
+                                )))
+                                .checked(is_checked)
+                                .xsmall()
+                                .on_click(cx.listener({
+                                    let task_id = task_id.clone();
+                                    move |this, _checked, _, cx| {
+                                        cx.stop_propagation();
+                                        this.toggle_task_selection(task_id.clone(), cx);
+                                    }
+                                })),
+                            )
                             .child(StatusIndicator::new(task.status.clone()).size(8.0))
                             .child(
                                 div()
@@ -1342,12 +1603,38 @@ impl TaskPanel {
                             ),
                     )
                     .child(
-                        div()
-                            .text_xs()
-                            .text_color(theme.muted_foreground)
-                            .min_w(px(60.0)) // Fixed width to prevent layout shift
-                            .text_right()
-                            .child(self.format_relative_time(&task.created_at)),
+                        h_flex()
+                            .gap_1()
+                            .items_center()
+                            .child({
+                                let is_favorite = self.pins.is_favorite(&task_id);
+                                Button::new(SharedString::from(format!(
+                                    "task-favorite-{}",
+                                    task_id
+                                )))
+                                .icon(if is_favorite {
+                                    Icon::new(IconName::Star).text_color(theme.yellow)
+                                } else {
+                                    Icon::new(IconName::StarOff)
+                                })
+                                .ghost()
+                                .xsmall()
+                                .on_click(cx.listener({
+                                    let task_id = task_id.clone();
+                                    move |this, _, _, cx| {
+                                        cx.stop_propagation();
+                                        this.toggle_favorite(&task_id, cx);
+                                    }
+                                }))
+                            })
+                            .child(
+                                div()
+                                    .text_xs()
+                                    .text_color(theme.muted_foreground)
+                                    .min_w(px(60.0)) // Fixed width to prevent layout shift
+                                    .text_right()
+                                    .child(self.format_relative_time(&task.created_at)),
+                            ),
                     ),
             )
             // Second row: agent name + last message + status badge (aligned with task name)
@@ -1684,6 +1971,66 @@ impl TaskPanel {
     }
 }
 
+impl EventEmitter<BatchRunRequested> for TaskPanel {}
+
+/// Tasks of a workspace in display order (newest first)
+fn sorted_tasks(workspace: &WorkspaceGroup) -> Vec<Rc<WorkspaceTask>> {
+    let mut tasks = workspace.tasks.clone();
+    tasks.sort_by_key(|task| std::cmp::Reverse(task.created_at));
+    tasks
+}
+
+/// How well a task fuzzy-matches a query, as `(tier, score)`: name matches
+/// outrank type and agent matches, which outrank description and last-message
+/// matches; the fuzzy score orders matches within a tier
+fn task_match_score(task: &WorkspaceTask, query: &str) -> Option<(u8, i64)> {
+    let best = |texts: &[Option<&str>]| {
+        texts
+            .iter()
+            .flatten()
+            .filter_map(|text| fuzzy_match(query, text))
+            .map(|m| m.score)
+            .max()
+    };
+    [
+        (3, best(&[Some(task.name.as_str())])),
+        (
+            2,
+            best(&[Some(task.mode.as_str()), Some(task.agent_name.as_str())]),
+        ),
+        (
+            1,
+            best(&[task.description.as_deref(), task.last_message.as_deref()]),
+        ),
+    ]
+    .into_iter()
+    .find_map(|(tier, score)| Some((tier, score?)))
+}
+
+/// Tasks matching `query`, best match first, then newest first
+fn filter_tasks(tasks: &[Rc<WorkspaceTask>], query: &str) -> Vec<Rc<WorkspaceTask>> {
+    let mut scored: Vec<((u8, i64), Rc<WorkspaceTask>)> = tasks
+        .iter()
+        .filter_map(|task| Some((task_match_score(task, query)?, task.clone())))
+        .collect();
+    scored.sort_by_key(|(score, task)| {
+        (
+            std::cmp::Reverse(*score),
+            std::cmp::Reverse(task.created_at),
+        )
+    });
+    scored.into_iter().map(|(_, task)| task).collect()
+}
+
+/// Ids from `anchor` to `target` inclusive, in either direction; `None` if
+/// either is missing from `ordered`
+fn task_range(ordered: &[String], anchor: &str, target: &str) -> Option<Vec<String>> {
+    let anchor = ordered.iter().position(|id| id == anchor)?;
+    let target = ordered.iter().position(|id| id == target)?;
+    let (start, end) = (anchor.min(target), anchor.max(target));
+    Some(ordered[start..=end].to_vec())
+}
+
 impl Focusable for TaskPanel {
     fn focus_handle(&self, _: &App) -> FocusHandle {
         self.focus_handle.clone()
@@ -1733,3 +2080,41 @@ impl Render for TaskPanel {
             .child(self.render_footer(cx))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn task_range_covers_both_directions() {
+        let ids: Vec<String> = ["a", "b", "c", "d"].map(String::from).to_vec();
+        assert_eq!(task_range(&ids, "b", "d").unwrap(), ["b", "c", "d"]);
+        assert_eq!(task_range(&ids, "d", "b").unwrap(), ["b", "c", "d"]);
+        assert_eq!(task_range(&ids, "c", "c").unwrap(), ["c"]);
+        // Anchor in another workspace
+        assert!(task_range(&ids, "x", "b").is_none());
+    }
+
+    #[test]
+    fn search_matches_description_below_name() {
+        let task = |name: &str, description: Option<&str>| {
+            let mut task = WorkspaceTask::new(
+                "ws".to_string(),
+                name.to_string(),
+                "agent".to_string(),
+                "code".to_string(),
+            );
+            task.description = description.map(str::to_string);
+            Rc::new(task)
+        };
+        let tasks = vec![
+            task("Tidy imports", Some("Squash and rebase the git history")),
+            task("Write changelog", None),
+            task("git bisect the flaky test", None),
+        ];
+
+        let matches = filter_tasks(&tasks, "git");
+        let names: Vec<&str> = matches.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, ["git bisect the flaky test", "Tidy imports"]);
+    }
+}
@@ -0,0 +1,177 @@
+//! Favorite and recently run tasks, pinned above the workspace groups and
+//! persisted to `<user_data_dir>/task-pins.json`
+
+use std::collections::{HashSet, VecDeque};
+use std::path::Path;
+use std::rc::Rc;
+
+use anyhow::{Context as _, Result};
+use serde::{Deserialize, Serialize};
+
+use super::panel::WorkspaceGroup;
+use crate::schemas::workspace_schema::WorkspaceTask;
+
+/// Number of tasks kept in the Recent section
+pub const MAX_RECENT_TASKS: usize = 5;
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct TaskPins {
+    #[serde(default)]
+    favorites: HashSet<String>,
+    /// Most recently run first
+    #[serde(default)]
+    recent: VecDeque<String>,
+}
+
+impl TaskPins {
+    /// Load pins from the user data directory, starting empty if missing or invalid
+    pub fn load() -> Self {
+        Self::load_from(&crate::core::config_manager::get_task_pins_path())
+    }
+
+    pub fn load_from(path: &Path) -> Self {
+        let Ok(json) = std::fs::read_to_string(path) else {
+            return Self::default();
+        };
+        serde_json::from_str(&json).unwrap_or_else(|e| {
+            log::warn!("Ignoring invalid task pins file {:?}: {}", path, e);
+            Self::default()
+        })
+    }
+
+    pub fn save(&self) {
+        let path = crate::core::config_manager::get_task_pins_path();
+        if let Err(e) = self.save_to(&path) {
+            log::error!("{:#}", e);
+        }
+    }
+
+    pub fn save_to(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, json).with_context(|| format!("Failed to write task pins: {:?}", path))
+    }
+
+    pub fn is_favorite(&self, task_id: &str) -> bool {
+        self.favorites.contains(task_id)
+    }
+
+    /// Star or unstar a task, returning whether it is now a favorite
+    pub fn toggle_favorite(&mut self, task_id: &str) -> bool {
+        if self.favorites.remove(task_id) {
+            return false;
+        }
+        self.favorites.insert(task_id.to_string());
+        true
+    }
+
+    /// Move a task to the front of the Recent ring, dropping the oldest
+    pub fn record_run(&mut self, task_id: &str) {
+        self.recent.retain(|id| id != task_id);
+        self.recent.push_front(task_id.to_string());
+        self.recent.truncate(MAX_RECENT_TASKS);
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PinnedKind {
+    Favorites,
+    Recent,
+}
+
+/// A synthetic section shown before the workspace groups
+#[derive(Clone, Debug)]
+pub struct PinnedSection {
+    pub kind: PinnedKind,
+    pub tasks: Vec<Rc<WorkspaceTask>>,
+}
+
+/// Build the Favorites and Recent sections from the visible workspaces,
+/// skipping empty ones. Favorites are newest first, Recent in run order.
+pub fn prepare_pinned_sections(
+    workspaces: &[WorkspaceGroup],
+    pins: &TaskPins,
+) -> Vec<PinnedSection> {
+    let find = |task_id: &str| {
+        workspaces
+            .iter()
+            .flat_map(|w| w.tasks.iter())
+            .find(|t| t.id == task_id)
+            .cloned()
+    };
+
+    let mut favorites: Vec<Rc<WorkspaceTask>> = workspaces
+        .iter()
+        .flat_map(|w| w.tasks.iter())
+        .filter(|t| pins.is_favorite(&t.id))
+        .cloned()
+        .collect();
+    favorites.sort_by_key(|task| std::cmp::Reverse(task.created_at));
+    let recent: Vec<Rc<WorkspaceTask>> = pins.recent.iter().filter_map(|id| find(id)).collect();
+
+    [
+        (PinnedKind::Favorites, favorites),
+        (PinnedKind::Recent, recent),
+    ]
+    .into_iter()
+    .filter(|(_, tasks)| !tasks.is_empty())
+    .map(|(kind, tasks)| PinnedSection { kind, tasks })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn workspace(tasks: &[&str]) -> WorkspaceGroup {
+        WorkspaceGroup {
+            id: "ws".to_string(),
+            name: "ws".to_string(),
+            path: "/tmp/ws".into(),
+            tasks: tasks
+                .iter()
+                .map(|name| {
+                    let mut task = WorkspaceTask::new(
+                        "ws".to_string(),
+                        name.to_string(),
+                        "agent".to_string(),
+                        "code".to_string(),
+                    );
+                    task.id = name.to_string();
+                    Rc::new(task)
+                })
+                .collect(),
+            is_expanded: true,
+        }
+    }
+
+    #[test]
+    fn favorite_survives_reload_and_is_pinned() {
+        let workspaces = vec![workspace(&["lint", "release", "triage"])];
+        let path =
+            std::env::temp_dir().join(format!("agentx-task-pins-{}.json", std::process::id()));
+
+        let mut pins = TaskPins::default();
+        assert!(pins.toggle_favorite("release"));
+        pins.save_to(&path).unwrap();
+
+        let reloaded = TaskPins::load_from(&path);
+        std::fs::remove_file(&path).ok();
+        let sections = prepare_pinned_sections(&workspaces, &reloaded);
+        assert_eq!(sections.len(), 1);
+        assert_eq!(sections[0].kind, PinnedKind::Favorites);
+        assert_eq!(sections[0].tasks[0].id, "release");
+    }
+
+    #[test]
+    fn recent_ring_keeps_latest_runs() {
+        let mut pins = TaskPins::default();
+        for i in 0..MAX_RECENT_TASKS + 2 {
+            pins.record_run(&format!("task-{i}"));
+        }
+        pins.record_run("task-3");
+
+        assert_eq!(pins.recent.len(), MAX_RECENT_TASKS);
+        assert_eq!(pins.recent.front().map(String::as_str), Some("task-3"));
+        assert!(!pins.recent.contains(&"task-0".to_string()));
+    }
+}
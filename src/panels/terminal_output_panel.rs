@@ -0,0 +1,590 @@
+//! Output of the terminals agents run commands in, one tab per terminal id.
+//! Conversation panels feed tool call updates into the shared
+//! `TerminalOutputs`; the panel follows it, interpreting ANSI colors.
+
+use std::ops::Range;
+
+use agent_client_protocol::{SessionUpdate, ToolCallContent};
+use gpui::{
+    AnyView, App, AppContext, Context, Entity, FocusHandle, Focusable, HighlightStyle, Hsla,
+    InteractiveElement, IntoElement, ParentElement, Render, ScrollHandle, SharedString,
+    StatefulInteractiveElement, Styled, StyledText, Subscription, Window, div,
+    prelude::FluentBuilder, px,
+};
+use gpui_component::{
+    ActiveTheme, Icon, IconName, Selectable, Sizable,
+    button::{Button, ButtonVariants},
+    h_flex, v_flex,
+};
+use rust_i18n::t;
+
+use crate::AppState;
+use crate::components::extract_terminal_output;
+use crate::panels::dock_panel::DockPanel;
+use crate::utils::ansi::{AnsiColor, AnsiStyle, AnsiText, parse_ansi};
+
+/// Distance from the bottom (in pixels) within which new output keeps the view
+/// scrolled to the end
+const AUTO_SCROLL_THRESHOLD_PX: f32 = 40.0;
+/// Bytes of output kept per terminal; the oldest lines are dropped past it
+pub const MAX_TERMINAL_OUTPUT_BYTES: usize = 1024 * 1024;
+/// Terminals kept; the oldest one is dropped when another starts
+pub const MAX_TERMINALS: usize = 32;
+
+/// Output carried by one terminal update
+#[derive(Debug, PartialEq, Eq)]
+enum TerminalText {
+    /// Everything the terminal printed so far
+    Snapshot(String),
+    /// Only what it printed since the last update
+    Chunk(String),
+}
+
+impl TerminalText {
+    /// Agents following the `terminal_output` extension stream new output as
+    /// `data`; the other fields hold the whole output so far
+    fn of(terminal: &agent_client_protocol::Terminal) -> Option<Self> {
+        let meta = terminal.meta.as_ref()?;
+        let nested = ["terminal_output", "terminalOutput"]
+            .iter()
+            .find_map(|key| meta.get(*key)?.as_object());
+        let chunk = nested
+            .and_then(|nested| nested.get("data"))
+            .or_else(|| meta.get("data"));
+        match chunk {
+            Some(data) => data.as_str().map(|data| Self::Chunk(data.to_string())),
+            None => extract_terminal_output(terminal).map(Self::Snapshot),
+        }
+    }
+}
+
+/// Everything one terminal printed, up to [`MAX_TERMINAL_OUTPUT_BYTES`]
+#[derive(Clone, Debug, Default)]
+pub struct TerminalOutput {
+    pub terminal_id: String,
+    /// Title of the tool call that ran in it, usually the command
+    pub title: Option<String>,
+    received: String,
+    /// Bytes dropped from the start of the output to stay under the cap
+    dropped: usize,
+    /// Bytes of `received` hidden by the clear button
+    cleared_at: usize,
+    /// Bumped whenever `output` changes, so views can cache what they parse
+    revision: u64,
+}
+
+impl TerminalOutput {
+    fn new(terminal_id: String) -> Self {
+        Self {
+            terminal_id,
+            ..Default::default()
+        }
+    }
+
+    /// Output since the last clear
+    pub fn output(&self) -> &str {
+        &self.received[self.cleared_at..]
+    }
+
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// A snapshot that extends what we have only adds its new part; any other
+    /// snapshot replaces the output (the agent redrew or restarted it)
+    fn receive(&mut self, text: TerminalText) {
+        match text {
+            TerminalText::Chunk(chunk) => self.received.push_str(&chunk),
+            TerminalText::Snapshot(snapshot) => {
+                let new = snapshot
+                    .get(self.dropped..)
+                    .and_then(|rest| rest.strip_prefix(self.received.as_str()));
+                match new {
+                    Some(new) if new.is_empty() => return,
+                    Some(new) => self.received.push_str(new),
+                    None => {
+                        self.received = snapshot;
+                        self.dropped = 0;
+                        self.cleared_at = 0;
+                    }
+                }
+            }
+        }
+        self.trim();
+        self.revision += 1;
+    }
+
+    /// Drop whole lines from the start until the output fits the cap
+    fn trim(&mut self) {
+        let excess = self
+            .received
+            .len()
+            .saturating_sub(MAX_TERMINAL_OUTPUT_BYTES);
+        if excess == 0 {
+            return;
+        }
+        let cut = match self.received[excess..].find('\n') {
+            Some(newline) => excess + newline + 1,
+            None => (excess..=self.received.len())
+                .find(|&ix| self.received.is_char_boundary(ix))
+                .unwrap_or(self.received.len()),
+        };
+        self.received.drain(..cut);
+        self.dropped += cut;
+        self.cleared_at = self.cleared_at.saturating_sub(cut);
+    }
+}
+
+/// Output of every terminal seen in tool call updates, in the order they
+/// appeared
+#[derive(Default)]
+pub struct TerminalOutputs {
+    terminals: Vec<TerminalOutput>,
+    /// Terminal a tool call asked to show; the panel switches to it
+    revealed: Option<String>,
+}
+
+impl TerminalOutputs {
+    pub fn terminals(&self) -> &[TerminalOutput] {
+        &self.terminals
+    }
+
+    pub fn get(&self, terminal_id: &str) -> Option<&TerminalOutput> {
+        self.terminals
+            .iter()
+            .find(|terminal| terminal.terminal_id == terminal_id)
+    }
+
+    /// Pick up terminal output carried by `update`; returns whether anything
+    /// was recorded
+    pub fn record(&mut self, update: &SessionUpdate) -> bool {
+        let (title, content) = match update {
+            SessionUpdate::ToolCall(tool_call) => {
+                (Some(&tool_call.title), tool_call.content.as_slice())
+            }
+            SessionUpdate::ToolCallUpdate(update) => (
+                update.fields.title.as_ref(),
+                update.fields.content.as_deref().unwrap_or_default(),
+            ),
+            _ => return false,
+        };
+
+        let mut recorded = false;
+        for content in content {
+            let ToolCallContent::Terminal(terminal) = content else {
+                continue;
+            };
+            let terminal_id = terminal.terminal_id.to_string();
+            let ix = match self
+                .terminals
+                .iter()
+                .position(|terminal| terminal.terminal_id == terminal_id)
+            {
+                Some(ix) => ix,
+                None => {
+                    if self.terminals.len() == MAX_TERMINALS {
+                        self.terminals.remove(0);
+                    }
+                    self.terminals.push(TerminalOutput::new(terminal_id));
+                    self.terminals.len() - 1
+                }
+            };
+            let entry = &mut self.terminals[ix];
+            if let Some(title) = title {
+                entry.title = Some(title.clone());
+            }
+            if let Some(text) = TerminalText::of(terminal) {
+                entry.receive(text);
+            }
+            recorded = true;
+        }
+        recorded
+    }
+
+    /// Hide what `terminal_id` printed so far
+    pub fn clear(&mut self, terminal_id: &str) {
+        if let Some(terminal) = self
+            .terminals
+            .iter_mut()
+            .find(|terminal| terminal.terminal_id == terminal_id)
+        {
+            terminal.cleared_at = terminal.received.len();
+            terminal.revision += 1;
+        }
+    }
+
+    /// Ask the panel to switch to `terminal_id`
+    pub fn reveal(&mut self, terminal_id: String) {
+        self.revealed = Some(terminal_id);
+    }
+
+    fn take_revealed(&mut self) -> Option<String> {
+        self.revealed.take()
+    }
+}
+
+/// One revision of a terminal's output, with its ANSI styles parsed out
+struct ParsedOutput {
+    terminal_id: String,
+    revision: u64,
+    text: SharedString,
+    styles: Vec<(Range<usize>, AnsiStyle)>,
+}
+
+/// Terminal Output Panel - command output of agent tool calls, one tab per terminal
+pub struct TerminalOutputPanel {
+    focus_handle: FocusHandle,
+    scroll_handle: ScrollHandle,
+    outputs: Entity<TerminalOutputs>,
+    /// Shown terminal; the newest one when unset
+    active: Option<String>,
+    /// Output of the shown terminal, parsed when it last changed
+    parsed: Option<ParsedOutput>,
+    _subscriptions: Vec<Subscription>,
+}
+
+impl DockPanel for TerminalOutputPanel {
+    fn title() -> &'static str {
+        "Output"
+    }
+
+    fn title_key() -> Option<&'static str> {
+        Some("terminal_output_panel.title")
+    }
+
+    fn description() -> &'static str {
+        "Output of commands run by agents"
+    }
+
+    fn new_view(window: &mut Window, cx: &mut App) -> Entity<impl Render> {
+        Self::view(window, cx)
+    }
+
+    fn on_active_any(view: AnyView, active: bool, _window: &mut Window, cx: &mut App) {
+        // A tab opened by a tool call link may have been revealed before this
+        // panel existed
+        if !active {
+            return;
+        }
+        if let Ok(panel) = view.downcast::<Self>() {
+            panel.update(cx, |panel, cx| panel.follow_revealed(cx));
+        }
+    }
+}
+
+impl TerminalOutputPanel {
+    pub fn view(_window: &mut Window, cx: &mut App) -> Entity<Self> {
+        cx.new(|cx| {
+            let outputs = AppState::global(cx).terminal_outputs.clone();
+            let subscription = cx.observe(&outputs, |this, _, cx| this.follow_revealed(cx));
+            let mut panel = Self {
+                focus_handle: cx.focus_handle(),
+                scroll_handle: ScrollHandle::new(),
+                outputs,
+                active: None,
+                parsed: None,
+                _subscriptions: vec![subscription],
+            };
+            panel.follow_revealed(cx);
+            panel
+        })
+    }
+
+    /// Switch to a revealed terminal and keep the view at the end of the output
+    fn follow_revealed(&mut self, cx: &mut Context<Self>) {
+        let revealed = self
+            .outputs
+            .update(cx, |outputs, _| outputs.take_revealed());
+        let at_bottom = self.is_scrolled_to_bottom();
+        if let Some(terminal_id) = revealed {
+            self.active = Some(terminal_id);
+            self.scroll_handle.scroll_to_bottom();
+        } else if at_bottom {
+            self.scroll_handle.scroll_to_bottom();
+        }
+        cx.notify();
+    }
+
+    fn is_scrolled_to_bottom(&self) -> bool {
+        let max_offset = self.scroll_handle.max_offset().height;
+        let offset = self.scroll_handle.offset().y;
+        max_offset + offset <= px(AUTO_SCROLL_THRESHOLD_PX)
+    }
+
+    fn select(&mut self, terminal_id: String, cx: &mut Context<Self>) {
+        self.active = Some(terminal_id);
+        self.scroll_handle.scroll_to_bottom();
+        cx.notify();
+    }
+
+    fn clear(&mut self, terminal_id: String, cx: &mut Context<Self>) {
+        self.outputs.update(cx, |outputs, cx| {
+            outputs.clear(&terminal_id);
+            cx.notify();
+        });
+    }
+
+    fn active_terminal<'a>(&self, outputs: &'a TerminalOutputs) -> Option<&'a TerminalOutput> {
+        self.active
+            .as_deref()
+            .and_then(|terminal_id| outputs.get(terminal_id))
+            .or_else(|| outputs.terminals().last())
+    }
+
+    fn render_tabs(&self, active: Option<&str>, cx: &Context<Self>) -> impl IntoElement {
+        let outputs = self.outputs.read(cx);
+
+        h_flex()
+            .id("terminal-output-tabs")
+            .flex_1()
+            .min_w_0()
+            .gap_1()
+            .overflow_x_scroll()
+            .children(outputs.terminals().iter().map(|terminal| {
+                let terminal_id = terminal.terminal_id.clone();
+                let label = terminal
+                    .title
+                    .clone()
+                    .unwrap_or_else(|| terminal_id.clone());
+                Button::new(SharedString::from(format!(
+                    "terminal-output-tab-{}",
+                    terminal_id
+                )))
+                .icon(IconName::SquareTerminal)
+                .label(label)
+                .ghost()
+                .xsmall()
+                .selected(active == Some(terminal_id.as_str()))
+                .on_click(cx.listener(move |this, _, _, cx| {
+                    this.select(terminal_id.clone(), cx);
+                }))
+            }))
+    }
+
+    /// Parse the shown terminal's output again only when it changed
+    fn parse_active(&mut self, cx: &App) {
+        let outputs = self.outputs.read(cx);
+        let Some(terminal) = self.active_terminal(outputs) else {
+            self.parsed = None;
+            return;
+        };
+        let fresh = self.parsed.as_ref().is_some_and(|parsed| {
+            parsed.terminal_id == terminal.terminal_id && parsed.revision == terminal.revision()
+        });
+        if !fresh {
+            let AnsiText { text, styles } = parse_ansi(terminal.output());
+            self.parsed = Some(ParsedOutput {
+                terminal_id: terminal.terminal_id.clone(),
+                revision: terminal.revision(),
+                text: text.into(),
+                styles,
+            });
+        }
+    }
+
+    fn render_output(&self, parsed: &ParsedOutput, cx: &Context<Self>) -> impl IntoElement {
+        let theme = cx.theme();
+        let highlights = parsed
+            .styles
+            .iter()
+            .map(|(range, style)| (range.clone(), highlight_style(*style, cx)))
+            .collect::<Vec<_>>();
+
+        div()
+            .w_full()
+            .p_3()
+            .font_family("Monaco, 'Courier New', monospace")
+            .text_size(px(12.))
+            .line_height(px(18.))
+            .text_color(theme.foreground)
+            .whitespace_normal()
+            .child(StyledText::new(parsed.text.clone()).with_highlights(highlights))
+    }
+}
+
+/// Theme color for an ANSI palette entry, matching the interactive terminal
+fn ansi_color(color: AnsiColor, cx: &App) -> Hsla {
+    let theme = cx.theme();
+    match color {
+        AnsiColor::Indexed(index) => match index {
+            0 | 8 => theme.muted_foreground,
+            1 => theme.red,
+            2 => theme.green,
+            3 => theme.yellow,
+            4 => theme.blue,
+            5 => theme.magenta,
+            6 => theme.cyan,
+            9 => theme.red_light,
+            10 => theme.green_light,
+            11 => theme.yellow_light,
+            12 => theme.blue_light,
+            13 => theme.magenta_light,
+            14 => theme.cyan_light,
+            _ => theme.foreground,
+        },
+        AnsiColor::Rgb(r, g, b) => gpui::rgb((r as u32) << 16 | (g as u32) << 8 | b as u32).into(),
+    }
+}
+
+fn highlight_style(style: AnsiStyle, cx: &App) -> HighlightStyle {
+    let color = style.foreground.map(|color| ansi_color(color, cx));
+    HighlightStyle {
+        color: if style.dim {
+            Some(color.unwrap_or(cx.theme().foreground).opacity(0.6))
+        } else {
+            color
+        },
+        font_weight: style.bold.then_some(gpui::FontWeight::BOLD),
+        ..Default::default()
+    }
+}
+
+impl Focusable for TerminalOutputPanel {
+    fn focus_handle(&self, _cx: &App) -> FocusHandle {
+        self.focus_handle.clone()
+    }
+}
+
+impl Render for TerminalOutputPanel {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        self.parse_active(cx);
+        let outputs = self.outputs.read(cx);
+        let active = self
+            .active_terminal(outputs)
+            .map(|terminal| terminal.terminal_id.clone());
+        let theme = cx.theme();
+
+        v_flex()
+            .id("terminal-output-panel")
+            .track_focus(&self.focus_handle)
+            .size_full()
+            .child(
+                h_flex()
+                    .w_full()
+                    .gap_2()
+                    .items_center()
+                    .px_2()
+                    .py_1()
+                    .border_b_1()
+                    .border_color(theme.border)
+                    .child(self.render_tabs(active.as_deref(), cx))
+                    .when_some(active.as_ref(), |this, terminal_id| {
+                        let terminal_id = terminal_id.clone();
+                        this.child(
+                            Button::new("terminal-output-clear")
+                                .icon(Icon::new(crate::assets::Icon::Trash2))
+                                .ghost()
+                                .xsmall()
+                                .tooltip(t!("terminal_output_panel.clear").to_string())
+                                .on_click(cx.listener(move |this, _, _, cx| {
+                                    this.clear(terminal_id.clone(), cx);
+                                })),
+                        )
+                    }),
+            )
+            .child(
+                div()
+                    .id("terminal-output-scroll")
+                    .flex_1()
+                    .min_h_0()
+                    .overflow_y_scroll()
+                    .track_scroll(&self.scroll_handle)
+                    .map(|this| match &self.parsed {
+                        Some(parsed) => this.child(self.render_output(parsed, cx)),
+                        None => this.child(
+                            div()
+                                .p_4()
+                                .text_sm()
+                                .text_color(theme.muted_foreground)
+                                .child(t!("terminal_output_panel.empty").to_string()),
+                        ),
+                    }),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use agent_client_protocol::{Terminal, ToolCall, ToolCallUpdate, ToolCallUpdateFields};
+
+    fn terminal_update(terminal_id: &str, output: &str) -> SessionUpdate {
+        terminal_meta_update(
+            terminal_id,
+            serde_json::json!({ "terminal_output": { "output": output } }),
+        )
+    }
+
+    fn terminal_chunk(terminal_id: &str, data: &str) -> SessionUpdate {
+        terminal_meta_update(
+            terminal_id,
+            serde_json::json!({ "terminal_output": { "data": data } }),
+        )
+    }
+
+    fn terminal_meta_update(terminal_id: &str, meta: serde_json::Value) -> SessionUpdate {
+        let terminal = Terminal::new(terminal_id.to_string()).meta(meta);
+        SessionUpdate::ToolCallUpdate(ToolCallUpdate::new(
+            "tc-1",
+            ToolCallUpdateFields::new().content(vec![ToolCallContent::Terminal(terminal)]),
+        ))
+    }
+
+    #[test]
+    fn snapshots_and_chunks_both_accumulate() {
+        let mut outputs = TerminalOutputs::default();
+        let mut started = ToolCall::new("tc-1", "cargo test");
+        started.content = vec![ToolCallContent::Terminal(Terminal::new("term-1"))];
+        assert!(outputs.record(&SessionUpdate::ToolCall(started)));
+        assert!(!outputs.record(&SessionUpdate::ToolCall(ToolCall::new("tc-2", "Read"))));
+
+        // Whole output so far, then only the new part
+        outputs.record(&terminal_update("term-1", "Compiling\n"));
+        outputs.record(&terminal_update("term-1", "Compiling\nRunning\n"));
+        outputs.record(&terminal_chunk("term-1", "ok\n"));
+
+        let terminal = outputs.get("term-1").unwrap();
+        assert_eq!(terminal.title.as_deref(), Some("cargo test"));
+        assert_eq!(terminal.output(), "Compiling\nRunning\nok\n");
+
+        // A snapshot that doesn't extend the output replaces it
+        outputs.record(&terminal_update("term-1", "Progress: 50%\n"));
+        assert_eq!(outputs.get("term-1").unwrap().output(), "Progress: 50%\n");
+    }
+
+    #[test]
+    fn output_is_capped_at_whole_lines() {
+        let mut outputs = TerminalOutputs::default();
+        let line = format!("{}\n", "x".repeat(99));
+        let snapshot = line.repeat(MAX_TERMINAL_OUTPUT_BYTES / 100 + 5);
+        outputs.record(&terminal_update("term-1", &snapshot));
+        let capped = outputs.get("term-1").unwrap();
+        assert!(capped.output().len() <= MAX_TERMINAL_OUTPUT_BYTES);
+        assert!(capped.output().starts_with('x'));
+
+        // The same snapshot grown by a line only adds that line
+        let revision = capped.revision();
+        outputs.record(&terminal_update("term-1", &format!("{}done\n", snapshot)));
+        let grown = outputs.get("term-1").unwrap();
+        assert!(grown.output().ends_with(&format!("{}done\n", line)));
+        assert!(grown.output().len() <= MAX_TERMINAL_OUTPUT_BYTES);
+        assert_eq!(grown.revision(), revision + 1);
+
+        for ix in 0..MAX_TERMINALS {
+            outputs.record(&terminal_chunk(&format!("term-{}", ix + 2), "hi\n"));
+        }
+        assert_eq!(outputs.terminals().len(), MAX_TERMINALS);
+        assert!(outputs.get("term-1").is_none());
+    }
+
+    #[test]
+    fn clearing_hides_only_earlier_output() {
+        let mut outputs = TerminalOutputs::default();
+        outputs.record(&terminal_update("term-1", "old\n"));
+        outputs.clear("term-1");
+        assert_eq!(outputs.get("term-1").unwrap().output(), "");
+
+        outputs.record(&terminal_update("term-1", "old\nnew\n"));
+        assert_eq!(outputs.get("term-1").unwrap().output(), "new\n");
+    }
+}
@@ -1,3 +1,9 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+    rc::Rc,
+};
+
 use gpui::{
     AnyElement, App, Context, Entity, FocusHandle, Focusable, InteractiveElement, IntoElement,
     ParentElement, Render, ScrollHandle, SharedString, StatefulInteractiveElement, Styled, Window,
@@ -5,9 +11,9 @@ use gpui::{
 };
 use gpui_component::{ActiveTheme, Icon, IconName, h_flex, text::TextView, v_flex};
 
-use agent_client_protocol::{ContentBlock, ToolCall, ToolCallContent};
+use agent_client_protocol::{ContentBlock, Diff, ToolCall, ToolCallContent};
 
-use crate::components::DiffView;
+use crate::components::{ContentRenderers, DiffView, render_unsupported};
 use crate::panels::dock_panel::DockPanel;
 
 /// Panel that displays detailed tool call content
@@ -16,6 +22,11 @@ pub struct ToolCallDetailPanel {
     scroll_handle: ScrollHandle,
     /// The tool call to display
     tool_call: Option<ToolCall>,
+    /// Collapsed diff sections the user expanded, by file and first old line
+    expanded_diff_sections: HashSet<(PathBuf, usize)>,
+    /// Diffs of the tool call by content index, shared with the diff views so
+    /// their cached highlighting is found by pointer
+    diffs: HashMap<usize, Rc<Diff>>,
 }
 
 impl ToolCallDetailPanel {
@@ -27,6 +38,8 @@ impl ToolCallDetailPanel {
             focus_handle,
             scroll_handle,
             tool_call: None,
+            expanded_diff_sections: HashSet::new(),
+            diffs: HashMap::new(),
         }
     }
 
@@ -48,28 +61,74 @@ impl ToolCallDetailPanel {
     // }
     /// Update the tool call to display
     pub fn update_tool_call(&mut self, tool_call: ToolCall, cx: &mut Context<Self>) {
-        self.tool_call = Some(tool_call);
+        self.set_tool_call(tool_call);
         cx.notify();
     }
     /// Setup the tool call to display
     pub fn set_tool_call(&mut self, tool_call: ToolCall) {
+        let same_call = self
+            .tool_call
+            .as_ref()
+            .is_some_and(|current| current.tool_call_id == tool_call.tool_call_id);
+        if !same_call {
+            self.expanded_diff_sections.clear();
+        }
+        self.diffs = tool_call
+            .content
+            .iter()
+            .enumerate()
+            .filter_map(|(ix, content)| match content {
+                ToolCallContent::Diff(diff) => Some((ix, Rc::new(diff.clone()))),
+                _ => None,
+            })
+            .collect();
         self.tool_call = Some(tool_call);
     }
 
     /// Clear the displayed tool call
     pub fn clear(&mut self, cx: &mut Context<Self>) {
         self.tool_call = None;
+        self.expanded_diff_sections.clear();
+        self.diffs.clear();
         cx.notify();
     }
 
     /// Render complete diff view using the DiffView component
     fn render_diff_view(
         &self,
-        diff: &agent_client_protocol::Diff,
+        ix: usize,
+        diff: &Diff,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) -> AnyElement {
-        let diff_view = DiffView::new(diff.clone()).context_lines(5).max_lines(5000);
+        let expanded = self
+            .expanded_diff_sections
+            .iter()
+            .filter(|(path, _)| *path == diff.path)
+            .map(|(_, start_old)| *start_old);
+        let path = diff.path.clone();
+        let panel = cx.entity().downgrade();
+        let shared = self
+            .diffs
+            .get(&ix)
+            .cloned()
+            .unwrap_or_else(|| Rc::new(diff.clone()));
+        let mut diff_view = DiffView::new(shared);
+        if let Some(tool_call) = &self.tool_call {
+            diff_view = diff_view.id(format!("detail-{}-{}", tool_call.tool_call_id, ix));
+        }
+        let diff_view = diff_view
+            .context_lines(5)
+            .max_lines(5000)
+            .show_edge_collapsed(true)
+            .expanded_sections(expanded)
+            .on_expand(move |start_old, _, cx| {
+                _ = panel.update(cx, |this, cx| {
+                    this.expanded_diff_sections
+                        .insert((path.clone(), start_old));
+                    cx.notify();
+                });
+            });
 
         diff_view.render(window, cx).into_any_element()
     }
@@ -93,6 +152,7 @@ impl ToolCallDetailPanel {
     /// Render content based on ToolCallContent type
     fn render_content(
         &self,
+        ix: usize,
         content: &ToolCallContent,
         window: &mut Window,
         cx: &mut Context<Self>,
@@ -128,13 +188,16 @@ impl ToolCallDetailPanel {
                         )
                         .into_any_element()
                 }
-                _ => div()
-                    .text_size(px(13.))
-                    .text_color(cx.theme().muted_foreground)
-                    .child("Unsupported content type")
-                    .into_any_element(),
+                block => ContentRenderers::default()
+                    .render(self.content_id("content", ix), block, cx)
+                    .unwrap_or_else(|| {
+                        render_unsupported(
+                            self.content_id("unsupported", ix),
+                            serde_json::to_value(block).unwrap_or_default(),
+                        )
+                    }),
             },
-            ToolCallContent::Diff(diff) => self.render_diff_view(diff, window, cx),
+            ToolCallContent::Diff(diff) => self.render_diff_view(ix, diff, window, cx),
             ToolCallContent::Terminal(terminal) => v_flex()
                 .w_full()
                 .gap_2()
@@ -173,13 +236,22 @@ impl ToolCallDetailPanel {
                         ),
                 )
                 .into_any_element(),
-            _ => div()
-                .text_size(px(13.))
-                .text_color(cx.theme().muted_foreground)
-                .child("Unknown content type")
-                .into_any_element(),
+            unknown => render_unsupported(
+                self.content_id("unsupported", ix),
+                serde_json::to_value(unknown).unwrap_or_default(),
+            ),
         }
     }
+
+    /// Element id for the `kind` element of the content at `ix`
+    fn content_id(&self, kind: &str, ix: usize) -> SharedString {
+        let tool_call_id = self
+            .tool_call
+            .as_ref()
+            .map(|tool_call| tool_call.tool_call_id.to_string())
+            .unwrap_or_default();
+        SharedString::from(format!("detail-{}-{}-{}", tool_call_id, kind, ix))
+    }
 }
 
 impl DockPanel for ToolCallDetailPanel {
@@ -259,9 +331,11 @@ impl Render for ToolCallDetailPanel {
                                                 ),
                                         )
                                         .child(div().w_full().h(px(1.)).bg(cx.theme().border))
-                                        .children(tool_call.content.iter().map(|content| {
-                                            self.render_content(content, window, cx)
-                                        })),
+                                        .children(tool_call.content.iter().enumerate().map(
+                                            |(ix, content)| {
+                                                self.render_content(ix, content, window, cx)
+                                            },
+                                        )),
                                 )
                             })
                             .when(self.tool_call.is_none(), |this| {
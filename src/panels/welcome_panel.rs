@@ -20,9 +20,11 @@ use crate::{
     AppState, CreateTaskFromWelcome, WelcomeSession,
     app::actions::AddCodeSelection,
     components::{
-        AgentItem, ChatInputBox, FileItem, FilePickerDelegate, ModeSelectItem, ModelSelectItem,
+        AgentItem, ChatInputBox, FileItem, FilePickerDelegate, ModeInputKind, ModeSelectItem,
+        ModelSelectItem, complete_mention, matching_commands, mention_query, slash_command_query,
+        truncate_chars,
     },
-    core::{config::McpServerConfig, services::AgentSessionInfo},
+    core::{config::McpServerConfig, event_bus::AgentConfigEvent, services::AgentSessionInfo},
 };
 
 // File picker delegate is now imported from components module
@@ -42,6 +44,8 @@ pub struct WelcomePanel {
     current_agent_name: Option<String>,
     current_session_id: Option<String>,
     has_agents: bool,
+    /// Agents that failed to start; shown in the select but not selectable
+    unavailable_agents: HashSet<String>,
     has_modes: bool,
     has_models: bool,
     is_session_loading: bool,
@@ -197,20 +201,29 @@ impl WelcomePanel {
             });
 
             // Spawn background task to process events
-            cx.spawn(async move |cx| {
-                while let Some(event) = rx.recv().await {
-                    if let Some(entity) = weak_entity.upgrade() {
-                        _ = cx.update(|cx| {
-                            entity.update(cx, |this, cx| {
-                                this.on_agent_config_event(&event, cx);
+            window
+                .spawn(cx, async move |cx| {
+                    while let Some(event) = rx.recv().await {
+                        if let Some(entity) = weak_entity.upgrade() {
+                            _ = cx.update(|window, cx| {
+                                entity.update(cx, |this, cx| {
+                                    this.on_agent_config_event(&event, cx);
+                                    // Keep the status dots in the agent select current
+                                    if matches!(
+                                        event,
+                                        AgentConfigEvent::AgentStatusChanged { .. }
+                                            | AgentConfigEvent::AgentRemoved { .. }
+                                    ) {
+                                        this.try_refresh_agents(window, cx);
+                                    }
+                                });
                             });
-                        });
-                    } else {
-                        break;
+                        } else {
+                            break;
+                        }
                     }
-                }
-            })
-            .detach();
+                })
+                .detach();
         }
 
         // Subscribe to agent_select focus to refresh agents list when no agents available
@@ -244,6 +257,7 @@ impl WelcomePanel {
                 window,
                 |this, _, _: &SelectEvent<Vec<AgentItem>>, window, cx| {
                     this.on_agent_changed(window, cx);
+                    this.remember_default_agent(cx);
                 },
             );
             this._subscriptions.push(agent_select_sub);
@@ -262,8 +276,10 @@ impl WelcomePanel {
             let mode_select_sub = cx.subscribe_in(
                 &this.mode_select,
                 window,
-                |this, _, _: &SelectEvent<Vec<ModeSelectItem>>, _window, cx| {
+                |this, _, _: &SelectEvent<Vec<ModeSelectItem>>, window, cx| {
                     this.on_mode_changed(cx);
+                    this.sync_mode_input_text(window, cx);
+                    this.remember_default_mode(cx);
                 },
             );
             this._subscriptions.push(mode_select_sub);
@@ -402,6 +418,7 @@ impl WelcomePanel {
             current_agent_name: None,
             current_session_id: None,
             has_agents,
+            unavailable_agents: HashSet::new(),
             has_modes: false,
             has_models: false,
             is_session_loading: false,
@@ -528,10 +545,11 @@ impl WelcomePanel {
 
         let agent_select = self.agent_select.clone();
         let current_selection = self.agent_select.read(cx).selected_value().cloned();
+        let default_agent = AppState::global(cx).default_agent().map(str::to_string);
         let no_agents_label = Self::no_agents_label();
         let weak_self = cx.entity().downgrade();
         cx.spawn_in(window, async move |_this, window| {
-            let agents = agent_service.list_agents().await;
+            let agents = agent_service.list_agents_with_status().await;
 
             _ = window.update(|window, cx| {
                 if let Some(this) = weak_self.upgrade() {
@@ -551,15 +569,28 @@ impl WelcomePanel {
                         }
 
                         this.has_agents = true;
+                        this.unavailable_agents = agents
+                            .iter()
+                            .filter(|agent| agent.status.is_error())
+                            .map(|agent| agent.name.clone())
+                            .collect();
+                        let available: Vec<String> = agents
+                            .iter()
+                            .filter(|agent| !agent.status.is_error())
+                            .map(|agent| agent.name.clone())
+                            .collect();
+                        let selected_name = available.get(initial_agent_index(
+                            &available,
+                            current_selection.as_deref(),
+                            default_agent.as_deref(),
+                        ));
+                        let selected_index = selected_name
+                            .and_then(|name| agents.iter().position(|agent| &agent.name == name))
+                            .unwrap_or(0);
                         let agent_items: Vec<AgentItem> = agents
-                            .clone()
                             .into_iter()
-                            .map(|name| AgentItem::new(name))
+                            .map(|agent| AgentItem::new(agent.name).status(agent.status))
                             .collect();
-                        let selected_index = current_selection
-                            .as_ref()
-                            .and_then(|name| agents.iter().position(|agent| agent == name))
-                            .unwrap_or(0);
                         agent_select.update(cx, |state, cx| {
                             state.set_items(agent_items, window, cx);
                             state.set_selected_index(
@@ -577,13 +608,7 @@ impl WelcomePanel {
     }
 
     /// Handle agent configuration events (add/remove/reload)
-    fn on_agent_config_event(
-        &mut self,
-        event: &crate::core::event_bus::AgentConfigEvent,
-        cx: &mut Context<Self>,
-    ) {
-        use crate::core::event_bus::AgentConfigEvent;
-
+    fn on_agent_config_event(&mut self, event: &AgentConfigEvent, cx: &mut Context<Self>) {
         log::info!("[WelcomePanel] Received agent config event: {:?}", event);
 
         match event {
@@ -601,6 +626,9 @@ impl WelcomePanel {
                 log::info!("[WelcomePanel] Agent updated: {}", name);
                 // No action needed - agent name hasn't changed
             }
+            AgentConfigEvent::AgentStatusChanged { name, status } => {
+                log::debug!("[WelcomePanel] Agent {} is now {:?}", name, status);
+            }
             AgentConfigEvent::ConfigReloaded { config } => {
                 log::info!("[WelcomePanel] Agent config reloaded");
                 // Force full refresh
@@ -638,6 +666,21 @@ impl WelcomePanel {
     fn on_agent_changed(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         let no_agents_label = Self::no_agents_label();
         let agent_name = match self.agent_select.read(cx).selected_value().cloned() {
+            Some(name) if self.unavailable_agents.contains(&name) => {
+                // Agents that failed to start can't take a session; keep the previous pick
+                let previous = self.current_agent_name.clone();
+                self.agent_select.update(cx, |state, cx| match &previous {
+                    Some(previous) => state.set_selected_value(previous, window, cx),
+                    None => state.set_selected_index(None, window, cx),
+                });
+                window.push_notification(
+                    Notification::warning(
+                        t!("welcome.error.agent_unavailable", agent = name).to_string(),
+                    ),
+                    cx,
+                );
+                return;
+            }
             Some(name) if name != no_agents_label => name,
             _ => {
                 // No valid agent selected, clear sessions
@@ -895,16 +938,7 @@ impl WelcomePanel {
     fn on_input_change(&mut self, cx: &mut Context<Self>) {
         let value = self.input_state.read(cx).value();
 
-        let mention_query = value.rfind('@').and_then(|at_index| {
-            let query = &value[at_index + 1..];
-            if query.chars().any(char::is_whitespace) {
-                None
-            } else {
-                Some(query)
-            }
-        });
-
-        if let Some(query) = mention_query {
+        if let Some(query) = mention_query(&value) {
             if self.show_command_suggestions {
                 self.show_command_suggestions = false;
                 self.command_suggestions.clear();
@@ -916,36 +950,11 @@ impl WelcomePanel {
         self.clear_file_suggestions(cx);
 
         // Check if input starts with / for command suggestions
-        if value.trim_start().starts_with('/') {
-            // Get the command prefix (everything after the /)
-            let trimmed = value.trim_start();
-            let command_text = trimmed.trim_start_matches('/');
-            if command_text.chars().any(char::is_whitespace) {
-                if self.show_command_suggestions {
-                    self.show_command_suggestions = false;
-                    self.command_suggestions.clear();
-                    cx.notify();
-                }
-                return;
-            }
-            let command_prefix = command_text;
-
-            // Get available commands for the current session
+        if let Some(command_prefix) = slash_command_query(&value) {
+            // Filter the current session's commands by prefix ("/" alone shows all)
             let all_commands = self.get_available_commands(cx);
-
-            // Filter commands by prefix
-            if command_prefix.is_empty() {
-                // Show all commands when just "/" is entered
-                self.command_suggestions = all_commands;
-                self.show_command_suggestions = !self.command_suggestions.is_empty();
-            } else {
-                // Filter commands that start with the prefix
-                self.command_suggestions = all_commands
-                    .into_iter()
-                    .filter(|cmd| cmd.name.starts_with(command_prefix))
-                    .collect();
-                self.show_command_suggestions = !self.command_suggestions.is_empty();
-            }
+            self.command_suggestions = matching_commands(all_commands, command_prefix);
+            self.show_command_suggestions = !self.command_suggestions.is_empty();
 
             log::debug!(
                 "[WelcomePanel] Command suggestions: {} matches for prefix '{}'",
@@ -1000,16 +1009,7 @@ impl WelcomePanel {
                     if let Some(this) = weak_self.upgrade() {
                         this.update(cx, |this, cx| {
                             let value = this.input_state.read(cx).value();
-                            let mention_query = value.rfind('@').and_then(|at_index| {
-                                let query = &value[at_index + 1..];
-                                if query.chars().any(char::is_whitespace) {
-                                    None
-                                } else {
-                                    Some(query.to_string())
-                                }
-                            });
-
-                            let Some(query) = mention_query else {
+                            let Some(query) = mention_query(&value).map(str::to_string) else {
                                 this.clear_file_suggestions(cx);
                                 return;
                             };
@@ -1124,6 +1124,10 @@ impl WelcomePanel {
             })
             .unwrap_or_else(|| (Vec::new(), None));
 
+        let default_mode_id = configured_mode_id(&mode_items, AppState::global(cx).default_mode());
+        let switch_mode = default_mode_id.is_some() && default_mode_id != selected_mode_id;
+        let selected_mode_id = default_mode_id.or(selected_mode_id);
+
         let has_items = !mode_items.is_empty();
         self.has_modes = has_items;
         self.mode_select.update(cx, |state, cx| {
@@ -1136,6 +1140,74 @@ impl WelcomePanel {
                 state.set_selected_index(None, window, cx);
             }
         });
+        self.sync_mode_input_text(window, cx);
+
+        // The session started in another mode; tell the agent about the configured one
+        if switch_mode {
+            self.on_mode_changed(cx);
+        }
+    }
+
+    /// Word the input's placeholder for the selected mode
+    fn sync_mode_input_text(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        let placeholder = self.selected_mode_input_kind(cx).placeholder();
+        self.input_state.update(cx, |state, cx| {
+            state.set_placeholder(placeholder, window, cx);
+        });
+    }
+
+    fn selected_mode_input_kind(&self, cx: &App) -> ModeInputKind {
+        if !self.has_modes {
+            return ModeInputKind::Default;
+        }
+        ModeInputKind::of(
+            self.mode_select
+                .read(cx)
+                .selected_value()
+                .map(String::as_str),
+        )
+    }
+
+    /// Remember the agent the user picked as `default_agent` in config.json
+    fn remember_default_agent(&self, cx: &mut Context<Self>) {
+        let Some(agent) = selected_agent_name(self.agent_select.read(cx).selected_value()) else {
+            return;
+        };
+        if AppState::global(cx).default_agent() == Some(agent.as_str()) {
+            return;
+        }
+        AppState::global_mut(cx).set_default_agent(Some(agent.clone()));
+
+        let Some(service) = AppState::global(cx).agent_config_service().cloned() else {
+            return;
+        };
+        cx.spawn(async move |_this, _cx| {
+            if let Err(e) = service.update_default_agent(Some(agent)).await {
+                log::error!("[WelcomePanel] Failed to save default agent: {}", e);
+            }
+        })
+        .detach();
+    }
+
+    /// Remember the mode the user picked as `default_mode` in config.json
+    fn remember_default_mode(&self, cx: &mut Context<Self>) {
+        let Some(mode) = self.mode_select.read(cx).selected_value().cloned() else {
+            return;
+        };
+        if AppState::global(cx).default_mode() == Some(mode.as_str()) {
+            return;
+        }
+        AppState::global_mut(cx).set_default_mode(Some(mode.clone()));
+
+        let Some(service) = AppState::global(cx).agent_config_service().cloned() else {
+            return;
+        };
+        cx.spawn(async move |_this, _cx| {
+            if let Err(e) = service.update_default_mode(Some(mode)).await {
+                log::error!("[WelcomePanel] Failed to save default mode: {}", e);
+            }
+        })
+        .detach();
     }
 
     fn update_model_select(
@@ -1211,11 +1283,7 @@ impl WelcomePanel {
             let session_display: Vec<String> = sessions
                 .iter()
                 .map(|s| {
-                    let short_id = if s.session_id.len() > 8 {
-                        &s.session_id[..8]
-                    } else {
-                        &s.session_id
-                    };
+                    let short_id = truncate_chars(&s.session_id, 8).0;
                     Self::session_label(short_id)
                 })
                 .collect();
@@ -1403,6 +1471,16 @@ impl WelcomePanel {
         self.begin_session_recreate(agent_name, window, cx);
     }
 
+    /// Current agent and mode selection as submitted with a new task.
+    ///
+    /// The agent is `None` while the select only shows a placeholder
+    /// ("No agents" / "Loading agents..."), so no task is dispatched to it.
+    pub fn current_selection(&self, cx: &App) -> (Option<String>, String) {
+        let agent_name = selected_agent_name(self.agent_select.read(cx).selected_value());
+        let mode = selected_mode(self.mode_select.read(cx).selected_value());
+        (agent_name, mode)
+    }
+
     /// Handles sending the task based on the current input, mode, and agent selections.
     fn handle_send_task(&mut self, window: &mut Window, cx: &mut Context<Self>) {
         // Check if workspace exists
@@ -1415,24 +1493,14 @@ impl WelcomePanel {
         let task_name = self.input_state.read(cx).text().to_string();
 
         if !task_name.is_empty() {
-            let mode = self
-                .mode_select
-                .read(cx)
-                .selected_value()
-                .cloned()
-                .unwrap_or_else(|| "default".to_string());
-
-            let agent_name = self
-                .agent_select
-                .read(cx)
-                .selected_value()
-                .cloned()
-                .unwrap_or_else(|| "test-agent".to_string());
-
-            let agent_name = if agent_name == Self::no_agents_label() {
-                "test-agent".to_string()
-            } else {
-                agent_name
+            let (agent_name, mode) = self.current_selection(cx);
+            let Some(agent_name) = agent_name else {
+                log::warn!("[WelcomePanel] Cannot create task: No agent selected");
+                window.push_notification(
+                    Notification::warning(t!("welcome.error.no_agent_selected").to_string()),
+                    cx,
+                );
+                return;
             };
 
             // Clear the input immediately
@@ -1463,6 +1531,49 @@ impl WelcomePanel {
     }
 }
 
+/// Selected agent name, ignoring the placeholder items of the agent select
+fn selected_agent_name(selected: Option<&String>) -> Option<String> {
+    let placeholders = [
+        WelcomePanel::no_agents_label(),
+        WelcomePanel::loading_agents_label(),
+    ];
+    selected
+        .filter(|name| !placeholders.contains(name))
+        .cloned()
+}
+
+/// Agent to preselect: the current selection, then the configured default
+/// agent, then the first agent
+fn initial_agent_index(
+    agents: &[String],
+    current: Option<&str>,
+    default_agent: Option<&str>,
+) -> usize {
+    [current, default_agent]
+        .into_iter()
+        .flatten()
+        .find_map(|name| agents.iter().position(|agent| agent == name))
+        .unwrap_or(0)
+}
+
+/// Id of the configured default mode if the agent offers it, matched by id or
+/// name ignoring case ("Plan" finds the mode with id "plan")
+fn configured_mode_id(items: &[ModeSelectItem], default_mode: Option<&str>) -> Option<String> {
+    let default_mode = default_mode?.trim();
+    items
+        .iter()
+        .find(|item| {
+            item.id.eq_ignore_ascii_case(default_mode)
+                || item.label.eq_ignore_ascii_case(default_mode)
+        })
+        .map(|item| item.id.clone())
+}
+
+/// Selected mode id, falling back to the default mode
+fn selected_mode(selected: Option<&String>) -> String {
+    selected.cloned().unwrap_or_else(|| "default".to_string())
+}
+
 impl Focusable for WelcomePanel {
     fn focus_handle(&self, _cx: &gpui::App) -> FocusHandle {
         self.focus_handle.clone()
@@ -1574,7 +1685,9 @@ impl Render for WelcomePanel {
                                 ChatInputBox::new("welcome-chat-input", self.input_state.clone());
                             if self.current_session_id.is_some() {
                                 if self.has_modes {
-                                    chat = chat.mode_select(self.mode_select.clone());
+                                    chat = chat
+                                        .mode_select(self.mode_select.clone())
+                                        .title(self.selected_mode_input_kind(cx).title());
                                 }
                                 if self.has_models {
                                     chat = chat.model_select(self.model_select.clone());
@@ -1603,20 +1716,14 @@ impl Render for WelcomePanel {
 
                                     let input_state = this.input_state.clone();
                                     let current_value = input_state.read(cx).value();
-                                    let mut applied_to_input = false;
-                                    if let Some(at_index) = current_value.rfind('@') {
-                                        let query = &current_value[at_index + 1..];
-                                        if !query.chars().any(char::is_whitespace) {
-                                            let prefix = &current_value[..at_index];
-                                            let new_value =
-                                                SharedString::from(format!("{prefix}@{filename} "));
-                                            window.defer(cx, move |window, cx| {
-                                                input_state.update(cx, |state, cx| {
-                                                    state.set_value(new_value, window, cx);
-                                                });
+                                    let completed = complete_mention(&current_value, &filename);
+                                    let applied_to_input = completed.is_some();
+                                    if let Some(new_value) = completed {
+                                        window.defer(cx, move |window, cx| {
+                                            input_state.update(cx, |state, cx| {
+                                                state.set_value(new_value, window, cx);
                                             });
-                                            applied_to_input = true;
-                                        }
+                                        });
                                     }
 
                                     if !applied_to_input
@@ -1693,3 +1800,122 @@ impl Render for WelcomePanel {
             )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn selection_reflects_chosen_items() {
+        let agents = vec!["claude".to_string(), "codex".to_string()];
+        let modes = vec!["default".to_string(), "plan".to_string()];
+
+        assert_eq!(
+            selected_agent_name(agents.get(1)),
+            Some("codex".to_string())
+        );
+        assert_eq!(selected_mode(modes.get(1)), "plan");
+        assert_eq!(selected_mode(None), "default");
+    }
+
+    #[test]
+    fn placeholder_agent_is_not_selected() {
+        let placeholder = WelcomePanel::no_agents_label();
+        assert_eq!(selected_agent_name(Some(&placeholder)), None);
+        assert_eq!(selected_agent_name(None), None);
+    }
+
+    #[test]
+    fn unknown_default_agent_falls_back_to_first() {
+        let agents = vec!["claude".to_string(), "codex".to_string()];
+
+        assert_eq!(initial_agent_index(&agents, None, Some("codex")), 1);
+        assert_eq!(initial_agent_index(&agents, None, Some("gemini")), 0);
+        assert_eq!(initial_agent_index(&agents, None, None), 0);
+        assert_eq!(
+            initial_agent_index(&agents, Some("claude"), Some("codex")),
+            0
+        );
+        assert_eq!(initial_agent_index(&[], None, Some("codex")), 0);
+    }
+
+    #[test]
+    fn default_mode_matches_id_or_name() {
+        let modes = vec![
+            ModeSelectItem::new("default", "Default"),
+            ModeSelectItem::new("plan", "Plan Mode"),
+        ];
+
+        assert_eq!(
+            configured_mode_id(&modes, Some("plan mode")).as_deref(),
+            Some("plan")
+        );
+        assert_eq!(
+            configured_mode_id(&modes, Some("PLAN")).as_deref(),
+            Some("plan")
+        );
+        assert_eq!(configured_mode_id(&modes, Some("auto")), None);
+        assert_eq!(configured_mode_id(&modes, None), None);
+    }
+
+    #[gpui::test]
+    fn current_selection_reads_both_selects(cx: &mut gpui::TestAppContext) {
+        cx.update(|cx| {
+            gpui_component::init(cx);
+            AppState::init(cx);
+        });
+
+        let cx = cx.add_empty_window();
+        let panel = cx.update(|window, cx| WelcomePanel::view(window, cx));
+        panel.read_with(cx, |panel, cx| {
+            assert_eq!(panel.current_selection(cx), (None, "default".to_string()));
+        });
+
+        cx.update(|window, cx| {
+            panel.update(cx, |panel, cx| {
+                panel.agent_select.update(cx, |state, cx| {
+                    state.set_items(
+                        vec![AgentItem::new("claude"), AgentItem::new("codex")],
+                        window,
+                        cx,
+                    );
+                    state.set_selected_index(Some(IndexPath::new(1)), window, cx);
+                });
+                panel.mode_select.update(cx, |state, cx| {
+                    state.set_items(
+                        vec![
+                            ModeSelectItem::new("default", "Default"),
+                            ModeSelectItem::new("plan", "Plan Mode"),
+                        ],
+                        window,
+                        cx,
+                    );
+                    state.set_selected_value(&"plan".to_string(), window, cx);
+                });
+            });
+        });
+        panel.read_with(cx, |panel, cx| {
+            assert_eq!(
+                panel.current_selection(cx),
+                (Some("codex".to_string()), "plan".to_string())
+            );
+        });
+
+        // The "No agents" placeholder is not an agent to send the task to
+        cx.update(|window, cx| {
+            panel.update(cx, |panel, cx| {
+                panel.agent_select.update(cx, |state, cx| {
+                    state.set_items(
+                        vec![AgentItem::new(WelcomePanel::no_agents_label())],
+                        window,
+                        cx,
+                    );
+                    state.set_selected_index(Some(IndexPath::default()), window, cx);
+                });
+            });
+        });
+        panel.read_with(cx, |panel, cx| {
+            assert_eq!(panel.current_selection(cx), (None, "plan".to_string()));
+        });
+    }
+}
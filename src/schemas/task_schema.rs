@@ -7,6 +7,9 @@ use crate::core::services::SessionStatus;
 pub struct AgentTask {
     pub name: String,
     pub task_type: String,
+    /// Optional longer description, matched by the task search
+    #[serde(default)]
+    pub description: Option<String>,
     pub add_new_code_lines: i16,
     pub delete_code_lines: i16,
     pub status: SessionStatus,
@@ -41,6 +44,7 @@ impl AgentTask {
         Self {
             name,
             task_type: "Default".to_string(),
+            description: None,
             add_new_code_lines: 0,
             delete_code_lines: 0,
             status: SessionStatus::InProgress,
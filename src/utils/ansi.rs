@@ -0,0 +1,180 @@
+//! SGR color codes in command output (`ESC[31m` and friends), split into
+//! plain text and styled byte ranges. Other escape sequences are dropped.
+
+use std::ops::Range;
+
+const ESC: char = '\x1b';
+
+/// A foreground color as the output asked for it
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AnsiColor {
+    /// One of the 16 palette colors; 8-15 are the bright variants
+    Indexed(u8),
+    Rgb(u8, u8, u8),
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AnsiStyle {
+    pub foreground: Option<AnsiColor>,
+    pub bold: bool,
+    pub dim: bool,
+}
+
+impl AnsiStyle {
+    fn is_plain(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Apply the parameters of one `ESC[...m` sequence
+    fn apply(&mut self, params: &str) {
+        let mut codes = params
+            .split(';')
+            .map(|code| code.parse::<u16>().unwrap_or(0));
+        while let Some(code) = codes.next() {
+            match code {
+                0 => *self = Self::default(),
+                1 => self.bold = true,
+                2 => self.dim = true,
+                22 => {
+                    self.bold = false;
+                    self.dim = false;
+                }
+                30..=37 => self.foreground = Some(AnsiColor::Indexed((code - 30) as u8)),
+                90..=97 => self.foreground = Some(AnsiColor::Indexed((code - 90 + 8) as u8)),
+                39 => self.foreground = None,
+                38 => match codes.next() {
+                    Some(5) => {
+                        let index = codes.next().unwrap_or(0);
+                        // Only the 16 palette colors map onto the theme
+                        self.foreground = (index < 16).then_some(AnsiColor::Indexed(index as u8));
+                    }
+                    Some(2) => {
+                        let mut channel = || codes.next().unwrap_or(0).min(255) as u8;
+                        self.foreground = Some(AnsiColor::Rgb(channel(), channel(), channel()));
+                    }
+                    _ => {}
+                },
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Text with its escape sequences removed
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AnsiText {
+    pub text: String,
+    /// Byte ranges of `text` with a non-default style, in order
+    pub styles: Vec<(Range<usize>, AnsiStyle)>,
+}
+
+/// Split `input` into plain text and the styles its SGR codes set
+pub fn parse_ansi(input: &str) -> AnsiText {
+    let mut parsed = AnsiText::default();
+    let mut style = AnsiStyle::default();
+    let mut run_start = 0;
+    let mut chars = input.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != ESC {
+            parsed.text.push(c);
+            continue;
+        }
+        match chars.peek() {
+            // CSI: parameters, then one final byte in @..~
+            Some('[') => {
+                chars.next();
+                let mut params = String::new();
+                let mut last = None;
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        last = Some(c);
+                        break;
+                    }
+                    params.push(c);
+                }
+                if last == Some('m') {
+                    let previous = style;
+                    style.apply(&params);
+                    if style != previous {
+                        push_run(&mut parsed, run_start, previous);
+                        run_start = parsed.text.len();
+                    }
+                }
+            }
+            // OSC (titles, hyperlinks): ends at BEL or ESC \
+            Some(']') => {
+                chars.next();
+                while let Some(c) = chars.next() {
+                    if c == '\x07' {
+                        break;
+                    }
+                    if c == ESC && chars.peek() == Some(&'\\') {
+                        chars.next();
+                        break;
+                    }
+                }
+            }
+            Some(_) => {
+                chars.next();
+            }
+            None => {}
+        }
+    }
+    push_run(&mut parsed, run_start, style);
+    parsed
+}
+
+fn push_run(parsed: &mut AnsiText, start: usize, style: AnsiStyle) {
+    let end = parsed.text.len();
+    if start < end && !style.is_plain() {
+        parsed.styles.push((start..end, style));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn colors_become_ranges_of_the_plain_text() {
+        let parsed = parse_ansi("ok \x1b[1;32mpassed\x1b[0m, \x1b[91mfailed\x1b[39m done");
+        assert_eq!(parsed.text, "ok passed, failed done");
+        assert_eq!(
+            parsed.styles,
+            vec![
+                (
+                    3..9,
+                    AnsiStyle {
+                        foreground: Some(AnsiColor::Indexed(2)),
+                        bold: true,
+                        dim: false,
+                    }
+                ),
+                (
+                    11..17,
+                    AnsiStyle {
+                        foreground: Some(AnsiColor::Indexed(9)),
+                        ..Default::default()
+                    }
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn other_sequences_are_dropped() {
+        let parsed = parse_ansi("\x1b]0;title\x07\x1b[2Kline\x1b[38;2;255;128;0m!");
+        assert_eq!(parsed.text, "line!");
+        assert_eq!(
+            parsed.styles,
+            vec![(
+                4..5,
+                AnsiStyle {
+                    foreground: Some(AnsiColor::Rgb(255, 128, 0)),
+                    ..Default::default()
+                }
+            )]
+        );
+    }
+}
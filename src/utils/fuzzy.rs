@@ -0,0 +1,122 @@
+//! Subsequence fuzzy matching (in the spirit of fzf) shared by the task list
+//! and file filters.
+
+/// Points for every matched character
+const SCORE_MATCH: i64 = 16;
+/// Bonus when a match directly follows the previous one
+const BONUS_CONSECUTIVE: i64 = 24;
+/// Bonus when a match starts a word (`task_list`, `task-list`, `taskList`)
+const BONUS_BOUNDARY: i64 = 20;
+/// Extra bonus when the first needle character matches the start of the haystack
+const BONUS_PREFIX: i64 = 24;
+/// Penalty per skipped haystack character between two matches
+const PENALTY_GAP: i64 = 2;
+
+/// A successful fuzzy match
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FuzzyMatch {
+    /// Higher is better; only comparable between matches of the same needle
+    pub score: i64,
+    /// Byte offsets of the matched characters in the haystack, for highlighting
+    pub indices: Vec<usize>,
+}
+
+/// Match `needle` as a case-insensitive subsequence of `haystack`.
+///
+/// Every occurrence of the first needle character is tried as a starting
+/// point and the best scoring alignment wins, so `"list"` in
+/// `"slow_list"` prefers the word `list` over the scattered `s..l..i..t`.
+/// An empty needle matches everything with a score of zero.
+pub fn fuzzy_match(needle: &str, haystack: &str) -> Option<FuzzyMatch> {
+    let needle: Vec<char> = needle.chars().flat_map(char::to_lowercase).collect();
+    if needle.is_empty() {
+        return Some(FuzzyMatch {
+            score: 0,
+            indices: Vec::new(),
+        });
+    }
+
+    let chars: Vec<(usize, char)> = haystack.char_indices().collect();
+    let lower: Vec<char> = chars
+        .iter()
+        .map(|(_, c)| c.to_lowercase().next().unwrap_or(*c))
+        .collect();
+
+    (0..chars.len())
+        .filter(|&start| lower[start] == needle[0])
+        .filter_map(|start| match_from(&needle, &chars, &lower, start))
+        .max_by_key(|m| m.score)
+}
+
+/// Greedily match the rest of `needle` after placing its first character at `start`
+fn match_from(
+    needle: &[char],
+    chars: &[(usize, char)],
+    lower: &[char],
+    start: usize,
+) -> Option<FuzzyMatch> {
+    let mut score = 0;
+    let mut indices = Vec::with_capacity(needle.len());
+    let mut previous: Option<usize> = None;
+    let mut position = start;
+
+    for &wanted in needle {
+        let found = (position..lower.len()).find(|&i| lower[i] == wanted)?;
+        score += SCORE_MATCH;
+        match previous {
+            Some(previous) if found == previous + 1 => score += BONUS_CONSECUTIVE,
+            Some(previous) => score -= PENALTY_GAP * (found - previous - 1) as i64,
+            None if found == 0 => score += BONUS_PREFIX,
+            None => {}
+        }
+        if is_word_start(chars, found) {
+            score += BONUS_BOUNDARY;
+        }
+        indices.push(chars[found].0);
+        previous = Some(found);
+        position = found + 1;
+    }
+
+    Some(FuzzyMatch { score, indices })
+}
+
+fn is_word_start(chars: &[(usize, char)], index: usize) -> bool {
+    let Some(index_before) = index.checked_sub(1) else {
+        return true;
+    };
+    let (before, current) = (chars[index_before].1, chars[index].1);
+    !before.is_alphanumeric() || (before.is_lowercase() && current.is_uppercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_subsequences() {
+        let m = fuzzy_match("tsklst", "task_list").unwrap();
+        assert_eq!(m.indices, [0, 2, 3, 5, 7, 8]);
+        assert!(fuzzy_match("lists", "task_list").is_none());
+        assert_eq!(fuzzy_match("", "anything").unwrap().score, 0);
+    }
+
+    #[test]
+    fn ignores_case() {
+        assert!(fuzzy_match("TASK", "task_list").is_some());
+        assert!(fuzzy_match("tl", "TaskList").is_some());
+        assert_eq!(
+            fuzzy_match("list", "TaskList").unwrap().indices,
+            [4, 5, 6, 7]
+        );
+    }
+
+    #[test]
+    fn prefix_outranks_scattered() {
+        let prefix = fuzzy_match("task", "task_list.rs").unwrap();
+        let scattered = fuzzy_match("task", "the_last_keystroke.rs").unwrap();
+        assert!(prefix.score > scattered.score);
+
+        let word = fuzzy_match("list", "slow_list").unwrap();
+        assert_eq!(word.indices, [5, 6, 7, 8]);
+    }
+}
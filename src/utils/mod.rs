@@ -1,6 +1,8 @@
+pub mod ansi;
 pub mod clipboard;
 pub mod external_editor;
 pub mod file;
+pub mod fuzzy;
 pub mod time;
 pub mod tool_call;
 /// Open a folder picker dialog and return the selected path
@@ -3,11 +3,12 @@ use gpui_component::{
     WindowExt,
     dock::{DockItem, DockPlacement, Panel, PanelInfo, PanelState, PanelView, TabPanel},
 };
-use std::sync::Arc;
+use std::{path::Path, sync::Arc};
 
 use crate::{
-    AppState, ConversationPanel, OpenSessionManager, PanelAction, SessionManagerPanel,
-    SettingsPanel, ToggleDockToggleButton, TogglePanelVisible, WelcomePanel,
+    AppState, CodeEditorPanel, ConversationPanel, OpenSessionManager, PanelAction,
+    SessionManagerPanel, SettingsPanel, TerminalOutputPanel, TerminalPanel, ToggleDockToggleButton,
+    TogglePanelVisible, WelcomePanel,
     app::actions::{PanelCommand, PanelKind, Submit},
     panels::{
         DockPanel,
@@ -40,7 +41,7 @@ impl DockWorkspace {
     /// Helper method to show ConversationPanel in the active center tab
     ///
     /// If session_id is provided, it will load the conversation history for that session.
-    /// Otherwise, it will create a new conversation panel with mock data.
+    /// Otherwise, it will create an empty conversation panel.
     pub(in crate::workspace) fn show_conversation_panel(
         &mut self,
         session_id: Option<String>,
@@ -244,6 +245,107 @@ impl DockWorkspace {
         }
     }
 
+    /// Focus a tab already showing `path`, in the center or any dock, and move
+    /// its cursor to `position`
+    fn activate_code_editor_for_file(
+        &mut self,
+        path: &Path,
+        position: lsp_types::Position,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        let dock_area = self.dock_area.read(cx);
+        let items: Vec<DockItem> = std::iter::once(dock_area.center().clone())
+            .chain(
+                [
+                    dock_area.right_dock(),
+                    dock_area.left_dock(),
+                    dock_area.bottom_dock(),
+                ]
+                .into_iter()
+                .flatten()
+                .map(|dock| dock.read(cx).panel().clone()),
+            )
+            .collect();
+        let Some(editor) = items
+            .iter()
+            .find_map(|item| Self::activate_code_editor_in_item(item, path, window, cx))
+        else {
+            return false;
+        };
+        editor.update(cx, |editor, cx| {
+            editor.open_at(
+                path.to_path_buf(),
+                position.line,
+                position.character,
+                window,
+                cx,
+            );
+        });
+        true
+    }
+
+    fn activate_code_editor_in_item(
+        item: &DockItem,
+        path: &Path,
+        window: &mut Window,
+        cx: &mut App,
+    ) -> Option<Entity<CodeEditorPanel>> {
+        match item {
+            DockItem::Tabs { view, .. } => {
+                let tab_state = view.read(cx).dump(cx);
+                let ix = tab_state
+                    .children
+                    .iter()
+                    .position(|child_state| Self::panel_state_shows_file(child_state, path))?;
+                let _ = item.clone().active_index(ix, cx);
+
+                let active_panel = view.read(cx).active_panel(cx)?;
+                active_panel.focus_handle(cx).focus(window, cx);
+                Self::code_editor_of(&active_panel, cx)
+            }
+            DockItem::Split { items, .. } => items
+                .iter()
+                .find_map(|item| Self::activate_code_editor_in_item(item, path, window, cx)),
+            DockItem::Panel { view, .. } => {
+                let editor = Self::code_editor_of(view, cx)?;
+                if editor.read(cx).current_file_path().map(|p| p.as_path()) != Some(path) {
+                    return None;
+                }
+                view.set_active(true, window, cx);
+                view.focus_handle(cx).focus(window, cx);
+                Some(editor)
+            }
+            DockItem::Tiles { .. } => None,
+        }
+    }
+
+    fn panel_state_shows_file(panel_state: &PanelState, path: &Path) -> bool {
+        if let PanelInfo::Panel(value) = &panel_state.info {
+            let dock_state = DockPanelState::from_value(value.clone());
+            if dock_state.agent_studio_klass.as_ref() == "CodeEditorPanel"
+                && dock_state.file_path.as_deref().map(Path::new) == Some(path)
+            {
+                return true;
+            }
+        }
+
+        panel_state
+            .children
+            .iter()
+            .any(|child| Self::panel_state_shows_file(child, path))
+    }
+
+    fn code_editor_of(panel: &Arc<dyn PanelView>, cx: &App) -> Option<Entity<CodeEditorPanel>> {
+        let container = panel.view().downcast::<DockPanelContainer>().ok()?;
+        container
+            .read(cx)
+            .agent_studio
+            .clone()?
+            .downcast::<CodeEditorPanel>()
+            .ok()
+    }
+
     fn panel_state_contains_session(panel_state: &PanelState, session_id: &str) -> bool {
         match &panel_state.info {
             PanelInfo::Panel(value) => {
@@ -338,6 +440,75 @@ impl DockWorkspace {
         Self::activate_panel_by_klass(&panel, SessionManagerPanel::klass(), window, cx)
     }
 
+    fn activate_existing_terminal_panel(
+        &mut self,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) -> bool {
+        let Some(bottom_dock) = self.dock_area.read(cx).bottom_dock().cloned() else {
+            return false;
+        };
+        let panel = bottom_dock.read(cx).panel().clone();
+        Self::activate_panel_by_klass(&panel, TerminalPanel::klass(), window, cx)
+    }
+
+    /// Reveal the terminal in the bottom dock, adding one if there is none
+    fn show_terminal_panel(
+        &mut self,
+        working_directory: Option<std::path::PathBuf>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.activate_existing_terminal_panel(window, cx) {
+            self.dock_area.update(cx, |dock_area, cx| {
+                if !dock_area.is_dock_open(DockPlacement::Bottom, cx) {
+                    dock_area.toggle_dock(DockPlacement::Bottom, window, cx);
+                }
+            });
+            return;
+        }
+
+        self.add_terminal_panel_to(working_directory, DockPlacement::Bottom, window, cx);
+    }
+
+    /// Reveal the terminal output panel in the bottom dock, adding one if there
+    /// is none, and switch it to `terminal_id`
+    fn show_terminal_output_panel(
+        &mut self,
+        terminal_id: Option<String>,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(terminal_id) = terminal_id {
+            let outputs = AppState::global(cx).terminal_outputs.clone();
+            outputs.update(cx, |outputs, cx| {
+                outputs.reveal(terminal_id);
+                cx.notify();
+            });
+        }
+
+        let activated = self
+            .dock_area
+            .read(cx)
+            .bottom_dock()
+            .cloned()
+            .is_some_and(|bottom_dock| {
+                let panel = bottom_dock.read(cx).panel().clone();
+                Self::activate_panel_by_klass(&panel, TerminalOutputPanel::klass(), window, cx)
+            });
+        if !activated {
+            let panel = Arc::new(DockPanelContainer::panel::<TerminalOutputPanel>(window, cx));
+            self.dock_area.update(cx, |dock_area, cx| {
+                dock_area.add_panel(panel, DockPlacement::Bottom, None, window, cx);
+            });
+        }
+        self.dock_area.update(cx, |dock_area, cx| {
+            if !dock_area.is_dock_open(DockPlacement::Bottom, cx) {
+                dock_area.toggle_dock(DockPlacement::Bottom, window, cx);
+            }
+        });
+    }
+
     fn activate_panel_by_klass(
         item: &DockItem,
         klass: &str,
@@ -425,12 +596,25 @@ impl DockWorkspace {
                 PanelKind::Conversation { session_id } => {
                     self.add_conversation_panel_to(session_id.clone(), *placement, window, cx);
                 }
+                PanelKind::SavedConversation { path } => {
+                    self.add_saved_conversation_panel_to(path.clone(), *placement, window, cx);
+                }
+                PanelKind::DemoConversation => {
+                    self.add_demo_conversation_panel_to(*placement, window, cx);
+                }
                 PanelKind::Terminal { working_directory } => {
                     self.add_terminal_panel_to(working_directory.clone(), *placement, window, cx);
                 }
-                PanelKind::CodeEditor { working_directory } => {
+                PanelKind::TerminalOutput { terminal_id } => {
+                    self.show_terminal_output_panel(terminal_id.clone(), window, cx);
+                }
+                PanelKind::CodeEditor {
+                    working_directory,
+                    open_at,
+                } => {
                     self.add_code_editor_panel_to(
                         working_directory.clone(),
+                        open_at.clone(),
                         *placement,
                         window,
                         cx,
@@ -446,36 +630,60 @@ impl DockWorkspace {
                     self.show_tool_call_detail_panel((**tool_call).clone(), window, cx);
                 }
             },
-            PanelCommand::Show(panel) => match panel {
-                PanelKind::Conversation { session_id } => {
-                    self.show_conversation_panel(session_id.clone(), window, cx);
-                }
-                PanelKind::Terminal { working_directory } => {
-                    self.add_terminal_panel_to(
-                        working_directory.clone(),
-                        DockPlacement::Bottom,
-                        window,
-                        cx,
-                    );
-                }
-                PanelKind::CodeEditor { working_directory } => {
-                    self.add_code_editor_panel_to(
-                        working_directory.clone(),
-                        DockPlacement::Right,
-                        window,
-                        cx,
-                    );
-                }
-                PanelKind::Welcome { workspace_id } => {
-                    self.show_welcome_panel(workspace_id.clone(), window, cx);
-                }
-                PanelKind::ToolCallDetail {
-                    tool_call_id: _,
-                    tool_call,
-                } => {
-                    self.show_tool_call_detail_panel((**tool_call).clone(), window, cx);
+            PanelCommand::Show(panel) => self.show_panel(panel, window, cx),
+            PanelCommand::Reveal(panel) => {
+                let focused = window.focused(cx);
+                self.show_panel(panel, window, cx);
+                if let Some(focused) = focused {
+                    focused.focus(window, cx);
                 }
-            },
+            }
+        }
+    }
+
+    fn show_panel(&mut self, panel: &PanelKind, window: &mut Window, cx: &mut Context<Self>) {
+        match panel {
+            PanelKind::Conversation { session_id } => {
+                self.show_conversation_panel(session_id.clone(), window, cx);
+            }
+            PanelKind::SavedConversation { path } => {
+                self.add_saved_conversation_panel_to(
+                    path.clone(),
+                    DockPlacement::Center,
+                    window,
+                    cx,
+                );
+            }
+            PanelKind::DemoConversation => {
+                self.add_demo_conversation_panel_to(DockPlacement::Center, window, cx);
+            }
+            PanelKind::Terminal { working_directory } => {
+                self.show_terminal_panel(working_directory.clone(), window, cx);
+            }
+            PanelKind::TerminalOutput { terminal_id } => {
+                self.show_terminal_output_panel(terminal_id.clone(), window, cx);
+            }
+            PanelKind::CodeEditor {
+                working_directory,
+                open_at,
+            } => {
+                self.add_code_editor_panel_to(
+                    working_directory.clone(),
+                    open_at.clone(),
+                    DockPlacement::Right,
+                    window,
+                    cx,
+                );
+            }
+            PanelKind::Welcome { workspace_id } => {
+                self.show_welcome_panel(workspace_id.clone(), window, cx);
+            }
+            PanelKind::ToolCallDetail {
+                tool_call_id: _,
+                tool_call,
+            } => {
+                self.show_tool_call_detail_panel((**tool_call).clone(), window, cx);
+            }
         }
     }
 
@@ -517,6 +725,41 @@ impl DockWorkspace {
         });
     }
 
+    fn add_saved_conversation_panel_to(
+        &mut self,
+        path: std::path::PathBuf,
+        placement: DockPlacement,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let panel = Arc::new(DockPanelContainer::panel_for_saved_session(
+            path, window, cx,
+        ));
+        self.dock_area.update(cx, |dock_area, cx| {
+            let was_dock_open = dock_area.is_dock_open(placement, cx);
+            dock_area.add_panel(panel, placement, None, window, cx);
+            if !was_dock_open {
+                dock_area.toggle_dock(placement, window, cx);
+            }
+        });
+    }
+
+    fn add_demo_conversation_panel_to(
+        &mut self,
+        placement: DockPlacement,
+        window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let panel = Arc::new(DockPanelContainer::panel_for_demo(window, cx));
+        self.dock_area.update(cx, |dock_area, cx| {
+            let was_dock_open = dock_area.is_dock_open(placement, cx);
+            dock_area.add_panel(panel, placement, None, window, cx);
+            if !was_dock_open {
+                dock_area.toggle_dock(placement, window, cx);
+            }
+        });
+    }
+
     fn add_welcome_panel_to(
         &mut self,
         workspace_id: Option<String>,
@@ -582,11 +825,28 @@ impl DockWorkspace {
     fn add_code_editor_panel_to(
         &mut self,
         working_directory: Option<std::path::PathBuf>,
+        open_at: Option<(std::path::PathBuf, lsp_types::Position)>,
         placement: DockPlacement,
         window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        let panel = if let Some(working_directory) = working_directory {
+        if let Some((path, position)) = &open_at
+            && self.activate_code_editor_for_file(path, *position, window, cx)
+        {
+            return;
+        }
+
+        let panel = if let Some((path, position)) = open_at {
+            let working_directory = working_directory
+                .unwrap_or_else(|| AppState::global(cx).current_working_dir().clone());
+            Arc::new(DockPanelContainer::panel_for_code_editor_at(
+                working_directory,
+                path,
+                position,
+                window,
+                cx,
+            ))
+        } else if let Some(working_directory) = working_directory {
             Arc::new(DockPanelContainer::panel_for_code_editor_with_cwd(
                 working_directory,
                 window,
@@ -8,7 +8,7 @@ use gpui_component::{
 use std::sync::Arc;
 
 use crate::{
-    AppState, ConversationPanel, CreateTaskFromWelcome, NewSessionConversationPanel,
+    AppState, ConversationPanel, CreateTaskFromWelcome, NewSessionConversationPanel, RetryPrompt,
     SendMessageToSession,
     app::actions::{AddCodeSelection, CancelSession},
     panels::{DockPanel, dock_panel::DockPanelContainer},
@@ -140,7 +140,7 @@ impl DockWorkspace {
                 );
                 ws.session_id
             } else {
-                let mcp_servers = if let Some(service) = agent_config_service {
+                let mcp_servers = if let Some(service) = &agent_config_service {
                     service
                         .list_mcp_servers()
                         .await
@@ -303,8 +303,18 @@ impl DockWorkspace {
             }
             log::debug!("Built {} content blocks for prompt", prompt_blocks.len());
 
+            let instructions = match &agent_config_service {
+                Some(service) => service.instructions_for(&agent_name).await,
+                None => None,
+            };
+
             match message_service
-                .send_message_to_session(&agent_name, &session_id_for_send, prompt_blocks)
+                .send_message_to_session(
+                    &agent_name,
+                    &session_id_for_send,
+                    prompt_blocks,
+                    instructions,
+                )
                 .await
             {
                 Ok(response) => {
@@ -375,9 +385,42 @@ impl DockWorkspace {
 
         log::info!("Sending message to session: {}", session_id);
 
+        let mut prompt_blocks: Vec<acp::ContentBlock> = Vec::new();
+        // Add code selections as text context before the user message
+        for selection in code_selections.iter() {
+            let code_context = format_code_selection_as_context(selection);
+            prompt_blocks.push(code_context.into());
+        }
+        prompt_blocks.push(message.into());
+        for (image_content, _filename) in images.iter() {
+            prompt_blocks.push(acp::ContentBlock::Image(image_content.clone()));
+        }
+
+        Self::send_prompt_to_session(session_id, prompt_blocks, cx);
+    }
+
+    /// Handle RetryPrompt action - send a failed prompt again as it was
+    pub(in crate::workspace) fn on_action_retry_prompt(
+        &mut self,
+        action: &RetryPrompt,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        log::info!("Retrying prompt for session: {}", action.session_id);
+        Self::send_prompt_to_session(action.session_id.clone(), action.prompt.clone(), cx);
+    }
+
+    /// Send `prompt_blocks` to the agent of `session_id`, led by its custom instructions
+    fn send_prompt_to_session(
+        session_id: String,
+        prompt_blocks: Vec<acp::ContentBlock>,
+        cx: &mut Context<Self>,
+    ) {
         cx.spawn(async move |_this, cx| {
             let agent_service = cx.update(|cx| AppState::global(cx).agent_service().cloned());
             let message_service = cx.update(|cx| AppState::global(cx).message_service().cloned());
+            let agent_config_service =
+                cx.update(|cx| AppState::global(cx).agent_config_service().cloned());
 
             let (agent_service, message_service) = match (agent_service, message_service) {
                 (Some(agent_service), Some(message_service)) => (agent_service, message_service),
@@ -390,33 +433,26 @@ impl DockWorkspace {
             let agent_name = match agent_service.get_agent_for_session(&session_id) {
                 Some(agent_name) => agent_name,
                 None => {
-                    log::error!(
-                        "Cannot send message: no agent found for session {}",
-                        session_id
-                    );
+                    let error = anyhow::anyhow!("No agent found for session {}", session_id);
+                    log::error!("Cannot send message: {}", error);
+                    message_service.publish_session_error(&session_id, &error, prompt_blocks);
                     return;
                 }
             };
 
-            let mut prompt_blocks: Vec<acp::ContentBlock> = Vec::new();
-            // Add code selections as text context before the user message
-            for selection in code_selections.iter() {
-                let code_context = format_code_selection_as_context(selection);
-                prompt_blocks.push(code_context.into());
-            }
-            prompt_blocks.push(message.clone().into());
-            for (image_content, _filename) in images.iter() {
-                prompt_blocks.push(acp::ContentBlock::Image(image_content.clone()));
-            }
-
             log::debug!(
                 "Sending prompt to agent {} for session {}",
                 agent_name,
                 session_id
             );
 
+            let instructions = match &agent_config_service {
+                Some(service) => service.instructions_for(&agent_name).await,
+                None => None,
+            };
+
             match message_service
-                .send_message_to_session(&agent_name, &session_id, prompt_blocks)
+                .send_message_to_session(&agent_name, &session_id, prompt_blocks, instructions)
                 .await
             {
                 Ok(_response) => {
@@ -11,8 +11,8 @@ use std::{sync::Arc, time::Duration};
 const EXCLUDED_PANELS: &[&str] = &["CodeEditorPanel", "ToolCallDetailPanel"];
 
 use crate::{
-    AppSettings, AppTitleBar, CodeEditorPanel, ConversationPanel, SessionManagerPanel, TaskPanel,
-    TerminalPanel,
+    AppSettings, AppTitleBar, CodeEditorPanel, ConversationPanel, SavedSessionsPanel,
+    SessionManagerPanel, TaskPanel, TerminalOutputPanel, TerminalPanel,
     core::updater::{UpdateCheckResult, UpdateManager},
     panels::dock_panel::DockPanelContainer,
 };
@@ -322,7 +322,9 @@ impl DockWorkspace {
             vec![DockItem::tabs(
                 vec![
                     Arc::new(DockPanelContainer::panel::<TerminalPanel>(window, cx)),
+                    Arc::new(DockPanelContainer::panel::<TerminalOutputPanel>(window, cx)),
                     Arc::new(DockPanelContainer::panel::<SessionManagerPanel>(window, cx)),
+                    Arc::new(DockPanelContainer::panel::<SavedSessionsPanel>(window, cx)),
                 ],
                 &dock_area,
                 window,
@@ -428,6 +430,11 @@ impl DockWorkspace {
                 .update(cx, |_, window, cx| {
                     window.activate_window();
                     window.set_window_title("Agent Studio");
+                    window
+                        .observe_window_appearance(|window, cx| {
+                            crate::themes::sync_system_appearance(window, cx);
+                        })
+                        .detach();
                     cx.on_release(|_, cx| {
                         // exit app
                         cx.quit();
@@ -475,6 +482,7 @@ impl Render for DockWorkspace {
             .on_action(cx.listener(Self::on_action_new_session_conversation_panel))
             .on_action(cx.listener(Self::on_action_create_task_from_welcome))
             .on_action(cx.listener(Self::on_action_send_message_to_session))
+            .on_action(cx.listener(Self::on_action_retry_prompt))
             .on_action(cx.listener(Self::on_action_cancel_session))
             .on_action(cx.listener(Self::on_action_open))
             .relative()